@@ -0,0 +1,131 @@
+use jiff::Timestamp;
+use serde_json::Value;
+
+use crate::jira::{parse_timestamp, Comment};
+
+/// Something that happened on an issue, as listed in the activity tab.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub at: Timestamp,
+    pub author: String,
+    pub kind: EventKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventKind {
+    /// A comment with its body as ADF.
+    Comment(Value),
+    /// Time logged like `1h 30m`, with the worklog comment as ADF.
+    Worklog { time_spent: String, comment: Value },
+    Status { from: String, to: String },
+}
+
+/// The status changes in the changelog of an issue fetched with `expand=changelog`.
+pub fn status_changes(issue: &Value) -> Vec<Event> {
+    issue["changelog"]["histories"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|history| {
+            let at = parse_timestamp(&history["created"]);
+            let author = history["author"]["displayName"].as_str().unwrap_or("Someone");
+            history["items"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|item| item["field"] == "status")
+                .filter_map(move |item| {
+                    Some(Event {
+                        at: at?,
+                        author: author.to_string(),
+                        kind: EventKind::Status {
+                            from: item["fromString"].as_str()?.to_string(),
+                            to: item["toString"].as_str()?.to_string(),
+                        },
+                    })
+                })
+        })
+        .collect()
+}
+
+/// The worklogs in a response of `/rest/api/3/issue/{key}/worklog`, at the time the
+/// work started.
+pub fn worklogs(body: &Value) -> Vec<Event> {
+    body["worklogs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|worklog| {
+            Some(Event {
+                at: parse_timestamp(&worklog["started"])?,
+                author: worklog["author"]["displayName"].as_str().unwrap_or("Someone").to_string(),
+                kind: EventKind::Worklog {
+                    time_spent: worklog["timeSpent"].as_str().unwrap_or_default().to_string(),
+                    comment: worklog["comment"].clone(),
+                },
+            })
+        })
+        .collect()
+}
+
+/// The comments interleaved with the other events, oldest first.
+pub fn stream(comments: &[Comment], events: &[Event]) -> Vec<Event> {
+    let mut stream: Vec<Event> = comments
+        .iter()
+        .filter_map(|comment| {
+            Some(Event {
+                at: comment.created?,
+                author: comment.author.clone(),
+                kind: EventKind::Comment(comment.body.clone()),
+            })
+        })
+        .chain(events.iter().cloned())
+        .collect();
+    stream.sort_by_key(|event| event.at);
+    stream
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_stream() {
+        let issue = json!({"changelog": {"histories": [
+            {"created": "2025-01-06T10:00:00.000+0000", "author": {"displayName": "Sam"}, "items": [
+                {"field": "assignee", "fromString": null, "toString": "Sam"},
+                {"field": "status", "fromString": "To Do", "toString": "In Progress"}
+            ]}
+        ]}});
+        let body = json!({"worklogs": [
+            {"started": "2025-01-07T09:00:00.000+0000", "author": {"displayName": "Sam"}, "timeSpent": "2h"}
+        ]});
+        let comments = [Comment {
+            id: "1".to_string(),
+            parent_id: None,
+            author: "Alex".to_string(),
+            created: "2025-01-06T12:00:00Z".parse().ok(),
+            body: Value::Null,
+        }];
+        let events: Vec<Event> = status_changes(&issue).into_iter().chain(worklogs(&body)).collect();
+        let stream = stream(&comments, &events);
+        let kinds: Vec<&str> = stream
+            .iter()
+            .map(|event| match &event.kind {
+                EventKind::Comment(_) => "comment",
+                EventKind::Worklog { .. } => "worklog",
+                EventKind::Status { .. } => "status",
+            })
+            .collect();
+        assert_eq!(kinds, ["status", "comment", "worklog"]);
+        assert_eq!(
+            stream[0].kind,
+            EventKind::Status {
+                from: "To Do".to_string(),
+                to: "In Progress".to_string()
+            }
+        );
+    }
+}
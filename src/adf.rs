@@ -0,0 +1,421 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use ratatui::{
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+};
+use serde_json::{json, Value};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Theme, ThemeSet},
+    parsing::SyntaxSet,
+};
+
+/// Renders an Atlassian Document Format document into styled lines.
+///
+/// `names` maps account ids of mentioned users to their display names.
+pub fn render(doc: &Value, names: &HashMap<String, String>) -> Vec<Line<'static>> {
+    let mut renderer = Renderer {
+        names,
+        lines: vec![],
+    };
+    renderer.blocks(&doc["content"], "");
+    renderer.lines
+}
+
+/// Account ids of all users mentioned in the document.
+pub fn mention_ids(node: &Value) -> Vec<String> {
+    let mut ids = vec![];
+    if node["type"] == "mention" {
+        if let Some(id) = node["attrs"]["id"].as_str() {
+            ids.push(id.to_string());
+        }
+    }
+    if let Some(children) = node["content"].as_array() {
+        for child in children {
+            ids.extend(mention_ids(child));
+        }
+    }
+    ids
+}
+
+/// Names of the media the document embeds, in order. Media nodes only reference the
+/// media store, their `alt` is the file name of the attachment they were uploaded as.
+pub fn media_names(node: &Value) -> Vec<String> {
+    let mut names = vec![];
+    if node["type"] == "media" {
+        if let Some(name) = node["attrs"]["alt"].as_str() {
+            names.push(name.to_string());
+        }
+    }
+    if let Some(children) = node["content"].as_array() {
+        for child in children {
+            names.extend(media_names(child));
+        }
+    }
+    names
+}
+
+/// Concatenated plain text of the document, used for excerpts and searching.
+pub fn plain_text(node: &Value) -> String {
+    if let Some(text) = node["text"].as_str() {
+        return text.to_string();
+    }
+    let Some(children) = node["content"].as_array() else {
+        return String::new();
+    };
+    let separator = if children.iter().any(|child| child["text"].is_string()) {
+        ""
+    } else {
+        "\n"
+    };
+    children
+        .iter()
+        .map(plain_text)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+struct Renderer<'a> {
+    names: &'a HashMap<String, String>,
+    lines: Vec<Line<'static>>,
+}
+
+impl Renderer<'_> {
+    fn blocks(&mut self, nodes: &Value, prefix: &str) {
+        let Some(nodes) = nodes.as_array() else {
+            return;
+        };
+        for node in nodes {
+            self.block(node, prefix);
+        }
+    }
+
+    fn block(&mut self, node: &Value, prefix: &str) {
+        match node["type"].as_str().unwrap_or("") {
+            "paragraph" => {
+                self.push_inlines(&node["content"], prefix, Style::default());
+            }
+            "heading" => {
+                let style = Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                self.push_inlines(&node["content"], prefix, style);
+            }
+            "bulletList" => self.list_items(node, prefix, None),
+            "orderedList" => {
+                let start = node["attrs"]["order"].as_u64().unwrap_or(1);
+                self.list_items(node, prefix, Some(start));
+            }
+            "codeBlock" => {
+                let language = node["attrs"]["language"].as_str().unwrap_or("");
+                let code = plain_text(node);
+                for line in highlight(&code, language) {
+                    let mut spans = vec![Span::raw(format!("{prefix}  "))];
+                    spans.extend(line.spans);
+                    self.lines.push(Line::from(spans));
+                }
+            }
+            "blockquote" => self.blocks(&node["content"], &format!("{prefix}│ ")),
+            "panel" => self.blocks(&node["content"], &format!("{prefix}▌ ")),
+            "rule" => self.lines.push(Line::from(format!("{prefix}────────").dark_gray())),
+            "table" => {
+                for row in node["content"].as_array().unwrap_or(&vec![]) {
+                    let cells: Vec<String> = row["content"]
+                        .as_array()
+                        .unwrap_or(&vec![])
+                        .iter()
+                        .map(|cell| plain_text(cell).replace('\n', " "))
+                        .collect();
+                    self.lines.push(Line::from(format!("{prefix}| {} |", cells.join(" | "))));
+                }
+            }
+            "mediaSingle" | "mediaGroup" => {
+                for media in node["content"].as_array().unwrap_or(&vec![]) {
+                    let name = media["attrs"]["alt"].as_str().unwrap_or("attachment");
+                    self.lines.push(Line::from(format!("{prefix}[image: {name}]").dark_gray()));
+                }
+            }
+            _ => self.blocks(&node["content"], prefix),
+        }
+        if prefix.is_empty() && !matches!(node["type"].as_str(), Some("listItem")) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn list_items(&mut self, node: &Value, prefix: &str, start: Option<u64>) {
+        let Some(items) = node["content"].as_array() else {
+            return;
+        };
+        for (index, item) in items.iter().enumerate() {
+            let bullet = match start {
+                Some(start) => format!("{}. ", start + index as u64),
+                None => "• ".to_string(),
+            };
+            let first_line = self.lines.len();
+            let indent = " ".repeat(bullet.chars().count());
+            self.blocks(&item["content"], &format!("{prefix}{indent}"));
+            // Replace the indentation of the item's first line by the bullet.
+            if let Some(line) = self.lines.get_mut(first_line) {
+                if let Some(span) = line.spans.first_mut() {
+                    span.content = format!("{prefix}{bullet}").into();
+                }
+            }
+        }
+    }
+
+    fn push_inlines(&mut self, nodes: &Value, prefix: &str, base: Style) {
+        let mut spans = vec![Span::raw(prefix.to_string())];
+        for node in nodes.as_array().unwrap_or(&vec![]) {
+            match node["type"].as_str().unwrap_or("") {
+                "hardBreak" => {
+                    self.lines.push(Line::from(std::mem::take(&mut spans)));
+                    spans.push(Span::raw(prefix.to_string()));
+                }
+                _ => spans.push(self.inline(node, base)),
+            }
+        }
+        self.lines.push(Line::from(spans));
+    }
+
+    fn inline(&self, node: &Value, base: Style) -> Span<'static> {
+        match node["type"].as_str().unwrap_or("") {
+            "text" => {
+                let text = node["text"].as_str().unwrap_or("").to_string();
+                let mut style = base;
+                for mark in node["marks"].as_array().unwrap_or(&vec![]) {
+                    style = match mark["type"].as_str().unwrap_or("") {
+                        "strong" => style.add_modifier(Modifier::BOLD),
+                        "em" => style.add_modifier(Modifier::ITALIC),
+                        "underline" => style.add_modifier(Modifier::UNDERLINED),
+                        "strike" => style.add_modifier(Modifier::CROSSED_OUT),
+                        "code" => style.fg(Color::Magenta),
+                        "link" => style.fg(Color::Blue).add_modifier(Modifier::UNDERLINED),
+                        _ => style,
+                    };
+                }
+                Span::styled(text, style)
+            }
+            "mention" => {
+                let id = node["attrs"]["id"].as_str().unwrap_or("");
+                let name = match self.names.get(id) {
+                    Some(name) => format!("@{name}"),
+                    None => node["attrs"]["text"].as_str().unwrap_or(id).to_string(),
+                };
+                Span::styled(name, base.fg(Color::Cyan).add_modifier(Modifier::BOLD))
+            }
+            "emoji" => {
+                let emoji = node["attrs"]["text"]
+                    .as_str()
+                    .or(node["attrs"]["shortName"].as_str())
+                    .unwrap_or("");
+                Span::styled(emoji.to_string(), base)
+            }
+            "inlineCard" => {
+                let url = node["attrs"]["url"].as_str().unwrap_or("").to_string();
+                Span::styled(url, base.fg(Color::Blue).add_modifier(Modifier::UNDERLINED))
+            }
+            "status" => {
+                let text = node["attrs"]["text"].as_str().unwrap_or("").to_uppercase();
+                Span::styled(format!("[{text}]"), base.add_modifier(Modifier::BOLD))
+            }
+            _ => Span::styled(plain_text(node), base),
+        }
+    }
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme() -> &'static Theme {
+    static THEME: OnceLock<Theme> = OnceLock::new();
+    THEME.get_or_init(|| ThemeSet::load_defaults().themes["base16-ocean.dark"].clone())
+}
+
+/// Syntax highlights a code block, falling back to plain text for unknown languages.
+fn highlight(code: &str, language: &str) -> Vec<Line<'static>> {
+    let syntax_set = syntax_set();
+    let syntax = syntax_set
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, theme());
+    code.lines()
+        .map(|line| {
+            let line = format!("{line}\n");
+            let regions = highlighter
+                .highlight_line(&line, syntax_set)
+                .unwrap_or_default();
+            let spans: Vec<Span> = regions
+                .into_iter()
+                .map(|(style, text)| {
+                    let color = Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b);
+                    Span::styled(text.trim_end_matches('\n').to_string(), Style::default().fg(color))
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// A document for plain text, with a paragraph per line and lines starting with
+/// `- ` gathered into bullet lists.
+pub fn from_text(text: &str) -> Value {
+    let paragraph = |text: &str| json!({"type": "paragraph", "content": [{"type": "text", "text": text}]});
+    let mut content: Vec<Value> = vec![];
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let Some(item) = line.strip_prefix("- ") else {
+            content.push(paragraph(line));
+            continue;
+        };
+        let item = json!({"type": "listItem", "content": [paragraph(item)]});
+        match content.last_mut() {
+            Some(list) if list["type"] == "bulletList" => {
+                list["content"].as_array_mut().unwrap().push(item);
+            }
+            _ => content.push(json!({"type": "bulletList", "content": [item]})),
+        }
+    }
+    json!({"type": "doc", "version": 1, "content": content})
+}
+
+/// The items under the "Acceptance criteria" heading or paragraph, up to the next
+/// heading: one per list item and per paragraph.
+pub fn acceptance_criteria(doc: &Value) -> Vec<String> {
+    let single_line = |node: &Value| plain_text(node).split_whitespace().collect::<Vec<_>>().join(" ");
+    let blocks = doc["content"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let Some(start) = blocks
+        .iter()
+        .position(|block| plain_text(block).to_lowercase().contains("acceptance criteria"))
+    else {
+        return vec![];
+    };
+    let mut criteria = vec![];
+    for block in blocks[start + 1..].iter().take_while(|block| block["type"] != "heading") {
+        match block["type"].as_str().unwrap_or("") {
+            "bulletList" | "orderedList" | "taskList" => {
+                criteria.extend(block["content"].as_array().into_iter().flatten().map(single_line));
+            }
+            _ => criteria.push(single_line(block)),
+        }
+    }
+    criteria.retain(|criterion| !criterion.is_empty());
+    criteria
+}
+
+/// The plain text on a single line, cut off after `max_chars` characters.
+pub fn excerpt(node: &Value, max_chars: usize) -> String {
+    let text = plain_text(node).split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let doc = json!({
+            "type": "doc",
+            "content": [
+                {"type": "heading", "attrs": {"level": 1}, "content": [{"type": "text", "text": "Goal"}]},
+                {"type": "paragraph", "content": [
+                    {"type": "text", "text": "Ask "},
+                    {"type": "mention", "attrs": {"id": "abc", "text": "@abc"}},
+                    {"type": "text", "text": " first", "marks": [{"type": "strong"}]}
+                ]},
+                {"type": "bulletList", "content": [
+                    {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "one"}]}]},
+                    {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "two"}]}]}
+                ]},
+                {"type": "codeBlock", "attrs": {"language": "rust"}, "content": [{"type": "text", "text": "let a = 1;"}]}
+            ]
+        });
+        let names = HashMap::from([("abc".to_string(), "Sam".to_string())]);
+        let lines = render(&doc, &names);
+        assert_eq!(
+            text(&lines),
+            ["Goal", "", "Ask @Sam first", "", "• one", "• two", "", "  let a = 1;", ""]
+        );
+        assert!(lines[2].spans[3].style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn test_mention_ids_and_plain_text() {
+        let doc = json!({
+            "type": "doc",
+            "content": [
+                {"type": "paragraph", "content": [
+                    {"type": "text", "text": "Hi "},
+                    {"type": "mention", "attrs": {"id": "abc"}}
+                ]},
+                {"type": "paragraph", "content": [{"type": "text", "text": "Bye"}]}
+            ]
+        });
+        assert_eq!(mention_ids(&doc), ["abc"]);
+        assert_eq!(plain_text(&doc), "Hi \nBye");
+    }
+
+    #[test]
+    fn test_media_names() {
+        let doc = json!({
+            "type": "doc",
+            "content": [
+                {"type": "mediaSingle", "content": [
+                    {"type": "media", "attrs": {"id": "5f1c", "type": "file", "alt": "crash.png"}}
+                ]},
+                {"type": "mediaGroup", "content": [
+                    {"type": "media", "attrs": {"id": "8a2e", "type": "file", "alt": "log.txt"}},
+                    {"type": "media", "attrs": {"id": "9b3f", "type": "file"}}
+                ]}
+            ]
+        });
+        assert_eq!(media_names(&doc), ["crash.png", "log.txt"]);
+    }
+
+    #[test]
+    fn test_from_text() {
+        let doc = from_text("Laps:\n- 09:12 Crash\n- 10:40 Fix\n\nDone");
+        assert_eq!(
+            text(&render(&doc, &HashMap::new())),
+            ["Laps:", "", "• 09:12 Crash", "• 10:40 Fix", "", "Done", ""]
+        );
+    }
+
+    #[test]
+    fn test_acceptance_criteria() {
+        let doc = json!({"type": "doc", "content": [
+            {"type": "paragraph", "content": [{"type": "text", "text": "Users get logged out."}]},
+            {"type": "heading", "attrs": {"level": 2}, "content": [{"type": "text", "text": "Acceptance Criteria"}]},
+            {"type": "bulletList", "content": [
+                {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Sessions last  a day"}]}]},
+                {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Logout still works"}]}]}
+            ]},
+            {"type": "paragraph", "content": [{"type": "text", "text": "No new warnings"}]},
+            {"type": "heading", "attrs": {"level": 2}, "content": [{"type": "text", "text": "Notes"}]},
+            {"type": "paragraph", "content": [{"type": "text", "text": "See IMG-2"}]}
+        ]});
+        assert_eq!(
+            acceptance_criteria(&doc),
+            ["Sessions last a day", "Logout still works", "No new warnings"]
+        );
+        assert!(acceptance_criteria(&from_text("Just a bug")).is_empty());
+    }
+
+    #[test]
+    fn test_excerpt() {
+        let doc = json!({"type": "doc", "content": [
+            {"type": "paragraph", "content": [{"type": "text", "text": "Crashes  on"}]},
+            {"type": "paragraph", "content": [{"type": "text", "text": "login"}]},
+        ]});
+        assert_eq!(excerpt(&doc, 100), "Crashes on login");
+        assert_eq!(excerpt(&doc, 9), "Crashes…");
+    }
+}
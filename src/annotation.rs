@@ -0,0 +1,112 @@
+use ratatui::{
+    style::{Color, Stylize},
+    text::Span,
+};
+use serde::{Deserialize, Serialize};
+
+/// Colors an issue can be tagged with, in the order they are cycled through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagColor {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+    Magenta,
+}
+
+const TAG_COLORS: [TagColor; 5] = [
+    TagColor::Red,
+    TagColor::Yellow,
+    TagColor::Green,
+    TagColor::Blue,
+    TagColor::Magenta,
+];
+
+impl TagColor {
+    /// The color after `tag`, `None` after the last one to remove the tag.
+    pub fn cycle(tag: Option<TagColor>) -> Option<TagColor> {
+        match tag {
+            None => Some(TAG_COLORS[0]),
+            Some(tag) => {
+                let index = TAG_COLORS.iter().position(|color| *color == tag).unwrap();
+                TAG_COLORS.get(index + 1).copied()
+            }
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            TagColor::Red => Color::Red,
+            TagColor::Yellow => Color::Yellow,
+            TagColor::Green => Color::Green,
+            TagColor::Blue => Color::Blue,
+            TagColor::Magenta => Color::Magenta,
+        }
+    }
+}
+
+/// A color tag and note on an issue, kept locally and never sent to Jira.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(default)]
+    pub tag: Option<TagColor>,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl Annotation {
+    pub fn is_empty(&self) -> bool {
+        self.tag.is_none() && self.note.trim().is_empty()
+    }
+
+    /// Spans put in front of the title in the issue list, like `● waiting on infra `.
+    pub fn spans(&self) -> Vec<Span<'static>> {
+        let mut spans = vec![];
+        if let Some(tag) = self.tag {
+            spans.push("● ".fg(tag.color()).bold());
+        }
+        let note = self.note.trim();
+        if !note.is_empty() {
+            let note = format!("{note} ");
+            spans.push(match self.tag {
+                Some(tag) => note.fg(tag.color()).italic(),
+                None => note.dark_gray().italic(),
+            });
+        }
+        spans
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cycle() {
+        let mut tag = None;
+        let mut tags = vec![];
+        for _ in 0..6 {
+            tag = TagColor::cycle(tag);
+            tags.push(tag);
+        }
+        assert_eq!(tags.first(), Some(&Some(TagColor::Red)));
+        assert_eq!(tags.last(), Some(&None));
+    }
+
+    #[test]
+    fn test_spans() {
+        let annotation = Annotation {
+            tag: Some(TagColor::Yellow),
+            note: " waiting on infra ".to_string(),
+        };
+        let text: String = annotation.spans().iter().map(|span| span.content.to_string()).collect();
+        assert_eq!(text, "● waiting on infra ");
+        assert!(Annotation::default().spans().is_empty());
+        assert!(Annotation {
+            tag: None,
+            note: "  ".to_string()
+        }
+        .is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use ratatui::style::Color;
+
+const COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+/// Two uppercase letters standing for the name, like `JD` for Jane Doe.
+pub fn initials(name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let letters: String = match words.as_slice() {
+        [] => String::new(),
+        [word] => word.chars().take(2).collect(),
+        [first, .., last] => first.chars().take(1).chain(last.chars().take(1)).collect(),
+    };
+    letters.to_uppercase()
+}
+
+/// Background color of the badge, the same for an account on every start.
+pub fn color(account_id: &str) -> Color {
+    let mut hasher = DefaultHasher::new();
+    account_id.hash(&mut hasher);
+    COLORS[(hasher.finish() % COLORS.len() as u64) as usize]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(initials("Jane Doe"), "JD");
+        assert_eq!(initials("Jane van der Berg"), "JB");
+        assert_eq!(initials("admin"), "AD");
+        assert_eq!(initials(""), "");
+    }
+
+    #[test]
+    fn test_color() {
+        assert_eq!(color("5b10ac8d82e05b22cc7d4ef5"), color("5b10ac8d82e05b22cc7d4ef5"));
+    }
+}
@@ -0,0 +1,531 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use jiff::{tz::TimeZone, Timestamp};
+
+use crate::{
+    config::Config,
+    jira::{self, Issue, Jira, Sprint, User},
+    jql::AutocompleteData,
+    notifications::Notification,
+    overlap::{self, MIN_OVERLAP_S},
+    permissions,
+    session::Session,
+    source::{FileSource, IssueSource, SourceConfig},
+    timesheet::{self, Tempo},
+};
+
+/// How often the spinner turns, and the interface redraws while waiting for Jira.
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+const SPINNER: [&str; 8] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧"];
+
+/// Frame of the spinner for the time waited so far.
+pub fn spinner(since: Instant) -> &'static str {
+    let frames = since.elapsed().as_millis() / SPINNER_INTERVAL.as_millis();
+    SPINNER[frames as usize % SPINNER.len()]
+}
+
+/// What the issue list shows: the sprint narrowed by quick filters, or a JQL query.
+#[derive(Debug, Clone, Default)]
+pub struct IssueQuery {
+    pub jql: Option<String>,
+    /// JQL of the active quick filters, with the index of the instance whose board
+    /// they belong to.
+    pub filters: Vec<(usize, String)>,
+}
+
+impl IssueQuery {
+    /// The quick filters of the instance at `index`, the others don't apply to it.
+    fn filters_of(&self, index: usize) -> Vec<&str> {
+        self.filters
+            .iter()
+            .filter(|(instance, _)| *instance == index)
+            .map(|(_, jql)| jql.as_str())
+            .collect()
+    }
+}
+
+/// The issues of a query, with the JQL and token of the next page of results if any.
+#[derive(Debug)]
+pub struct LoadedIssues {
+    pub issues: Vec<Issue>,
+    pub next_page: Option<(String, String)>,
+    /// Whether the issues are a further page of the listed results, not a new list.
+    pub more: bool,
+}
+
+/// Sprint issues of the main source merged with those of the others, or the first
+/// page of results of the JQL query.
+pub fn fetch_issues(sources: &[&dyn IssueSource], query: &IssueQuery) -> Result<LoadedIssues> {
+    if let Some(jql) = &query.jql {
+        let jql = jira::combine_jql(jql, &query.filters_of(0));
+        let (issues, page_token) = sources[0].search(&jql, None)?;
+        return Ok(LoadedIssues {
+            issues,
+            next_page: page_token.map(|page_token| (jql, page_token)),
+            more: false,
+        });
+    }
+    let mut issues = sources[0].sprint_issues(&query.filters_of(0))?;
+    for (index, source) in sources.iter().enumerate().skip(1) {
+        let mut listed = source.sprint_issues(&query.filters_of(index)).unwrap_or_default();
+        for issue in &mut listed {
+            issue.set_instance(index);
+        }
+        issues.extend(listed);
+    }
+    Ok(LoadedIssues {
+        issues,
+        next_page: None,
+        more: false,
+    })
+}
+
+/// The page of results of the JQL query after `page_token`.
+fn fetch_page(source: &dyn IssueSource, jql: String, page_token: &str) -> Result<LoadedIssues> {
+    let (issues, page_token) = source.search(&jql, Some(page_token))?;
+    Ok(LoadedIssues {
+        issues,
+        next_page: page_token.map(|page_token| (jql, page_token)),
+        more: true,
+    })
+}
+
+#[derive(Debug)]
+enum LoadRequest {
+    Query(IssueQuery),
+    /// The next page of results of the JQL query listed, after the page token.
+    NextPage { jql: String, page_token: String },
+}
+
+/// Fetches the issue list in a background thread, so the interface is usable while
+/// Jira answers, both on startup and when refreshing.
+#[derive(Debug)]
+pub struct IssueLoader {
+    requests: Sender<(u64, LoadRequest)>,
+    results: Receiver<(u64, Result<LoadedIssues>)>,
+    /// Number of the latest query, answers to earlier ones are dropped.
+    generation: u64,
+    loading: bool,
+    pub started: Instant,
+}
+
+impl IssueLoader {
+    /// Starts the loader on the sprint issues.
+    pub fn start(config: &Config) -> IssueLoader {
+        let (requests, request_receiver) = mpsc::channel::<(u64, LoadRequest)>();
+        let (result_sender, results) = mpsc::channel();
+        let config = config.clone();
+        thread::spawn(move || {
+            let file_source = match &config.issue_source {
+                SourceConfig::File(path) => Some(FileSource::new(path.clone(), &config)),
+                SourceConfig::Jira => None,
+            };
+            let jira = Jira::from_config(&config);
+            let instances: Vec<Jira> = config.instance_configs().iter().map(Jira::from_config).collect();
+            let sources: Vec<&dyn IssueSource> = match &file_source {
+                Some(source) => vec![source],
+                None => std::iter::once(&jira)
+                    .chain(&instances)
+                    .map(|jira| jira as &dyn IssueSource)
+                    .collect(),
+            };
+            while let Ok(request) = request_receiver.recv() {
+                // Only the latest of the queries that queued up is answered.
+                let (generation, request) = request_receiver.try_iter().last().unwrap_or(request);
+                let result = match request {
+                    LoadRequest::Query(query) => fetch_issues(&sources, &query),
+                    LoadRequest::NextPage { jql, page_token } => fetch_page(sources[0], jql, &page_token),
+                };
+                if result_sender.send((generation, result)).is_err() {
+                    return;
+                }
+            }
+        });
+        let mut loader = IssueLoader {
+            requests,
+            results,
+            generation: 0,
+            loading: false,
+            started: Instant::now(),
+        };
+        loader.load(IssueQuery::default());
+        loader
+    }
+
+    /// Loads the issues of the query, replacing any query still loading.
+    pub fn load(&mut self, query: IssueQuery) {
+        if !self.loading {
+            self.started = Instant::now();
+        }
+        self.generation += 1;
+        self.loading = true;
+        let _ = self.requests.send((self.generation, LoadRequest::Query(query)));
+    }
+
+    /// Loads the next page of the listed JQL results. The page is dropped when another
+    /// query is loaded meanwhile.
+    pub fn load_page(&mut self, jql: String, page_token: String) {
+        if !self.loading {
+            self.started = Instant::now();
+        }
+        self.loading = true;
+        let _ = self.requests.send((self.generation, LoadRequest::NextPage { jql, page_token }));
+    }
+
+    pub fn loading(&self) -> bool {
+        self.loading
+    }
+
+    /// The issues of the latest query once they arrived.
+    pub fn try_take(&mut self) -> Option<Result<LoadedIssues>> {
+        let mut latest = None;
+        loop {
+            match self.results.try_recv() {
+                Ok((generation, result)) if generation == self.generation => latest = Some(result),
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) if self.loading => {
+                    latest = Some(Err(anyhow!("Loading the issues stopped")));
+                    break;
+                }
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+        if latest.is_some() {
+            self.loading = false;
+        }
+        latest
+    }
+}
+
+/// The user, the board and the time zones, fetched once on startup. What could not be
+/// fetched stays unknown.
+#[derive(Debug, Default)]
+pub struct Profile {
+    /// Active sprint of the main instance, then of the additional ones in order.
+    pub sprints: Vec<Option<Sprint>>,
+    pub myself: Option<User>,
+    /// Time zone of the main instance, then of the additional ones in order.
+    pub time_zones: Vec<Option<TimeZone>>,
+    /// The granted permissions per project.
+    pub permissions: Vec<(String, Vec<String>)>,
+}
+
+/// Fetches the profile in a background thread.
+pub fn load_profile(config: &Config) -> Receiver<Profile> {
+    let (sender, receiver) = mpsc::channel();
+    let config = config.clone();
+    thread::spawn(move || {
+        let mut jira = Jira::from_config(&config);
+        let mut instances: Vec<Jira> = config.instance_configs().iter().map(Jira::from_config).collect();
+        let time_zones = std::iter::once(&mut jira)
+            .chain(&mut instances)
+            .map(|jira| jira.sync_time_zone().ok().map(|_| jira.time_zone().clone()))
+            .collect();
+        let _ = sender.send(Profile {
+            sprints: std::iter::once(&jira)
+                .chain(&instances)
+                .map(|jira| jira.get_active_sprint().ok().flatten())
+                .collect(),
+            myself: jira.get_myself().ok(),
+            time_zones,
+            permissions: fetch_permissions(std::iter::once(&jira).chain(&instances)),
+        });
+    });
+    receiver
+}
+
+/// What I may do in the projects of the instances. Projects whose permissions could
+/// not be fetched are left out, and so not restricted.
+pub fn fetch_permissions<'a>(jiras: impl Iterator<Item = &'a Jira>) -> Vec<(String, Vec<String>)> {
+    let keys: Vec<&str> = permissions::ACTIONS.iter().map(|action| action.key()).collect();
+    let mut granted = vec![];
+    for jira in jiras {
+        for project in jira.projects() {
+            if let Ok(permissions) = jira.get_my_permissions(project, &keys) {
+                granted.push((project.clone(), permissions));
+            }
+        }
+    }
+    granted
+}
+
+/// A periodic check with Jira, answered by the poller.
+#[derive(Debug)]
+pub enum Poll {
+    /// Comments and status changes by others on the issue the timer runs for.
+    ActiveIssue { issue_key: String, since: Timestamp },
+    /// My issues moved to the status after `since`.
+    StartedIssues { status: String, since: Timestamp },
+    /// Assignments, mentions and comments concerning me since `since`, on every instance.
+    Notifications { since: Timestamp },
+    /// Field names, operators and functions to complete JQL with.
+    JqlData,
+    /// Values of `field` starting with `value`, to complete the JQL `input` with.
+    JqlValues { input: String, field: String, value: String },
+    /// Syntax and field errors in `jql`, before it becomes the source of the issue list.
+    ValidateJql { jql: String },
+}
+
+#[derive(Debug)]
+pub enum Polled {
+    ActiveIssue { issue_key: String, changes: Vec<Notification> },
+    StartedIssues(Vec<Issue>),
+    Notifications(Vec<Notification>),
+    JqlData(Option<AutocompleteData>),
+    JqlValues { input: String, values: Vec<String> },
+    ValidatedJql { jql: String, errors: Result<Vec<String>> },
+}
+
+/// Runs the periodic checks with Jira in a background thread, as background work
+/// yielding to interactive requests.
+#[derive(Debug)]
+pub struct Poller {
+    requests: Sender<Poll>,
+    results: Receiver<Polled>,
+}
+
+impl Poller {
+    pub fn start(config: &Config) -> Poller {
+        let (requests, request_receiver) = mpsc::channel::<Poll>();
+        let (result_sender, results) = mpsc::channel();
+        let config = config.clone();
+        thread::spawn(move || {
+            let jira = Jira::from_config(&config);
+            let instances: Vec<Jira> = config.instance_configs().iter().map(Jira::from_config).collect();
+            for poll in request_receiver {
+                let polled = match poll {
+                    Poll::ActiveIssue { issue_key, since } => {
+                        let jira = instances
+                            .iter()
+                            .find(|instance| instance.owns_issue(&issue_key))
+                            .unwrap_or(&jira);
+                        let changes = jira
+                            .in_background(|jira| jira.get_issue_changes(&issue_key, since))
+                            .unwrap_or_default();
+                        Polled::ActiveIssue { issue_key, changes }
+                    }
+                    Poll::StartedIssues { status, since } => Polled::StartedIssues(
+                        std::iter::once(&jira)
+                            .chain(&instances)
+                            .filter_map(|jira| jira.in_background(|jira| jira.get_started_issues(&status, since)).ok())
+                            .flatten()
+                            .collect(),
+                    ),
+                    Poll::Notifications { since } => Polled::Notifications(
+                        std::iter::once(&jira)
+                            .chain(&instances)
+                            .filter_map(|jira| jira.in_background(|jira| jira.get_notifications(since)).ok())
+                            .flatten()
+                            .collect(),
+                    ),
+                    // Asked while typing, so not yielding like the periodic checks.
+                    Poll::JqlData => Polled::JqlData(jira.get_jql_autocomplete_data().ok()),
+                    Poll::JqlValues { input, field, value } => Polled::JqlValues {
+                        input,
+                        values: jira.get_jql_value_suggestions(&field, &value).unwrap_or_default(),
+                    },
+                    Poll::ValidateJql { jql } => {
+                        let errors = jira.validate_jql(&jql);
+                        Polled::ValidatedJql { jql, errors }
+                    }
+                };
+                if result_sender.send(polled).is_err() {
+                    return;
+                }
+            }
+        });
+        Poller { requests, results }
+    }
+
+    pub fn poll(&self, poll: Poll) {
+        let _ = self.requests.send(poll);
+    }
+
+    /// The checks answered since the last call.
+    pub fn take_results(&self) -> Vec<Polled> {
+        self.results.try_iter().collect()
+    }
+}
+
+/// A worklog sent to Jira in the background, with what is needed to roll it back.
+#[derive(Debug)]
+pub struct Submission {
+    pub journal_id: u64,
+    pub session: Session,
+    /// The issue as listed before the time was added to it.
+    pub previous: Option<Issue>,
+    /// The overlaps were already confirmed, so the session is logged without checking again.
+    pub confirmed: bool,
+}
+
+/// What became of a submission.
+#[derive(Debug)]
+pub struct Submitted {
+    pub submission: Submission,
+    /// The id of the created worklog, `None` when the session was too short or held back.
+    pub result: Result<Option<String>>,
+    /// Whether the worklog was booked on its billing account.
+    pub booked: Result<()>,
+    /// The existing worklogs the session overlaps, which held it back.
+    pub overlapping: Vec<Session>,
+    /// Why the worklogs of an instance could not be checked for overlaps.
+    pub unchecked: Vec<anyhow::Error>,
+    /// The issue as Jira lists it once the worklog was added.
+    pub refreshed: Option<Issue>,
+}
+
+/// Submits worklogs one after the other in a background thread, so stopping the timer
+/// doesn't wait for Jira. Sessions overlapping worklogs already in Jira are held back
+/// for confirmation.
+#[derive(Debug)]
+pub struct Submitter {
+    requests: Sender<Submission>,
+    results: Receiver<Submitted>,
+    in_flight: usize,
+    pub started: Instant,
+}
+
+impl Submitter {
+    pub fn start(config: &Config) -> Submitter {
+        let (requests, request_receiver) = mpsc::channel::<Submission>();
+        let (result_sender, results) = mpsc::channel();
+        let config = config.clone();
+        thread::spawn(move || {
+            let mut jira = Jira::from_config(&config);
+            let mut instances: Vec<Jira> = config.instance_configs().iter().map(Jira::from_config).collect();
+            // Worklogs are dated in the time zone of the Jira profile.
+            for jira in std::iter::once(&mut jira).chain(&mut instances) {
+                let _ = jira.sync_time_zone();
+            }
+            let tempo = Tempo::from_config(&config);
+            for submission in request_receiver {
+                let session = &submission.session;
+                let (worklogs, unchecked) = if submission.confirmed {
+                    (vec![], vec![])
+                } else {
+                    overlap::my_worklogs(std::iter::once(&jira).chain(&instances), &session.started, &session.ended())
+                };
+                let overlapping: Vec<Session> = overlap::overlaps(std::slice::from_ref(session), &worklogs, MIN_OVERLAP_S)
+                    .into_iter()
+                    .map(|overlap| overlap.worklog)
+                    .collect();
+                if !overlapping.is_empty() {
+                    let held = Submitted {
+                        submission,
+                        result: Ok(None),
+                        booked: Ok(()),
+                        overlapping,
+                        unchecked,
+                        refreshed: None,
+                    };
+                    if result_sender.send(held).is_err() {
+                        return;
+                    }
+                    continue;
+                }
+                let jira = instances
+                    .iter()
+                    .find(|instance| instance.owns_issue(&session.issue_key))
+                    .unwrap_or(&jira);
+                let result = jira.log_time(
+                    &session.issue_key,
+                    &session.started,
+                    &session.ended(),
+                    session.comment.as_deref(),
+                );
+                let booked = match (&result, &session.account) {
+                    (Ok(Some(worklog_id)), Some(account)) => {
+                        timesheet::book_account(tempo.as_ref(), worklog_id, account)
+                    }
+                    _ => Ok(()),
+                };
+                let refreshed = result.is_ok().then(|| jira.get_issue(&session.issue_key).ok()).flatten();
+                let submitted = Submitted {
+                    submission,
+                    result,
+                    booked,
+                    overlapping: vec![],
+                    unchecked,
+                    refreshed,
+                };
+                if result_sender.send(submitted).is_err() {
+                    return;
+                }
+            }
+        });
+        Submitter {
+            requests,
+            results,
+            in_flight: 0,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn submit(&mut self, submission: Submission) {
+        if self.in_flight == 0 {
+            self.started = Instant::now();
+        }
+        self.in_flight += 1;
+        self.requests.send(submission).expect("The submitter stopped");
+    }
+
+    /// Number of worklogs not answered yet.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight
+    }
+
+    /// The submissions answered since the last call.
+    pub fn take_results(&mut self) -> Vec<Submitted> {
+        let results: Vec<_> = self.results.try_iter().collect();
+        self.in_flight -= results.len();
+        results
+    }
+
+    /// Waits for the answer to the next submission, `None` with none in flight.
+    pub fn wait(&mut self) -> Option<Submitted> {
+        if self.in_flight == 0 {
+            return None;
+        }
+        let result = self.results.recv().ok()?;
+        self.in_flight -= 1;
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::demo;
+
+    #[test]
+    fn test_submitter() {
+        let mut submitter = Submitter::start(&demo::config());
+        assert!(submitter.wait().is_none());
+        let session = Session {
+            issue_key: "DEMO-1".to_string(),
+            started: "2025-01-10T09:00:00+01:00[Europe/Brussels]".parse().unwrap(),
+            duration_s: 1800,
+            comment: None,
+            account: None,
+        };
+        submitter.submit(Submission {
+            journal_id: 7,
+            session,
+            previous: None,
+            confirmed: false,
+        });
+        assert_eq!(submitter.in_flight(), 1);
+        let submitted = submitter.wait().unwrap();
+        assert_eq!(submitted.submission.journal_id, 7);
+        assert!(submitted.result.is_ok() && submitted.booked.is_ok());
+        assert_eq!(submitted.refreshed.map(|issue| issue.key).as_deref(), Some("DEMO-1"));
+        assert_eq!(submitter.in_flight(), 0);
+    }
+}
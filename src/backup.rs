@@ -0,0 +1,223 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use dirs::home_dir;
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use toml::Table;
+
+/// Version of the backup format, raised when restoring needs to tell formats apart.
+const FORMAT_VERSION: u64 = 1;
+
+/// The files under `~/.local/share/jiratrack` worth keeping. The dry-run log and
+/// branch checkouts waiting to be picked up are left out.
+const DATA_FILES: [&str; 6] = [
+    "state.json",
+    "history.json",
+    "follow-ups.json",
+    "snoozes.json",
+    "journal.json",
+    "ledger.json",
+];
+
+/// Config and local data in a single file, to move to another machine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u64,
+    pub created: Timestamp,
+    /// The config file with every token blanked.
+    pub config: Option<String>,
+    /// Contents of the data files by name.
+    pub files: BTreeMap<String, Value>,
+}
+
+pub fn config_path() -> PathBuf {
+    home_dir().unwrap().join(".config/jiratrack/config.toml")
+}
+
+pub fn data_dir() -> PathBuf {
+    home_dir().unwrap().join(".local/share/jiratrack")
+}
+
+impl Backup {
+    pub fn create(config_path: &Path, data_dir: &Path) -> Result<Backup> {
+        let config = match fs::read_to_string(config_path) {
+            Ok(config) => Some(strip_tokens(&config)?),
+            Err(_) => None,
+        };
+        let mut files = BTreeMap::new();
+        for name in DATA_FILES {
+            if let Ok(contents) = fs::read_to_string(data_dir.join(name)) {
+                files.insert(name.to_string(), serde_json::from_str(&contents)?);
+            }
+        }
+        Ok(Backup {
+            version: FORMAT_VERSION,
+            created: Timestamp::now(),
+            config,
+            files,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Backup> {
+        let backup: Backup = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if backup.version > FORMAT_VERSION {
+            return Err(anyhow!("{} was made by a newer jiratrack", path.display()));
+        }
+        Ok(backup)
+    }
+
+    pub fn persist(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Writes the config and data files back and returns their paths. Existing files
+    /// are only replaced with `force`; the tokens of an existing config are kept.
+    pub fn restore(&self, config_path: &Path, data_dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+        let mut paths: Vec<PathBuf> = self.files.keys().map(|name| data_dir.join(name)).collect();
+        if self.config.is_some() {
+            paths.insert(0, config_path.to_path_buf());
+        }
+        let existing: Vec<String> = paths
+            .iter()
+            .filter(|path| path.exists())
+            .map(|path| path.display().to_string())
+            .collect();
+        if !existing.is_empty() && !force {
+            return Err(anyhow!(
+                "Not replacing {}, pass --force to replace them",
+                existing.join(", ")
+            ));
+        }
+
+        if let Some(config) = &self.config {
+            let config = match fs::read_to_string(config_path) {
+                Ok(current) => keep_tokens(config, &current)?,
+                Err(_) => config.clone(),
+            };
+            fs::create_dir_all(config_path.parent().unwrap())?;
+            fs::write(config_path, config)?;
+        }
+        fs::create_dir_all(data_dir)?;
+        for (name, contents) in &self.files {
+            if !DATA_FILES.contains(&name.as_str()) {
+                return Err(anyhow!("Unknown file {name} in the backup"));
+            }
+            fs::write(data_dir.join(name), serde_json::to_string(contents)?)?;
+        }
+        Ok(paths)
+    }
+}
+
+/// The config with the value of every key ending in `token` blanked, in the
+/// instances too.
+pub fn strip_tokens(config: &str) -> Result<String> {
+    let mut table: Table = config.parse()?;
+    visit_tokens(&mut table, "", &mut |_, token| token.clear());
+    Ok(toml::to_string(&table)?)
+}
+
+/// The restored config with the tokens of the current one filled in, those of
+/// instances matched by their URL.
+fn keep_tokens(restored: &str, current: &str) -> Result<String> {
+    let mut current: Table = current.parse()?;
+    let mut tokens: BTreeMap<String, String> = BTreeMap::new();
+    visit_tokens(&mut current, "", &mut |path, token| {
+        tokens.insert(path.to_string(), token.clone());
+    });
+    let mut table: Table = restored.parse()?;
+    visit_tokens(&mut table, "", &mut |path, token| {
+        if let Some(current) = tokens.get(path).filter(|_| token.is_empty()) {
+            *token = current.clone();
+        }
+    });
+    Ok(toml::to_string(&table)?)
+}
+
+/// Calls `f` with every token and a path naming it, tables in arrays being named by
+/// their `atlassian_url`.
+fn visit_tokens(table: &mut Table, path: &str, f: &mut impl FnMut(&str, &mut String)) {
+    let url = table
+        .get("atlassian_url")
+        .and_then(|url| url.as_str())
+        .unwrap_or_default()
+        .to_string();
+    for (key, value) in table.iter_mut() {
+        let path = format!("{path}{url}.{key}");
+        match value {
+            toml::Value::String(token) if key.ends_with("token") => f(&path, token),
+            toml::Value::Table(table) => visit_tokens(table, &path, f),
+            toml::Value::Array(values) => {
+                for value in values {
+                    if let toml::Value::Table(table) = value {
+                        visit_tokens(table, &path, f);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CONFIG: &str = r#"
+atlassian_url = "https://example.atlassian.net"
+user_email = "me@example.com"
+user_api_token = "secret"
+webhook_token = "hook-secret"
+
+[[instances]]
+atlassian_url = "https://other.atlassian.net"
+user_email = "me@other.com"
+user_api_token = "other-secret"
+projects = ["OPS"]
+"#;
+
+    #[test]
+    fn test_strip_and_keep_tokens() {
+        let stripped = strip_tokens(CONFIG).unwrap();
+        assert!(!stripped.contains("secret"));
+        assert!(stripped.contains("me@other.com"));
+
+        let current = CONFIG.replace("other-secret", "new-secret");
+        let restored = keep_tokens(&stripped, &current).unwrap();
+        let table: Table = restored.parse().unwrap();
+        assert_eq!(table["user_api_token"].as_str(), Some("secret"));
+        assert_eq!(table["webhook_token"].as_str(), Some("hook-secret"));
+        assert_eq!(table["instances"][0]["user_api_token"].as_str(), Some("new-secret"));
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = std::env::temp_dir().join(format!("jiratrack-backup-{}", std::process::id()));
+        let (config_path, data_dir) = (dir.join("config.toml"), dir.join("data"));
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(&config_path, CONFIG).unwrap();
+        fs::write(data_dir.join("history.json"), r#"{"entries": []}"#).unwrap();
+        fs::write(data_dir.join("dry-run.log"), "").unwrap();
+
+        let backup = Backup::create(&config_path, &data_dir).unwrap();
+        assert_eq!(backup.files.keys().collect::<Vec<_>>(), ["history.json"]);
+        let path = dir.join("backup.json");
+        backup.persist(&path).unwrap();
+        let backup = Backup::load(&path).unwrap();
+
+        let target = dir.join("restored");
+        let (restored_config, restored_data) = (target.join("config.toml"), target.join("data"));
+        let paths = backup.restore(&restored_config, &restored_data, false).unwrap();
+        assert_eq!(paths, [restored_config.clone(), restored_data.join("history.json")]);
+        assert!(fs::read_to_string(&restored_config).unwrap().contains("user_api_token = \"\""));
+        assert!(backup.restore(&restored_config, &restored_data, false).is_err());
+        assert!(backup.restore(&restored_config, &restored_data, true).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,61 @@
+use std::{cell::RefCell, collections::HashMap};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedResponse {
+    pub etag: String,
+    pub body: String,
+}
+
+/// JSON bodies of GET responses by URL, revalidated with `If-None-Match`.
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+    entries: RefCell<HashMap<String, CachedResponse>>,
+}
+
+impl ResponseCache {
+    /// Cache key of a request, independent of the order of its query parameters.
+    pub fn key(url: &str, params: Option<&HashMap<String, String>>) -> String {
+        let mut params: Vec<String> = params
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        params.sort();
+        format!("{url}?{}", params.join("&"))
+    }
+
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    pub fn insert(&self, key: String, etag: String, body: String) {
+        self.entries.borrow_mut().insert(key, CachedResponse { etag, body });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_key() {
+        let a = HashMap::from([
+            ("jql".to_string(), "project = CLI".to_string()),
+            ("fields".to_string(), "summary".to_string()),
+        ]);
+        let b = HashMap::from([
+            ("fields".to_string(), "summary".to_string()),
+            ("jql".to_string(), "project = CLI".to_string()),
+        ]);
+        assert_eq!(ResponseCache::key("/search", Some(&a)), ResponseCache::key("/search", Some(&b)));
+        assert_ne!(ResponseCache::key("/search", Some(&a)), ResponseCache::key("/search", None));
+    }
+
+    #[test]
+    fn test_insert() {
+        let cache = ResponseCache::default();
+        cache.insert("/myself?".to_string(), "\"1\"".to_string(), "{}".to_string());
+        assert_eq!(cache.get("/myself?").unwrap().etag, "\"1\"");
+        assert!(cache.get("/issue?").is_none());
+    }
+}
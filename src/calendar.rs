@@ -0,0 +1,201 @@
+use std::{fs, path::Path};
+
+use anyhow::Result;
+use jiff::{
+    civil::{Date, DateTime},
+    tz::TimeZone,
+    ToSpan, Zoned,
+};
+
+/// A timed event from an iCalendar file, all-day events are left out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub uid: String,
+    pub summary: String,
+    pub start: Zoned,
+    pub end: Zoned,
+}
+
+impl Event {
+    /// Identifies the occurrence, as recurring events share their uid.
+    pub fn id(&self) -> String {
+        format!("{}@{}", self.uid, self.start.timestamp())
+    }
+
+    pub fn is_ongoing(&self, now: &Zoned) -> bool {
+        self.start <= *now && *now < self.end
+    }
+}
+
+pub fn load(path: &Path) -> Result<Vec<Event>> {
+    Ok(parse(&fs::read_to_string(path)?))
+}
+
+/// Events of an iCalendar document. Recurrence rules are not expanded.
+pub fn parse(ics: &str) -> Vec<Event> {
+    properties(ics).iter().filter_map(|properties| to_event(properties)).collect()
+}
+
+/// Days covered by the all-day events of an iCalendar document, like holidays.
+pub fn all_day_dates(ics: &str) -> Vec<Date> {
+    let mut dates = vec![];
+    for properties in properties(ics) {
+        let date = |name: &str| {
+            let (_, value) = properties
+                .iter()
+                .find(|(key, _)| key.split(';').next() == Some(name) && key.contains("VALUE=DATE"))?;
+            Date::strptime("%Y%m%d", value).ok()
+        };
+        let Some(start) = date("DTSTART") else {
+            continue;
+        };
+        // The end is exclusive and defaults to the day after the start.
+        let end = date("DTEND").filter(|end| *end > start).unwrap_or(start.saturating_add(1.day()));
+        dates.extend(start.series(1.day()).take_while(|day| *day < end));
+    }
+    dates
+}
+
+/// The properties of each event, by name with their parameters.
+fn properties(ics: &str) -> Vec<Vec<(String, String)>> {
+    let mut events = vec![];
+    let mut current: Option<Vec<(String, String)>> = None;
+    for line in unfold(ics) {
+        match line.as_str() {
+            "BEGIN:VEVENT" => current = Some(vec![]),
+            "END:VEVENT" => events.extend(current.take()),
+            _ => {
+                if let (Some(properties), Some((name, value))) = (&mut current, line.split_once(':')) {
+                    properties.push((name.to_string(), value.to_string()));
+                }
+            }
+        }
+    }
+    events
+}
+
+/// Joins lines continued with leading whitespace, as long lines are folded.
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for line in ics.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn to_event(properties: &[(String, String)]) -> Option<Event> {
+    let property = |name: &str| {
+        properties.iter().find(|(key, _)| key.split(';').next() == Some(name))
+    };
+    let summary = property("SUMMARY").map(|(_, value)| unescape(value)).unwrap_or_default();
+    let uid = property("UID").map(|(_, value)| value.clone()).unwrap_or_default();
+    let (start_key, start) = property("DTSTART")?;
+    let start = parse_time(start_key, start)?;
+    let end = match property("DTEND") {
+        Some((end_key, end)) => parse_time(end_key, end)?,
+        None => start.clone(),
+    };
+    Some(Event {
+        uid,
+        summary,
+        start,
+        end,
+    })
+}
+
+/// Parses `20250110T090000Z`, `TZID=Europe/Berlin` and floating local times.
+fn parse_time(key: &str, value: &str) -> Option<Zoned> {
+    if key.contains("VALUE=DATE") && !key.contains("VALUE=DATE-TIME") {
+        return None;
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let datetime = DateTime::strptime("%Y%m%dT%H%M%S", utc).ok()?;
+        let timestamp = datetime.to_zoned(TimeZone::UTC).ok()?.timestamp();
+        return Some(timestamp.to_zoned(TimeZone::system()));
+    }
+    let datetime = DateTime::strptime("%Y%m%dT%H%M%S", value).ok()?;
+    let time_zone = key
+        .split(';')
+        .find_map(|parameter| parameter.strip_prefix("TZID="))
+        .and_then(|tzid| TimeZone::get(tzid.trim_matches('"')).ok())
+        .unwrap_or_else(TimeZone::system);
+    let zoned = datetime.to_zoned(time_zone).ok()?;
+    Some(zoned.timestamp().to_zoned(TimeZone::system()))
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+/// The event running at the given time, if any.
+pub fn ongoing<'a>(events: &'a [Event], now: &Zoned) -> Option<&'a Event> {
+    events.iter().find(|event| event.is_ongoing(now))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ICS: &str = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:standup-1\r
+SUMMARY:Daily standup\\, team\r
+DTSTART:20250110T090000Z\r
+DTEND:20250110T091500Z\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:planning\r
+SUMMARY:Sprint planning for IMG-12\r
+ 3\r
+DTSTART;TZID=Europe/Berlin:20250110T140000\r
+DTEND;TZID=Europe/Berlin:20250110T150000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Holiday\r
+DTSTART;VALUE=DATE:20250110\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_parse() {
+        let events = parse(ICS);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].summary, "Daily standup, team");
+        assert_eq!(events[0].start.timestamp(), "2025-01-10T09:00:00Z".parse().unwrap());
+        assert_eq!(events[1].summary, "Sprint planning for IMG-123");
+        assert_eq!(events[1].end.timestamp(), "2025-01-10T14:00:00Z".parse().unwrap());
+    }
+
+    #[test]
+    fn test_all_day_dates() {
+        let ics = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+SUMMARY:Christmas break\r
+DTSTART;VALUE=DATE:20251224\r
+DTEND;VALUE=DATE:20251227\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let dates: Vec<String> = all_day_dates(&format!("{ICS}{ics}")).iter().map(Date::to_string).collect();
+        assert_eq!(dates, ["2025-01-10", "2025-12-24", "2025-12-25", "2025-12-26"]);
+    }
+
+    #[test]
+    fn test_ongoing() {
+        let events = parse(ICS);
+        let during: Zoned = "2025-01-10T09:10:00+00:00[UTC]".parse().unwrap();
+        assert_eq!(ongoing(&events, &during).unwrap().uid, "standup-1");
+        let after: Zoned = "2025-01-10T09:15:00+00:00[UTC]".parse().unwrap();
+        assert!(ongoing(&events, &after).is_none());
+    }
+}
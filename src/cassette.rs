@@ -0,0 +1,410 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use dirs::home_dir;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Address recorded in place of the Jira instance.
+const EXAMPLE_URL: &str = "https://example.atlassian.net";
+
+/// Query parameters holding account ids, pseudonymized like the responses.
+const ACCOUNT_PARAMS: [&str; 2] = ["accountId", "accountIds"];
+/// Query parameters and fields holding typed text, like a JQL query or the start of
+/// a name, recorded as a pseudonym as a whole.
+const FREE_TEXT: [&str; 3] = ["jql", "query", "queries"];
+
+/// A request to Jira and its answer, without credentials or personal data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    /// The path with the query parameters sorted, like `/rest/api/3/search/jql?jql=...`.
+    pub endpoint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<Value>,
+    pub status: u16,
+    pub response: Value,
+}
+
+/// Where `JIRATRACK_RECORD` records to: `1` for the default cassette, else a path.
+pub fn record_path() -> Option<PathBuf> {
+    let value = env::var("JIRATRACK_RECORD").ok().filter(|value| !value.is_empty() && value != "0")?;
+    if value == "1" {
+        return Some(home_dir().unwrap().join(".local/share/jiratrack/cassette.jsonl"));
+    }
+    Some(PathBuf::from(value))
+}
+
+/// The endpoint as recorded, with the query parameters sorted so it matches on replay.
+pub fn endpoint(path: &str, params: Option<&HashMap<String, String>>) -> String {
+    let Some(params) = params.filter(|params| !params.is_empty()) else {
+        return path.to_string();
+    };
+    let mut params: Vec<(&String, String)> = params
+        .iter()
+        .map(|(key, value)| {
+            if ACCOUNT_PARAMS.contains(&key.as_str()) {
+                (key, value.split(',').map(account_pseudonym).collect::<Vec<_>>().join(","))
+            } else if FREE_TEXT.contains(&key.as_str()) {
+                (key, text_pseudonym(value))
+            } else {
+                (key, value.clone())
+            }
+        })
+        .collect();
+    params.sort();
+    let query: Vec<String> = params.iter().map(|(key, value)| format!("{key}={value}")).collect();
+    format!("{path}?{}", query.join("&"))
+}
+
+/// A stand-in for a personal value, the same for the same value on every run and with
+/// every build, so cassettes keep matching.
+fn pseudonym(prefix: &str, value: &str) -> String {
+    format!("{prefix}{:08x}", fnv1a(value) as u32)
+}
+
+/// The 64 bit FNV-1a hash, which unlike the hasher of the standard library is fixed.
+fn fnv1a(value: &str) -> u64 {
+    value
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3))
+}
+
+/// The pseudonym of typed text. Account ids in it are pseudonymized first, so a query
+/// built from a replayed account id matches the recorded one.
+fn text_pseudonym(text: &str) -> String {
+    let mut normalized = String::new();
+    let mut run = String::new();
+    let is_id_char = |char: char| char.is_ascii_hexdigit() || char == ':' || char == '-';
+    for char in text.chars().chain(std::iter::once(' ')) {
+        if is_id_char(char) {
+            run.push(char);
+            continue;
+        }
+        match looks_like_account_id(&run) {
+            true => normalized.push_str(&account_pseudonym(&run)),
+            false => normalized.push_str(&run),
+        }
+        run.clear();
+        normalized.push(char);
+    }
+    normalized.pop();
+    pseudonym("text-", &normalized)
+}
+
+/// The pseudonym of an account id, kept as is when replaying with one already.
+fn account_pseudonym(id: &str) -> String {
+    match id.starts_with("account-") {
+        true => id.to_string(),
+        false => pseudonym("account-", id),
+    }
+}
+
+/// Replaces names, email addresses and account ids by pseudonyms, also inside links,
+/// links to the instance by the example address, and drops avatars.
+pub fn sanitize(value: &mut Value, instance_url: &str) {
+    let mut account_ids = vec![];
+    collect_account_ids(value, &mut account_ids);
+    scrub(value, instance_url, &account_ids);
+}
+
+fn collect_account_ids(value: &Value, account_ids: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                match (key.as_str(), value.as_str()) {
+                    ("accountId", Some(id)) => account_ids.push(id.to_string()),
+                    ("id" | "authorAccountId", Some(id)) if looks_like_account_id(id) => {
+                        account_ids.push(id.to_string())
+                    }
+                    _ => collect_account_ids(value, account_ids),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_account_ids(value, account_ids)),
+        _ => {}
+    }
+}
+
+fn scrub(value: &mut Value, instance_url: &str, account_ids: &[String]) {
+    match value {
+        Value::Object(object) => {
+            object.remove("avatarUrls");
+            // Mentions in rich text carry the name of the mentioned user.
+            if object.get("type").and_then(Value::as_str) == Some("mention") {
+                if let Some(text) = object.get_mut("attrs").and_then(|attrs| attrs.get_mut("text")) {
+                    let name = text.as_str().unwrap_or_default().trim_start_matches('@');
+                    *text = Value::from(format!("@{}", pseudonym("User ", name)));
+                }
+            }
+            for (key, value) in object.iter_mut() {
+                match (key.as_str(), value.as_str()) {
+                    ("displayName", Some(name)) => *value = Value::from(pseudonym("User ", name)),
+                    ("emailAddress", Some(email)) => {
+                        *value = Value::from(format!("{}@example.com", pseudonym("user-", email)))
+                    }
+                    (key, Some(text)) if FREE_TEXT.contains(&key) => *value = Value::from(text_pseudonym(text)),
+                    (key, None) if FREE_TEXT.contains(&key) && value.is_array() => {
+                        for text in value.as_array_mut().into_iter().flatten() {
+                            if let Some(query) = text.as_str() {
+                                *text = Value::from(text_pseudonym(query));
+                            }
+                        }
+                    }
+                    _ => scrub(value, instance_url, account_ids),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| scrub(value, instance_url, account_ids)),
+        Value::String(text) => {
+            if !instance_url.is_empty() {
+                *text = text.replace(instance_url, EXAMPLE_URL);
+            }
+            for id in account_ids.iter().filter(|id| !id.is_empty()) {
+                *text = text.replace(id.as_str(), &account_pseudonym(id));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Account ids of Atlassian accounts, like `5b10ac8d82e05b22cc7d4ef5` or
+/// `712020:2c4a...`, as opposed to the numeric ids of issues and comments.
+fn looks_like_account_id(id: &str) -> bool {
+    id.len() >= 24 && id.chars().all(|char| char.is_ascii_hexdigit() || char == ':' || char == '-')
+}
+
+/// Appends every answered request to a cassette, one interaction per line.
+#[derive(Debug)]
+pub struct Recorder {
+    path: PathBuf,
+    instance_url: String,
+}
+
+impl Recorder {
+    pub fn new(path: PathBuf, instance_url: &str) -> Recorder {
+        Recorder {
+            path,
+            instance_url: instance_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn record(&self, method: &str, endpoint: &str, request: Option<&Value>, status: u16, body: &str) {
+        let mut response = serde_json::from_str(body).unwrap_or_else(|_| Value::from(body));
+        sanitize(&mut response, &self.instance_url);
+        let mut request = request.cloned();
+        if let Some(request) = &mut request {
+            sanitize(request, &self.instance_url);
+        }
+        let interaction = Interaction {
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            request,
+            status,
+            response,
+        };
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&self.path) else {
+            return;
+        };
+        let _ = writeln!(file, "{}", serde_json::to_string(&interaction).unwrap());
+    }
+}
+
+/// Recorded interactions answering the requests in place of Jira. Requests made
+/// several times get the recorded answers in order, then the last one again.
+#[derive(Debug, Default)]
+pub struct Cassette {
+    interactions: Vec<Interaction>,
+    replayed: RefCell<Vec<bool>>,
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> Result<Cassette> {
+        let text = fs::read_to_string(path).map_err(|error| anyhow!("{}: {error}", path.display()))?;
+        Cassette::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Cassette> {
+        let interactions: Vec<Interaction> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| serde_json::from_str(line).map_err(|error| anyhow!("line {}: {error}", index + 1)))
+            .collect::<Result<_>>()?;
+        Ok(Cassette {
+            replayed: RefCell::new(vec![false; interactions.len()]),
+            interactions,
+        })
+    }
+
+    pub fn replay(&self, method: &str, endpoint: &str) -> Option<&Interaction> {
+        let matching: Vec<usize> = (0..self.interactions.len())
+            .filter(|index| {
+                let interaction = &self.interactions[*index];
+                interaction.method == method && interaction.endpoint == endpoint
+            })
+            .collect();
+        let mut replayed = self.replayed.borrow_mut();
+        let index = matching
+            .iter()
+            .find(|index| !replayed[**index])
+            .or(matching.last())
+            .copied()?;
+        replayed[index] = true;
+        Some(&self.interactions[index])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{demo, jira::Jira};
+    use serde_json::json;
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+        thread,
+    };
+
+    /// A Jira answering `requests` requests: who is logged in, and a refusal for the rest.
+    fn fake_jira(requests: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let myself = json!({
+            "self": format!("{url}/rest/api/3/user?accountId=5b10ac8d82e05b22cc7d4ef5"),
+            "accountId": "5b10ac8d82e05b22cc7d4ef5",
+            "emailAddress": "jane@acme.com",
+            "displayName": "Jane Doe",
+            "timeZone": "Europe/Brussels",
+        });
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(&stream);
+                reader.read_line(&mut request_line).unwrap();
+                while reader.read_line(&mut String::new()).unwrap() > 2 {}
+                let (status, body) = match request_line.contains("/rest/api/3/myself") {
+                    true => ("200 OK", myself.to_string()),
+                    false => ("403 Forbidden", json!({"errorMessages": ["No access"]}).to_string()),
+                };
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let path = std::env::temp_dir().join(format!("jiratrack-cassette-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut config = demo::config();
+        config.demo = false;
+        config.atlassian_url = fake_jira(2);
+        config.record_path = Some(path.clone());
+        let jira = Jira::from_config(&config);
+        let recorded = jira.get_myself().unwrap();
+        assert_eq!(recorded.display_name, "Jane Doe");
+        assert!(jira.get_my_permissions("IMG", &["WORK_ON_ISSUES"]).is_err());
+
+        let cassette = fs::read_to_string(&path).unwrap();
+        assert!(!cassette.contains("Jane") && !cassette.contains("acme") && !cassette.contains("127.0.0.1"));
+
+        let mut config = demo::config();
+        config.demo = false;
+        config.atlassian_url = "http://127.0.0.1:9".to_string();
+        config.replay_path = Some(path.clone());
+        let jira = Jira::from_config(&config);
+        let replayed = jira.get_myself().unwrap();
+        assert_eq!(replayed.time_zone.as_deref(), Some("Europe/Brussels"));
+        assert!(replayed.account_id.starts_with("account-"));
+        let refusal = jira.get_my_permissions("IMG", &["WORK_ON_ISSUES"]).unwrap_err();
+        assert!(refusal.to_string().starts_with("403"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_endpoint() {
+        let params = HashMap::from([
+            ("maxResults".to_string(), "100".to_string()),
+            ("jql".to_string(), "project = IMG".to_string()),
+        ]);
+        assert_eq!(
+            endpoint("/rest/api/3/search/jql", Some(&params)),
+            format!("/rest/api/3/search/jql?jql={}&maxResults=100", text_pseudonym("project = IMG"))
+        );
+        let jql = |id: &str| HashMap::from([("jql".to_string(), format!("worklogAuthor = \"{id}\""))]);
+        let recorded = endpoint("/rest/api/3/search/jql", Some(&jql("5b10ac8d82e05b22cc7d4ef5")));
+        assert_eq!(recorded, endpoint("/rest/api/3/search/jql", Some(&jql(&account_pseudonym("5b10ac8d82e05b22cc7d4ef5")))));
+        let params = HashMap::from([("query".to_string(), "jane".to_string())]);
+        assert!(!endpoint("/rest/api/3/user/search", Some(&params)).contains("jane"));
+        assert_eq!(endpoint("/rest/api/3/myself", None), "/rest/api/3/myself");
+        let params = HashMap::from([("accountId".to_string(), "5b10ac8d82e05b22cc7d4ef5".to_string())]);
+        assert!(!endpoint("/rest/api/3/user", Some(&params)).contains("5b10ac8d"));
+    }
+
+    #[test]
+    fn test_pseudonym() {
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(pseudonym("account-", "a"), "account-8601ec8c");
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let mut body = json!({
+            "self": "https://acme.atlassian.net/rest/api/3/user?accountId=5b10ac8d82e05b22cc7d4ef5",
+            "accountId": "5b10ac8d82e05b22cc7d4ef5",
+            "emailAddress": "jane@acme.com",
+            "displayName": "Jane Doe",
+            "avatarUrls": {"48x48": "https://avatar"},
+            "issues": [{"id": "10001", "fields": {"assignee": {"displayName": "Jane Doe"}}}],
+            "comment": {"type": "doc", "content": [{"type": "paragraph", "content": [
+                {"type": "mention", "attrs": {"id": "5b10ac8d82e05b22cc7d4ef5", "text": "@Jane Doe"}},
+            ]}]},
+            "queries": ["assignee = \"Jane Doe\""],
+        });
+        sanitize(&mut body, "https://acme.atlassian.net");
+        let text = body.to_string();
+        assert!(!text.contains("Jane") && !text.contains("acme") && !text.contains("avatar"));
+        assert!(!text.contains("5b10ac8d"));
+        assert_eq!(body["displayName"], body["issues"][0]["fields"]["assignee"]["displayName"]);
+        let mention = &body["comment"]["content"][0]["content"][0]["attrs"]["text"];
+        assert_eq!(mention.as_str().unwrap(), format!("@{}", body["displayName"].as_str().unwrap()));
+        assert_eq!(body["issues"][0]["id"], "10001");
+    }
+
+    #[test]
+    fn test_replay() {
+        let line = |status: u16, total: u64| {
+            serde_json::to_string(&Interaction {
+                method: "GET".to_string(),
+                endpoint: "/rest/api/3/issue/IMG-1".to_string(),
+                request: None,
+                status,
+                response: json!({ "total": total }),
+            })
+            .unwrap()
+        };
+        let cassette = Cassette::parse(&format!("{}\n{}\n", line(200, 1), line(200, 2))).unwrap();
+        let total = |cassette: &Cassette| cassette.replay("GET", "/rest/api/3/issue/IMG-1").unwrap().response["total"].clone();
+        assert_eq!(total(&cassette), 1);
+        assert_eq!(total(&cassette), 2);
+        assert_eq!(total(&cassette), 2);
+        assert!(cassette.replay("PUT", "/rest/api/3/issue/IMG-1").is_none());
+    }
+}
@@ -0,0 +1,153 @@
+use ratatui::layout::Constraint;
+use serde::{Deserialize, Serialize};
+
+/// Fields every issue in the list is fetched with, the assignee is needed to
+/// list my issues first.
+const BASE_FIELDS: [&str; 5] = ["id", "key", "summary", "status", "assignee"];
+
+/// Features of the issue list that need fields beyond those of the columns.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// Offering to log to the subtasks of an issue.
+    Subtasks,
+    /// Filtering by label and component in the filter menu.
+    Filters,
+    /// Epics of the issues in the statistics.
+    Epics,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 3] = [Feature::Subtasks, Feature::Filters, Feature::Epics];
+
+    fn fields(&self) -> &'static [&'static str] {
+        match self {
+            Feature::Subtasks => &["subtasks"],
+            Feature::Filters => &["labels", "components"],
+            Feature::Epics => &["parent"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Project,
+    Key,
+    TimeSpent,
+    /// Time tracked locally today, including the running timer.
+    Today,
+    Assignee,
+    /// The status, colored by `status_colors`.
+    Status,
+    /// The assignee as a colored badge with their initials.
+    AssigneeInitials,
+    Title,
+    /// Filled by the entry of `script_columns` at the index.
+    #[serde(skip)]
+    Script { index: usize, width: u16 },
+}
+
+impl Column {
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Project => "Project",
+            Column::Key => "Key",
+            Column::TimeSpent => "Time Spent",
+            Column::Today => "Today",
+            Column::Assignee => "Assignee",
+            Column::Status => "Status",
+            Column::AssigneeInitials => "Who",
+            Column::Title => "Title",
+            Column::Script { .. } => "",
+        }
+    }
+
+    pub fn constraint(&self) -> Constraint {
+        match self {
+            Column::Project => Constraint::Length(8),
+            Column::Key => Constraint::Length(10),
+            Column::TimeSpent => Constraint::Length(12),
+            Column::Today => Constraint::Length(8),
+            Column::Assignee => Constraint::Length(20),
+            Column::Status => Constraint::Length(14),
+            Column::AssigneeInitials => Constraint::Length(4),
+            Column::Title => Constraint::Min(20),
+            Column::Script { width, .. } => Constraint::Length(*width),
+        }
+    }
+
+    /// The Jira fields needed to fill the column, beyond the base fields.
+    fn fields(&self) -> &'static [&'static str] {
+        match self {
+            Column::TimeSpent => &["timetracking", "aggregatetimespent"],
+            Column::Project
+            | Column::Key
+            | Column::Today
+            | Column::Assignee
+            | Column::Status
+            | Column::AssigneeInitials
+            | Column::Title
+            | Column::Script { .. } => &[],
+        }
+    }
+}
+
+/// The character a title too long for `width` columns continues from on the second
+/// line: after the last space that fits, or mid-word without one. `None` when it fits.
+pub fn wrap_point(title: &str, width: usize) -> Option<usize> {
+    let chars: Vec<char> = title.chars().collect();
+    if chars.len() <= width {
+        return None;
+    }
+    match chars[..width].iter().rposition(|char| *char == ' ') {
+        Some(space) if space > 0 => Some(space + 1),
+        _ => Some(width),
+    }
+}
+
+/// The `fields` parameter needed to show the given columns and features, in rows of
+/// `row_height` lines. The second line of taller rows shows the epic and labels.
+pub fn fields(columns: &[Column], features: &[Feature], row_height: u16) -> String {
+    let mut fields: Vec<&str> = BASE_FIELDS.to_vec();
+    let second_line: &[&str] = if row_height > 1 { &["parent", "labels"] } else { &[] };
+    let needed = columns
+        .iter()
+        .flat_map(Column::fields)
+        .chain(features.iter().flat_map(Feature::fields))
+        .chain(second_line);
+    for field in needed {
+        if !fields.contains(field) {
+            fields.push(field);
+        }
+    }
+    fields.join(",")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_wrap_point() {
+        assert_eq!(wrap_point("Fix login", 20), None);
+        let title = "Fix crash when the network drops";
+        assert_eq!(wrap_point(title, 16), Some(15));
+        assert_eq!(&title[15..], "the network drops");
+        assert_eq!(wrap_point("Internationalization", 8), Some(8));
+    }
+
+    #[test]
+    fn test_fields() {
+        assert_eq!(fields(&[Column::Key, Column::Title], &[], 1), "id,key,summary,status,assignee");
+        assert_eq!(
+            fields(&[Column::TimeSpent, Column::Key, Column::TimeSpent], &[], 1),
+            "id,key,summary,status,assignee,timetracking,aggregatetimespent"
+        );
+        assert_eq!(
+            fields(&[Column::Key], &Feature::ALL, 2),
+            "id,key,summary,status,assignee,subtasks,labels,components,parent"
+        );
+        assert_eq!(fields(&[Column::Key], &[Feature::Epics], 2), "id,key,summary,status,assignee,parent,labels");
+    }
+}
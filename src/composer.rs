@@ -0,0 +1,224 @@
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::jira::User;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Text(String),
+    Mention { account_id: String, name: String },
+}
+
+/// How long typing has to pause before users are searched for the mention.
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Text input for new comments, keeping mentions as separate segments.
+#[derive(Debug, Default)]
+pub struct Composer {
+    pub segments: Vec<Segment>,
+    pub suggestions: Vec<User>,
+    pub selected: usize,
+    /// When the mention query last changed, while its suggestions are not fetched yet.
+    typed_on: Option<Instant>,
+}
+
+impl Composer {
+    pub fn push_char(&mut self, new_char: char) {
+        match self.segments.last_mut() {
+            Some(Segment::Text(text)) => text.push(new_char),
+            _ => self.segments.push(Segment::Text(new_char.to_string())),
+        }
+    }
+
+    pub fn delete_char(&mut self) {
+        match self.segments.last_mut() {
+            Some(Segment::Text(text)) => {
+                text.pop();
+                if text.is_empty() {
+                    self.segments.pop();
+                }
+            }
+            Some(Segment::Mention { .. }) => {
+                self.segments.pop();
+            }
+            None => {}
+        }
+    }
+
+    /// The partial name typed after an `@`, if a mention is being written.
+    pub fn mention_query(&self) -> Option<&str> {
+        let Some(Segment::Text(text)) = self.segments.last() else {
+            return None;
+        };
+        let (before, query) = text.rsplit_once('@')?;
+        let starts_word = before.is_empty() || before.ends_with(char::is_whitespace);
+        if !starts_word || query.contains(char::is_whitespace) {
+            return None;
+        }
+        Some(query)
+    }
+
+    /// Notes a keystroke: suggestions are fetched once typing pauses, and dropped when
+    /// no mention is being written anymore.
+    pub fn typed(&mut self) {
+        match self.mention_query() {
+            Some(_) => self.typed_on = Some(Instant::now()),
+            None => {
+                self.typed_on = None;
+                self.suggestions.clear();
+            }
+        }
+    }
+
+    /// Whether a changed mention query waits for the debounce delay to pass.
+    pub fn is_pending(&self) -> bool {
+        self.typed_on.is_some()
+    }
+
+    /// The mention query to fetch suggestions for, once typing paused for [`DEBOUNCE`].
+    pub fn due_query(&mut self) -> Option<String> {
+        if self.typed_on?.elapsed() < DEBOUNCE {
+            return None;
+        }
+        self.typed_on = None;
+        self.mention_query()
+            .filter(|query| !query.is_empty())
+            .map(|query| query.to_string())
+    }
+
+    /// Replaces the typed `@query` by a mention of the selected suggestion.
+    pub fn accept_suggestion(&mut self) -> bool {
+        let Some(user) = self.suggestions.get(self.selected).cloned() else {
+            return false;
+        };
+        if let Some(Segment::Text(text)) = self.segments.last_mut() {
+            if let Some(index) = text.rfind('@') {
+                text.truncate(index);
+                if text.is_empty() {
+                    self.segments.pop();
+                }
+            }
+        }
+        self.segments.push(Segment::Mention {
+            account_id: user.account_id,
+            name: user.display_name,
+        });
+        self.segments.push(Segment::Text(" ".to_string()));
+        self.suggestions.clear();
+        self.selected = 0;
+        true
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.suggestions.is_empty() {
+            self.selected = (self.selected + 1) % self.suggestions.len();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.suggestions.is_empty() {
+            self.selected = (self.selected + self.suggestions.len() - 1) % self.suggestions.len();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text().trim().is_empty()
+    }
+
+    /// The comment as displayed in the input, with mentions as `@Name`.
+    pub fn text(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.clone(),
+                Segment::Mention { name, .. } => format!("@{name}"),
+            })
+            .collect()
+    }
+
+    /// The comment as an Atlassian Document Format document.
+    pub fn to_adf(&self) -> Value {
+        let content: Vec<Value> = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => json!({"type": "text", "text": text}),
+                Segment::Mention { account_id, name } => json!({
+                    "type": "mention",
+                    "attrs": {"id": account_id, "text": format!("@{name}")}
+                }),
+            })
+            .collect();
+        json!({
+            "type": "doc",
+            "version": 1,
+            "content": [{"type": "paragraph", "content": content}]
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn type_text(composer: &mut Composer, text: &str) {
+        for c in text.chars() {
+            composer.push_char(c);
+        }
+    }
+
+    #[test]
+    fn test_mention_query() {
+        let mut composer = Composer::default();
+        type_text(&mut composer, "ping @sa");
+        assert_eq!(composer.mention_query(), Some("sa"));
+        type_text(&mut composer, "m done");
+        assert_eq!(composer.mention_query(), None);
+
+        let mut email = Composer::default();
+        type_text(&mut email, "mail sam@example");
+        assert_eq!(email.mention_query(), None);
+    }
+
+    #[test]
+    fn test_due_query() {
+        let mut composer = Composer::default();
+        type_text(&mut composer, "ping @sa");
+        composer.typed();
+        assert!(composer.is_pending());
+        assert_eq!(composer.due_query(), None);
+        composer.typed_on = composer.typed_on.map(|typed_on| typed_on - DEBOUNCE);
+        assert_eq!(composer.due_query().as_deref(), Some("sa"));
+        assert!(!composer.is_pending());
+
+        type_text(&mut composer, " ");
+        composer.typed();
+        assert!(!composer.is_pending());
+    }
+
+    #[test]
+    fn test_accept_suggestion_and_adf() {
+        let mut composer = Composer::default();
+        type_text(&mut composer, "ping @sa");
+        composer.suggestions = vec![User {
+            account_id: "abc".to_string(),
+            display_name: "Sam".to_string(),
+            time_zone: None,
+            locale: None,
+        }];
+        assert!(composer.accept_suggestion());
+        type_text(&mut composer, "please");
+        assert_eq!(composer.text(), "ping @Sam please");
+        assert_eq!(
+            composer.to_adf()["content"][0]["content"],
+            json!([
+                {"type": "text", "text": "ping "},
+                {"type": "mention", "attrs": {"id": "abc", "text": "@Sam"}},
+                {"type": "text", "text": " please"}
+            ])
+        );
+        composer.delete_char();
+        assert_eq!(composer.text(), "ping @Sam pleas");
+    }
+}
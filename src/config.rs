@@ -1,13 +1,298 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::{collections::HashMap, fs, path::PathBuf};
 use anyhow::Result;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::column::{Column, Feature};
+use crate::holidays::DaysOff;
+use crate::duration::WorkTime;
+use crate::reminder::WorkHours;
+use crate::locale::{Clock, Lang, Locale, WeekStart};
+use crate::import::CalendarRule;
+use crate::rules::Rule;
+use crate::scripting::{ScriptAction, ScriptColumn};
+use crate::source::SourceConfig;
+use crate::statuscolor::ConfigColor;
+use crate::theme::Theme;
+
+/// An additional Atlassian instance whose issues are merged into the list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceConfig {
+    pub atlassian_url: String,
+    pub user_email: String,
+    pub user_api_token: String,
+    pub projects: Vec<String>,
+    /// Query of the issue list of this instance, the open sprints of its projects unset.
+    #[serde(default)]
+    pub jql: Option<String>,
+}
+
+/// A JQL query from the config, cycled through with <A-q>.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedQuery {
+    pub name: String,
+    pub jql: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
     pub atlassian_url: String,
     pub user_email: String,
     pub user_api_token: String,
+    #[serde(default)]
     pub project: String,
+    #[serde(default)]
+    pub projects: Vec<String>,
+    pub board_id: Option<u64>,
+    pub story_points_field: Option<String>,
+    /// Query of the issue list, the open sprints of the projects while unset.
+    pub jql: Option<String>,
+    /// Queries cycled through with <A-q>, before returning to `jql`.
+    #[serde(default)]
+    pub queries: Vec<NamedQuery>,
+    #[serde(default)]
+    pub instances: Vec<InstanceConfig>,
+    /// Where the issue list comes from, Jira unless set.
+    #[serde(default)]
+    pub issue_source: SourceConfig,
+    #[serde(default = "default_hours_per_day")]
+    pub hours_per_day: f64,
+    #[serde(default = "default_days_per_week")]
+    pub days_per_week: f64,
+    pub timezone: Option<String>,
+    pub log_as_account_id: Option<String>,
+    /// Tempo work attribute the billing account is booked in, `_Account_` for Tempo
+    /// accounts. Takes `tempo_api_token`, worklogs carry no account while unset.
+    pub billing_attribute: Option<String>,
+    /// Billing account per project key, used unless another one is entered.
+    #[serde(default)]
+    pub billing_accounts: HashMap<String, String>,
+    /// Ask for the billing account of every stopped session.
+    #[serde(default)]
+    pub ask_billing_account: bool,
+    /// Token of the Tempo API, enables the approval of timesheets.
+    pub tempo_api_token: Option<String>,
+    /// Account id of the reviewer weeks are submitted to, Tempo picks one while unset.
+    pub tempo_reviewer_account_id: Option<String>,
+    /// WebDAV folder or endpoint answering GET and PUT that the timer and submitted
+    /// worklogs are synced through, e.g. `https://cloud.example.com/remote.php/dav/files/me/jiratrack`.
+    pub sync_url: Option<String>,
+    /// User for basic authentication at the sync backend, the token is sent as a
+    /// bearer token without one.
+    pub sync_user: Option<String>,
+    pub sync_token: Option<String>,
+    #[serde(default)]
+    pub review_mode: bool,
+    #[serde(default = "default_round_to_minutes")]
+    pub round_to_minutes: u64,
+    pub merge_window_minutes: Option<u64>,
+    pub max_worklog_hours: Option<f64>,
+    pub work_hours: Option<WorkHours>,
+    #[serde(default = "default_reminder_after_minutes")]
+    pub reminder_after_minutes: u64,
+    #[serde(default)]
+    pub desktop_notifications: bool,
+    #[serde(default = "default_max_clock_skew_s")]
+    pub max_clock_skew_s: i64,
+    /// Minutes between checks for new notifications, 0 disables them.
+    #[serde(default = "default_notification_poll_minutes")]
+    pub notification_poll_minutes: u64,
+    /// Minutes between checks for changes by others on the issue the timer runs for,
+    /// 0 disables them.
+    #[serde(default = "default_active_issue_poll_minutes")]
+    pub active_issue_poll_minutes: u64,
+    /// Status that an issue assigned to the user is moved to elsewhere, like on the web
+    /// board, to be offered to start the timer on it. Unset never offers it.
+    pub auto_start_status: Option<String>,
+    /// Minutes after which the issue list is marked as outdated, 0 never marks it.
+    #[serde(default = "default_stale_after_minutes")]
+    pub stale_after_minutes: u64,
+    /// Number of submitted worklogs listed in the receipts popup.
+    #[serde(default = "default_receipt_count")]
+    pub receipt_count: usize,
+    /// Port on which to receive Jira webhooks for live updates.
+    pub webhook_port: Option<u16>,
+    /// Address the webhook listener binds, `127.0.0.1` unless set to e.g. `0.0.0.0`.
+    pub webhook_address: Option<String>,
+    /// Token webhooks have to carry, the listener doesn't start without one.
+    pub webhook_token: Option<String>,
+    /// Columns of the issue list, only the fields they need are fetched.
+    pub columns: Option<Vec<Column>>,
+    /// Features of the issue list needing fields beyond the columns, all unless set.
+    pub features: Option<Vec<Feature>>,
+    /// Colors of the status column by status name or category key (`new`,
+    /// `indeterminate`, `done`), a color-blind-safe palette fills in the rest.
+    #[serde(default)]
+    pub status_colors: HashMap<String, ConfigColor>,
+    /// Lines per issue in the list, `2` wraps long titles or shows the epic and labels.
+    #[serde(default = "default_row_height")]
+    pub row_height: u16,
+    /// Submit the running timer when quitting with Ctrl-C or a termination signal,
+    /// instead of keeping it running for the next start.
+    #[serde(default)]
+    pub submit_on_exit: bool,
+    /// Hour of the day from which quitting first shows a summary of the day, 24 never shows it.
+    #[serde(default = "default_end_of_day_hour")]
+    pub end_of_day_hour: i8,
+    /// iCalendar file checked for meetings while no timer runs.
+    pub calendar_path: Option<PathBuf>,
+    /// Days off left out of the targets, like `2025-12-24` or `2025-12-24..2025-12-31`.
+    #[serde(default)]
+    pub holidays: Vec<DaysOff>,
+    /// iCalendar file whose all-day events are days off too.
+    pub holidays_path: Option<PathBuf>,
+    /// Issue that meetings are logged to.
+    pub meeting_issue: Option<String>,
+    /// Issues that imported calendar events are logged to, by title.
+    #[serde(default)]
+    pub calendar_rules: Vec<CalendarRule>,
+    /// Automation run when the timer starts, time is logged or an issue changes status.
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    /// Command whose output names the issue being worked on, e.g. the focused tmux window.
+    pub focus_command: Option<String>,
+    /// Rhai script defining the functions of the script columns and actions.
+    pub script_path: Option<PathBuf>,
+    /// Columns shown before the title, filled by script functions.
+    #[serde(default)]
+    pub script_columns: Vec<ScriptColumn>,
+    /// Script functions that can be run on the selected issue.
+    #[serde(default)]
+    pub script_actions: Vec<ScriptAction>,
+    /// How long another issue has to stay in focus before switching to it is suggested.
+    #[serde(default = "default_focus_switch_seconds")]
+    pub focus_switch_seconds: u64,
+    /// Switch the timer without asking when the git hook reports a branch of another issue.
+    #[serde(default)]
+    pub checkout_switch: bool,
+    /// List the issues assigned to you before everyone else's.
+    #[serde(default)]
+    pub my_issues_first: bool,
+    /// List only the issues assigned to you.
+    #[serde(default)]
+    pub only_my_issues: bool,
+    /// Status of the issues in the review queue.
+    #[serde(default = "default_review_status")]
+    pub review_status: String,
+    /// Field naming the reviewer of an issue, by name or as `cf[10050]`.
+    #[serde(default = "default_reviewer_field")]
+    pub reviewer_field: String,
+    /// Commit message copied with <C-g>, with `{key}`, `{summary}` and `{project}` placeholders.
+    #[serde(default = "default_commit_template")]
+    pub commit_template: String,
+    /// Merge request description copied with <A-y>, with `{key}`, `{summary}`, `{project}`,
+    /// `{link}` and `{criteria}` (the acceptance criteria of the description) placeholders.
+    #[serde(default = "default_mr_description_template")]
+    pub mr_description_template: String,
+    /// Summary of issues cloned with <A-c>, with `{key}`, `{summary}` and `{project}` placeholders.
+    #[serde(default = "default_clone_template")]
+    pub clone_template: String,
+    /// Comment of worklogs without an explicit one, with `{key}`, `{summary}`, `{project}`,
+    /// `{notes}` and `{branch}` placeholders.
+    pub worklog_comment_template: Option<String>,
+    /// Ask for the comment of every stopped session, filled in with the template.
+    #[serde(default = "default_ask_worklog_comment")]
+    pub ask_worklog_comment: bool,
+    /// Statuses that are closed besides the ones in the done category, e.g. `Archived`.
+    #[serde(default)]
+    pub closed_statuses: Vec<String>,
+    /// Refuse to start the timer on a closed issue instead of asking first.
+    #[serde(default)]
+    pub refuse_closed_issues: bool,
+    /// Language of the labels.
+    pub lang: Option<Lang>,
+    /// Whether times are shown with a `24h` or `12h` clock.
+    pub clock: Option<Clock>,
+    pub week_start: Option<WeekStart>,
+    /// Time zone and formats of the Jira profile, used where the settings above are not set.
+    #[serde(skip)]
+    pub profile_locale: Locale,
+    /// Color theme, `--no-color` and the `NO_COLOR` variable select `no_color`.
+    #[serde(default)]
+    pub theme: Theme,
+    /// Log writes to Jira instead of sending them, also set by `--dry-run`.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Serve bundled fixtures instead of talking to Jira, set by `--demo`.
+    #[serde(skip)]
+    pub demo: bool,
+    /// Cassette the requests to Jira are recorded to, set by `JIRATRACK_RECORD`.
+    #[serde(skip)]
+    pub record_path: Option<PathBuf>,
+    /// Cassette answering the requests in place of Jira, set by `JIRATRACK_REPLAY`.
+    #[serde(skip)]
+    pub replay_path: Option<PathBuf>,
+}
+
+fn default_hours_per_day() -> f64 {
+    WorkTime::default().hours_per_day
+}
+
+fn default_days_per_week() -> f64 {
+    WorkTime::default().days_per_week
+}
+
+fn default_max_clock_skew_s() -> i64 {
+    60
+}
+
+fn default_round_to_minutes() -> u64 {
+    15
+}
+
+fn default_reminder_after_minutes() -> u64 {
+    15
+}
+
+fn default_focus_switch_seconds() -> u64 {
+    60
+}
+
+fn default_ask_worklog_comment() -> bool {
+    true
+}
+
+fn default_review_status() -> String {
+    "In Review".to_string()
+}
+
+fn default_reviewer_field() -> String {
+    "Reviewer".to_string()
+}
+
+fn default_commit_template() -> String {
+    "feat({key}): {summary}".to_string()
+}
+
+fn default_mr_description_template() -> String {
+    "## [{key}] {summary}\n\nJira: {link}\n\n### Acceptance criteria\n\n{criteria}\n".to_string()
+}
+
+fn default_clone_template() -> String {
+    "CLONE - {summary}".to_string()
+}
+
+fn default_notification_poll_minutes() -> u64 {
+    5
+}
+
+fn default_active_issue_poll_minutes() -> u64 {
+    1
+}
+
+fn default_stale_after_minutes() -> u64 {
+    15
+}
+
+fn default_row_height() -> u16 {
+    1
+}
+
+fn default_end_of_day_hour() -> i8 {
+    17
+}
+
+fn default_receipt_count() -> usize {
+    20
 }
 
 impl Config {
@@ -23,4 +308,61 @@ impl Config {
         let config = toml::from_str::<Config>(&config)?;
         Ok(config)
     }
+
+    /// All configured projects, combining `project` and `projects`.
+    pub fn projects(&self) -> Vec<String> {
+        let mut projects = self.projects.clone();
+        if !self.project.is_empty() && !projects.contains(&self.project) {
+            projects.insert(0, self.project.clone());
+        }
+        projects
+    }
+
+    /// Configs of the additional instances, inheriting all other settings but the
+    /// account logged as, which belongs to the main instance.
+    pub fn instance_configs(&self) -> Vec<Config> {
+        self.instances
+            .iter()
+            .map(|instance| Config {
+                atlassian_url: instance.atlassian_url.clone(),
+                user_email: instance.user_email.clone(),
+                user_api_token: instance.user_api_token.clone(),
+                project: String::new(),
+                projects: instance.projects.clone(),
+                board_id: None,
+                jql: instance.jql.clone(),
+                log_as_account_id: None,
+                instances: vec![],
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    /// The billing account for worklogs on the issue, if billing is enabled.
+    pub fn billing_account(&self, issue_key: &str) -> Option<String> {
+        self.billing_attribute.as_ref()?;
+        let project = issue_key.split('-').next().unwrap_or("");
+        self.billing_accounts.get(project).cloned()
+    }
+
+    pub fn locale(&self) -> Locale {
+        Locale {
+            lang: self.lang.unwrap_or(self.profile_locale.lang),
+            clock: self.clock.unwrap_or(self.profile_locale.clock),
+            week_start: self.week_start.unwrap_or(self.profile_locale.week_start),
+            time_zone: self.profile_locale.time_zone.clone(),
+        }
+    }
+
+    /// The enabled features of the issue list.
+    pub fn features(&self) -> Vec<Feature> {
+        self.features.clone().unwrap_or_else(|| Feature::ALL.to_vec())
+    }
+
+    pub fn work_time(&self) -> WorkTime {
+        WorkTime {
+            hours_per_day: self.hours_per_day,
+            days_per_week: self.days_per_week,
+        }
+    }
 }
@@ -0,0 +1,106 @@
+use std::sync::OnceLock;
+
+use serde_json::{json, Value};
+
+use crate::{config::Config, permissions};
+
+const FIXTURES: &str = include_str!("../fixtures/demo.json");
+
+const CONFIG: &str = r#"
+atlassian_url = "https://demo.atlassian.net"
+user_email = "demo@example.com"
+user_api_token = ""
+projects = ["DEMO"]
+board_id = 1
+story_points_field = "customfield_10016"
+
+[[queries]]
+name = "Mine"
+jql = "project = DEMO AND assignee = currentUser() ORDER BY updated DESC"
+"#;
+
+/// Configuration used with `--demo`, which needs no config file or credentials.
+pub fn config() -> Config {
+    let mut config: Config = toml::from_str(CONFIG).unwrap();
+    config.demo = true;
+    config
+}
+
+fn fixtures() -> &'static Value {
+    static FIXTURES_JSON: OnceLock<Value> = OnceLock::new();
+    FIXTURES_JSON.get_or_init(|| serde_json::from_str(FIXTURES).unwrap())
+}
+
+/// The body Jira would answer a request with, served from the bundled fixtures.
+/// Writes are accepted without any effect.
+pub fn response(method: &str, endpoint: &str) -> Value {
+    let fixtures = fixtures();
+    let segments: Vec<&str> = endpoint.trim_start_matches('/').split('/').skip(1).collect();
+    let issues = fixtures["issues"].as_array().unwrap();
+    match (method, segments.as_slice()) {
+        ("GET", ["api", "3", "myself"]) => fixtures["myself"].clone(),
+        ("GET", ["api", "3", "user", "search"]) => fixtures["users"].clone(),
+        ("GET", ["api", "3", "filter", "favourite"]) => fixtures["filters"].clone(),
+        ("GET", ["api", "3", "mypermissions"]) => {
+            let granted: serde_json::Map<String, Value> = permissions::ACTIONS
+                .iter()
+                .map(|action| (action.key().to_string(), json!({"havePermission": true})))
+                .collect();
+            json!({"permissions": granted})
+        }
+        ("GET", ["api", "3", "search", "jql"]) => {
+            let open: Vec<&Value> = issues
+                .iter()
+                .filter(|issue| issue["fields"]["status"]["statusCategory"]["key"] != "done")
+                .collect();
+            json!({"issues": open})
+        }
+        ("GET", ["agile", "1.0", "sprint", _, "issue"]) => json!({"issues": issues}),
+        ("GET", ["agile", "1.0", "board", _, "sprint"]) => json!({"values": [fixtures["sprint"]]}),
+        ("GET", ["agile", "1.0", "board", _, "quickfilter"]) => {
+            json!({"values": fixtures["quick_filters"]})
+        }
+        ("GET", ["api", "3", "issue", key, "comment"]) => {
+            let comments = fixtures["comments"][*key].as_array().cloned().unwrap_or_default();
+            json!({"total": comments.len(), "comments": comments})
+        }
+        ("GET", ["api", "3", "issue", key]) => issues
+            .iter()
+            .chain(issues.iter().flat_map(|issue| issue["fields"]["subtasks"].as_array().into_iter().flatten()))
+            .find(|issue| issue["key"] == *key)
+            .cloned()
+            .unwrap_or(Value::Null),
+        ("GET", ["api", "3", "issue", key, "worklog"]) => {
+            json!({"worklogs": fixtures["worklogs"][*key].as_array().cloned().unwrap_or_default()})
+        }
+        ("POST", ["api", "3", "issue"]) => json!({"id": "10099", "key": "DEMO-99"}),
+        (_, [.., "worklog"]) | (_, [.., "worklog", _]) => json!({"id": "demo"}),
+        _ => json!({}),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_response() {
+        let open = response("GET", "/rest/api/3/search/jql");
+        assert_eq!(open["issues"].as_array().unwrap().len(), 5);
+        let all = response("GET", "/rest/agile/1.0/sprint/1/issue");
+        assert_eq!(all["issues"].as_array().unwrap().len(), 6);
+        assert_eq!(response("GET", "/rest/api/3/issue/DEMO-2")["key"], "DEMO-2");
+        assert_eq!(response("GET", "/rest/api/3/issue/DEMO-8")["key"], "DEMO-8");
+        assert_eq!(response("GET", "/rest/api/3/issue/DEMO-1/comment")["total"], 2);
+        assert_eq!(response("GET", "/rest/api/3/issue/DEMO-1/worklog")["worklogs"][0]["timeSpent"], "2h");
+        assert_eq!(response("POST", "/rest/api/3/issue/DEMO-1/worklog")["id"], "demo");
+    }
+
+    #[test]
+    fn test_config() {
+        let config = config();
+        assert!(config.demo);
+        assert_eq!(config.projects(), ["DEMO"]);
+        assert_eq!(config.work_time().hours_per_day, 8.0);
+    }
+}
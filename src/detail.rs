@@ -0,0 +1,476 @@
+use std::{collections::HashMap, fmt};
+
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Wrap},
+    Frame,
+};
+use ratatui_image::{protocol::StatefulProtocol, StatefulImage};
+use serde_json::Value;
+
+use crate::{
+    activity::{self, Event, EventKind},
+    adf,
+    jira::{Attachment, Comment, Issue},
+    statustime,
+};
+
+pub const COMMENT_PAGE_SIZE: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetailTab {
+    Description,
+    Comments,
+    Attachments,
+    Activity,
+}
+
+/// Image preview in a terminal graphics protocol, or `None` when unsupported.
+pub struct ImagePreview(pub Option<StatefulProtocol>);
+
+impl fmt::Debug for ImagePreview {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ImagePreview")
+    }
+}
+
+/// Full screen view of a single issue.
+#[derive(Debug)]
+pub struct DetailView {
+    pub issue: Issue,
+    pub description: Value,
+    pub comments: Vec<Comment>,
+    pub comments_total: usize,
+    pub attachments: Vec<Attachment>,
+    /// Time spent in each status, like `In Review: 3d 4h`.
+    pub status_times: Vec<(String, u64)>,
+    /// Status changes and worklogs, shown in the activity tab between the comments.
+    pub activity: Vec<Event>,
+    pub selected_attachment: usize,
+    pub preview: Option<ImagePreview>,
+    pub names: HashMap<String, String>,
+    pub tab: DetailTab,
+    pub scroll: u16,
+}
+
+impl DetailView {
+    pub fn new(issue: Issue, description: Value, names: HashMap<String, String>) -> Self {
+        DetailView {
+            issue,
+            description,
+            comments: vec![],
+            comments_total: 0,
+            attachments: vec![],
+            status_times: vec![],
+            activity: vec![],
+            selected_attachment: 0,
+            preview: None,
+            names,
+            tab: DetailTab::Description,
+            scroll: 0,
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        self.tab = match self.tab {
+            DetailTab::Description => DetailTab::Comments,
+            DetailTab::Comments => DetailTab::Attachments,
+            DetailTab::Attachments => DetailTab::Activity,
+            DetailTab::Activity => DetailTab::Description,
+        };
+        self.scroll = 0;
+    }
+
+    /// Selects the next or previous attachment, in the description tab among the
+    /// images the description embeds.
+    pub fn select_attachment(&mut self, next: bool) {
+        let choices: Vec<usize> = match self.tab {
+            DetailTab::Description => self.embedded_images(),
+            _ => (0..self.attachments.len()).collect(),
+        };
+        if choices.is_empty() {
+            return;
+        }
+        let count = choices.len();
+        let position = choices
+            .iter()
+            .position(|index| *index == self.selected_attachment)
+            .map_or(0, |position| if next { (position + 1) % count } else { (position + count - 1) % count });
+        self.selected_attachment = choices[position];
+        self.preview = None;
+    }
+
+    /// Takes the attachments of the issue, selecting the first image the description
+    /// embeds so it is previewed right away.
+    pub fn set_attachments(&mut self, attachments: Vec<Attachment>) {
+        self.attachments = attachments;
+        self.selected_attachment = self.embedded_images().first().copied().unwrap_or(0);
+        self.preview = None;
+    }
+
+    /// Indices of the image attachments the description embeds, in document order.
+    /// Media nodes carry no attachment id, so they are matched by file name.
+    pub fn embedded_images(&self) -> Vec<usize> {
+        let mut indices = vec![];
+        for name in adf::media_names(&self.description) {
+            let found = self
+                .attachments
+                .iter()
+                .position(|attachment| attachment.is_image() && attachment.filename == name);
+            if let Some(index) = found.filter(|index| !indices.contains(index)) {
+                indices.push(index);
+            }
+        }
+        indices
+    }
+
+    /// Whether the selected attachment is previewed in the current tab: any image in
+    /// the attachments tab, the embedded ones in the description tab.
+    pub fn shows_preview(&self) -> bool {
+        match self.tab {
+            DetailTab::Attachments => self.selected_attachment().is_some_and(Attachment::is_image),
+            DetailTab::Description => self.embedded_images().contains(&self.selected_attachment),
+            DetailTab::Comments | DetailTab::Activity => false,
+        }
+    }
+
+    pub fn selected_attachment(&self) -> Option<&Attachment> {
+        self.attachments.get(self.selected_attachment)
+    }
+
+    pub fn has_more_comments(&self) -> bool {
+        self.comments.len() < self.comments_total
+    }
+
+    pub fn scroll_down(&mut self, lines: u16) {
+        self.scroll = self.scroll.saturating_add(lines);
+    }
+
+    pub fn scroll_up(&mut self, lines: u16) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    pub fn scroll_to_end(&mut self) {
+        self.scroll = self.lines().len().saturating_sub(10) as u16;
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        match self.tab {
+            DetailTab::Description => self.description_lines(),
+            DetailTab::Comments => self.comment_lines(),
+            DetailTab::Attachments => self.attachment_lines(),
+            DetailTab::Activity => self.activity_lines(),
+        }
+    }
+
+    fn attachment_lines(&self) -> Vec<Line<'static>> {
+        if self.attachments.is_empty() {
+            return vec![Line::from(" No attachments".italic())];
+        }
+        let mut lines: Vec<Line> = self
+            .attachments
+            .iter()
+            .enumerate()
+            .map(|(index, attachment)| {
+                let line = Line::from(format!(" {} ({})", attachment.filename, attachment.mime_type));
+                if index == self.selected_attachment {
+                    line.reversed()
+                } else {
+                    line
+                }
+            })
+            .collect();
+        lines.push(Line::default());
+        match (self.selected_attachment(), &self.preview) {
+            (Some(attachment), Some(ImagePreview(None))) if attachment.is_image() => {
+                lines.push(Line::from(
+                    format!(" [image: {}] (no terminal graphics support)", attachment.filename)
+                        .dark_gray(),
+                ));
+            }
+            (Some(attachment), _) if !attachment.is_image() => {
+                lines.push(Line::from(" No preview available".italic()));
+            }
+            _ => {}
+        }
+        lines
+    }
+
+    fn description_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![
+            Line::from(vec![" Status: ".bold(), self.issue.status.clone().into()]),
+            Line::from(vec![" Assignee: ".bold(), self.issue.assignee.clone().into()]),
+        ];
+        if !self.status_times.is_empty() {
+            let times: Vec<String> = self
+                .status_times
+                .iter()
+                .map(|(status, seconds)| format!("{status}: {}", statustime::format(*seconds)))
+                .collect();
+            lines.push(Line::from(vec![" Time in status: ".bold(), times.join(", ").into()]));
+        }
+        lines.push(Line::default());
+        if self.description.is_null() {
+            lines.push(Line::from(" No description".italic()));
+        } else {
+            lines.extend(adf::render(&self.description, &self.names));
+        }
+        let embedded = self.embedded_images();
+        if let (Some(position), Some(attachment)) = (
+            embedded.iter().position(|index| *index == self.selected_attachment),
+            self.selected_attachment(),
+        ) {
+            let mut image = format!(" Image {}/{}: {}", position + 1, embedded.len(), attachment.filename);
+            if let Some(ImagePreview(None)) = &self.preview {
+                image += " (no terminal graphics support)";
+            }
+            lines.push(Line::from(image.dark_gray()));
+        }
+        lines
+    }
+
+    fn comment_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![];
+        if self.comments.is_empty() {
+            lines.push(Line::from(" No comments".italic()));
+        }
+        for (comment, depth) in thread_order(&self.comments) {
+            let indent = "    ".repeat(depth);
+            let created = comment
+                .created
+                .map(|created| created.strftime("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            let marker = if depth > 0 { "↳ " } else { "" };
+            lines.push(Line::from(vec![
+                Span::raw(format!("{indent}{marker}")),
+                comment.author.clone().bold(),
+                format!("  {created}").dark_gray(),
+            ]));
+            for line in adf::render(&comment.body, &self.names) {
+                let mut spans = vec![Span::raw(format!("{indent}  "))];
+                spans.extend(line.spans);
+                lines.push(Line::from(spans));
+            }
+        }
+        if self.has_more_comments() {
+            lines.push(Line::from(
+                format!(
+                    " {} more comments, press <n> to load",
+                    self.comments_total - self.comments.len()
+                )
+                .italic(),
+            ));
+        }
+        lines
+    }
+
+    fn activity_lines(&self) -> Vec<Line<'static>> {
+        let stream = activity::stream(&self.comments, &self.activity);
+        let mut lines = vec![];
+        if stream.is_empty() {
+            lines.push(Line::from(" No activity".italic()));
+        }
+        for event in stream {
+            let at = event.at.strftime("%Y-%m-%d %H:%M").to_string();
+            let (action, body) = match event.kind {
+                EventKind::Comment(body) => ("commented".to_string(), body),
+                EventKind::Worklog { time_spent, comment } => (format!("logged {time_spent}"), comment),
+                EventKind::Status { from, to } => (format!("moved it from {from} to {to}"), Value::Null),
+            };
+            lines.push(Line::from(vec![
+                format!(" {at}  ").dark_gray(),
+                event.author.bold(),
+                format!(" {action}").into(),
+            ]));
+            if !body.is_null() {
+                for line in adf::render(&body, &self.names) {
+                    let mut spans = vec![Span::raw("   ")];
+                    spans.extend(line.spans);
+                    lines.push(Line::from(spans));
+                }
+            }
+        }
+        if self.has_more_comments() {
+            lines.push(Line::from(
+                format!(
+                    " {} more comments, press <n> to load",
+                    self.comments_total - self.comments.len()
+                )
+                .italic(),
+            ));
+        }
+        lines
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let title = Line::from(format!(" {} {} ", self.issue.key, self.issue.summary).bold());
+        let tabs = Line::from(vec![
+            tab_label("Description", self.tab == DetailTab::Description),
+            tab_label(
+                &format!("Comments ({})", self.comments_total),
+                self.tab == DetailTab::Comments,
+            ),
+            tab_label(
+                &format!("Attachments ({})", self.attachments.len()),
+                self.tab == DetailTab::Attachments,
+            ),
+            tab_label("Activity", self.tab == DetailTab::Activity),
+        ]);
+        let instructions = Line::from(vec![
+            " Switch Tab ".into(),
+            "<tab>  ".blue().bold(),
+            " Scroll ".into(),
+            "<Up/Down>  ".blue().bold(),
+            " Attachment ".into(),
+            "<Left/Right>  ".blue().bold(),
+            " Newest ".into(),
+            "<end>  ".blue().bold(),
+            " Comment ".into(),
+            "<c>  ".blue().bold(),
+            " Close ".into(),
+            "<esc> ".blue().bold(),
+        ]);
+        let block = Block::bordered()
+            .title(title.centered())
+            .title(tabs.right_aligned())
+            .title_bottom(instructions.centered())
+            .border_set(border::THICK);
+
+        let inner = block.inner(area);
+        let embeds_images = self.tab == DetailTab::Description && !self.embedded_images().is_empty();
+        let [text_area, image_area] = match embeds_images {
+            true => Layout::horizontal([Constraint::Min(40), Constraint::Percentage(40)]).areas(inner),
+            false => [inner, Rect::default()],
+        };
+        let paragraph = Paragraph::new(self.lines())
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(paragraph, text_area);
+
+        if embeds_images {
+            if let Some(ImagePreview(Some(protocol))) = &mut self.preview {
+                frame.render_stateful_widget(StatefulImage::default(), image_area, protocol);
+            }
+        }
+        if self.tab == DetailTab::Attachments {
+            if let Some(ImagePreview(Some(protocol))) = &mut self.preview {
+                let list_height = self.attachments.len() as u16 + 1;
+                let [_, image_area] =
+                    Layout::vertical([Constraint::Length(list_height), Constraint::Min(1)])
+                        .areas(inner);
+                frame.render_stateful_widget(StatefulImage::default(), image_area, protocol);
+            }
+        }
+    }
+}
+
+fn tab_label(label: &str, selected: bool) -> Span<'static> {
+    let label = format!(" {label} ");
+    if selected {
+        label.reversed()
+    } else {
+        label.into()
+    }
+}
+
+/// Orders comments so replies follow their parent, paired with their nesting depth.
+fn thread_order(comments: &[Comment]) -> Vec<(&Comment, usize)> {
+    fn push_replies<'a>(
+        comments: &'a [Comment],
+        parent: &Comment,
+        depth: usize,
+        ordered: &mut Vec<(&'a Comment, usize)>,
+    ) {
+        for reply in comments
+            .iter()
+            .filter(|c| c.parent_id.as_deref() == Some(parent.id.as_str()))
+        {
+            ordered.push((reply, depth));
+            push_replies(comments, reply, depth + 1, ordered);
+        }
+    }
+
+    let mut ordered = vec![];
+    for comment in comments {
+        let has_loaded_parent = comment
+            .parent_id
+            .as_ref()
+            .is_some_and(|parent_id| comments.iter().any(|c| &c.id == parent_id));
+        if has_loaded_parent {
+            continue;
+        }
+        ordered.push((comment, 0));
+        push_replies(comments, comment, 1, &mut ordered);
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn comment(id: &str, parent_id: Option<&str>) -> Comment {
+        Comment {
+            id: id.to_string(),
+            parent_id: parent_id.map(|id| id.to_string()),
+            author: "Sam".to_string(),
+            created: None,
+            body: Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_embedded_images() {
+        let description = serde_json::json!({
+            "type": "doc",
+            "content": [
+                {"type": "mediaSingle", "content": [{"type": "media", "attrs": {"alt": "after.png"}}]},
+                {"type": "mediaSingle", "content": [{"type": "media", "attrs": {"alt": "log.txt"}}]},
+                {"type": "mediaSingle", "content": [{"type": "media", "attrs": {"alt": "before.png"}}]}
+            ]
+        });
+        let attachment = |id: &str, filename: &str, mime_type: &str| Attachment {
+            id: id.to_string(),
+            filename: filename.to_string(),
+            mime_type: mime_type.to_string(),
+        };
+        let mut detail = DetailView::new(Issue::default(), description, HashMap::new());
+        detail.set_attachments(vec![
+            attachment("1", "before.png", "image/png"),
+            attachment("2", "log.txt", "text/plain"),
+            attachment("3", "after.png", "image/png"),
+        ]);
+        assert_eq!(detail.embedded_images(), [2, 0]);
+        assert_eq!(detail.selected_attachment, 2);
+        assert!(detail.shows_preview());
+
+        detail.select_attachment(true);
+        assert_eq!(detail.selected_attachment, 0);
+        detail.select_attachment(true);
+        assert_eq!(detail.selected_attachment, 2);
+
+        detail.next_tab();
+        assert!(!detail.shows_preview());
+    }
+
+    #[test]
+    fn test_thread_order() {
+        let comments = [
+            comment("1", None),
+            comment("2", None),
+            comment("3", Some("1")),
+            comment("4", Some("3")),
+            comment("5", Some("99")),
+        ];
+        let ordered: Vec<(&str, usize)> = thread_order(&comments)
+            .into_iter()
+            .map(|(comment, depth)| (comment.id.as_str(), depth))
+            .collect();
+        assert_eq!(ordered, [("1", 0), ("3", 1), ("4", 2), ("2", 0), ("5", 0)]);
+    }
+}
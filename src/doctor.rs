@@ -0,0 +1,69 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::{schema, source::IssueSource, App};
+
+impl App {
+    /// Checks the connection to Jira and whether its answers still have the fields
+    /// jiratrack reads, then lists the differences logged on earlier runs.
+    pub fn doctor(&mut self, mut output: impl Write) -> Result<()> {
+        writeln!(output, "Jira at {} as {}", self.config.atlassian_url, self.config.user_email)?;
+        match self.jira.get_myself() {
+            Ok(user) => writeln!(output, "ok    Signed in as {}", user.display_name)?,
+            Err(error) => {
+                writeln!(output, "fail  Signing in: {error}")?;
+                return Ok(());
+            }
+        }
+        match self.jira.get_active_sprint() {
+            Ok(Some(sprint)) => writeln!(output, "ok    Active sprint {}", sprint.name)?,
+            Ok(None) => writeln!(output, "warn  No active sprint on the board")?,
+            Err(error) => writeln!(output, "fail  Finding the active sprint: {error}")?,
+        }
+        let issues = match self.jira.sprint_issues(&[]) {
+            Ok(issues) => {
+                writeln!(output, "ok    {} issues listed", issues.len())?;
+                issues
+            }
+            Err(error) => {
+                writeln!(output, "fail  Listing the issues: {error}")?;
+                vec![]
+            }
+        };
+        if let Some(issue) = issues.first() {
+            let _ = self.jira.get_worklogs(&issue.key);
+            if let Some(field) = &self.config.story_points_field {
+                match self.jira.has_field(&issue.key, field) {
+                    Ok(true) => writeln!(output, "ok    Story points in {field}")?,
+                    Ok(false) => writeln!(
+                        output,
+                        "warn  story_points_field {field} is not a field of {}, story points stay blank",
+                        issue.key
+                    )?,
+                    Err(error) => writeln!(output, "fail  Reading {field}: {error}")?,
+                }
+            }
+        }
+
+        let found = schema::found();
+        if found.is_empty() {
+            writeln!(output, "ok    Answers have the expected fields")?;
+        }
+        for drift in &found {
+            writeln!(output, "warn  Unexpected answer, {drift}")?;
+        }
+        let earlier: Vec<_> = schema::logged()
+            .into_iter()
+            .filter(|(_, message)| !found.contains(message))
+            .collect();
+        if !earlier.is_empty() {
+            writeln!(output)?;
+            writeln!(output, "Logged before in {}:", schema::get_path().display())?;
+            for (at, message) in earlier {
+                writeln!(output, "{at} {message}")?;
+            }
+        }
+        Ok(())
+    }
+}
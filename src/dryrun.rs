@@ -0,0 +1,72 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use dirs::home_dir;
+use jiff::Timestamp;
+use serde_json::Value;
+
+/// Writes that are read-only despite being sent with POST.
+const READ_ONLY_ENDPOINTS: [&str; 1] = ["/rest/api/3/jql/parse"];
+
+pub fn get_path() -> PathBuf {
+    home_dir()
+        .unwrap()
+        .join(".local/share/jiratrack/dry-run.log")
+}
+
+/// Whether a request to the endpoint changes anything in Jira.
+pub fn is_write(endpoint: &str) -> bool {
+    !READ_ONLY_ENDPOINTS.contains(&endpoint)
+}
+
+fn format_entry(now: Timestamp, method: &str, endpoint: &str, data: Option<&Value>) -> String {
+    match data {
+        Some(data) => format!("{now} {method} {endpoint} {data}"),
+        None => format!("{now} {method} {endpoint}"),
+    }
+}
+
+/// Appends a request that would have been sent to the dry-run log.
+pub fn record(method: &str, endpoint: &str, data: Option<&Value>) {
+    let path = get_path();
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let mut file = OpenOptions::new().create(true).append(true).open(path).unwrap();
+    writeln!(file, "{}", format_entry(Timestamp::now(), method, endpoint, data)).unwrap();
+}
+
+/// Appends what an automation rule would have done to the dry-run log.
+pub fn note(message: &str) {
+    let path = get_path();
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let mut file = OpenOptions::new().create(true).append(true).open(path).unwrap();
+    writeln!(file, "{} RULE {message}", Timestamp::now()).unwrap();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_format_entry() {
+        let now: Timestamp = "2025-01-10T09:00:00Z".parse().unwrap();
+        let data = json!({"timeSpentSeconds": 900});
+        assert_eq!(
+            format_entry(now, "POST", "/rest/api/3/issue/IMG-237/worklog", Some(&data)),
+            r#"2025-01-10T09:00:00Z POST /rest/api/3/issue/IMG-237/worklog {"timeSpentSeconds":900}"#
+        );
+        assert_eq!(
+            format_entry(now, "PUT", "/rest/agile/1.0/issue/rank", None),
+            "2025-01-10T09:00:00Z PUT /rest/agile/1.0/issue/rank"
+        );
+    }
+
+    #[test]
+    fn test_is_write() {
+        assert!(is_write("/rest/api/3/issue/IMG-237/assignee"));
+        assert!(!is_write("/rest/api/3/jql/parse"));
+    }
+}
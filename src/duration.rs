@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+/// Working-hours configuration Jira uses to expand `d` and `w` in time strings.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorkTime {
+    pub hours_per_day: f64,
+    pub days_per_week: f64,
+}
+
+impl Default for WorkTime {
+    fn default() -> Self {
+        WorkTime {
+            hours_per_day: 8.0,
+            days_per_week: 5.0,
+        }
+    }
+}
+
+impl WorkTime {
+    fn seconds_per_day(&self) -> u64 {
+        (self.hours_per_day * 3600.0).round() as u64
+    }
+
+    fn seconds_per_week(&self) -> u64 {
+        (self.hours_per_day * self.days_per_week * 3600.0).round() as u64
+    }
+
+    /// Parses a Jira time string like `1w 2d 3h 30m` into seconds.
+    pub fn parse(&self, input: &str) -> Option<u64> {
+        let mut total = 0.0;
+        let mut parts = input.split_whitespace().peekable();
+        parts.peek()?;
+        for part in parts {
+            let unit = part.chars().last()?;
+            let value: f64 = part[..part.len() - unit.len_utf8()].parse().ok()?;
+            let unit_seconds = match unit {
+                'w' => self.seconds_per_week(),
+                'd' => self.seconds_per_day(),
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+            total += value * unit_seconds as f64;
+        }
+        Some(total.round() as u64)
+    }
+
+    /// Formats seconds as a Jira time string, e.g. `1d 2h 30m`.
+    pub fn format(&self, seconds: u64) -> String {
+        let mut remaining = seconds;
+        let mut parts = vec![];
+        for (unit, unit_seconds) in [
+            ("w", self.seconds_per_week()),
+            ("d", self.seconds_per_day()),
+            ("h", 3600),
+            ("m", 60),
+        ] {
+            if unit_seconds == 0 {
+                continue;
+            }
+            let value = remaining / unit_seconds;
+            if value > 0 {
+                parts.push(format!("{value}{unit}"));
+                remaining -= value * unit_seconds;
+            }
+        }
+        if parts.is_empty() {
+            return "0h".to_string();
+        }
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let work_time = WorkTime::default();
+        assert_eq!(work_time.parse("1w 2d 3h 30m"), Some((40 + 16 + 3) * 3600 + 1800));
+        assert_eq!(work_time.parse("45m"), Some(2700));
+        assert_eq!(work_time.parse("1.5h"), Some(5400));
+        assert_eq!(work_time.parse(""), None);
+        assert_eq!(work_time.parse("3x"), None);
+    }
+
+    #[test]
+    fn test_custom_workday() {
+        let work_time = WorkTime {
+            hours_per_day: 7.5,
+            days_per_week: 4.0,
+        };
+        assert_eq!(work_time.parse("1d"), Some(27000));
+        assert_eq!(work_time.parse("1w"), Some(4 * 27000));
+        assert_eq!(work_time.format(27000 + 3600), "1d 1h");
+    }
+
+    #[test]
+    fn test_format() {
+        let work_time = WorkTime::default();
+        assert_eq!(work_time.format(0), "0h");
+        assert_eq!(work_time.format(5400), "1h 30m");
+        assert_eq!(work_time.format(9 * 3600), "1d 1h");
+        assert_eq!(work_time.format(40 * 3600), "1w");
+    }
+}
@@ -0,0 +1,83 @@
+use crate::jira::Issue;
+
+/// Refinement in one go: the unestimated issues one by one, story points typed for each.
+#[derive(Debug, Default)]
+pub struct Estimation {
+    pub issues: Vec<Issue>,
+    pub index: usize,
+    /// Points typed for the current issue.
+    pub input: String,
+    /// Number of issues estimated so far.
+    pub estimated: usize,
+}
+
+impl Estimation {
+    pub fn new(issues: Vec<Issue>) -> Estimation {
+        Estimation {
+            issues,
+            ..Estimation::default()
+        }
+    }
+
+    /// The issue being estimated, `None` once all were passed.
+    pub fn current(&self) -> Option<&Issue> {
+        self.issues.get(self.index)
+    }
+
+    /// Moves on to the next issue, also after skipping one.
+    pub fn next(&mut self) {
+        self.index = (self.index + 1).min(self.issues.len());
+        self.input.clear();
+    }
+
+    pub fn previous(&mut self) {
+        self.index = self.index.saturating_sub(1);
+        self.input.clear();
+    }
+}
+
+/// Story points typed like `3`, `0.5` or `½`, `None` unless a number of at least zero.
+pub fn parse_points(input: &str) -> Option<f64> {
+    let input = input.trim();
+    if input == "½" {
+        return Some(0.5);
+    }
+    input
+        .replace(',', ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|points| points.is_finite() && *points >= 0.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_points() {
+        assert_eq!(parse_points(" 5 "), Some(5.0));
+        assert_eq!(parse_points("0,5"), Some(0.5));
+        assert_eq!(parse_points("½"), Some(0.5));
+        assert_eq!(parse_points("-1"), None);
+        assert_eq!(parse_points("xl"), None);
+        assert_eq!(parse_points(""), None);
+    }
+
+    #[test]
+    fn test_walk() {
+        let issue = |key: &str| Issue {
+            key: key.to_string(),
+            ..Default::default()
+        };
+        let mut estimation = Estimation::new(vec![issue("IMG-1"), issue("IMG-2")]);
+        estimation.input.push('3');
+        estimation.next();
+        assert_eq!(estimation.current().map(|issue| issue.key.as_str()), Some("IMG-2"));
+        assert!(estimation.input.is_empty());
+        estimation.next();
+        estimation.next();
+        assert!(estimation.current().is_none());
+        estimation.previous();
+        assert_eq!(estimation.current().map(|issue| issue.key.as_str()), Some("IMG-2"));
+    }
+}
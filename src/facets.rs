@@ -0,0 +1,166 @@
+use crate::jira::Issue;
+
+/// A property the issue list can be narrowed down by in the filter menu.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Facet {
+    Status,
+    Label,
+    Component,
+}
+
+const FACETS: [Facet; 3] = [Facet::Status, Facet::Label, Facet::Component];
+
+impl Facet {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Facet::Status => "Status",
+            Facet::Label => "Label",
+            Facet::Component => "Component",
+        }
+    }
+
+    fn values<'a>(&self, issue: &'a Issue) -> Vec<&'a str> {
+        match self {
+            Facet::Status => vec![issue.status.as_str()],
+            Facet::Label => issue.labels.iter().map(String::as_str).collect(),
+            Facet::Component => issue.components.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// A value of a facet with the number of issues having it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FacetValue {
+    pub facet: Facet,
+    pub value: String,
+    pub count: usize,
+}
+
+/// The distinct statuses, labels and components of the issues, in that order, each
+/// the most common first.
+pub fn values(issues: &[Issue]) -> Vec<FacetValue> {
+    let mut values: Vec<FacetValue> = vec![];
+    for facet in FACETS {
+        let mut facet_values: Vec<FacetValue> = vec![];
+        for value in issues.iter().flat_map(|issue| facet.values(issue)) {
+            match facet_values.iter_mut().find(|facet_value| facet_value.value == value) {
+                Some(facet_value) => facet_value.count += 1,
+                None => facet_values.push(FacetValue {
+                    facet,
+                    value: value.to_string(),
+                    count: 1,
+                }),
+            }
+        }
+        facet_values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        values.extend(facet_values);
+    }
+    values
+}
+
+/// The facet values toggled on in the filter menu. An issue is listed when it has one
+/// of the selected values of every facet something is selected of.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FacetFilter {
+    selected: Vec<(Facet, String)>,
+}
+
+impl FacetFilter {
+    pub fn toggle(&mut self, facet: Facet, value: &str) {
+        match self.position(facet, value) {
+            Some(index) => {
+                self.selected.remove(index);
+            }
+            None => self.selected.push((facet, value.to_string())),
+        }
+    }
+
+    pub fn is_selected(&self, facet: Facet, value: &str) -> bool {
+        self.position(facet, value).is_some()
+    }
+
+    fn position(&self, facet: Facet, value: &str) -> Option<usize> {
+        self.selected
+            .iter()
+            .position(|(selected_facet, selected)| *selected_facet == facet && selected == value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.selected.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    pub fn matches(&self, issue: &Issue) -> bool {
+        FACETS.iter().all(|facet| {
+            let mut selected = self
+                .selected
+                .iter()
+                .filter(|(selected_facet, _)| selected_facet == facet)
+                .peekable();
+            selected.peek().is_none()
+                || selected.any(|(_, value)| facet.values(issue).contains(&value.as_str()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn issue(status: &str, labels: &[&str], components: &[&str]) -> Issue {
+        Issue {
+            status: status.to_string(),
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+            components: components.iter().map(|component| component.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_values() {
+        let issues = [
+            issue("To Do", &["ui"], &["App"]),
+            issue("In Progress", &["ui", "auth"], &[]),
+            issue("To Do", &[], &["Backend"]),
+        ];
+        let values: Vec<String> = values(&issues)
+            .iter()
+            .map(|value| format!("{}: {} ({})", value.facet.name(), value.value, value.count))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                "Status: To Do (2)",
+                "Status: In Progress (1)",
+                "Label: ui (2)",
+                "Label: auth (1)",
+                "Component: App (1)",
+                "Component: Backend (1)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        let todo_ui = issue("To Do", &["ui"], &[]);
+        let todo_auth = issue("To Do", &["auth"], &[]);
+        let review_ui = issue("In Review", &["ui"], &[]);
+        let mut filter = FacetFilter::default();
+        assert!(filter.matches(&todo_ui));
+
+        filter.toggle(Facet::Label, "ui");
+        filter.toggle(Facet::Label, "auth");
+        assert!(filter.matches(&todo_ui) && filter.matches(&todo_auth));
+
+        filter.toggle(Facet::Status, "To Do");
+        assert!(filter.matches(&todo_auth));
+        assert!(!filter.matches(&review_ui));
+
+        filter.toggle(Facet::Status, "To Do");
+        assert!(filter.matches(&review_ui));
+        assert_eq!(filter.len(), 2);
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use crate::jira::find_issue_key;
+
+/// Time between two runs of the focus command.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The issue key in the output of the focus command, e.g. the title of the
+/// focused tmux window.
+pub fn read(command: &str) -> Option<String> {
+    let output = Command::new("sh").arg("-c").arg(command).output().ok()?;
+    find_issue_key(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Follows the issue key being worked on, to suggest switching the timer once
+/// the same key stays in focus for a while.
+#[derive(Debug, Default)]
+pub struct Focus {
+    /// Key in focus and since when.
+    current: Option<(String, Instant)>,
+    checked: Option<Instant>,
+}
+
+impl Focus {
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.checked.is_none_or(|checked| now - checked >= CHECK_INTERVAL)
+    }
+
+    pub fn observe(&mut self, key: Option<String>, now: Instant) {
+        self.checked = Some(now);
+        if key.as_ref() != self.current.as_ref().map(|(current, _)| current) {
+            self.current = key.map(|key| (key, now));
+        }
+    }
+
+    /// The key in focus, once it has been for at least `after`.
+    pub fn persistent(&self, after: Duration, now: Instant) -> Option<&str> {
+        self.current
+            .as_ref()
+            .filter(|(_, since)| now - *since >= after)
+            .map(|(key, _)| key.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_persistent() {
+        let start = Instant::now();
+        let after = Duration::from_secs(60);
+        let mut focus = Focus::default();
+        focus.observe(Some("IMG-1".to_string()), start);
+        focus.observe(Some("IMG-1".to_string()), start + Duration::from_secs(30));
+        assert_eq!(focus.persistent(after, start + Duration::from_secs(30)), None);
+        assert_eq!(focus.persistent(after, start + after), Some("IMG-1"));
+
+        focus.observe(Some("IMG-2".to_string()), start + after);
+        assert_eq!(focus.persistent(after, start + after), None);
+        focus.observe(None, start + after * 3);
+        assert_eq!(focus.persistent(after, start + after * 3), None);
+    }
+
+    #[test]
+    fn test_read() {
+        assert_eq!(read("echo 'vim IMG-42-fix-login'"), Some("IMG-42".to_string()));
+        assert_eq!(read("echo zsh"), None);
+    }
+}
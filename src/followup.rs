@@ -0,0 +1,133 @@
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+};
+
+use dirs::home_dir;
+use jiff::{Timestamp, ToSpan, Zoned};
+use serde::{Deserialize, Serialize};
+
+/// A reminder to come back to an issue at a certain time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FollowUp {
+    pub issue_key: String,
+    pub at: Timestamp,
+    /// Whether the reminder was already shown, so it is announced only once.
+    #[serde(default)]
+    pub notified: bool,
+}
+
+/// Reminders set on issues, kept between starts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FollowUps {
+    pub entries: Vec<FollowUp>,
+}
+
+impl FollowUps {
+    fn get_path() -> PathBuf {
+        home_dir()
+            .unwrap()
+            .join(".local/share/jiratrack/follow-ups.json")
+    }
+
+    pub fn load() -> FollowUps {
+        match File::open(FollowUps::get_path()) {
+            Ok(file) => serde_json::from_reader(file).expect("Invalid follow-ups"),
+            Err(_) => FollowUps::default(),
+        }
+    }
+
+    pub fn persist(&self) {
+        let path = FollowUps::get_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = File::create(path).unwrap();
+        serde_json::to_writer(file, self).unwrap();
+    }
+
+    pub fn get(&self, issue_key: &str) -> Option<&FollowUp> {
+        self.entries.iter().find(|entry| entry.issue_key == issue_key)
+    }
+
+    /// Sets the reminder of an issue, replacing an earlier one. Setting a due
+    /// reminder again snoozes it.
+    pub fn set(&mut self, issue_key: &str, at: Timestamp) {
+        self.clear(issue_key);
+        self.entries.push(FollowUp {
+            issue_key: issue_key.to_string(),
+            at,
+            notified: false,
+        });
+    }
+
+    pub fn clear(&mut self, issue_key: &str) {
+        self.entries.retain(|entry| entry.issue_key != issue_key);
+    }
+
+    pub fn is_due(&self, issue_key: &str, now: Timestamp) -> bool {
+        self.get(issue_key).is_some_and(|entry| entry.at <= now)
+    }
+
+    /// Marks the reminders that became due as shown and returns them.
+    pub fn take_due(&mut self, now: Timestamp) -> Vec<FollowUp> {
+        self.entries
+            .iter_mut()
+            .filter(|entry| !entry.notified && entry.at <= now)
+            .map(|entry| {
+                entry.notified = true;
+                entry.clone()
+            })
+            .collect()
+    }
+}
+
+/// Parses a time of day like `15:00`, the next occurrence of it, or a delay like
+/// `30m` or `2h`.
+pub fn parse_time(input: &str, now: &Zoned) -> Option<Timestamp> {
+    let input = input.trim();
+    if let Some(minutes) = input.strip_suffix('m') {
+        return now.timestamp().checked_add(minutes.parse::<i64>().ok()?.minutes()).ok();
+    }
+    if let Some(hours) = input.strip_suffix('h') {
+        return now.timestamp().checked_add(hours.parse::<i64>().ok()?.hours()).ok();
+    }
+    let time: jiff::civil::Time = input.parse().ok()?;
+    let today = now.with().time(time).build().ok()?;
+    let at = if today <= *now {
+        today.checked_add(1.day()).ok()?
+    } else {
+        today
+    };
+    Some(at.timestamp())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_time() {
+        let now: Zoned = "2025-01-10T14:00:00+00:00[UTC]".parse().unwrap();
+        let at = |time: &str| parse_time(time, &now).map(|at| at.to_string());
+        assert_eq!(at("15:00").as_deref(), Some("2025-01-10T15:00:00Z"));
+        assert_eq!(at("09:30").as_deref(), Some("2025-01-11T09:30:00Z"));
+        assert_eq!(at("30m").as_deref(), Some("2025-01-10T14:30:00Z"));
+        assert_eq!(at("2h").as_deref(), Some("2025-01-10T16:00:00Z"));
+        assert_eq!(at("later"), None);
+    }
+
+    #[test]
+    fn test_take_due() {
+        let now: Timestamp = "2025-01-10T14:00:00Z".parse().unwrap();
+        let mut follow_ups = FollowUps::default();
+        follow_ups.set("IMG-1", "2025-01-10T13:00:00Z".parse().unwrap());
+        follow_ups.set("IMG-2", "2025-01-10T15:00:00Z".parse().unwrap());
+        assert!(follow_ups.is_due("IMG-1", now));
+        assert!(!follow_ups.is_due("IMG-2", now));
+        assert_eq!(follow_ups.take_due(now).len(), 1);
+        assert!(follow_ups.take_due(now).is_empty());
+
+        follow_ups.set("IMG-1", "2025-01-10T14:10:00Z".parse().unwrap());
+        assert!(!follow_ups.is_due("IMG-1", now));
+        assert_eq!(follow_ups.entries.len(), 2);
+    }
+}
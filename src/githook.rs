@@ -0,0 +1,134 @@
+use std::{
+    fs::{self, File},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{anyhow, Result};
+use dirs::home_dir;
+use jiff::{SignedDuration, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::jira::find_issue_key;
+
+/// Marks hooks written by jiratrack, which are replaced when installing again.
+const MARKER: &str = "# Installed by jiratrack";
+
+/// Checkouts older than this are not acted on, e.g. made while jiratrack wasn't running.
+const MAX_AGE: SignedDuration = SignedDuration::from_secs(60);
+
+/// The post-checkout hook, calling `program` with the checked out branch.
+pub fn checkout_hook(program: &Path) -> String {
+    let program = program.display().to_string().replace('\'', r"'\''");
+    format!(
+        "#!/bin/sh\n{MARKER}, switches the timer to the issue of the checked out branch.\n\
+         # Only branch checkouts, not those of single files.\n\
+         [ \"$3\" = 1 ] || exit 0\n\
+         '{program}' githook post-checkout \"$(git rev-parse --abbrev-ref HEAD)\" >/dev/null 2>&1 || true\n"
+    )
+}
+
+/// Installs the post-checkout hook into the git repository of the working directory
+/// and returns its path. A hook not written by jiratrack is left alone.
+pub fn install_checkout() -> Result<PathBuf> {
+    let output = Command::new("git").args(["rev-parse", "--git-path", "hooks"]).output()?;
+    if !output.status.success() {
+        return Err(anyhow!("Not inside a git repository"));
+    }
+    let hooks = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    let path = hooks.join("post-checkout");
+    if fs::read_to_string(&path).is_ok_and(|hook| !hook.contains(MARKER)) {
+        return Err(anyhow!(
+            "{} already exists, call `jiratrack githook post-checkout <branch>` from it instead",
+            path.display()
+        ));
+    }
+    fs::create_dir_all(&hooks)?;
+    fs::write(&path, checkout_hook(&std::env::current_exe()?))?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    Ok(path)
+}
+
+/// A branch checked out by the hook, for the running interface to pick up.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkout {
+    pub branch: String,
+    pub issue_key: String,
+    pub at: Timestamp,
+}
+
+impl Checkout {
+    /// The checkout of a branch naming an issue, e.g. `IMG-12-fix-login` or
+    /// `feature/img-12`.
+    pub fn of_branch(branch: &str, at: Timestamp) -> Option<Checkout> {
+        let issue_key = find_issue_key(branch).or_else(|| find_issue_key(&branch.to_uppercase()))?;
+        Some(Checkout {
+            branch: branch.to_string(),
+            issue_key,
+            at,
+        })
+    }
+
+    fn get_path() -> PathBuf {
+        home_dir()
+            .unwrap()
+            .join(".local/share/jiratrack/checkout.json")
+    }
+
+    pub fn persist(&self) -> Result<()> {
+        self.persist_to(&Checkout::get_path())
+    }
+
+    fn persist_to(&self, path: &Path) -> Result<()> {
+        fs::create_dir_all(path.parent().unwrap())?;
+        serde_json::to_writer(File::create(path)?, self)?;
+        Ok(())
+    }
+
+    /// The checkout left by the hook, if it is recent, removing it so it is acted on once.
+    pub fn take(now: Timestamp) -> Option<Checkout> {
+        Checkout::take_from(&Checkout::get_path(), now)
+    }
+
+    fn take_from(path: &Path, now: Timestamp) -> Option<Checkout> {
+        let file = File::open(path).ok()?;
+        let checkout: Option<Checkout> = serde_json::from_reader(file).ok();
+        fs::remove_file(path).ok()?;
+        checkout.filter(|checkout| now.duration_since(checkout.at) <= MAX_AGE)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checkout_hook() {
+        let hook = checkout_hook(Path::new("/opt/it's/jiratrack"));
+        assert!(hook.starts_with("#!/bin/sh\n# Installed by jiratrack"));
+        assert!(hook.contains(r"'/opt/it'\''s/jiratrack' githook post-checkout"));
+    }
+
+    #[test]
+    fn test_of_branch() {
+        let at = Timestamp::UNIX_EPOCH;
+        assert_eq!(Checkout::of_branch("IMG-12-fix-login", at).unwrap().issue_key, "IMG-12");
+        assert_eq!(Checkout::of_branch("feature/img-7", at).unwrap().issue_key, "IMG-7");
+        assert_eq!(Checkout::of_branch("main", at), None);
+    }
+
+    #[test]
+    fn test_take() {
+        let path = std::env::temp_dir().join(format!("jiratrack-checkout-{}.json", std::process::id()));
+        let now: Timestamp = "2025-01-10T09:00:00Z".parse().unwrap();
+        let checkout = Checkout::of_branch("IMG-12-fix-login", now).unwrap();
+        checkout.persist_to(&path).unwrap();
+        assert_eq!(Checkout::take_from(&path, now), Some(checkout.clone()));
+        assert_eq!(Checkout::take_from(&path, now), None);
+
+        checkout.persist_to(&path).unwrap();
+        assert_eq!(Checkout::take_from(&path, now + SignedDuration::from_mins(5)), None);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+};
+
+use dirs::home_dir;
+use jiff::{civil::Date, Zoned};
+use serde::{Deserialize, Serialize};
+
+use crate::session::Session;
+
+/// A worklog submitted to Jira, as remembered locally.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub issue_key: String,
+    pub started: Zoned,
+    pub time_spent_s: u64,
+    pub worklog_id: Option<String>,
+    /// Account the worklog was submitted on behalf of, if not the current user.
+    #[serde(default)]
+    pub logged_as: Option<String>,
+    /// When Jira accepted the worklog, unknown for entries from older versions.
+    #[serde(default)]
+    pub submitted: Option<Zoned>,
+    /// Comment sent with the worklog, kept so a merge doesn't replace it.
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn session(&self) -> Session {
+        Session {
+            issue_key: self.issue_key.clone(),
+            started: self.started.clone(),
+            duration_s: self.time_spent_s,
+            comment: self.comment.clone(),
+            account: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+    /// Sessions awaiting review before submission.
+    #[serde(default)]
+    pub pending: Vec<Session>,
+}
+
+impl History {
+    fn get_path() -> PathBuf {
+        home_dir()
+            .unwrap()
+            .join(".local/share/jiratrack/history.json")
+    }
+
+    pub fn load() -> History {
+        match File::open(History::get_path()) {
+            Ok(file) => serde_json::from_reader(file).expect("Invalid history"),
+            Err(_) => History::default(),
+        }
+    }
+
+    pub fn persist(&self) {
+        let path = History::get_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = File::create(path).unwrap();
+        serde_json::to_writer(file, self).unwrap();
+    }
+
+    pub fn record(&mut self, entry: HistoryEntry) {
+        self.entries.push(entry);
+        self.persist();
+    }
+
+    /// Worklogs started on the given day, in the order they were submitted.
+    pub fn entries_on(&self, date: Date) -> Vec<&HistoryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.started.date() == date)
+            .collect()
+    }
+
+    /// The last `count` worklogs Jira returned an ID for, newest first.
+    pub fn receipts(&self, count: usize) -> Vec<&HistoryEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.worklog_id.is_some())
+            .take(count)
+            .collect()
+    }
+
+    /// The issue of the latest worklog, submitted or awaiting review.
+    pub fn last_issue_key(&self) -> Option<String> {
+        self.entries
+            .iter()
+            .map(|entry| (&entry.started, &entry.issue_key))
+            .chain(self.pending.iter().map(|session| (&session.started, &session.issue_key)))
+            .max_by_key(|(started, _)| *started)
+            .map(|(_, issue_key)| issue_key.clone())
+    }
+
+    /// Seconds submitted in worklogs started at or after the given time.
+    pub fn logged_since(&self, since: &Zoned) -> u64 {
+        self.entries
+            .iter()
+            .filter(|entry| entry.started >= *since)
+            .map(|entry| entry.time_spent_s)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_logged_since() {
+        let entry = |started: &str, time_spent_s| HistoryEntry {
+            issue_key: "IMG-237".to_string(),
+            started: started.parse().unwrap(),
+            time_spent_s,
+            worklog_id: None,
+            logged_as: None,
+            submitted: None,
+            comment: None,
+        };
+        let history = History {
+            entries: vec![
+                entry("2025-01-03T09:00:00+00:00[UTC]", 3600),
+                entry("2025-01-06T09:00:00+00:00[UTC]", 1800),
+                entry("2025-01-08T09:00:00+00:00[UTC]", 900),
+            ],
+            pending: vec![],
+        };
+        let since = "2025-01-06T00:00:00+00:00[UTC]".parse().unwrap();
+        assert_eq!(history.logged_since(&since), 2700);
+        let day = jiff::civil::date(2025, 1, 8);
+        assert_eq!(history.entries_on(day).len(), 1);
+        assert_eq!(history.entries_on(day)[0].time_spent_s, 900);
+    }
+
+    #[test]
+    fn test_receipts() {
+        let entry = |issue_key: &str, worklog_id: Option<&str>| HistoryEntry {
+            issue_key: issue_key.to_string(),
+            started: "2025-01-08T09:00:00+00:00[UTC]".parse().unwrap(),
+            time_spent_s: 900,
+            worklog_id: worklog_id.map(|id| id.to_string()),
+            logged_as: None,
+            submitted: None,
+            comment: None,
+        };
+        let history = History {
+            entries: vec![
+                entry("IMG-1", Some("10001")),
+                entry("IMG-2", None),
+                entry("IMG-3", Some("10003")),
+                entry("IMG-4", Some("10004")),
+            ],
+            pending: vec![],
+        };
+        let keys: Vec<&str> = history.receipts(2).iter().map(|entry| entry.issue_key.as_str()).collect();
+        assert_eq!(keys, ["IMG-4", "IMG-3"]);
+        assert_eq!(history.receipts(10).len(), 3);
+    }
+
+    #[test]
+    fn test_last_issue_key() {
+        let mut history = History::default();
+        assert_eq!(history.last_issue_key(), None);
+        history.entries.push(HistoryEntry {
+            issue_key: "IMG-1".to_string(),
+            started: "2025-01-08T09:00:00+00:00[UTC]".parse().unwrap(),
+            time_spent_s: 900,
+            worklog_id: None,
+            logged_as: None,
+            submitted: None,
+            comment: None,
+        });
+        history.pending.push(Session {
+            issue_key: "IMG-2".to_string(),
+            started: "2025-01-08T10:00:00+00:00[UTC]".parse().unwrap(),
+            duration_s: 900,
+            comment: None,
+            account: None,
+        });
+        assert_eq!(history.last_issue_key().as_deref(), Some("IMG-2"));
+    }
+}
@@ -0,0 +1,121 @@
+use std::{collections::BTreeSet, fs};
+
+use jiff::{
+    civil::{Date, Weekday},
+    ToSpan,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{calendar, config::Config};
+
+/// Days off in the config, a single day like `2025-12-24` or a range like
+/// `2025-12-24..2026-01-02` including both ends.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct DaysOff {
+    pub first: Date,
+    pub last: Date,
+}
+
+impl TryFrom<String> for DaysOff {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let (first, last) = value.split_once("..").unwrap_or((&value, &value));
+        let parse = |date: &str| {
+            date.trim()
+                .parse::<Date>()
+                .map_err(|_| format!("{value} is not a day like 2025-12-24 or a range like 2025-12-24..2025-12-31"))
+        };
+        let (first, last) = (parse(first)?, parse(last)?);
+        if last < first {
+            return Err(format!("{value} ends before it starts"));
+        }
+        Ok(DaysOff { first, last })
+    }
+}
+
+impl From<DaysOff> for String {
+    fn from(days_off: DaysOff) -> Self {
+        if days_off.first == days_off.last {
+            days_off.first.to_string()
+        } else {
+            format!("{}..{}", days_off.first, days_off.last)
+        }
+    }
+}
+
+/// Holidays and time off, which count towards no target.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Holidays {
+    days: BTreeSet<Date>,
+}
+
+impl Holidays {
+    pub fn new(days_off: &[DaysOff], dates: impl IntoIterator<Item = Date>) -> Holidays {
+        let mut days: BTreeSet<Date> = dates.into_iter().collect();
+        for days_off in days_off {
+            days.extend(days_off.first.series(1.day()).take_while(|day| *day <= days_off.last));
+        }
+        Holidays { days }
+    }
+
+    /// The configured days off and the all-day events of the holidays calendar. An
+    /// unreadable calendar is left out.
+    pub fn from_config(config: &Config) -> Holidays {
+        let ics = config
+            .holidays_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        Holidays::new(&config.holidays, calendar::all_day_dates(&ics))
+    }
+
+    /// Whether the day is a weekday that is not off.
+    pub fn is_workday(&self, date: Date) -> bool {
+        !matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday) && !self.days.contains(&date)
+    }
+
+    /// Number of working days from `first` to `last`, both included.
+    pub fn workdays(&self, first: Date, last: Date) -> usize {
+        first
+            .series(1.day())
+            .take_while(|day| *day <= last)
+            .filter(|day| self.is_workday(*day))
+            .count()
+    }
+
+    /// Number of weekdays off from `first` to `last`, both included.
+    pub fn days_off(&self, first: Date, last: Date) -> usize {
+        self.days
+            .range(first..=last)
+            .filter(|day| !matches!(day.weekday(), Weekday::Saturday | Weekday::Sunday))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_days_off() {
+        let days_off = |value: &str| DaysOff::try_from(value.to_string());
+        assert_eq!(String::from(days_off("2025-12-24").unwrap()), "2025-12-24");
+        assert_eq!(String::from(days_off("2025-12-24..2026-01-02").unwrap()), "2025-12-24..2026-01-02");
+        assert!(days_off("2026-01-02..2025-12-24").is_err());
+        assert!(days_off("christmas").is_err());
+    }
+
+    #[test]
+    fn test_workdays() {
+        let christmas = DaysOff::try_from("2025-12-24..2025-12-26".to_string()).unwrap();
+        let holidays = Holidays::new(&[christmas], ["2025-12-31".parse().unwrap()]);
+        let (first, last) = ("2025-12-22".parse().unwrap(), "2026-01-04".parse().unwrap());
+        // Ten weekdays, of which four around Christmas and new year's eve are off.
+        assert_eq!(holidays.workdays(first, last), 6);
+        assert_eq!(holidays.days_off(first, last), 4);
+        assert!(!holidays.is_workday("2025-12-25".parse().unwrap()));
+        assert!(holidays.is_workday("2025-12-29".parse().unwrap()));
+    }
+}
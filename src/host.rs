@@ -0,0 +1,83 @@
+use std::{env, fs, process::Command};
+
+use jiff::{Timestamp, Zoned};
+
+/// Name of this machine, telling the state written here from state synced from
+/// another machine through a shared home directory.
+pub fn name() -> String {
+    env::var("HOSTNAME")
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .or_else(|| {
+            let output = Command::new("uname").arg("-n").output().ok()?;
+            Some(String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// The timer another machine wrote to the state, `None` when it stopped one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignTimer {
+    pub host: String,
+    pub issue_key: Option<String>,
+    pub activated_on: Option<Zoned>,
+}
+
+/// What to do about a state file with another timer than the running one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StateChange {
+    Unchanged,
+    /// Written on this machine, e.g. by `jiratrack toggle`.
+    Adopt,
+    /// Another machine runs an older timer, the one here wins.
+    Keep,
+    /// Another machine started a newer timer or stopped one, which could track time
+    /// twice. Asked about.
+    Conflict,
+}
+
+/// Compares the timer in the state file with the local one, both as issue and start.
+pub fn compare(
+    local: Option<(&str, Timestamp)>,
+    file: Option<(&str, Timestamp)>,
+    file_host: Option<&str>,
+    host: &str,
+) -> StateChange {
+    if local == file {
+        return StateChange::Unchanged;
+    }
+    // State from before hosts were recorded was written here.
+    if file_host.is_none_or(|file_host| file_host == host) {
+        return StateChange::Adopt;
+    }
+    match (local, file) {
+        (Some((_, local)), Some((_, file))) if file < local => StateChange::Keep,
+        _ => StateChange::Conflict,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let earlier: Timestamp = "2025-01-10T09:00:00Z".parse().unwrap();
+        let later: Timestamp = "2025-01-10T10:00:00Z".parse().unwrap();
+        let local = Some(("IMG-1", earlier));
+        let compare = |file, file_host| compare(local, file, file_host, "laptop");
+        assert_eq!(compare(local, Some("desktop")), StateChange::Unchanged);
+        assert_eq!(compare(Some(("IMG-2", later)), Some("laptop")), StateChange::Adopt);
+        assert_eq!(compare(None, None), StateChange::Adopt);
+        assert_eq!(compare(Some(("IMG-2", later)), Some("desktop")), StateChange::Conflict);
+        assert_eq!(compare(None, Some("desktop")), StateChange::Conflict);
+        let older = "2025-01-10T08:00:00Z".parse().unwrap();
+        assert_eq!(compare(Some(("IMG-2", older)), Some("desktop")), StateChange::Keep);
+        assert_eq!(
+            super::compare(None, Some(("IMG-2", later)), Some("desktop"), "laptop"),
+            StateChange::Conflict
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+};
+
+use anyhow::Result;
+use jiff::{Unit, Zoned};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calendar::{self, Event},
+    history::History,
+    jira::find_issue_key,
+    journal::Journal,
+    ledger::Ledger,
+    overlap::{self, MIN_OVERLAP_S},
+    session::Session,
+    App,
+};
+
+/// Logs calendar events whose title contains `pattern` to `issue`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarRule {
+    pub pattern: String,
+    pub issue: String,
+}
+
+/// The issue an event is logged to: a key in its title, the first matching rule
+/// or the meeting issue.
+fn issue_for(event: &Event, rules: &[CalendarRule], fallback: Option<&str>) -> Option<String> {
+    let title = event.summary.to_lowercase();
+    find_issue_key(&event.summary)
+        .or_else(|| {
+            rules
+                .iter()
+                .find(|rule| title.contains(&rule.pattern.to_lowercase()))
+                .map(|rule| rule.issue.clone())
+        })
+        .or_else(|| fallback.map(|issue| issue.to_string()))
+}
+
+impl App {
+    /// Creates worklogs for the events of an iCalendar file, after showing what
+    /// would be logged and asking for confirmation.
+    pub fn import_calendar(
+        &mut self,
+        path: &Path,
+        mut input: impl BufRead,
+        mut output: impl Write,
+    ) -> Result<()> {
+        self.history = History::load();
+        self.ledger = Ledger::load();
+        self.journal = Journal::load();
+        let work_time = self.config.work_time();
+        let locale = self.config.locale();
+        let now = Zoned::now();
+
+        let mut sessions = vec![];
+        writeln!(output, "{:<11} {:<9} {:<12} Event", "Started", "Duration", "Issue")?;
+        for event in calendar::load(path)? {
+            let issue = issue_for(
+                &event,
+                &self.config.calendar_rules,
+                self.config.meeting_issue.as_deref(),
+            );
+            let session = issue.as_ref().map(|issue| Session {
+                comment: self.worklog_comment(issue, None),
+                account: self.config.billing_account(issue),
+                ..Session::new(issue, &event.start, &event.end)
+            });
+            let logged = session.as_ref().is_some_and(|session| {
+                self.history
+                    .entries
+                    .iter()
+                    .any(|entry| entry.issue_key == session.issue_key && entry.started == session.started)
+            });
+            let issue_column = match (&issue, logged, event.end > now) {
+                (None, _, _) => "no issue".to_string(),
+                (Some(_), true, _) => "logged".to_string(),
+                (Some(_), false, true) => "not over".to_string(),
+                (Some(issue), false, false) => issue.clone(),
+            };
+            writeln!(
+                output,
+                "{:<11} {:<9} {:<12} {}",
+                locale.weekday_time(&event.start),
+                work_time.format((&event.end - &event.start).total(Unit::Second)?.max(0.0) as u64),
+                issue_column,
+                event.summary
+            )?;
+            if let (Some(session), false, false) = (session, logged, event.end > now) {
+                sessions.push(session);
+            }
+        }
+
+        if sessions.is_empty() {
+            writeln!(output, "Nothing to log.")?;
+            return Ok(());
+        }
+        let start = sessions.iter().map(|session| session.started.clone()).min().unwrap();
+        let end = sessions.iter().map(Session::ended).max().unwrap();
+        let (worklogs, unchecked) = overlap::my_worklogs(std::iter::once(&self.jira).chain(&self.instances), &start, &end);
+        for error in unchecked {
+            writeln!(output, "Warning: could not check for overlapping worklogs: {error}")?;
+        }
+        for overlap in overlap::overlaps(&sessions, &worklogs, MIN_OVERLAP_S) {
+            let session = &sessions[overlap.session];
+            writeln!(
+                output,
+                "Warning: {} at {} overlaps {} already logged to {} at {}.",
+                session.issue_key,
+                locale.weekday_time(&session.started),
+                work_time.format(overlap.overlap_s),
+                overlap.worklog.issue_key,
+                locale.weekday_time(&overlap.worklog.started)
+            )?;
+        }
+        write!(output, "Create {} worklogs? [y/N] ", sessions.len())?;
+        output.flush()?;
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+        if answer.trim() != "y" {
+            return Ok(());
+        }
+        self.jira.sync_time_zone()?;
+        for session in sessions {
+            self.ledger.record(&session);
+            match self.submit_session(&session) {
+                Ok(()) => writeln!(
+                    output,
+                    "Logged {} to {}.",
+                    work_time.format(session.duration_s),
+                    session.issue_key
+                )?,
+                Err(error) => writeln!(output, "Could not log to {}: {error}", session.issue_key)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn event(summary: &str) -> Event {
+        Event {
+            uid: String::new(),
+            summary: summary.to_string(),
+            start: "2025-01-10T09:00:00+00:00[UTC]".parse().unwrap(),
+            end: "2025-01-10T09:30:00+00:00[UTC]".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_issue_for() {
+        let rules = [CalendarRule {
+            pattern: "standup".to_string(),
+            issue: "MEET-1".to_string(),
+        }];
+        assert_eq!(issue_for(&event("Review IMG-5"), &rules, None), Some("IMG-5".to_string()));
+        assert_eq!(issue_for(&event("Daily Standup"), &rules, None), Some("MEET-1".to_string()));
+        assert_eq!(issue_for(&event("Lunch"), &rules, Some("MEET-2")), Some("MEET-2".to_string()));
+        assert_eq!(issue_for(&event("Lunch"), &rules, None), None);
+    }
+}
@@ -1,71 +1,399 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose, Engine as _};
-use jiff::{Unit, Zoned};
+use jiff::{tz::TimeZone, Timestamp, ToSpan, Unit, Zoned};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::{
+    cell::{Cell, OnceCell},
+    collections::HashMap,
+    io::Read,
+    sync::{Arc, Mutex},
+    thread,
+    time::Instant,
+};
 
-use ureq::{json, Error, Response};
+use ureq::{json, Agent, Error, Request, Response};
 
+use crate::activity::{self, Event};
+use crate::adf;
+use crate::column::{self, Column, Feature};
 use crate::config::Config;
+use crate::demo;
+use crate::dryrun;
+use crate::duration::WorkTime;
+use crate::jql::{AutocompleteData, JqlField};
+use crate::notifications::{self, Notification};
+use crate::cache::ResponseCache;
+use crate::cassette::{self, Cassette, Recorder};
+use crate::ratelimit::{self, Priority, Scheduler, MAX_RETRIES};
+use crate::permissions;
+use crate::report::FlowTimes;
+use crate::schema::{self, ISSUE, SPRINT, USER, WORKLOG};
+use crate::session::{Session, MIN_WORKLOG_S};
 
+pub const ISSUE_PAGE_SIZE: usize = 100;
+/// Most issues the Agile API lists at once.
+const AGILE_PAGE_SIZE: usize = 50;
 
 #[derive(Debug)]
 pub struct Jira {
     atlassian_url: String,
     user_email: String,
     user_api_token: String,
-    project: String
+    project: String,
+    projects: Vec<String>,
+    jql: Option<String>,
+    work_time: WorkTime,
+    board_id: Option<u64>,
+    story_points_field: Option<String>,
+    columns: Option<Vec<Column>>,
+    features: Vec<Feature>,
+    row_height: u16,
+    log_as_account_id: Option<String>,
+    time_zone_override: Option<String>,
+    time_zone: TimeZone,
+    clock_skew_s: Cell<Option<i64>>,
+    scheduler: Arc<Mutex<Scheduler>>,
+    priority: Cell<Priority>,
+    cache: ResponseCache,
+    agent: OnceCell<Agent>,
+    /// Account id of the current user, asked for once.
+    account_id: OnceCell<String>,
+    demo: bool,
+    dry_run: bool,
+    recorder: Option<Recorder>,
+    cassette: Option<Cassette>,
 }
 
 #[derive(Debug, Clone)]
+pub struct User {
+    pub account_id: String,
+    pub display_name: String,
+    pub time_zone: Option<String>,
+    /// Locale of the profile, like `en_US`.
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Issue {
     pub id: String,
     pub key: String,
     pub summary: String,
-    pub time_spent: String,
+    pub time_spent: u64,
+    pub estimate: u64,
     pub assignee: String,
+    pub assignee_id: Option<String>,
+    pub status: String,
+    pub status_category: String,
+    pub story_points: Option<f64>,
+    pub subtasks: Vec<Issue>,
+    /// Time logged on the issue and its subtasks.
+    pub aggregate_time_spent: u64,
+    pub labels: Vec<String>,
+    /// Names of the components.
+    pub components: Vec<String>,
+    /// Summary of the epic the issue belongs to.
+    pub epic: Option<String>,
+    /// Index of the instance the issue was listed from, 0 for the main one.
+    pub instance: usize,
+}
+
+impl Issue {
+    pub fn project(&self) -> &str {
+        self.key.split('-').next().unwrap_or("")
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.status_category == "done"
+    }
+
+    /// Marks the issue and its subtasks as listed from the instance at `index`.
+    pub fn set_instance(&mut self, index: usize) {
+        self.instance = index;
+        for subtask in &mut self.subtasks {
+            subtask.set_instance(index);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sprint {
+    pub id: u64,
+    pub name: String,
+    pub goal: String,
+    pub end_date: Option<Timestamp>,
+}
+
+impl Sprint {
+    /// Human readable countdown to the end of the sprint, e.g. `3 days left`.
+    pub fn countdown(&self, now: Timestamp) -> Option<String> {
+        let remaining_s = self.end_date?.as_second() - now.as_second();
+        let countdown = match remaining_s {
+            s if s < 0 => "ended".to_string(),
+            s if s < 86400 => "last day".to_string(),
+            s if s < 2 * 86400 => "1 day left".to_string(),
+            s => format!("{} days left", s / 86400),
+        };
+        Some(countdown)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Comment {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub author: String,
+    pub created: Option<Timestamp>,
+    pub body: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub id: String,
+    pub filename: String,
+    pub mime_type: String,
+}
+
+impl Attachment {
+    pub fn is_image(&self) -> bool {
+        self.mime_type.starts_with("image/")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QuickFilter {
+    pub id: u64,
+    pub name: String,
+    pub jql: String,
+}
+
+/// A saved Jira filter, usable as the source of the issue list.
+#[derive(Debug, Clone)]
+pub struct SavedFilter {
+    pub id: String,
+    pub name: String,
+    pub owner: String,
+    pub jql: String,
+}
+
+/// ANDs the given filter clauses onto a base JQL query.
+pub fn combine_jql(base: &str, filters: &[&str]) -> String {
+    if filters.is_empty() {
+        return base.to_string();
+    }
+    // Filters go before the ordering, which has to come last.
+    let (query, order) = match base.to_ascii_uppercase().find("ORDER BY") {
+        Some(index) => (base[..index].trim_end(), &base[index..]),
+        None => (base, ""),
+    };
+    let mut jql = format!("({query})");
+    for filter in filters {
+        jql = format!("{jql} AND ({filter})");
+    }
+    if !order.is_empty() {
+        jql = format!("{jql} {order}");
+    }
+    jql
+}
+
+/// Issues in the review status on which the current user is the reviewer. Custom
+/// fields can be given by name or as `cf[10050]`.
+pub fn review_queue_jql(status: &str, reviewer_field: &str) -> String {
+    let field = if reviewer_field.starts_with("cf[") {
+        reviewer_field.to_string()
+    } else {
+        format!("\"{reviewer_field}\"")
+    };
+    format!("status = \"{status}\" AND {field} = currentUser() ORDER BY updated ASC")
+}
+
+/// Open issues of the projects in the open sprints or the backlog that have no story
+/// points yet, in the order of the board.
+pub fn estimation_jql(projects: &[String], story_points_field: &str) -> String {
+    let field = match story_points_field.strip_prefix("customfield_") {
+        Some(id) => format!("cf[{id}]"),
+        None => format!("\"{story_points_field}\""),
+    };
+    format!(
+        "{} AND (sprint in openSprints() OR sprint is EMPTY) AND statusCategory != Done AND {field} is EMPTY ORDER BY Rank ASC",
+        project_jql(projects)
+    )
+}
+
+/// Fields of a new issue copying the type, description, labels and components of
+/// `issue`, under a new summary.
+pub fn clone_fields(issue: &Value, summary: &str) -> Value {
+    let source = &issue["fields"];
+    let project = issue["key"].as_str().unwrap_or_default().split('-').next().unwrap_or_default();
+    let components: Vec<Value> = source["components"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|component| json!({"id": component["id"]}))
+        .collect();
+    let mut fields = json!({
+        "project": {"key": project},
+        "issuetype": {"id": source["issuetype"]["id"]},
+        "summary": summary,
+        "labels": source["labels"].as_array().cloned().unwrap_or_default(),
+        "components": components,
+    });
+    if !source["description"].is_null() {
+        fields["description"] = source["description"].clone();
+    }
+    json!({"fields": fields})
+}
+
+fn project_jql(projects: &[String]) -> String {
+    let projects: Vec<String> = projects.iter().map(|project| format!("\"{project}\"")).collect();
+    format!("project in ({})", projects.join(", "))
+}
+
+fn demo_response(method: &str, endpoint: &str) -> Result<Response> {
+    let body = demo::response(method, endpoint);
+    if body.is_null() {
+        return Err(anyhow!("404 Issue does not exist"));
+    }
+    let body = body.to_string();
+    Ok(Response::new(200, "OK", &body)?)
 }
 
 fn create_basic_auth_header(user: &str, password: &str) -> String {
     let user_pass = String::from(user) + ":" + password;
-    String::from("Basic ") + &general_purpose::STANDARD.encode(user_pass.as_bytes())
+    format!("Basic {}", general_purpose::STANDARD.encode(user_pass.as_bytes()))
 }
 
 impl Jira {
     pub fn new() -> Self {
         let config = Config::from_config_file().unwrap();
+        Jira::from_config(&config)
+    }
+
+    pub fn from_config(config: &Config) -> Self {
         Jira {
-            atlassian_url: config.atlassian_url,
-            user_email: config.user_email,
-            user_api_token: config.user_api_token,
-            project: config.project,
+            atlassian_url: config.atlassian_url.clone(),
+            user_email: config.user_email.clone(),
+            user_api_token: config.user_api_token.clone(),
+            project: config.projects().first().cloned().unwrap_or_default(),
+            projects: config.projects(),
+            jql: config.jql.clone().filter(|jql| !jql.trim().is_empty()),
+            work_time: config.work_time(),
+            board_id: config.board_id,
+            story_points_field: config.story_points_field.clone(),
+            columns: config.columns.clone(),
+            features: config.features(),
+            row_height: config.row_height,
+            log_as_account_id: config.log_as_account_id.clone(),
+            time_zone_override: config.timezone.clone(),
+            time_zone: TimeZone::system(),
+            clock_skew_s: Cell::new(None),
+            scheduler: ratelimit::shared(&config.atlassian_url),
+            priority: Cell::new(Priority::Interactive),
+            cache: ResponseCache::default(),
+            agent: OnceCell::new(),
+            account_id: OnceCell::new(),
+            demo: config.demo,
+            dry_run: config.dry_run,
+            recorder: config
+                .record_path
+                .clone()
+                .map(|path| Recorder::new(path, &config.atlassian_url)),
+            cassette: config.replay_path.as_ref().and_then(|path| Cassette::load(path).ok()),
+        }
+    }
+
+    /// Whether writes are recorded instead of sent.
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Difference in seconds between the server clock and the local clock,
+    /// as observed on the last response carrying a `Date` header.
+    pub fn clock_skew_s(&self) -> Option<i64> {
+        self.clock_skew_s.get()
+    }
+
+    /// The project keys this instance covers.
+    pub fn projects(&self) -> &[String] {
+        &self.projects
+    }
+
+    /// Whether the issue belongs to one of the projects of this instance.
+    pub fn owns_issue(&self, issue_key: &str) -> bool {
+        let project = issue_key.split('-').next().unwrap_or("");
+        self.projects.iter().any(|p| p == project)
+    }
+
+    fn record_clock_skew(&self, response: &Response) {
+        let server_time = response
+            .header("Date")
+            .and_then(|date| jiff::fmt::rfc2822::parse(date).ok());
+        if let Some(server_time) = server_time {
+            let skew = server_time.timestamp().as_second() - Timestamp::now().as_second();
+            self.clock_skew_s.set(Some(skew));
         }
     }
 
+    /// The HTTP agent, built on first use and reused so connections are kept alive.
+    fn agent(&self) -> &Agent {
+        self.agent.get_or_init(|| {
+            ureq::AgentBuilder::new()
+                .redirect_auth_headers(ureq::RedirectAuthHeaders::SameHost)
+                .build()
+        })
+    }
+
     fn get_request(
         &self,
         endpoint: &str,
         params: Option<HashMap<String, String>>,
     ) -> Result<Response> {
+        if let Some(response) = self.replay("GET", endpoint, params.as_ref()) {
+            return response;
+        }
+        if self.demo {
+            return demo_response("GET", endpoint);
+        }
+        let recorded = cassette::endpoint(endpoint, params.as_ref());
+        let response = self.fetch(endpoint, params);
+        self.record("GET", &recorded, None, response)
+    }
+
+    fn fetch(&self, endpoint: &str, params: Option<HashMap<String, String>>) -> Result<Response> {
         let url = format!("{}{endpoint}", &self.atlassian_url);
 
         let auth_header = create_basic_auth_header(&self.user_email, &self.user_api_token);
-        let agent = ureq::AgentBuilder::new()
-            .redirect_auth_headers(ureq::RedirectAuthHeaders::SameHost)
-            .build();
-        let mut request = agent
+        let mut request = self
+            .agent()
             .get(&url)
             .set("Accept", "application/json")
             .set("Authorization", &auth_header);
 
-        if params.is_some() {
-            for (key, value) in params.unwrap().into_iter() {
+        let cache_key = ResponseCache::key(&url, params.as_ref());
+        let cached = self.cache.get(&cache_key);
+        if let Some(cached) = &cached {
+            request = request.set("If-None-Match", &cached.etag);
+        }
+
+        if let Some(params) = params {
+            for (key, value) in params.into_iter() {
                 request = request.query(&key, &value)
             }
         }
 
-        let response = request.call()?;
-        Ok(response)
+        let response = self.call(request, None)?;
+        if let (304, Some(cached)) = (response.status(), cached) {
+            return Ok(Response::new(200, "OK", &cached.body)?);
+        }
+        let etag = response.header("ETag").map(|etag| etag.to_string());
+        match etag {
+            Some(etag) if response.content_type() == "application/json" => {
+                let body = response.into_string()?;
+                let cached = Response::new(200, "OK", &body)?;
+                self.cache.insert(cache_key, etag, body);
+                Ok(cached)
+            }
+            _ => Ok(response),
+        }
     }
 
     fn post_request(
@@ -74,14 +402,42 @@ impl Jira {
         params: Option<HashMap<String, String>>,
         data: Option<Value>,
     ) -> Result<Response> {
+        self.send_request("POST", endpoint, params, data)
+    }
+
+    fn put_request(
+        &self,
+        endpoint: &str,
+        params: Option<HashMap<String, String>>,
+        data: Option<Value>,
+    ) -> Result<Response> {
+        self.send_request("PUT", endpoint, params, data)
+    }
+
+    fn send_request(
+        &self,
+        method: &str,
+        endpoint: &str,
+        params: Option<HashMap<String, String>>,
+        data: Option<Value>,
+    ) -> Result<Response> {
+        if let Some(response) = self.replay(method, endpoint, params.as_ref()) {
+            return response;
+        }
+        if self.demo {
+            return demo_response(method, endpoint);
+        }
+        if self.dry_run && dryrun::is_write(endpoint) {
+            dryrun::record(method, endpoint, data.as_ref());
+            return Ok(Response::new(200, "OK", r#"{"id":"dry-run"}"#)?);
+        }
+        let recorded = cassette::endpoint(endpoint, params.as_ref());
         let url = format!("{}{endpoint}", &self.atlassian_url);
 
         let auth_header = create_basic_auth_header(&self.user_email, &self.user_api_token);
-        let agent = ureq::AgentBuilder::new()
-            .redirect_auth_headers(ureq::RedirectAuthHeaders::SameHost)
-            .build();
-        let mut request = agent
-            .post(&url)
+        let mut request = self
+            .agent()
+            .request(method, &url)
             .set("Accept", "application/json")
             .set("Authorization", &auth_header);
 
@@ -91,20 +447,169 @@ impl Jira {
             }
         }
 
-        let response = match &data {
-            Some(data) => request.send_json(data),
-            None => request.call(),
+        let response = self.call(request, data.as_ref());
+        self.record(method, &recorded, data.as_ref(), response)
+    }
+
+    /// The recorded answer when replaying a cassette.
+    fn replay(
+        &self,
+        method: &str,
+        endpoint: &str,
+        params: Option<&HashMap<String, String>>,
+    ) -> Option<Result<Response>> {
+        let cassette = self.cassette.as_ref()?;
+        let endpoint = cassette::endpoint(endpoint, params);
+        let Some(interaction) = cassette.replay(method, &endpoint) else {
+            return Some(Err(anyhow!("404 {method} {endpoint} is not in the cassette")));
+        };
+        let body = match &interaction.response {
+            Value::String(body) => body.clone(),
+            body => body.to_string(),
+        };
+        if interaction.status >= 400 {
+            return Some(Err(anyhow!("{} {}", interaction.status, error_message(&body))));
+        }
+        Some(Response::new(interaction.status, "OK", &body).map_err(Into::into))
+    }
+
+    /// Appends the answer to the cassette when recording, Jira's refusals included.
+    fn record(
+        &self,
+        method: &str,
+        endpoint: &str,
+        data: Option<&Value>,
+        response: Result<Response>,
+    ) -> Result<Response> {
+        let Some(recorder) = &self.recorder else {
+            return response;
         };
+        match response {
+            Ok(response) => {
+                let status = response.status();
+                let body = response.into_string()?;
+                recorder.record(method, endpoint, data, status, &body);
+                Ok(Response::new(status, "OK", &body)?)
+            }
+            Err(error) => {
+                let message = error.to_string();
+                let refusal = message
+                    .split_once(' ')
+                    .and_then(|(status, message)| Some((status.parse::<u16>().ok()?, message)));
+                if let Some((status, message)) = refusal {
+                    let body = json!({ "errorMessages": [message] }).to_string();
+                    recorder.record(method, endpoint, data, status, &body);
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Sends a request through the scheduler, retrying when Jira rate limited it.
+    fn call(&self, request: Request, data: Option<&Value>) -> Result<Response> {
+        let priority = self.priority.get();
+        for _ in 0..=MAX_RETRIES {
+            let reserved = self.scheduler.lock().unwrap().reserve(priority, Instant::now());
+            let Some(delay) = reserved else {
+                return Err(self.rate_limited());
+            };
+            thread::sleep(delay);
 
-        let result = match response {
-            Ok(result) => result,
-            Err(Error::Status(_code, response)) => {
-                panic!("{} {:?}", response.into_string().unwrap(), data)
+            let response = match data {
+                Some(data) => request.clone().send_json(data),
+                None => request.clone().call(),
+            };
+            match response {
+                Ok(response) => {
+                    self.record_clock_skew(&response);
+                    self.record_rate_limit(&response);
+                    return Ok(response);
+                }
+                Err(Error::Status(429, response)) => self.record_rate_limit(&response),
+                Err(Error::Status(code, response)) => {
+                    let message = response.into_string().unwrap_or_default();
+                    return Err(anyhow!("{code} {}", error_message(&message)));
+                }
+                Err(error) => return Err(error.into()),
             }
-            _ => panic!("Request failed"),
+        }
+        Err(self.rate_limited())
+    }
+
+    fn rate_limited(&self) -> anyhow::Error {
+        match self.scheduler.lock().unwrap().limited_for(Instant::now()) {
+            Some(limited_for) => anyhow!("Rate limited by Jira, try again in {}s", limited_for.as_secs().max(1)),
+            None => anyhow!("Rate limited by Jira, try again later"),
+        }
+    }
+
+    fn record_rate_limit(&self, response: &Response) {
+        let backoff = ratelimit::backoff(
+            response.header("Retry-After"),
+            response.header("X-RateLimit-Remaining"),
+            response.header("X-RateLimit-Reset"),
+            Timestamp::now(),
+        );
+        if let Some(backoff) = backoff {
+            self.scheduler.lock().unwrap().record_limit(backoff, Instant::now());
+        }
+    }
+
+    /// Runs `f` with requests marked as background work, which yields to interactive
+    /// requests and is skipped while Jira is rate limiting.
+    pub fn in_background<T>(&self, f: impl FnOnce(&Jira) -> T) -> T {
+        let priority = self.priority.replace(Priority::Background);
+        let result = f(self);
+        self.priority.set(priority);
+        result
+    }
+
+    pub fn get_myself(&self) -> Result<User> {
+        let body: Value = self.get_request("/rest/api/3/myself", None)?.into_json()?;
+        Ok(parse_user(&body))
+    }
+
+    /// The account id of the current user, only asked for the first time.
+    pub fn my_account_id(&self) -> Result<String> {
+        if let Some(account_id) = self.account_id.get() {
+            return Ok(account_id.clone());
+        }
+        let account_id = self.get_myself()?.account_id;
+        Ok(self.account_id.get_or_init(|| account_id).clone())
+    }
+
+    /// The keys of the `permissions` the current user has in the project.
+    pub fn get_my_permissions(&self, project: &str, permissions: &[&str]) -> Result<Vec<String>> {
+        let mut params = HashMap::new();
+        params.insert("projectKey".to_string(), project.to_string());
+        params.insert("permissions".to_string(), permissions.join(","));
+        let body: Value = self
+            .get_request("/rest/api/3/mypermissions", Some(params))?
+            .into_json()?;
+        Ok(permissions::granted(&body))
+    }
+
+    /// Resolves the timezone used for worklog timestamps: the configured
+    /// override if any, otherwise the timezone of the Jira user profile.
+    pub fn sync_time_zone(&mut self) -> Result<()> {
+        let name = match &self.time_zone_override {
+            Some(name) => Some(name.clone()),
+            None => self.get_myself()?.time_zone,
         };
+        if let Some(name) = name {
+            self.time_zone = TimeZone::get(&name)?;
+        }
+        Ok(())
+    }
 
-        Ok(result)
+    /// Uses the time zone fetched elsewhere, like by [`Jira::sync_time_zone`].
+    pub fn set_time_zone(&mut self, time_zone: TimeZone) {
+        self.time_zone = time_zone;
+    }
+
+    /// Timezone of worklog timestamps, see [`Jira::sync_time_zone`].
+    pub fn time_zone(&self) -> &TimeZone {
+        &self.time_zone
     }
 
     pub fn get_issue(&self, key: &str) -> Result<Issue> {
@@ -114,21 +619,329 @@ impl Jira {
         Ok(self.parse_issue(&body))
     }
 
-    pub fn log_time(&self, issue_key: &str, started_on: &Zoned, ended_on: &Zoned) -> Result<()> {
+    /// The description of an issue in Atlassian Document Format.
+    pub fn get_issue_description(&self, key: &str) -> Result<Value> {
+        let mut params = HashMap::new();
+        params.insert("fields".to_string(), "description".to_string());
+        let body: Value = self
+            .get_request(&format!("/rest/api/3/issue/{key}"), Some(params))?
+            .into_json()?;
+        Ok(body["fields"]["description"].clone())
+    }
+
+    /// Whether Jira knows the field, which it leaves out of the issue otherwise.
+    pub fn has_field(&self, key: &str, field: &str) -> Result<bool> {
+        let mut params = HashMap::new();
+        params.insert("fields".to_string(), field.to_string());
+        let body: Value = self
+            .get_request(&format!("/rest/api/3/issue/{key}"), Some(params))?
+            .into_json()?;
+        Ok(body["fields"].get(field).is_some())
+    }
+
+    /// The issue with its changelog, to read the time in each status and the status
+    /// changes from.
+    pub fn get_changelog(&self, key: &str) -> Result<Value> {
+        let mut params = HashMap::new();
+        params.insert("fields".to_string(), "created,status".to_string());
+        params.insert("expand".to_string(), "changelog".to_string());
+        let mut issue: Value = self
+            .get_request(&format!("/rest/api/3/issue/{key}"), Some(params))?
+            .into_json()?;
+        self.complete_changelog(&mut issue)?;
+        Ok(issue)
+    }
+
+    /// The worklogs on an issue as activity events.
+    pub fn get_worklogs(&self, key: &str) -> Result<Vec<Event>> {
+        let body: Value = self
+            .get_request(&format!("/rest/api/3/issue/{key}/worklog"), None)?
+            .into_json()?;
+        for worklog in body["worklogs"].as_array().into_iter().flatten() {
+            schema::validate(&WORKLOG, worklog);
+        }
+        Ok(activity::worklogs(&body))
+    }
+
+    /// A page of comments on an issue, oldest first, together with the total count.
+    pub fn get_comments(
+        &self,
+        key: &str,
+        start_at: usize,
+        max_results: usize,
+    ) -> Result<(Vec<Comment>, usize)> {
+        let mut params = HashMap::new();
+        params.insert("startAt".to_string(), start_at.to_string());
+        params.insert("maxResults".to_string(), max_results.to_string());
+        params.insert("orderBy".to_string(), "created".to_string());
+        let body: Value = self
+            .get_request(&format!("/rest/api/3/issue/{key}/comment"), Some(params))?
+            .into_json()?;
+        let comments = body["comments"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(|comment| Comment {
+                id: comment["id"].as_str().unwrap_or("").to_string(),
+                parent_id: comment["parentId"]
+                    .as_str()
+                    .map(|id| id.to_string())
+                    .or_else(|| comment["parentId"].as_u64().map(|id| id.to_string())),
+                author: comment["author"]["displayName"]
+                    .as_str()
+                    .unwrap_or("")
+                    .to_string(),
+                created: parse_timestamp(&comment["created"]),
+                body: comment["body"].clone(),
+            })
+            .collect();
+        let total = body["total"].as_u64().unwrap_or(0) as usize;
+        Ok((comments, total))
+    }
+
+    pub fn add_comment(&self, key: &str, body: &Value) -> Result<()> {
+        let data = json!({"body": body});
+        self.post_request(&format!("/rest/api/3/issue/{key}/comment"), None, Some(data))?;
+        Ok(())
+    }
+
+    pub fn search_users(&self, query: &str) -> Result<Vec<User>> {
+        let mut params = HashMap::new();
+        params.insert("query".to_string(), query.to_string());
+        params.insert("maxResults".to_string(), "5".to_string());
+        let body: Value = self
+            .get_request("/rest/api/3/user/search", Some(params))?
+            .into_json()?;
+        let users = body
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(parse_user)
+            .collect();
+        Ok(users)
+    }
+
+    pub fn get_attachments(&self, key: &str) -> Result<Vec<Attachment>> {
+        let mut params = HashMap::new();
+        params.insert("fields".to_string(), "attachment".to_string());
+        let body: Value = self
+            .get_request(&format!("/rest/api/3/issue/{key}"), Some(params))?
+            .into_json()?;
+        let attachments = body["fields"]["attachment"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(|attachment| Attachment {
+                id: attachment["id"].as_str().unwrap_or("").to_string(),
+                filename: attachment["filename"].as_str().unwrap_or("").to_string(),
+                mime_type: attachment["mimeType"].as_str().unwrap_or("").to_string(),
+            })
+            .collect();
+        Ok(attachments)
+    }
+
+    pub fn download_attachment(&self, attachment_id: &str) -> Result<Vec<u8>> {
+        let endpoint = format!("/rest/api/3/attachment/content/{attachment_id}");
+        let mut bytes = vec![];
+        self.get_request(&endpoint, None)?
+            .into_reader()
+            .read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn get_user_display_name(&self, account_id: &str) -> Result<String> {
+        let mut params = HashMap::new();
+        params.insert("accountId".to_string(), account_id.to_string());
+        let body: Value = self.get_request("/rest/api/3/user", Some(params))?.into_json()?;
+        Ok(body["displayName"].as_str().unwrap_or(account_id).to_string())
+    }
+
+    /// Submits a worklog and returns its id, or `None` when less than a minute was tracked.
+    pub fn log_time(
+        &self,
+        issue_key: &str,
+        started_on: &Zoned,
+        ended_on: &Zoned,
+        comment: Option<&str>,
+    ) -> Result<Option<String>> {
         let time_spent_s = (ended_on - started_on).total(Unit::Second)?.floor() as u32;
-        if time_spent_s < 60 {
-            return Ok(());
+        if u64::from(time_spent_s) < MIN_WORKLOG_S {
+            return Ok(None);
         }
-        let data = json!({
+        let started_on = started_on.with_time_zone(self.time_zone.clone());
+        let mut data = json!({
             "started": started_on.strftime("%Y-%m-%dT%H:%M:%S.%3f%z").to_string(),
             "timeSpentSeconds": time_spent_s,
         });
+        if let Some(account_id) = &self.log_as_account_id {
+            data["author"] = json!({"accountId": account_id});
+        }
+        if let Some(comment) = comment {
+            data["comment"] = adf::from_text(comment);
+        }
         let endpoint = format!("/rest/api/3/issue/{issue_key}/worklog");
-        let result = self.post_request(&endpoint, None, Some(data));
-        match result {
-            Ok(_) => Ok(()),
-            Err(err) => Err(err),
+        let body: Value = self.post_request(&endpoint, None, Some(data))?.into_json()?;
+        let worklog_id = body["id"].as_str().map(|id| id.to_string());
+        // Jira Cloud ignores the author unless the instance allows logging for others,
+        // and books the work on the current user instead.
+        let author = body["author"]["accountId"].as_str();
+        if let (Some(account_id), Some(author)) = (&self.log_as_account_id, author) {
+            if author != account_id {
+                if let Some(worklog_id) = &worklog_id {
+                    self.send_request("DELETE", &format!("{endpoint}/{worklog_id}"), None, None)?;
+                }
+                return Err(anyhow!(
+                    "Jira logged the work as {author} instead of {account_id}, this instance does not permit log_as_account_id"
+                ));
+            }
+        }
+        Ok(worklog_id)
+    }
+
+    /// The worklogs of the account work is logged as that cover part of the time
+    /// between `start` and `end`, on any issue, as sessions.
+    pub fn get_my_worklogs(&self, start: &Zoned, end: &Zoned) -> Result<Vec<Session>> {
+        // Worklogs are found by their start, which may lie before `start`.
+        let from = start.checked_sub(1.day())?;
+        let account_id = match &self.log_as_account_id {
+            Some(account_id) => account_id.clone(),
+            None => self.my_account_id()?,
+        };
+        let jql = format!(
+            "worklogAuthor = \"{account_id}\" AND worklogDate >= \"{}\" AND worklogDate <= \"{}\"",
+            from.with_time_zone(self.time_zone.clone()).date(),
+            end.with_time_zone(self.time_zone.clone()).date()
+        );
+        let mut params = HashMap::new();
+        params.insert("jql".to_string(), jql);
+        params.insert("fields".to_string(), "summary".to_string());
+        params.insert("maxResults".to_string(), ISSUE_PAGE_SIZE.to_string());
+        let data: Value = self
+            .get_request("/rest/api/3/search/jql", Some(params))?
+            .into_json()?;
+
+        let mut sessions = vec![];
+        for key in data["issues"].as_array().into_iter().flatten().filter_map(|issue| issue["key"].as_str()) {
+            let mut params = HashMap::new();
+            params.insert("startedAfter".to_string(), from.timestamp().as_millisecond().to_string());
+            params.insert("startedBefore".to_string(), end.timestamp().as_millisecond().to_string());
+            let body: Value = self
+                .get_request(&format!("/rest/api/3/issue/{key}/worklog"), Some(params))?
+                .into_json()?;
+            let worklogs = body["worklogs"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|worklog| worklog["author"]["accountId"] == account_id.as_str());
+            for worklog in worklogs {
+                let Some(started) = parse_timestamp(&worklog["started"]) else {
+                    continue;
+                };
+                let started = started.to_zoned(TimeZone::system());
+                let session = Session {
+                    duration_s: worklog["timeSpentSeconds"].as_u64().unwrap_or_default(),
+                    ..Session::new(key, &started, &started)
+                };
+                if session.ended() > *start && started < *end {
+                    sessions.push(session);
+                }
+            }
+        }
+        Ok(sessions)
+    }
+
+    pub fn update_worklog(
+        &self,
+        issue_key: &str,
+        worklog_id: &str,
+        started_on: &Zoned,
+        time_spent_s: u64,
+        comment: Option<&str>,
+    ) -> Result<()> {
+        let started_on = started_on.with_time_zone(self.time_zone.clone());
+        let mut data = json!({
+            "started": started_on.strftime("%Y-%m-%dT%H:%M:%S.%3f%z").to_string(),
+            "timeSpentSeconds": time_spent_s,
+        });
+        if let Some(comment) = comment {
+            data["comment"] = adf::from_text(comment);
         }
+        let endpoint = format!("/rest/api/3/issue/{issue_key}/worklog/{worklog_id}");
+        self.put_request(&endpoint, None, Some(data))?;
+        Ok(())
+    }
+
+    /// Address of the issue in the Jira web interface.
+    pub fn browse_url(&self, issue_key: &str) -> String {
+        format!("{}/browse/{issue_key}", self.atlassian_url)
+    }
+
+    /// Permalink to a worklog, opening the issue with the worklog highlighted.
+    pub fn worklog_url(&self, issue_key: &str, worklog_id: &str) -> String {
+        format!("{}?focusedWorklogId={worklog_id}", self.browse_url(issue_key))
+    }
+
+    pub fn log_as_account_id(&self) -> Option<&str> {
+        self.log_as_account_id.as_deref()
+    }
+
+    /// Creates a copy of the issue under a new summary, returning the key of the copy.
+    pub fn clone_issue(&self, issue_key: &str, summary: &str) -> Result<String> {
+        let mut params = HashMap::new();
+        params.insert(
+            "fields".to_string(),
+            "issuetype,description,labels,components".to_string(),
+        );
+        let issue: Value = self
+            .get_request(&format!("/rest/api/3/issue/{issue_key}"), Some(params))?
+            .into_json()?;
+        let data = clone_fields(&issue, summary);
+        let created: Value = self.post_request("/rest/api/3/issue", None, Some(data))?.into_json()?;
+        Ok(created["key"].as_str().unwrap_or_default().to_string())
+    }
+
+    pub fn get_unestimated_issues(&self) -> Result<Vec<Issue>> {
+        let field = self.story_points_field()?;
+        self.get_issues_jql(&estimation_jql(&self.projects, field))
+    }
+
+    /// Writes the story points to the configured field.
+    pub fn set_story_points(&self, issue_key: &str, points: f64) -> Result<()> {
+        let data = json!({"fields": {self.story_points_field()?: points}});
+        self.put_request(&format!("/rest/api/3/issue/{issue_key}"), None, Some(data))?;
+        Ok(())
+    }
+
+    fn story_points_field(&self) -> Result<&str> {
+        self.story_points_field
+            .as_deref()
+            .ok_or_else(|| anyhow!("Set story_points_field in the config to estimate issues"))
+    }
+
+    pub fn assign_issue(&self, issue_key: &str, account_id: &str) -> Result<()> {
+        let data = json!({"accountId": account_id});
+        self.put_request(&format!("/rest/api/3/issue/{issue_key}/assignee"), None, Some(data))?;
+        Ok(())
+    }
+
+    /// Moves the issue to the status through the first transition leading there.
+    pub fn transition_issue(&self, issue_key: &str, status: &str) -> Result<()> {
+        let endpoint = format!("/rest/api/3/issue/{issue_key}/transitions");
+        let body: Value = self.get_request(&endpoint, None)?.into_json()?;
+        let transition_id = body["transitions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|transition| {
+                transition["to"]["name"]
+                    .as_str()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(status))
+            })
+            .and_then(|transition| transition["id"].as_str())
+            .ok_or_else(|| anyhow!("{issue_key} can't be moved to {status}"))?;
+        let data = json!({"transition": {"id": transition_id}});
+        self.post_request(&endpoint, None, Some(data))?;
+        Ok(())
     }
 
     pub fn assign_to_current_user(&self, issue_key: &str) -> Result<()> {
@@ -139,50 +952,533 @@ impl Jira {
         Ok(())
     }
 
-    fn get_issues_jql(&self, jql: &str) -> Result<Vec<Issue>> {
+    /// Ranks `issue_key` directly before (`before = true`) or after `other_key` on the board.
+    pub fn rank_issue(&self, issue_key: &str, other_key: &str, before: bool) -> Result<()> {
+        let position = if before {
+            "rankBeforeIssue"
+        } else {
+            "rankAfterIssue"
+        };
+        let data = json!({"issues": [issue_key], position: other_key});
+        self.put_request("/rest/agile/1.0/issue/rank", None, Some(data))?;
+        Ok(())
+    }
+
+    pub fn get_jql_autocomplete_data(&self) -> Result<AutocompleteData> {
+        let body: Value = self
+            .get_request("/rest/api/3/jql/autocompletedata", None)?
+            .into_json()?;
+        let fields = body["visibleFieldNames"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(|field| JqlField {
+                value: field["value"].as_str().unwrap_or("").to_string(),
+                operators: field["operators"]
+                    .as_array()
+                    .unwrap_or(&vec![])
+                    .iter()
+                    .filter_map(|operator| Some(operator.as_str()?.to_string()))
+                    .collect(),
+            })
+            .collect();
+        let functions = body["visibleFunctionNames"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .filter_map(|function| Some(function["value"].as_str()?.to_string()))
+            .collect();
+        Ok(AutocompleteData { fields, functions })
+    }
+
+    pub fn get_jql_value_suggestions(&self, field: &str, value: &str) -> Result<Vec<String>> {
+        let mut params = HashMap::new();
+        params.insert("fieldName".to_string(), field.to_string());
+        params.insert("fieldValue".to_string(), value.to_string());
+        let body: Value = self
+            .get_request("/rest/api/3/jql/autocompletedata/suggestions", Some(params))?
+            .into_json()?;
+        let suggestions = body["results"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .filter_map(|result| Some(result["value"].as_str()?.to_string()))
+            .collect();
+        Ok(suggestions)
+    }
+
+    /// Validates a JQL query, returning Jira's error messages.
+    pub fn validate_jql(&self, jql: &str) -> Result<Vec<String>> {
+        let mut params = HashMap::new();
+        params.insert("validation".to_string(), "strict".to_string());
+        let data = json!({"queries": [jql]});
+        let body: Value = self
+            .post_request("/rest/api/3/jql/parse", Some(params), Some(data))?
+            .into_json()?;
+        let errors = body["queries"][0]["errors"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .filter_map(|error| Some(error.as_str()?.to_string()))
+            .collect();
+        Ok(errors)
+    }
+
+    pub fn get_issues_jql(&self, jql: &str) -> Result<Vec<Issue>> {
+        Ok(self.get_issues_page(jql, None)?.0)
+    }
+
+    /// One page of issues matching the JQL, with the token of the next page if there is one.
+    pub fn get_issues_page(
+        &self,
+        jql: &str,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<Issue>, Option<String>)> {
         let mut params = HashMap::new();
         params.insert("jql".to_string(), jql.to_string());
-        params.insert(
-            "fields".to_string(),
-            "id,summary,key,timetracking,assignee".to_string(),
-        );
+        params.insert("fields".to_string(), self.list_fields());
+        params.insert("maxResults".to_string(), ISSUE_PAGE_SIZE.to_string());
+        if let Some(page_token) = page_token {
+            params.insert("nextPageToken".to_string(), page_token.to_string());
+        }
         let data: serde_json::Value = self
             .get_request("/rest/api/3/search/jql", Some(params))?
             .into_json()?;
 
         let issues = data["issues"]
             .as_array()
-            .unwrap()
+            .ok_or_else(|| anyhow!("Jira listed no issues"))?
             .iter()
             .map(|issue| self.parse_issue(issue))
             .collect();
+        let next_page_token = data["nextPageToken"].as_str().map(|token| token.to_string());
 
-        Ok(issues)
+        Ok((issues, next_page_token))
+    }
+
+    /// Fields for the issue list, limited to the configured columns and features if
+    /// columns are set.
+    fn list_fields(&self) -> String {
+        match &self.columns {
+            Some(columns) => column::fields(columns, &self.features, self.row_height),
+            None => self.issue_fields(),
+        }
+    }
+
+    fn issue_fields(&self) -> String {
+        let mut fields =
+            "id,summary,key,timetracking,aggregatetimespent,assignee,status,subtasks,labels,components,parent"
+                .to_string();
+        if let Some(story_points_field) = &self.story_points_field {
+            fields += &format!(",{story_points_field}");
+        }
+        fields
+    }
+
+    /// Assignments, mentions and comments concerning the current user since `since`,
+    /// on issues they are assigned to, reported or watch.
+    pub fn get_notifications(&self, since: Timestamp) -> Result<Vec<Notification>> {
+        let account_id = self.my_account_id()?;
+        let minutes = (Timestamp::now().as_second() - since.as_second()) / 60 + 1;
+        let jql = format!(
+            "updated >= -{minutes}m AND (assignee = currentUser() OR reporter = currentUser() OR watcher = currentUser())"
+        );
+        let mut params = HashMap::new();
+        params.insert("jql".to_string(), jql);
+        params.insert("fields".to_string(), "summary,assignee,reporter,comment".to_string());
+        params.insert("expand".to_string(), "changelog".to_string());
+        let data: Value = self
+            .get_request("/rest/api/3/search/jql", Some(params))?
+            .into_json()?;
+        let mut notifications: Vec<Notification> = data["issues"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .flat_map(|issue| notifications::from_issue(issue, &account_id, since))
+            .collect();
+        notifications.sort_by_key(|notification| notification.created);
+        Ok(notifications)
+    }
+
+    /// Issues assigned to the current user that were moved to `status` after `since`
+    /// and are still in it.
+    pub fn get_started_issues(&self, status: &str, since: Timestamp) -> Result<Vec<Issue>> {
+        let minutes = (Timestamp::now().as_second() - since.as_second()) / 60 + 1;
+        let jql = format!(
+            "assignee = currentUser() AND status = \"{status}\" AND status CHANGED TO \"{status}\" AFTER -{minutes}m"
+        );
+        Ok(self.get_issues_page(&jql, None)?.0)
+    }
+
+    /// Comments and status changes by others on an issue after `since`. Only the
+    /// `updated` field is fetched as long as nothing changed.
+    pub fn get_issue_changes(&self, key: &str, since: Timestamp) -> Result<Vec<Notification>> {
+        let endpoint = format!("/rest/api/3/issue/{key}");
+        let mut params = HashMap::new();
+        params.insert("fields".to_string(), "updated".to_string());
+        let issue: Value = self.get_request(&endpoint, Some(params))?.into_json()?;
+        if parse_timestamp(&issue["fields"]["updated"]).is_none_or(|updated| updated <= since) {
+            return Ok(vec![]);
+        }
+        let account_id = self.my_account_id()?;
+        let mut params = HashMap::new();
+        params.insert("fields".to_string(), "summary,comment".to_string());
+        params.insert("expand".to_string(), "changelog".to_string());
+        let issue: Value = self.get_request(&endpoint, Some(params))?.into_json()?;
+        Ok(notifications::from_active_issue(&issue, &account_id, since))
+    }
+
+    /// All issues of a sprint, including the ones that are already done.
+    pub fn get_sprint_issues(&self, sprint_id: u64) -> Result<Vec<Issue>> {
+        let mut params = HashMap::new();
+        params.insert("fields".to_string(), self.issue_fields());
+        let issues = self.get_agile_issues(&format!("/rest/agile/1.0/sprint/{sprint_id}/issue"), params)?;
+        Ok(issues.iter().map(|issue| self.parse_issue(issue)).collect())
+    }
+
+    /// The issues listed by an Agile endpoint, page by page. Jira answers with fewer
+    /// issues than asked for, so pages continue where the last one ended.
+    fn get_agile_issues(&self, endpoint: &str, params: HashMap<String, String>) -> Result<Vec<Value>> {
+        let mut issues = vec![];
+        loop {
+            let mut params = params.clone();
+            params.insert("startAt".to_string(), issues.len().to_string());
+            params.insert("maxResults".to_string(), AGILE_PAGE_SIZE.to_string());
+            let data: Value = self.get_request(endpoint, Some(params))?.into_json()?;
+            let page = data["issues"]
+                .as_array()
+                .ok_or_else(|| anyhow!("Jira listed no issues"))?;
+            issues.extend(page.iter().cloned());
+            let total = data["total"].as_u64().unwrap_or(0) as usize;
+            if page.is_empty() || issues.len() >= total {
+                return Ok(issues);
+            }
+        }
+    }
+
+    /// Lead and cycle times of the resolved issues of a sprint.
+    pub fn get_sprint_flow_times(&self, sprint_id: u64) -> Result<Vec<FlowTimes>> {
+        let mut params = HashMap::new();
+        params.insert("fields".to_string(), "summary,created,resolutiondate".to_string());
+        params.insert("expand".to_string(), "changelog".to_string());
+        let mut issues = self.get_agile_issues(&format!("/rest/agile/1.0/sprint/{sprint_id}/issue"), params)?;
+        for issue in issues.iter_mut().filter(|issue| !issue["fields"]["resolutiondate"].is_null()) {
+            self.complete_changelog(issue)?;
+        }
+        Ok(issues.iter().filter_map(FlowTimes::from_issue).collect())
+    }
+
+    /// Fetches the rest of the changelog of an issue when the issue came with only
+    /// its first histories.
+    fn complete_changelog(&self, issue: &mut Value) -> Result<()> {
+        let changelog = &issue["changelog"];
+        let total = changelog["total"].as_u64().unwrap_or(0) as usize;
+        if changelog["histories"].as_array().is_none_or(|histories| histories.len() >= total) {
+            return Ok(());
+        }
+        let key = issue["key"].as_str().unwrap_or_default().to_string();
+        let mut histories = vec![];
+        loop {
+            let mut params = HashMap::new();
+            params.insert("startAt".to_string(), histories.len().to_string());
+            params.insert("maxResults".to_string(), ISSUE_PAGE_SIZE.to_string());
+            let page: Value = self
+                .get_request(&format!("/rest/api/3/issue/{key}/changelog"), Some(params))?
+                .into_json()?;
+            let values = page["values"].as_array().cloned().unwrap_or_default();
+            let done = values.is_empty() || page["isLast"].as_bool().unwrap_or(true);
+            histories.extend(values);
+            if done || histories.len() >= total {
+                break;
+            }
+        }
+        issue["changelog"]["histories"] = Value::Array(histories);
+        Ok(())
     }
 
-    fn parse_issue(&self, issue: &serde_json::Value) -> Issue {
-        Issue {
-            id: issue["id"].as_str().unwrap().to_string(),
-            key: issue["key"].as_str().unwrap().to_string(),
-            summary: issue["fields"]["summary"].as_str().unwrap().to_string(),
-            time_spent: issue["fields"]["timetracking"]["timeSpent"]
-                .as_str()
-                .unwrap_or("0h")
-                .to_owned(),
-            assignee: issue["fields"]["assignee"]["displayName"]
-                .as_str()
-                .unwrap_or("")
-                .to_owned(),
+    pub fn parse_issue(&self, issue: &serde_json::Value) -> Issue {
+        issue_from_json(issue, self.story_points_field.as_deref(), &self.work_time)
+    }
+
+    /// Returns the configured board, or the first Scrum board of the project.
+    pub fn get_board_id(&self) -> Result<Option<u64>> {
+        if self.board_id.is_some() {
+            return Ok(self.board_id);
         }
+        let mut params = HashMap::new();
+        params.insert("projectKeyOrId".to_string(), self.project.clone());
+        params.insert("type".to_string(), "scrum".to_string());
+        let body: Value = self
+            .get_request("/rest/agile/1.0/board", Some(params))?
+            .into_json()?;
+        Ok(body["values"][0]["id"].as_u64())
+    }
+
+    pub fn get_active_sprint(&self) -> Result<Option<Sprint>> {
+        Ok(self.get_sprints("active")?.into_iter().next())
+    }
+
+    /// Sprints of the board in the given comma separated states, e.g. `active,future`.
+    pub fn get_sprints(&self, state: &str) -> Result<Vec<Sprint>> {
+        let Some(board_id) = self.get_board_id()? else {
+            return Ok(vec![]);
+        };
+        let mut params = HashMap::new();
+        params.insert("state".to_string(), state.to_string());
+        let body: Value = self
+            .get_request(&format!("/rest/agile/1.0/board/{board_id}/sprint"), Some(params))?
+            .into_json()?;
+        Ok(body["values"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(parse_sprint)
+            .collect())
+    }
+
+    pub fn move_issue_to_sprint(&self, issue_key: &str, sprint_id: u64) -> Result<()> {
+        let data = json!({"issues": [issue_key]});
+        let endpoint = format!("/rest/agile/1.0/sprint/{sprint_id}/issue");
+        self.post_request(&endpoint, None, Some(data))?;
+        Ok(())
+    }
+
+    pub fn move_issue_to_backlog(&self, issue_key: &str) -> Result<()> {
+        let data = json!({"issues": [issue_key]});
+        self.post_request("/rest/agile/1.0/backlog/issue", None, Some(data))?;
+        Ok(())
+    }
+
+    pub fn get_quick_filters(&self) -> Result<Vec<QuickFilter>> {
+        let Some(board_id) = self.get_board_id()? else {
+            return Ok(vec![]);
+        };
+        let body: Value = self
+            .get_request(&format!("/rest/agile/1.0/board/{board_id}/quickfilter"), None)?
+            .into_json()?;
+        let quick_filters = body["values"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(|filter| QuickFilter {
+                id: filter["id"].as_u64().unwrap_or(0),
+                name: filter["name"].as_str().unwrap_or("").to_string(),
+                jql: filter["jql"].as_str().unwrap_or("").to_string(),
+            })
+            .collect();
+        Ok(quick_filters)
+    }
+
+    pub fn get_favourite_filters(&self) -> Result<Vec<SavedFilter>> {
+        let body: Value = self
+            .get_request("/rest/api/3/filter/favourite", None)?
+            .into_json()?;
+        let filters = body
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(|filter| SavedFilter {
+                id: filter["id"].as_str().unwrap_or("").to_string(),
+                name: filter["name"].as_str().unwrap_or("").to_string(),
+                owner: filter["owner"]["displayName"].as_str().unwrap_or("").to_string(),
+                jql: filter["jql"].as_str().unwrap_or("").to_string(),
+            })
+            .collect();
+        Ok(filters)
     }
 
     pub fn get_current_sprint_issues(&self) -> Result<Vec<Issue>> {
-        let jql = format!("sprint in openSprints() AND project = \"{}\" AND status != done AND status != archived", self.project);
+        self.get_filtered_sprint_issues(&[])
+    }
+
+    /// Issues of the configured query, by default the open sprints of the projects.
+    pub fn get_filtered_sprint_issues(&self, filters: &[&str]) -> Result<Vec<Issue>> {
+        let jql = match &self.jql {
+            Some(jql) => combine_jql(jql, filters),
+            None => {
+                let jql = format!("sprint in openSprints() AND {} AND status != done AND status != archived", project_jql(&self.projects));
+                combine_jql(&jql, filters) + " ORDER BY Rank ASC"
+            }
+        };
         let issues = self.get_issues_jql(&jql)?;
         Ok(issues)
     }
 }
 
+/// An issue as returned by the search and issue endpoints.
+pub fn issue_from_json(issue: &Value, story_points_field: Option<&str>, work_time: &WorkTime) -> Issue {
+    schema::validate(&ISSUE, issue);
+    Issue {
+        id: match &issue["id"] {
+            Value::Number(id) => id.to_string(),
+            id => id.as_str().unwrap_or("").to_string(),
+        },
+        key: issue["key"].as_str().unwrap_or("").to_string(),
+        summary: issue["fields"]["summary"].as_str().unwrap_or("").to_string(),
+        time_spent: parse_time_spent(&issue["fields"]["timetracking"], work_time),
+        estimate: issue["fields"]["timetracking"]["originalEstimateSeconds"]
+            .as_u64()
+            .unwrap_or(0),
+        assignee: issue["fields"]["assignee"]["displayName"]
+            .as_str()
+            .unwrap_or("")
+            .to_owned(),
+        assignee_id: issue["fields"]["assignee"]["accountId"]
+            .as_str()
+            .map(|account_id| account_id.to_string()),
+        status: issue["fields"]["status"]["name"]
+            .as_str()
+            .unwrap_or("")
+            .to_owned(),
+        status_category: issue["fields"]["status"]["statusCategory"]["key"]
+            .as_str()
+            .unwrap_or("")
+            .to_owned(),
+        story_points: story_points_field.and_then(|field| issue["fields"][field].as_f64()),
+        subtasks: issue["fields"]["subtasks"]
+            .as_array()
+            .map(|subtasks| {
+                subtasks
+                    .iter()
+                    .map(|subtask| issue_from_json(subtask, story_points_field, work_time))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        aggregate_time_spent: issue["fields"]["aggregatetimespent"].as_u64().unwrap_or(0),
+        labels: issue["fields"]["labels"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|label| Some(label.as_str()?.to_string()))
+            .collect(),
+        components: issue["fields"]["components"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|component| Some(component["name"].as_str()?.to_string()))
+            .collect(),
+        epic: Some(&issue["fields"]["parent"])
+            .filter(|parent| parent["fields"]["issuetype"]["hierarchyLevel"] == 1)
+            .and_then(|parent| Some(parent["fields"]["summary"].as_str()?.to_string())),
+        instance: 0,
+    }
+}
+
+fn parse_time_spent(timetracking: &Value, work_time: &WorkTime) -> u64 {
+    timetracking["timeSpentSeconds"]
+        .as_u64()
+        .or_else(|| work_time.parse(timetracking["timeSpent"].as_str()?))
+        .unwrap_or(0)
+}
+
+/// The first issue key in the text, like `IMG-123` in `feature/IMG-123-login`.
+pub fn find_issue_key(text: &str) -> Option<String> {
+    text.match_indices('-').find_map(|(dash, _)| {
+        let project_start = text[..dash]
+            .rfind(|c: char| !c.is_ascii_alphanumeric())
+            .map_or(0, |index| index + text[index..].chars().next().unwrap().len_utf8());
+        let project = &text[project_start..dash];
+        let rest = &text[dash + 1..];
+        let number = &rest[..rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len())];
+        let is_key = project.starts_with(|c: char| c.is_ascii_uppercase())
+            && project.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            && !number.is_empty()
+            && !rest[number.len()..].starts_with(|c: char| c.is_ascii_alphanumeric());
+        is_key.then(|| format!("{project}-{number}"))
+    })
+}
+
+/// Turns typed input into an issue key: a bare number like `237` gets the project
+/// prefixed and `img-237` is uppercased. `None` if it is no issue key.
+pub fn normalize_key(input: &str, project: &str) -> Option<String> {
+    let input = input.trim();
+    let key = if !input.is_empty() && input.chars().all(|c| c.is_ascii_digit()) {
+        format!("{project}-{input}")
+    } else {
+        input.to_ascii_uppercase()
+    };
+    find_issue_key(&key).filter(|found| *found == key)
+}
+
+/// The keys among `candidates` one typo away from `key`, or with the same number in
+/// another project.
+pub fn close_keys(key: &str, candidates: &[String]) -> Vec<String> {
+    let number = key.split_once('-').map(|(_, number)| number);
+    candidates
+        .iter()
+        .filter(|candidate| *candidate != key)
+        .filter(|candidate| {
+            edit_distance(key, candidate) <= 1 || candidate.split_once('-').map(|(_, n)| n) == number
+        })
+        .cloned()
+        .collect()
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Parses Jira timestamps such as `2025-01-10T14:32:00.000+0100`.
+pub fn parse_timestamp(value: &Value) -> Option<Timestamp> {
+    let value = value.as_str()?;
+    value
+        .parse()
+        .ok()
+        .or_else(|| jiff::fmt::strtime::parse("%Y-%m-%dT%H:%M:%S%.f%z", value).ok()?.to_timestamp().ok())
+}
+
+fn parse_user(user: &Value) -> User {
+    schema::validate(&USER, user);
+    User {
+        account_id: user["accountId"].as_str().unwrap_or("").to_string(),
+        display_name: user["displayName"].as_str().unwrap_or("").to_string(),
+        time_zone: user["timeZone"].as_str().map(|tz| tz.to_string()),
+        locale: user["locale"].as_str().map(|locale| locale.to_string()),
+    }
+}
+
+fn parse_sprint(sprint: &Value) -> Sprint {
+    schema::validate(&SPRINT, sprint);
+    Sprint {
+        id: sprint["id"].as_u64().unwrap_or(0),
+        name: sprint["name"].as_str().unwrap_or("").to_string(),
+        goal: sprint["goal"].as_str().unwrap_or("").to_string(),
+        end_date: sprint["endDate"].as_str().and_then(|date| date.parse().ok()),
+    }
+}
+
+/// The error messages of a Jira error response, or the raw body if it has none.
+fn error_message(body: &str) -> String {
+    let Ok(body) = serde_json::from_str::<Value>(body) else {
+        return body.to_string();
+    };
+    let mut messages: Vec<String> = body["errorMessages"]
+        .as_array()
+        .unwrap_or(&vec![])
+        .iter()
+        .filter_map(|message| Some(message.as_str()?.to_string()))
+        .collect();
+    if let Some(errors) = body["errors"].as_object() {
+        messages.extend(errors.values().filter_map(|error| Some(error.as_str()?.to_string())));
+    }
+    if messages.is_empty() {
+        body.to_string()
+    } else {
+        messages.join(" ")
+    }
+}
+
 impl Default for Jira {
     fn default() -> Self {
         Jira::new()
@@ -194,6 +1490,36 @@ mod test {
     use jiff::ToSpan;
 
     use super::*;
+
+    /// Jira answering from a cassette written to a temporary file, which is removed
+    /// again when dropped, also after a failed assert.
+    struct Replay {
+        path: std::path::PathBuf,
+        config: Config,
+    }
+
+    impl Replay {
+        fn new(name: &str, interactions: &[Value]) -> Replay {
+            let path = std::env::temp_dir().join(format!("jiratrack-{name}-{}.jsonl", std::process::id()));
+            let lines: Vec<String> = interactions.iter().map(Value::to_string).collect();
+            std::fs::write(&path, lines.join("\n")).unwrap();
+            let mut config = demo::config();
+            config.demo = false;
+            config.replay_path = Some(path.clone());
+            Replay { path, config }
+        }
+
+        fn jira(&self) -> Jira {
+            Jira::from_config(&self.config)
+        }
+    }
+
+    impl Drop for Replay {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
     #[test]
     fn test_get_issue() {
         let api = Jira::new();
@@ -219,7 +1545,70 @@ mod test {
         let started_on = &Zoned::now() - 10.minutes();
         let ended_on = Zoned::now();
 
-        api.log_time(&issue_key, &started_on, &ended_on).unwrap()
+        api.log_time(&issue_key, &started_on, &ended_on, None).unwrap();
+    }
+
+    #[test]
+    fn test_estimation_jql() {
+        let projects = ["IMG".to_string()];
+        let jql = estimation_jql(&projects, "customfield_10016");
+        assert!(jql.starts_with("project in (\"IMG\") AND"));
+        assert!(jql.contains("cf[10016] is EMPTY"));
+        assert!(estimation_jql(&projects, "Story Points").contains("\"Story Points\" is EMPTY"));
+    }
+
+    #[test]
+    fn test_combine_jql() {
+        assert_eq!(combine_jql("project = IMG", &[]), "project = IMG");
+        assert_eq!(
+            combine_jql("project = IMG", &["assignee = currentUser()", "labels = ui"]),
+            "(project = IMG) AND (assignee = currentUser()) AND (labels = ui)"
+        );
+        assert_eq!(
+            combine_jql("project = IMG OR labels = ui order by updated", &["assignee = currentUser()"]),
+            "(project = IMG OR labels = ui) AND (assignee = currentUser()) order by updated"
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let expected: Timestamp = "2025-01-10T13:32:00Z".parse().unwrap();
+        assert_eq!(parse_timestamp(&json!("2025-01-10T14:32:00.000+0100")), Some(expected));
+        assert_eq!(parse_timestamp(&json!("2025-01-10T13:32:00.000Z")), Some(expected));
+        assert_eq!(parse_timestamp(&json!(null)), None);
+    }
+
+    #[test]
+    fn test_error_message() {
+        let body = r#"{"errorMessages":["Issue does not exist"],"errors":{"assignee":"Not allowed"}}"#;
+        assert_eq!(error_message(body), "Issue does not exist Not allowed");
+        assert_eq!(error_message("Bad Gateway"), "Bad Gateway");
+    }
+
+    #[test]
+    fn test_project_jql() {
+        let projects = ["IMG".to_string(), "OPS".to_string()];
+        assert_eq!(project_jql(&projects), "project in (\"IMG\", \"OPS\")");
+    }
+
+    #[test]
+    fn test_sprint_countdown() {
+        let now: Timestamp = "2025-01-10T12:00:00Z".parse().unwrap();
+        let mut sprint = Sprint {
+            id: 1,
+            name: "Sprint 1".to_string(),
+            goal: "".to_string(),
+            end_date: None,
+        };
+        assert_eq!(sprint.countdown(now), None);
+        sprint.end_date = Some("2025-01-13T18:00:00Z".parse().unwrap());
+        assert_eq!(sprint.countdown(now).unwrap(), "3 days left");
+        sprint.end_date = Some("2025-01-11T18:00:00Z".parse().unwrap());
+        assert_eq!(sprint.countdown(now).unwrap(), "1 day left");
+        sprint.end_date = Some("2025-01-10T18:00:00Z".parse().unwrap());
+        assert_eq!(sprint.countdown(now).unwrap(), "last day");
+        sprint.end_date = Some("2025-01-09T18:00:00Z".parse().unwrap());
+        assert_eq!(sprint.countdown(now).unwrap(), "ended");
     }
 
     #[test]
@@ -228,4 +1617,136 @@ mod test {
         let issue_key = format!("{}-1", api.project);
         api.assign_to_current_user(&issue_key).unwrap()
     }
+
+    #[test]
+    fn test_review_queue_jql() {
+        assert_eq!(
+            review_queue_jql("In Review", "Reviewers"),
+            "status = \"In Review\" AND \"Reviewers\" = currentUser() ORDER BY updated ASC"
+        );
+        assert_eq!(
+            review_queue_jql("Code Review", "cf[10050]"),
+            "status = \"Code Review\" AND cf[10050] = currentUser() ORDER BY updated ASC"
+        );
+    }
+
+    #[test]
+    fn test_find_issue_key() {
+        assert_eq!(find_issue_key("Pairing on IMG-123, part 2"), Some("IMG-123".to_string()));
+        assert_eq!(find_issue_key("Retro (A1B-7)"), Some("A1B-7".to_string()));
+        assert_eq!(find_issue_key("Catch-up with Sam"), None);
+        assert_eq!(find_issue_key("1:1 - planning"), None);
+        assert_eq!(find_issue_key("vim feature/IMG-42-fix-login"), Some("IMG-42".to_string()));
+        assert_eq!(find_issue_key("Ärger mit IMG-7x"), None);
+    }
+
+    #[test]
+    fn test_normalize_key() {
+        assert_eq!(normalize_key("237", "IMG"), Some("IMG-237".to_string()));
+        assert_eq!(normalize_key(" img-237 ", "IMG"), Some("IMG-237".to_string()));
+        assert_eq!(normalize_key("OPS-12", "IMG"), Some("OPS-12".to_string()));
+        assert_eq!(normalize_key("IMG-237x", "IMG"), None);
+        assert_eq!(normalize_key("", "IMG"), None);
+    }
+
+    #[test]
+    fn test_close_keys() {
+        let candidates = ["IMG-237", "IMG-23", "OPS-2377", "IMG-999"].map(String::from);
+        assert_eq!(close_keys("IMG-2377", &candidates), vec!["IMG-237", "OPS-2377"]);
+        assert!(close_keys("IMG-500", &candidates).is_empty());
+    }
+
+    #[test]
+    fn test_log_time_as_other_account() {
+        let worklog = json!({"id": "10100", "author": {"accountId": "account-1"}});
+        let cassette = [
+            json!({"method": "POST", "endpoint": "/rest/api/3/issue/IMG-1/worklog", "status": 201, "response": worklog}),
+            json!({"method": "DELETE", "endpoint": "/rest/api/3/issue/IMG-1/worklog/10100", "status": 204, "response": ""}),
+        ];
+        let mut replay = Replay::new("log-as", &cassette);
+        let started_on = &Zoned::now() - 10.minutes();
+
+        replay.config.log_as_account_id = Some("account-1".to_string());
+        let jira = replay.jira();
+        let worklog_id = jira.log_time("IMG-1", &started_on, &Zoned::now(), None).unwrap();
+        assert_eq!(worklog_id.as_deref(), Some("10100"));
+
+        replay.config.log_as_account_id = Some("account-2".to_string());
+        let jira = replay.jira();
+        let error = jira.log_time("IMG-1", &started_on, &Zoned::now(), None).unwrap_err();
+        assert!(error.to_string().contains("does not permit log_as_account_id"));
+    }
+
+    #[test]
+    fn test_get_agile_issues() {
+        let issue = |key: &str| json!({"id": "1", "key": key, "fields": {"summary": key}});
+        let cassette = [
+            json!({"method": "GET", "endpoint": "/rest/agile/1.0/sprint/7/issue?maxResults=50&startAt=0", "status": 200,
+                "response": {"startAt": 0, "maxResults": 2, "total": 3, "issues": [issue("IMG-1"), issue("IMG-2")]}}),
+            json!({"method": "GET", "endpoint": "/rest/agile/1.0/sprint/7/issue?maxResults=50&startAt=2", "status": 200,
+                "response": {"startAt": 2, "maxResults": 2, "total": 3, "issues": [issue("IMG-3")]}}),
+        ];
+        let jira = Replay::new("agile", &cassette).jira();
+        let issues = jira.get_agile_issues("/rest/agile/1.0/sprint/7/issue", HashMap::new()).unwrap();
+        let keys: Vec<_> = issues.iter().map(|issue| issue["key"].as_str().unwrap()).collect();
+        assert_eq!(keys, ["IMG-1", "IMG-2", "IMG-3"]);
+    }
+
+    #[test]
+    fn test_complete_changelog() {
+        let history = |id: &str| json!({"id": id, "created": "2025-01-10T10:00:00.000+0100", "items": []});
+        let cassette = [
+            json!({"method": "GET", "endpoint": "/rest/api/3/issue/IMG-1/changelog?maxResults=100&startAt=0", "status": 200,
+                "response": {"total": 3, "isLast": false, "values": [history("1"), history("2")]}}),
+            json!({"method": "GET", "endpoint": "/rest/api/3/issue/IMG-1/changelog?maxResults=100&startAt=2", "status": 200,
+                "response": {"total": 3, "isLast": true, "values": [history("3")]}}),
+        ];
+        let jira = Replay::new("changelog", &cassette).jira();
+
+        let mut issue = json!({"key": "IMG-1", "changelog": {"total": 1, "histories": [history("1")]}});
+        jira.complete_changelog(&mut issue).unwrap();
+        assert_eq!(issue["changelog"]["histories"].as_array().unwrap().len(), 1);
+        let mut issue = json!({"key": "IMG-1", "changelog": {"total": 3, "histories": [history("1")]}});
+        jira.complete_changelog(&mut issue).unwrap();
+        let ids: Vec<_> = issue["changelog"]["histories"].as_array().unwrap().iter().map(|history| history["id"].clone()).collect();
+        assert_eq!(ids, ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_issue_from_json() {
+        let work_time = WorkTime::default();
+        let issue = json!({"id": "10001", "key": "IMG-1", "fields": {"summary": "Fix login"}});
+        let issue = issue_from_json(&issue, None, &work_time);
+        assert_eq!((issue.id.as_str(), issue.key.as_str()), ("10001", "IMG-1"));
+        assert_eq!(issue.summary, "Fix login");
+
+        let issue = json!({"id": 10002, "key": "IMG-2", "fields": {"title": "Fix logout"}});
+        let issue = issue_from_json(&issue, None, &work_time);
+        assert_eq!((issue.id.as_str(), issue.key.as_str()), ("10002", "IMG-2"));
+        assert_eq!(issue.summary, "");
+        assert!(issue_from_json(&json!({}), None, &work_time).key.is_empty());
+    }
+
+    #[test]
+    fn test_clone_fields() {
+        let issue = json!({
+            "key": "IMG-12",
+            "fields": {
+                "issuetype": {"id": "10001", "name": "Task"},
+                "description": {"type": "doc", "version": 1, "content": []},
+                "labels": ["recurring"],
+                "components": [{"id": "10200", "name": "Backend"}]
+            }
+        });
+        let data = clone_fields(&issue, "CLONE - Rotate keys");
+        assert_eq!(data["fields"]["project"]["key"], "IMG");
+        assert_eq!(data["fields"]["issuetype"]["id"], "10001");
+        assert_eq!(data["fields"]["summary"], "CLONE - Rotate keys");
+        assert_eq!(data["fields"]["labels"], json!(["recurring"]));
+        assert_eq!(data["fields"]["components"], json!([{"id": "10200"}]));
+        assert_eq!(data["fields"]["description"]["type"], "doc");
+
+        let bare = clone_fields(&json!({"key": "IMG-13", "fields": {}}), "Copy");
+        assert!(bare["fields"].get("description").is_none());
+    }
 }
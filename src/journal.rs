@@ -0,0 +1,136 @@
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+};
+
+use dirs::home_dir;
+use serde::{Deserialize, Serialize};
+
+use crate::{duration::WorkTime, session::Session};
+
+/// A write to Jira that is about to be made.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Intent {
+    LogWork(Session),
+    UpdateWorklog { worklog_id: String, session: Session },
+}
+
+impl Intent {
+    pub fn describe(&self, work_time: &WorkTime) -> String {
+        match self {
+            Intent::LogWork(session) => format!(
+                "submitting {} to {}",
+                work_time.format(session.duration_s),
+                session.issue_key
+            ),
+            Intent::UpdateWorklog { session, .. } => format!(
+                "updating worklog on {} to {}",
+                session.issue_key,
+                work_time.format(session.duration_s)
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub intent: Intent,
+}
+
+/// Writes to Jira that were started but not known to be finished.
+///
+/// Entries are written before a request is sent and removed once its outcome
+/// is known, so entries found on startup were interrupted by a crash.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Journal {
+    next_id: u64,
+    pub entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    fn get_path() -> PathBuf {
+        home_dir()
+            .unwrap()
+            .join(".local/share/jiratrack/journal.json")
+    }
+
+    pub fn load() -> Journal {
+        match File::open(Journal::get_path()) {
+            Ok(file) => serde_json::from_reader(file).expect("Invalid journal"),
+            Err(_) => Journal::default(),
+        }
+    }
+
+    fn persist(&self) {
+        let path = Journal::get_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = File::create(path).unwrap();
+        serde_json::to_writer(&file, self).unwrap();
+        file.sync_all().unwrap();
+    }
+
+    /// Records the intent and returns the id to finish it with.
+    pub fn begin(&mut self, intent: Intent) -> u64 {
+        let id = self.add(intent);
+        self.persist();
+        id
+    }
+
+    pub fn finish(&mut self, id: u64) {
+        self.remove(id);
+        self.persist();
+    }
+
+    fn add(&mut self, intent: Intent) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(JournalEntry { id, intent });
+        id
+    }
+
+    fn remove(&mut self, id: u64) -> Option<JournalEntry> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Removes an interrupted entry, to retry or discard it.
+    pub fn take(&mut self, id: u64) -> Option<JournalEntry> {
+        let entry = self.remove(id);
+        self.persist();
+        entry
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session() -> Session {
+        Session {
+            issue_key: "IMG-237".to_string(),
+            started: "2025-01-10T09:00:00+00:00[UTC]".parse().unwrap(),
+            duration_s: 5520,
+            comment: None,
+            account: None,
+        }
+    }
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut journal = Journal::default();
+        let first = journal.add(Intent::LogWork(session()));
+        let second = journal.add(Intent::LogWork(session()));
+        assert_ne!(first, second);
+        assert!(journal.remove(first).is_some());
+        assert!(journal.remove(first).is_none());
+        assert_eq!(journal.entries.len(), 1);
+        assert_eq!(journal.entries[0].id, second);
+    }
+
+    #[test]
+    fn test_describe() {
+        let intent = Intent::LogWork(session());
+        assert_eq!(intent.describe(&WorkTime::default()), "submitting 1h 32m to IMG-237");
+    }
+}
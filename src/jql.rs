@@ -0,0 +1,291 @@
+use std::time::{Duration, Instant};
+
+/// Pause in typing before Jira is asked for values matching the value being typed.
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Field names, operators and functions Jira accepts in JQL.
+#[derive(Debug, Clone, Default)]
+pub struct AutocompleteData {
+    pub fields: Vec<JqlField>,
+    pub functions: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct JqlField {
+    pub value: String,
+    pub operators: Vec<String>,
+}
+
+/// What the token under the cursor is expected to be.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expect {
+    Field,
+    Operator { field: String },
+    Value { field: String },
+    Keyword,
+}
+
+/// State of the JQL query input.
+#[derive(Debug, Clone, Default)]
+pub struct JqlInput {
+    pub input: String,
+    pub suggestions: Vec<String>,
+    pub selected: usize,
+    pub errors: Vec<String>,
+    /// When the value being typed last changed, while Jira wasn't asked for it yet.
+    typed_on: Option<Instant>,
+}
+
+impl JqlInput {
+    pub fn new(input: &str) -> Self {
+        JqlInput {
+            input: input.to_string(),
+            ..JqlInput::default()
+        }
+    }
+
+    pub fn accept_suggestion(&mut self) {
+        if let Some(suggestion) = self.suggestions.get(self.selected) {
+            self.input = complete(&self.input, suggestion);
+            self.suggestions.clear();
+            self.selected = 0;
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.suggestions.is_empty() {
+            self.selected = (self.selected + 1) % self.suggestions.len();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.suggestions.is_empty() {
+            self.selected = (self.selected + self.suggestions.len() - 1) % self.suggestions.len();
+        }
+    }
+
+    /// Notes that the input changed, so values are looked up once typing pauses.
+    pub fn typed(&mut self) {
+        let (expect, partial) = context(&self.input);
+        let typing_value = matches!(expect, Expect::Value { .. }) && !partial.is_empty();
+        self.typed_on = typing_value.then(Instant::now);
+    }
+
+    /// Whether a changed value waits for the debounce delay to pass.
+    pub fn is_pending(&self) -> bool {
+        self.typed_on.is_some()
+    }
+
+    /// The field and the partial value to ask Jira values for, once typing paused for
+    /// [`DEBOUNCE`].
+    pub fn due_value(&mut self) -> Option<(String, String)> {
+        if self.typed_on?.elapsed() < DEBOUNCE {
+            return None;
+        }
+        self.typed_on = None;
+        let (Expect::Value { field }, partial) = context(&self.input) else {
+            return None;
+        };
+        Some((field, partial.trim_start_matches(['"', '(']).to_string()))
+    }
+}
+
+const OPERATOR_WORDS: [&str; 3] = ["is", "was", "not"];
+
+/// Splits JQL into tokens, keeping quoted strings together.
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut quote = None;
+    for c in input.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => {
+                current.push(c);
+                quote = None;
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                current.push(c);
+                quote = Some(c);
+            }
+            (None, c) if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            (None, c) => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Returns what is expected at the end of `input` and the partially typed token.
+pub fn context(input: &str) -> (Expect, String) {
+    let mut tokens = tokenize(input);
+    let ends_token = input.is_empty() || input.ends_with(char::is_whitespace);
+    let partial = if ends_token {
+        String::new()
+    } else {
+        tokens.pop().unwrap_or_default()
+    };
+
+    let mut expect = Expect::Field;
+    let mut open_list = false;
+    let mut previous = String::new();
+    for token in tokens {
+        let lower = token.to_lowercase();
+        expect = match expect {
+            // `is not` is complete, whereas `not` and `was not` may still be followed by `in`.
+            Expect::Operator { field } if lower == "not" && previous == "is" => {
+                Expect::Value { field }
+            }
+            Expect::Field if lower == "order" || lower == "by" || lower.starts_with('(') => {
+                Expect::Field
+            }
+            Expect::Field if lower == "not" => Expect::Field,
+            Expect::Field => Expect::Operator {
+                field: token.trim_matches('"').to_string(),
+            },
+            Expect::Operator { field } if OPERATOR_WORDS.contains(&lower.as_str()) => {
+                Expect::Operator { field }
+            }
+            Expect::Operator { field } => Expect::Value { field },
+            Expect::Value { field } => {
+                open_list = (open_list || token.starts_with('(')) && !token.ends_with(')');
+                if open_list {
+                    Expect::Value { field }
+                } else {
+                    Expect::Keyword
+                }
+            }
+            Expect::Keyword if lower == "and" || lower == "or" || lower == "by" => Expect::Field,
+            Expect::Keyword => Expect::Keyword,
+        };
+        previous = lower;
+    }
+    (expect, partial)
+}
+
+/// Static suggestions for the current context; values have to be fetched from Jira.
+pub fn suggestions(data: &AutocompleteData, expect: &Expect, partial: &str) -> Vec<String> {
+    let partial = partial.to_lowercase();
+    let candidates: Vec<String> = match expect {
+        Expect::Field => data.fields.iter().map(|field| field.value.clone()).collect(),
+        Expect::Operator { field } => data
+            .fields
+            .iter()
+            .find(|f| f.value.eq_ignore_ascii_case(field))
+            .map(|f| f.operators.clone())
+            .unwrap_or_default(),
+        Expect::Value { .. } => data.functions.clone(),
+        Expect::Keyword => vec!["AND".to_string(), "OR".to_string(), "ORDER BY".to_string()],
+    };
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.to_lowercase().starts_with(&partial))
+        .take(8)
+        .collect()
+}
+
+/// Replaces the partially typed token at the end of `input` by `suggestion`.
+pub fn complete(input: &str, suggestion: &str) -> String {
+    let (_, partial) = context(input);
+    let base = &input[..input.len() - partial.len()];
+    let needs_quotes =
+        suggestion.contains(' ') && !suggestion.starts_with(['"', '(']) && !is_keyword(suggestion);
+    let suggestion = if needs_quotes {
+        format!("\"{suggestion}\"")
+    } else {
+        suggestion.to_string()
+    };
+    format!("{base}{suggestion} ")
+}
+
+fn is_keyword(suggestion: &str) -> bool {
+    suggestion == "ORDER BY"
+        || suggestion.split(' ').all(|word| {
+            OPERATOR_WORDS.contains(&word.to_lowercase().as_str()) || word.eq_ignore_ascii_case("in")
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn field(field: &str) -> String {
+        field.to_string()
+    }
+
+    #[test]
+    fn test_context() {
+        assert_eq!(context(""), (Expect::Field, "".to_string()));
+        assert_eq!(context("sta"), (Expect::Field, "sta".to_string()));
+        assert_eq!(
+            context("status "),
+            (Expect::Operator { field: field("status") }, "".to_string())
+        );
+        assert_eq!(
+            context("status = In"),
+            (Expect::Value { field: field("status") }, "In".to_string())
+        );
+        assert_eq!(
+            context("status is not "),
+            (Expect::Value { field: field("status") }, "".to_string())
+        );
+        assert_eq!(context("status = \"In Progress\" "), (Expect::Keyword, "".to_string()));
+        assert_eq!(
+            context("status = Done AND assignee in (a, "),
+            (Expect::Value { field: field("assignee") }, "".to_string())
+        );
+        assert_eq!(context("status = Done AND ass"), (Expect::Field, "ass".to_string()));
+        assert_eq!(context("status = Done ORDER BY "), (Expect::Field, "".to_string()));
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let data = AutocompleteData {
+            fields: vec![
+                JqlField {
+                    value: "status".to_string(),
+                    operators: vec!["=".to_string(), "!=".to_string(), "in".to_string()],
+                },
+                JqlField {
+                    value: "summary".to_string(),
+                    operators: vec!["~".to_string()],
+                },
+            ],
+            functions: vec!["currentUser()".to_string()],
+        };
+        assert_eq!(suggestions(&data, &Expect::Field, "s"), ["status", "summary"]);
+        assert_eq!(
+            suggestions(&data, &Expect::Operator { field: field("status") }, "!"),
+            ["!="]
+        );
+        assert_eq!(suggestions(&data, &Expect::Keyword, "o"), ["OR", "ORDER BY"]);
+    }
+
+    #[test]
+    fn test_complete() {
+        assert_eq!(complete("sta", "status"), "status ");
+        assert_eq!(complete("status = In", "In Progress"), "status = \"In Progress\" ");
+        assert_eq!(complete("status = Done o", "ORDER BY"), "status = Done ORDER BY ");
+    }
+
+    #[test]
+    fn test_due_value() {
+        let mut jql_input = JqlInput::new("status = \"In");
+        jql_input.typed();
+        assert!(jql_input.is_pending());
+        assert_eq!(jql_input.due_value(), None);
+        jql_input.typed_on = jql_input.typed_on.map(|typed_on| typed_on - DEBOUNCE);
+        assert_eq!(jql_input.due_value(), Some((field("status"), "In".to_string())));
+        assert!(!jql_input.is_pending());
+
+        jql_input.input = "status = ".to_string();
+        jql_input.typed();
+        assert!(!jql_input.is_pending());
+    }
+}
@@ -0,0 +1,48 @@
+use jiff::Zoned;
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Locale;
+
+/// A labelled moment within the running session, like a stopwatch lap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lap {
+    pub at: Zoned,
+    pub label: String,
+}
+
+/// Worklog comment listing the laps with their times, `None` without laps.
+pub fn comment(laps: &[Lap], locale: &Locale) -> Option<String> {
+    if laps.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = laps
+        .iter()
+        .map(|lap| format!("- {} {}", locale.time(&lap.at), lap.label))
+        .collect();
+    Some(format!("Laps:\n{}", lines.join("\n")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_comment() {
+        let locale = Locale::default();
+        let laps = [
+            Lap {
+                at: "2025-01-10T09:12:00+00:00[UTC]".parse().unwrap(),
+                label: "Reproduced the crash".to_string(),
+            },
+            Lap {
+                at: "2025-01-10T10:40:00+00:00[UTC]".parse().unwrap(),
+                label: "Found the race".to_string(),
+            },
+        ];
+        assert_eq!(
+            comment(&laps, &locale).unwrap(),
+            "Laps:\n- 09:12 Reproduced the crash\n- 10:40 Found the race"
+        );
+        assert_eq!(comment(&[], &locale), None);
+    }
+}
@@ -0,0 +1,93 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    path::PathBuf,
+};
+
+use dirs::home_dir;
+use jiff::civil::Date;
+use serde::{Deserialize, Serialize};
+
+use crate::session::Session;
+
+/// Time tracked locally per day and issue, whether or not it was submitted to Jira.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Ledger {
+    days: BTreeMap<Date, HashMap<String, u64>>,
+}
+
+impl Ledger {
+    fn get_path() -> PathBuf {
+        home_dir()
+            .unwrap()
+            .join(".local/share/jiratrack/ledger.json")
+    }
+
+    pub fn load() -> Ledger {
+        match File::open(Ledger::get_path()) {
+            Ok(file) => serde_json::from_reader(file).expect("Invalid ledger"),
+            Err(_) => Ledger::default(),
+        }
+    }
+
+    fn persist(&self) {
+        let path = Ledger::get_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = File::create(path).unwrap();
+        serde_json::to_writer(file, self).unwrap();
+    }
+
+    pub fn record(&mut self, session: &Session) {
+        self.add(session);
+        self.persist();
+    }
+
+    fn add(&mut self, session: &Session) {
+        *self
+            .days
+            .entry(session.started.date())
+            .or_default()
+            .entry(session.issue_key.clone())
+            .or_default() += session.duration_s;
+    }
+
+    /// Seconds tracked on the issue in sessions started on the given day.
+    pub fn seconds_on(&self, date: Date, issue_key: &str) -> u64 {
+        self.days
+            .get(&date)
+            .and_then(|issues| issues.get(issue_key))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Seconds tracked on all issues in sessions started on the given day.
+    pub fn total_on(&self, date: Date) -> u64 {
+        self.days.get(&date).map(|issues| issues.values().sum()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session(started: &str, duration_s: u64) -> Session {
+        Session {
+            issue_key: "IMG-237".to_string(),
+            started: started.parse().unwrap(),
+            duration_s,
+            comment: None,
+            account: None,
+        }
+    }
+
+    #[test]
+    fn test_seconds_on() {
+        let mut ledger = Ledger::default();
+        ledger.add(&session("2025-01-10T09:00:00+00:00[UTC]", 1800));
+        ledger.add(&session("2025-01-10T14:00:00+00:00[UTC]", 900));
+        ledger.add(&session("2025-01-09T09:00:00+00:00[UTC]", 3600));
+        let day = jiff::civil::date(2025, 1, 10);
+        assert_eq!(ledger.seconds_on(day, "IMG-237"), 2700);
+        assert_eq!(ledger.seconds_on(day, "IMG-1"), 0);
+    }
+}
@@ -0,0 +1,46 @@
+/// Link to an issue as `[IMG-123: summary](url)`.
+pub fn markdown(key: &str, summary: &str, url: &str) -> String {
+    let summary = summary.replace('[', "\\[").replace(']', "\\]");
+    format!("[{key}: {summary}]({url})")
+}
+
+/// Link to an issue for pasting as rich text, in Slack or documents.
+pub fn html(key: &str, summary: &str, url: &str) -> String {
+    format!(
+        "<a href=\"{}\">{}: {}</a>",
+        escape_html(url),
+        escape_html(key),
+        escape_html(summary)
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const URL: &str = "https://example.atlassian.net/browse/IMG-123";
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            markdown("IMG-123", "Fix [beta] login", URL),
+            "[IMG-123: Fix \\[beta\\] login](https://example.atlassian.net/browse/IMG-123)"
+        );
+    }
+
+    #[test]
+    fn test_html() {
+        assert_eq!(
+            html("IMG-123", "Use <b> & \"quotes\"", URL),
+            "<a href=\"https://example.atlassian.net/browse/IMG-123\">\
+             IMG-123: Use &lt;b&gt; &amp; &quot;quotes&quot;</a>"
+        );
+    }
+}
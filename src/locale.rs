@@ -0,0 +1,347 @@
+use jiff::{
+    civil::{Date, Weekday},
+    tz::TimeZone,
+    Zoned,
+};
+use serde::{Deserialize, Serialize};
+
+/// Language of the labels, English ones are used where no translation exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    En,
+    De,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Clock {
+    #[default]
+    #[serde(rename = "24h")]
+    H24,
+    #[serde(rename = "12h")]
+    H12,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Saturday,
+    Sunday,
+}
+
+impl WeekStart {
+    fn weekday(&self) -> Weekday {
+        match self {
+            WeekStart::Monday => Weekday::Monday,
+            WeekStart::Saturday => Weekday::Saturday,
+            WeekStart::Sunday => Weekday::Sunday,
+        }
+    }
+}
+
+/// German labels, keyed by their English text.
+const DE: [(&str, &str); 134] = [
+    ("Activate Issue", "Vorgang aktivieren"),
+    ("Submit Worklog", "Arbeitszeit buchen"),
+    ("Cancel Worklog", "Buchung verwerfen"),
+    ("Copy Active MR Title", "MR-Titel kopieren"),
+    ("Copy Link", "Link kopieren"),
+    ("Remind Me", "Erinnern"),
+    ("Lap", "Runde"),
+    ("Review Queue", "Review-Warteschlange"),
+    ("Clone", "Klonen"),
+    ("Search Mode", "Suchmodus"),
+    ("Refresh", "Aktualisieren"),
+    ("Distribute Time", "Zeit verteilen"),
+    ("Receipts", "Belege"),
+    ("Copy Commit Message", "Commit-Nachricht kopieren"),
+    ("Copy MR Description", "MR-Beschreibung kopieren"),
+    ("Sprint Summary", "Sprint-Übersicht"),
+    ("Workload", "Auslastung"),
+    ("Quick Filters", "Schnellfilter"),
+    ("Filter Menu", "Filtermenü"),
+    ("Rank", "Reihenfolge"),
+    ("Move to Sprint", "In Sprint verschieben"),
+    ("Switch Project", "Projekt wechseln"),
+    ("Review Sessions", "Sitzungen prüfen"),
+    ("Today's Worklogs", "Heutige Buchungen"),
+    ("Total", "Summe"),
+    ("Details", "Details"),
+    ("Saved Filters", "Gespeicherte Filter"),
+    ("Script Actions", "Skriptaktionen"),
+    ("Timeline", "Zeitleiste"),
+    ("Select Gap", "Lücke wählen"),
+    ("Backfill to", "Nachtragen auf"),
+    ("Unsubmitted", "Nicht gebucht"),
+    ("Untracked", "Nicht erfasst"),
+    ("No untracked time", "Keine Lücken"),
+    ("Statistics", "Statistik"),
+    ("days", "Tage"),
+    ("Sessions", "Sitzungen"),
+    ("on average", "im Schnitt"),
+    ("Context switches", "Kontextwechsel"),
+    ("per tracked day", "pro erfasstem Tag"),
+    ("Tracked", "Erfasst"),
+    ("Most tracked epics", "Meisterfasste Epics"),
+    ("Nothing tracked yet", "Noch nichts erfasst"),
+    ("Other", "Sonstige"),
+    ("Snooze", "Zurückstellen"),
+    ("Snoozed Issues", "Zurückgestellte Vorgänge"),
+    ("Wake", "Wieder anzeigen"),
+    ("Annotate", "Notiz"),
+    ("Save", "Speichern"),
+    ("Color", "Farbe"),
+    ("Timesheet", "Stundenzettel"),
+    ("Submit for Approval", "Zur Freigabe einreichen"),
+    ("Week", "Woche"),
+    ("Approval", "Freigabe"),
+    ("Open", "Offen"),
+    ("In Review", "In Prüfung"),
+    ("Approved", "Freigegeben"),
+    ("Rejected", "Abgelehnt"),
+    ("Days off", "Freie Tage"),
+    ("left out of the target", "nicht im Soll"),
+    ("Timer on Another Machine", "Timer auf anderem Rechner"),
+    ("Adopt", "Übernehmen"),
+    ("Ignore", "Ignorieren"),
+    ("Estimate", "Schätzen"),
+    ("Skip", "Überspringen"),
+    ("points", "Punkte"),
+    ("Story points", "Story Points"),
+    ("Submitting worklogs...", "Buchungen werden gesendet..."),
+    ("Run", "Ausführen"),
+    ("Notifications", "Benachrichtigungen"),
+    ("Assign to Me", "Mir zuweisen"),
+    ("Resize Panes", "Bereiche anpassen"),
+    ("Toggle Search Bar", "Suchleiste ein/aus"),
+    ("Quit", "Beenden"),
+    ("Close", "Schließen"),
+    ("Merge", "Zusammenführen"),
+    ("Round", "Runden"),
+    ("Delete", "Löschen"),
+    ("Submit All", "Alle buchen"),
+    ("Overlapping Worklogs", "Überschneidende Buchungen"),
+    ("Submit Anyway", "Trotzdem buchen"),
+    ("Back", "Zurück"),
+    ("Keep Separate", "Getrennt lassen"),
+    ("Submit", "Buchen"),
+    ("Cap", "Kürzen"),
+    ("Split", "Aufteilen"),
+    ("Discard", "Verwerfen"),
+    ("Current Issue", "Aktueller Vorgang"),
+    ("No issue active", "Kein Vorgang aktiv"),
+    ("Loading issues...", "Lade Vorgänge..."),
+    ("Merge Sessions", "Sitzungen zusammenführen"),
+    ("Long Worklog", "Lange Buchung"),
+    ("Closed Issue", "Geschlossener Vorgang"),
+    ("End of Day", "Feierabend"),
+    ("Submit Pending and Quit", "Offenes buchen und beenden"),
+    ("Track Anyway", "Trotzdem erfassen"),
+    ("Issue Started", "Vorgang begonnen"),
+    ("Start Timer", "Timer starten"),
+    ("Cancel", "Abbrechen"),
+    ("Project", "Projekt"),
+    ("Key", "Schlüssel"),
+    ("Time Spent", "Aufwand"),
+    ("Today", "Heute"),
+    ("Assignee", "Bearbeiter"),
+    ("Who", "Wer"),
+    ("Title", "Titel"),
+    ("Started", "Beginn"),
+    ("Submitted", "Gebucht"),
+    ("Logged For", "Gebucht für"),
+    ("Duration", "Dauer"),
+    ("Session", "Sitzung"),
+    ("Worklog", "Buchung"),
+    ("Overlap", "Überschneidung"),
+    ("This week", "Diese Woche"),
+    ("Dry run", "Probelauf"),
+    ("Team Workload", "Team-Auslastung"),
+    ("Favourite Filters", "Favoritenfilter"),
+    ("Interrupted Actions", "Unterbrochene Aktionen"),
+    ("New Comment", "Neuer Kommentar"),
+    ("JQL Query", "JQL-Abfrage"),
+    ("Mention", "Erwähnen"),
+    ("Pick", "Auswählen"),
+    ("Toggle", "Umschalten"),
+    ("Apply", "Anwenden"),
+    ("Load", "Laden"),
+    ("Sprint Issues", "Sprint-Vorgänge"),
+    ("Retry", "Wiederholen"),
+    ("Later", "Später"),
+    ("Open Issue", "Vorgang öffnen"),
+    ("Dismiss", "Ausblenden"),
+    ("Dismiss All", "Alle ausblenden"),
+    ("Move", "Verschieben"),
+    ("Complete", "Vervollständigen"),
+    ("Next", "Weiter"),
+];
+
+const DE_WEEKDAYS: [&str; 7] = ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"];
+
+/// Regions that read the time on a 12 hour clock.
+const H12_REGIONS: [&str; 6] = ["US", "CA", "AU", "NZ", "IN", "PH"];
+
+/// Regions whose weeks start on Sunday or Saturday, Monday elsewhere.
+const SUNDAY_REGIONS: [&str; 8] = ["US", "CA", "JP", "BR", "MX", "IL", "PH", "IN"];
+const SATURDAY_REGIONS: [&str; 4] = ["EG", "SA", "KW", "QA"];
+
+/// Language and date formats of the interface.
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    pub lang: Lang,
+    pub clock: Clock,
+    pub week_start: WeekStart,
+    /// Time zone times are shown in, as they were recorded when `None`.
+    pub time_zone: Option<TimeZone>,
+}
+
+impl Locale {
+    /// Formats matching a Jira profile locale like `de_DE` or `en_US`.
+    pub fn from_tag(tag: &str) -> Locale {
+        let mut parts = tag.split(['_', '-']);
+        let lang = match parts.next() {
+            Some("de") => Lang::De,
+            _ => Lang::En,
+        };
+        let region = parts.next().unwrap_or("").to_uppercase();
+        let clock = if H12_REGIONS.contains(&region.as_str()) {
+            Clock::H12
+        } else {
+            Clock::H24
+        };
+        let week_start = if SUNDAY_REGIONS.contains(&region.as_str()) {
+            WeekStart::Sunday
+        } else if SATURDAY_REGIONS.contains(&region.as_str()) {
+            WeekStart::Saturday
+        } else {
+            WeekStart::Monday
+        };
+        Locale {
+            lang,
+            clock,
+            week_start,
+            time_zone: None,
+        }
+    }
+
+    /// The label in the configured language.
+    pub fn text(&self, english: &'static str) -> &'static str {
+        let table: &[(&str, &str)] = match self.lang {
+            Lang::En => &[],
+            Lang::De => &DE,
+        };
+        table
+            .iter()
+            .find(|(key, _)| *key == english)
+            .map_or(english, |(_, text)| text)
+    }
+
+    /// The label padded for the instruction lines and titles.
+    pub fn label(&self, english: &'static str) -> String {
+        format!(" {} ", self.text(english))
+    }
+
+    /// Time of day, like `14:05` or `2:05 PM`.
+    pub fn time(&self, zoned: &Zoned) -> String {
+        let zoned = self.in_time_zone(zoned);
+        match self.clock {
+            Clock::H24 => zoned.strftime("%H:%M").to_string(),
+            Clock::H12 => zoned.strftime("%-I:%M %p").to_string(),
+        }
+    }
+
+    /// Abbreviated weekday and time of day, like `Fri 14:05`.
+    pub fn weekday_time(&self, zoned: &Zoned) -> String {
+        let zoned = self.in_time_zone(zoned);
+        let weekday = match self.lang {
+            Lang::En => zoned.strftime("%a").to_string(),
+            Lang::De => DE_WEEKDAYS[zoned.weekday().to_monday_zero_offset() as usize].to_string(),
+        };
+        format!("{weekday} {}", self.time(&zoned))
+    }
+
+    fn in_time_zone(&self, zoned: &Zoned) -> Zoned {
+        match &self.time_zone {
+            Some(time_zone) => zoned.with_time_zone(time_zone.clone()),
+            None => zoned.clone(),
+        }
+    }
+
+    /// The first day of the week containing the date.
+    pub fn week_start(&self, date: Date) -> Date {
+        let days = date.weekday().since(self.week_start.weekday());
+        date.checked_sub(jiff::Span::new().days(days)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn zoned() -> Zoned {
+        "2025-01-10T14:05:00+00:00[UTC]".parse().unwrap()
+    }
+
+    #[test]
+    fn test_text() {
+        let german = Locale {
+            lang: Lang::De,
+            ..Locale::default()
+        };
+        assert_eq!(german.text("Quit"), "Beenden");
+        assert_eq!(german.text("Not translated"), "Not translated");
+        assert_eq!(Locale::default().text("Quit"), "Quit");
+    }
+
+    #[test]
+    fn test_time() {
+        let locale = Locale::default();
+        assert_eq!(locale.weekday_time(&zoned()), "Fri 14:05");
+        let locale = Locale {
+            lang: Lang::De,
+            clock: Clock::H12,
+            week_start: WeekStart::Monday,
+            time_zone: None,
+        };
+        assert_eq!(locale.weekday_time(&zoned()), "Fr 2:05 PM");
+        let locale = Locale {
+            time_zone: Some(TimeZone::get("Asia/Tokyo").unwrap()),
+            ..Locale::default()
+        };
+        assert_eq!(locale.weekday_time(&"2025-01-10T20:30:00+00:00[UTC]".parse().unwrap()), "Sat 05:30");
+    }
+
+    #[test]
+    fn test_from_tag() {
+        let american = Locale::from_tag("en_US");
+        assert_eq!(
+            (american.lang, american.clock, american.week_start),
+            (Lang::En, Clock::H12, WeekStart::Sunday)
+        );
+        let german = Locale::from_tag("de_DE");
+        assert_eq!(
+            (german.lang, german.clock, german.week_start),
+            (Lang::De, Clock::H24, WeekStart::Monday)
+        );
+        assert_eq!(Locale::from_tag("ar-sa").week_start, WeekStart::Saturday);
+        assert_eq!(Locale::from_tag("").lang, Lang::En);
+    }
+
+    #[test]
+    fn test_week_start() {
+        let friday = zoned().date();
+        let monday = Locale::default();
+        assert_eq!(monday.week_start(friday), jiff::civil::date(2025, 1, 6));
+        let sunday = Locale {
+            week_start: WeekStart::Sunday,
+            ..Locale::default()
+        };
+        assert_eq!(sunday.week_start(friday), jiff::civil::date(2025, 1, 5));
+        let first = jiff::civil::date(2025, 1, 5);
+        assert_eq!(sunday.week_start(first), first);
+    }
+}
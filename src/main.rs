@@ -1,48 +1,488 @@
 use std::{
+    cell::OnceCell,
+    collections::BTreeMap,
+    env,
     fs::{self, File},
+    io,
     path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    sync::mpsc::Receiver,
+    time::{Duration, Instant, SystemTime},
 };
 
 use anyhow::Result;
 use arboard::Clipboard;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use dirs::home_dir;
-use fuzzy_matcher::clangd::fuzzy_match;
-use jiff::{Unit, Zoned};
-use jira::{Issue, Jira};
+use jiff::{Timestamp, ToSpan, Unit, Zoned};
+use column::Column;
+use composer::Composer;
+use config::Config;
+use facets::FacetValue;
+use focus::Focus;
+use followup::FollowUps;
+use lap::Lap;
+use locale::Locale;
+use redistribute::Redistribution;
+use permissions::{Action, Permissions};
+use detail::{DetailTab, DetailView, ImagePreview, COMMENT_PAGE_SIZE};
+use history::{History, HistoryEntry};
+use journal::{Intent, Journal};
+use ledger::Ledger;
+use jira::{Issue, Jira, QuickFilter, SavedFilter, Sprint, User};
+use jql::{AutocompleteData, JqlInput};
+use notifications::Notification;
+use overlap::{Overlap, MIN_OVERLAP_S};
+use rules::{RuleAction, RuleEvent, Trigger};
+use scripting::{ScriptOutcome, Scripts};
+use backup::Backup;
+use githook::Checkout;
+use snooze::Snoozes;
+use annotation::{Annotation, TagColor};
+use timesheet::{ApprovalStatus, Tempo, Week};
+use holidays::Holidays;
+use host::{ForeignTimer, StateChange};
+use sync::{SyncBackend, Syncer, SYNC_INTERVAL};
+use estimation::Estimation;
+use background::{IssueLoader, IssueQuery, Poll, Polled, Poller, Profile, Submission, Submitted, Submitter, SPINNER_INTERVAL};
+use statuscolor::StatusColors;
+use cassette::Cassette;
+use stats::{Stats, STATS_DAYS};
+use timeline::{SegmentKind, Timeline};
+use source::{FileSource, IssueSource, SourceConfig};
+use panes::Panes;
+use ratatui_image::picker::{Picker, ProtocolType};
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Style, Stylize},
     symbols::border,
-    text::{Line, Text},
-    widgets::{Block, Cell, Paragraph, Row, Table, TableState},
+    text::{Line, Span, Text},
+    widgets::{
+        Block, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState, Wrap,
+    },
     DefaultTerminal, Frame,
 };
 use serde::{Deserialize, Serialize};
+use signal_hook::consts::TERM_SIGNALS;
+use prefetch::{IssueDetails, Prefetcher};
+use search::IssueSearch;
+use theme::Theme;
+use timing::Timing;
+use session::Session;
+use webhook::WebhookEvent;
+use summary::{workload_by_assignee, SprintSummary};
+use wrapup::DaySummary;
 pub mod jira;
 pub mod config;
+pub mod duration;
+pub mod summary;
+pub mod history;
+pub mod session;
+pub mod reminder;
+pub mod notify;
+pub mod adf;
+pub mod detail;
+pub mod composer;
+pub mod jql;
+pub mod search;
+pub mod ratelimit;
+pub mod cache;
+pub mod column;
+pub mod prefetch;
+pub mod timing;
+pub mod journal;
+pub mod demo;
+pub mod dryrun;
+pub mod theme;
+pub mod locale;
+pub mod plain;
+pub mod link;
+pub mod template;
+pub mod ledger;
+pub mod avatar;
+pub mod panes;
+pub mod calendar;
+pub mod notifications;
+pub mod webhook;
+pub mod import;
+pub mod statustime;
+pub mod report;
+pub mod snapshot;
+pub mod followup;
+pub mod lap;
+pub mod focus;
+pub mod redistribute;
+pub mod permissions;
+pub mod wrapup;
+pub mod activity;
+pub mod facets;
+pub mod overlap;
+pub mod rules;
+pub mod scripting;
+pub mod source;
+pub mod githook;
+pub mod timeline;
+pub mod backup;
+pub mod stats;
+pub mod snooze;
+pub mod annotation;
+pub mod timesheet;
+pub mod holidays;
+pub mod host;
+pub mod sync;
+pub mod estimation;
+pub mod background;
+pub mod statuscolor;
+pub mod cassette;
+pub mod schema;
+pub mod doctor;
 
 fn main() -> Result<()> {
+    let args: Vec<String> = env::args().collect();
+    // The git hook works without a config file.
+    if args.windows(2).any(|pair| pair[0] == "githook" && pair[1] == "install-checkout") {
+        let path = githook::install_checkout()?;
+        println!("Installed {}", path.display());
+        return Ok(());
+    }
+    if let Some(branch) = args.iter().skip_while(|arg| *arg != "post-checkout").nth(1) {
+        if let Some(checkout) = Checkout::of_branch(branch, Timestamp::now()) {
+            checkout.persist()?;
+        }
+        return Ok(());
+    }
+    // Backing up and restoring read the config file without loading it.
+    if args.iter().any(|arg| arg == "backup") {
+        let path = args
+            .iter()
+            .skip_while(|arg| *arg != "backup")
+            .nth(1)
+            .map_or_else(|| format!("jiratrack-backup-{}.json", Zoned::now().date()).into(), PathBuf::from);
+        Backup::create(&backup::config_path(), &backup::data_dir())?.persist(&path)?;
+        println!("Backed up to {}, without tokens", path.display());
+        return Ok(());
+    }
+    if let Some(path) = args.iter().skip_while(|arg| *arg != "restore").nth(1) {
+        let force = args.iter().any(|arg| arg == "--force");
+        let backup = Backup::load(path.as_ref())?;
+        for path in backup.restore(&backup::config_path(), &backup::data_dir(), force)? {
+            println!("Restored {}", path.display());
+        }
+        return Ok(());
+    }
+    let report_timing = env::args().any(|arg| arg == "--timing");
+    let mut timing = Timing::default();
+    let mut config = if env::args().any(|arg| arg == "--demo") {
+        demo::config()
+    } else {
+        Config::from_config_file()?
+    };
+    config.dry_run |= env::args().any(|arg| arg == "--dry-run");
+    config.record_path = cassette::record_path();
+    config.replay_path = env::var_os("JIRATRACK_REPLAY").map(PathBuf::from);
+    if let Some(path) = &config.replay_path {
+        Cassette::load(path)?;
+    }
+    if env::args().any(|arg| arg == "--no-color") || env::var_os("NO_COLOR").is_some() {
+        config.theme = Theme::NoColor;
+    }
+    if args.iter().any(|arg| arg == "toggle") {
+        return App::new(config).toggle_plain(io::stdin().lock(), io::stdout());
+    }
+    if let Some(key) = args.iter().skip_while(|arg| *arg != "start").nth(1) {
+        return App::new(config).start_plain(key, io::stdin().lock(), io::stdout());
+    }
+    if let Some(path) = args.iter().skip_while(|arg| *arg != "import-calendar").nth(1) {
+        return App::new(config).import_calendar(path.as_ref(), io::stdin().lock(), io::stdout());
+    }
+    if args.windows(2).any(|pair| pair[0] == "report" && pair[1] == "cycle-time") {
+        let sprint = args
+            .iter()
+            .skip_while(|arg| *arg != "--sprint")
+            .nth(1)
+            .map_or("current", String::as_str);
+        return App::new(config).report_cycle_time(sprint, io::stdout());
+    }
+    if args.iter().any(|arg| arg == "doctor") {
+        return App::new(config).doctor(io::stdout());
+    }
+    if args.iter().any(|arg| arg == "export") {
+        let format = args
+            .iter()
+            .skip_while(|arg| *arg != "--format")
+            .nth(1)
+            .map_or("markdown", String::as_str);
+        return App::new(config).export_snapshot(format, io::stdout());
+    }
+    if env::args().any(|arg| arg == "--plain") {
+        return App::new(config).run_plain(io::stdin().lock(), io::stdout());
+    }
     let mut terminal = ratatui::init();
-    let app_result = App::new().run(&mut terminal);
+    timing.phase("terminal");
+    let mut app = App::new(config);
+    timing.phase("config");
+    app.timing = timing;
+    let app_result = app.run(&mut terminal);
     ratatui::restore();
+    if report_timing {
+        eprintln!("{}", app.timing.report());
+    }
     app_result
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistedState {
     active_issue: Option<String>,
     activated_on: Option<Zoned>,
+    #[serde(default)]
+    panes: Panes,
+    #[serde(default)]
+    laps: Vec<Lap>,
+    #[serde(default)]
+    annotations: BTreeMap<String, Annotation>,
+    /// Issue the timer ran for last, started again by `jiratrack toggle`.
+    #[serde(default)]
+    last_issue: Option<String>,
+    /// Machine that wrote the state, which may be synced between machines.
+    #[serde(default)]
+    host: Option<String>,
+    /// When the timer stopped, telling which machine changed it last.
+    #[serde(default)]
+    stopped_on: Option<Zoned>,
+}
+
+/// Rows rendered beyond the visible ones, and the distance from the end of the
+/// list at which the next page of results is loaded.
+const ROW_MARGIN: usize = 20;
+
+/// Terminal height below which everything is condensed into single lines.
+const COMPACT_HEIGHT: u16 = 12;
+
+/// How often the calendar file is read again.
+const CALENDAR_RELOAD: Duration = Duration::from_secs(60);
+
+/// How long the selection rests on a row before its preview is shown.
+const PREVIEW_DELAY: Duration = Duration::from_millis(500);
+
+/// How often Jira is asked for issues moved to the `auto_start_status`.
+const AUTO_START_POLL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Popup {
+    SprintSummary,
+    Workload,
+    QuickFilters,
+    SavedFilters,
+    Notifications,
+    SprintPicker,
+    Review,
+    MergeConfirm,
+    MaxDuration,
+    Detail,
+    Composer,
+    Recovery,
+    CopyLink,
+    Today,
+    FollowUp,
+    Snooze,
+    Snoozes,
+    Annotate,
+    Timesheet,
+    ForeignTimer,
+    Estimation,
+    Lap,
+    SubtaskPicker,
+    BillingAccount,
+    WorklogComment,
+    Clone,
+    Redistribute,
+    ClosedIssue,
+    Receipts,
+    EndOfDay,
+    Facets,
+    Overlaps,
+    StartOffer,
+    ScriptActions,
+    Timeline,
+    Stats,
+}
+
+/// Earlier session a newly stopped session can be merged into.
+#[derive(Debug, Clone, Copy)]
+enum MergeTarget {
+    Pending(usize),
+    Submitted(usize),
+}
+
+/// A change already shown in the issue list that still has to be sent to Jira.
+#[derive(Debug)]
+enum IssueUpdate {
+    Assign { previous: Issue, account_id: String },
+    Log { previous: Option<Issue>, session: Session },
+    Rank { key: String, other_key: String, before: bool },
 }
 
 #[derive(Debug, Default)]
 pub struct App {
+    config: Config,
     jira: Jira,
+    instances: Vec<Jira>,
+    /// Replaces Jira as the source of the issue list when set.
+    file_source: Option<FileSource>,
     issues: Vec<Issue>,
+    /// Active sprint of the main instance, then of the additional ones in order.
+    sprints: Vec<Option<Sprint>>,
+    sprint_issues: Vec<Issue>,
+    popup: Option<Popup>,
+    /// Quick filters of the boards, with the index of their instance.
+    quick_filters: Vec<(usize, QuickFilter)>,
+    active_quick_filters: Vec<(usize, u64)>,
+    quick_filter_state: ListState,
+    /// Statuses, labels and components of the loaded issues, listed in the filter menu.
+    facet_values: Vec<FacetValue>,
+    facet_state: ListState,
+    saved_filters: Vec<SavedFilter>,
+    saved_filter_state: ListState,
+    scripts: Option<Scripts>,
+    script_action_state: ListState,
+    /// Gap selected on the timeline, counted among the gaps.
+    timeline_gap: usize,
+    target_sprints: Vec<Sprint>,
+    sprint_picker_state: ListState,
+    review_state: TableState,
+    pending_merge: Option<(Session, MergeTarget)>,
+    oversized_session: Option<Session>,
+    detail: Option<DetailView>,
+    composer: Composer,
+    /// Terminal graphics support, queried when the first image is previewed.
+    picker: OnceCell<Option<Picker>>,
     search_input: String,
-    filtered_issues: Vec<Issue>,
+    jql_input: Option<JqlInput>,
+    jql_data: Option<AutocompleteData>,
+    active_jql: Option<String>,
+    project_filter: Option<String>,
+    search: IssueSearch,
 
     active_issue: Option<String>,
     activated_on: Option<Zoned>,
+    history: History,
+    ledger: Ledger,
+    journal: Journal,
+    recovery_state: ListState,
+    receipts_state: TableState,
+    /// Pending sessions covering time already logged in Jira, waiting for confirmation.
+    overlaps: Vec<Overlap>,
+    idle_since: Zoned,
+    reminded: bool,
+    /// Events of the configured calendar and when they were last read.
+    calendar: Vec<calendar::Event>,
+    calendar_loaded: Option<Instant>,
+    /// Ongoing meeting that can be logged to the meeting issue.
+    meeting: Option<calendar::Event>,
+    /// Meetings that were logged already.
+    handled_meetings: Vec<String>,
+    notified_meeting: Option<String>,
+    follow_ups: FollowUps,
+    /// Time typed in the reminder popup, like `15:00` or `30m`.
+    follow_up_input: String,
+    snoozes: Snoozes,
+    /// Day typed in the snooze popup, like `2025-02-01` or `3d`.
+    snooze_input: String,
+    snooze_state: ListState,
+    /// Local color tags and notes by issue key.
+    annotations: BTreeMap<String, Annotation>,
+    /// Annotation being edited for the selected issue.
+    annotation_draft: Annotation,
+    last_issue: Option<String>,
+    stopped_on: Option<Zoned>,
+    /// Name of this machine, written with the state.
+    host: String,
+    /// Timer of another machine found in the synced state, awaiting a decision.
+    foreign_timer: Option<ForeignTimer>,
+    /// Timer of another machine that was ignored, not asked about again.
+    ignored_timer: Option<ForeignTimer>,
+    /// Modification time of the state file when it was last checked.
+    state_modified: Option<SystemTime>,
+    /// Remote copy of the state and history, `None` without a `sync_url`.
+    sync: Option<SyncBackend>,
+    /// Syncs while the interface runs, started with it.
+    syncer: Option<Syncer>,
+    synced: Option<Instant>,
+    /// Whether the last sync failed, so the error is shown once while offline.
+    sync_failing: bool,
+    estimation: Estimation,
+    status_colors: StatusColors,
+    /// Timesheet approvals, `None` without a Tempo token.
+    tempo: Option<Tempo>,
+    timesheet: Vec<Week>,
+    timesheet_state: TableState,
+    /// Laps taken in the running session, submitted as the worklog comment.
+    laps: Vec<Lap>,
+    lap_input: String,
+    focus: Focus,
+    /// Parent whose subtasks are offered when activating it, the parent itself first.
+    subtask_parent: Option<Issue>,
+    subtask_state: ListState,
+    /// Stopped session waiting for its billing account to be entered.
+    unbilled_session: Option<Session>,
+    billing_input: String,
+    /// Stopped session waiting for its worklog comment to be entered.
+    uncommented_session: Option<Session>,
+    comment_input: String,
+    clone_input: String,
+    redistribution: Option<Redistribution>,
+    /// Summary of the day shown before quitting, and whether quitting shuts down.
+    day_summary: Option<(DaySummary, bool)>,
+    permissions: Permissions,
+    /// Closed issue waiting for confirmation before its timer starts.
+    pending_activation: Option<String>,
+    /// Issue moved to the `auto_start_status` elsewhere, offered to start the timer on,
+    /// when Jira was last asked for such issues and the keys it answered with.
+    start_offer: Option<Issue>,
+    start_offers_checked: Timestamp,
+    start_offers_seen: Vec<String>,
+    /// Issue that stayed in focus while the timer runs for another one.
+    focus_suggestion: Option<String>,
+    /// Branch of another issue checked out while the timer runs, reported by the git hook.
+    checkout_suggestion: Option<Checkout>,
+    notifications: Vec<Notification>,
+    notification_state: ListState,
+    notifications_checked: Timestamp,
+    /// When the active issue was last checked for changes by others, and the latest one.
+    active_issue_checked: Timestamp,
+    active_issue_change: Option<Notification>,
+    webhook_events: Option<Receiver<WebhookEvent>>,
+    /// When the issue list was last fetched, and whether refreshing it failed since.
+    issues_fetched: Option<Instant>,
+    refresh_failed: bool,
+    /// Issue to select once the reloading list arrived, like a fresh clone.
+    select_after_load: Option<String>,
+    /// JQL and page token of the next page of results, if more can be loaded.
+    next_page: Option<(String, String)>,
+    prefetcher: Option<Prefetcher>,
+    /// Fetches the issue list, `None` in plain mode where it is fetched right away.
+    issue_loader: Option<IssueLoader>,
+    /// Sends worklogs without blocking the interface, `None` in plain mode.
+    submitter: Option<Submitter>,
+    /// The profile fetched on startup, `None` once it arrived.
+    profile_loader: Option<Receiver<Profile>>,
+    poller: Option<Poller>,
+    loaded: bool,
+    pub timing: Timing,
+    /// Set when a termination signal is received.
+    shutdown: Arc<AtomicBool>,
+    /// Number of issue rows that fit on screen.
+    visible_rows: usize,
+    panes: Panes,
+    /// Whether the terminal is too small for the bordered layout.
+    compact: bool,
+    /// Key of the selected issue and when it was selected, for the preview.
+    hovered: Option<(String, Instant)>,
+    myself: Option<User>,
+    pending_updates: Vec<IssueUpdate>,
+    toast: Option<(String, Instant)>,
 
     table_state: TableState,
     colors: AppColor,
@@ -50,14 +490,124 @@ pub struct App {
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(config: Config) -> Self {
         App {
-            jira: Jira::new(),
+            jira: Jira::from_config(&config),
+            instances: config.instance_configs().iter().map(Jira::from_config).collect(),
+            file_source: match &config.issue_source {
+                SourceConfig::File(path) => Some(FileSource::new(path.clone(), &config)),
+                SourceConfig::Jira => None,
+            },
+            tempo: Tempo::from_config(&config),
+            status_colors: StatusColors::new(&config.status_colors),
+            sync: if config.demo { None } else { SyncBackend::from_config(&config) },
+            syncer: None,
+            config,
             issues: vec![],
-            filtered_issues: vec![],
+            sprints: vec![],
+            sprint_issues: vec![],
+            popup: None,
+            quick_filters: vec![],
+            active_quick_filters: vec![],
+            quick_filter_state: ListState::default().with_selected(Some(0)),
+            facet_values: vec![],
+            facet_state: ListState::default().with_selected(Some(0)),
+            saved_filters: vec![],
+            saved_filter_state: ListState::default().with_selected(Some(0)),
+            scripts: None,
+            script_action_state: ListState::default().with_selected(Some(0)),
+            timeline_gap: 0,
+            target_sprints: vec![],
+            sprint_picker_state: ListState::default().with_selected(Some(0)),
+            review_state: TableState::default().with_selected(Some(0)),
+            pending_merge: None,
+            oversized_session: None,
+            detail: None,
+            composer: Composer::default(),
+            picker: OnceCell::new(),
+            search: IssueSearch::default(),
             search_input: "".to_string(),
+            jql_input: None,
+            jql_data: None,
+            active_jql: None,
+            project_filter: None,
             active_issue: None,
             activated_on: None,
+            history: History::default(),
+            ledger: Ledger::default(),
+            journal: Journal::default(),
+            recovery_state: ListState::default().with_selected(Some(0)),
+            receipts_state: TableState::default(),
+            overlaps: vec![],
+            idle_since: Zoned::now(),
+            calendar: vec![],
+            calendar_loaded: None,
+            meeting: None,
+            handled_meetings: vec![],
+            notified_meeting: None,
+            follow_ups: FollowUps::default(),
+            follow_up_input: String::new(),
+            snoozes: Snoozes::default(),
+            snooze_input: String::new(),
+            snooze_state: ListState::default().with_selected(Some(0)),
+            annotations: BTreeMap::new(),
+            annotation_draft: Annotation::default(),
+            last_issue: None,
+            stopped_on: None,
+            host: host::name(),
+            foreign_timer: None,
+            ignored_timer: None,
+            state_modified: None,
+            synced: None,
+            sync_failing: false,
+            estimation: Estimation::default(),
+            timesheet: vec![],
+            timesheet_state: TableState::default(),
+            laps: vec![],
+            lap_input: String::new(),
+            focus: Focus::default(),
+            subtask_parent: None,
+            subtask_state: ListState::default().with_selected(Some(0)),
+            unbilled_session: None,
+            billing_input: String::new(),
+            uncommented_session: None,
+            comment_input: String::new(),
+            clone_input: String::new(),
+            redistribution: None,
+            day_summary: None,
+            pending_activation: None,
+            start_offer: None,
+            start_offers_checked: Timestamp::now(),
+            start_offers_seen: vec![],
+            permissions: Permissions::default(),
+            focus_suggestion: None,
+            checkout_suggestion: None,
+            reminded: false,
+            notifications: vec![],
+            notification_state: ListState::default().with_selected(Some(0)),
+            notifications_checked: Timestamp::now(),
+            active_issue_checked: Timestamp::now(),
+            active_issue_change: None,
+            webhook_events: None,
+            issues_fetched: None,
+            refresh_failed: false,
+            select_after_load: None,
+            next_page: None,
+            prefetcher: None,
+            issue_loader: None,
+            profile_loader: None,
+            poller: None,
+            submitter: None,
+            loaded: false,
+            timing: Timing::default(),
+            shutdown: Arc::new(AtomicBool::new(false)),
+            visible_rows: 0,
+            hovered: None,
+            panes: Panes::default(),
+            compact: false,
+            myself: None,
+            pending_updates: vec![],
+            toast: None,
 
             table_state: TableState::default().with_selected(Some(0)),
             colors: AppColor::default(),
@@ -65,33 +615,177 @@ impl App {
         }
     }
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
-        self.issues = self.jira.get_current_sprint_issues()?;
+        for signal in TERM_SIGNALS {
+            signal_hook::flag::register(*signal, Arc::clone(&self.shutdown))?;
+        }
+        self.issue_loader = Some(IssueLoader::start(&self.config));
+        self.profile_loader = Some(background::load_profile(&self.config));
+        self.submitter = Some(Submitter::start(&self.config));
+        self.poller = Some(Poller::start(&self.config));
+        // The synced state follows in the background, a timer it brings is asked about.
         self.load_state();
+        self.history = History::load();
+        if !self.config.demo {
+            self.syncer = SyncBackend::from_config(&self.config).map(Syncer::start);
+        }
+        self.ledger = Ledger::load();
+        self.follow_ups = FollowUps::load();
+        self.snoozes = Snoozes::load();
+        self.search.set_hidden(self.snoozes.keys());
+        self.journal = Journal::load();
+        if !self.journal.entries.is_empty() {
+            self.popup = Some(Popup::Recovery);
+        }
+        self.load_scripts();
+        self.timing.phase("local state");
+        // Show the tracker right away instead of waiting for Jira.
+        terminal.draw(|frame| self.draw(frame))?;
+        self.timing.phase("first frame");
+
+        self.prefetcher = Some(Prefetcher::start(&self.config));
+        if let Some(port) = self.config.webhook_port {
+            let address = self.config.webhook_address.as_deref().unwrap_or("127.0.0.1");
+            self.webhook_events = Some(webhook::listen(address, port, self.config.webhook_token.clone())?);
+        }
+        self.timing.phase("background tasks");
 
         while !self.exit {
             terminal.draw(|frame| self.draw(frame))?;
+            // Redraw right away so the outcome of the updates is visible.
+            if self.send_pending_updates() {
+                continue;
+            }
             self.handle_events()?;
+            if self.shutdown.load(Ordering::Relaxed) {
+                self.shut_down();
+            }
+            self.check_issue_loader();
+            self.check_profile();
+            self.check_submissions();
+            self.check_reminder();
+            self.check_meeting();
+            self.check_follow_ups();
+            self.check_snoozes();
+            self.check_focus();
+            self.check_checkout();
+            self.check_sync();
+            self.check_state_file();
+            self.check_notifications();
+            self.check_active_issue();
+            self.check_start_offers();
+            self.check_polls();
+            self.check_mention_suggestions();
+            self.check_jql_values();
+            self.apply_webhook_events();
+            self.load_next_page();
+            self.prefetch_visible_issues();
         }
+        self.send_pending_updates();
+        self.wait_for_submissions();
+        self.sync_state();
         Ok(())
     }
 
     fn draw(&mut self, frame: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
+        self.search
+            .update(&self.issues, &self.search_input, self.project_filter.as_deref());
+        self.compact = frame.area().height < COMPACT_HEIGHT;
+        let chunks = if self.compact {
+            let search_height = if self.is_searching() { 1 } else { 0 };
+            let chunks = Layout::vertical([
+                Constraint::Length(1),
                 Constraint::Min(1),
-                Constraint::Length(3),
-                Constraint::Length(3),
+                Constraint::Length(search_height),
             ])
             .split(frame.area());
+            self.render_compact_status(frame, chunks[0]);
+            self.render_issue_list(frame, chunks[1]);
+            self.render_compact_search(frame, chunks[2]);
+            chunks
+        } else {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(self.panes.constraints(self.is_searching()))
+                .split(frame.area());
+            self.render_issue_list(frame, chunks[0]);
+            self.render_current_issue(frame, chunks[1]);
+            self.render_search(frame, chunks[2]);
+            chunks
+        };
 
-        self.filtered_issues = self.search_issues();
-        self.render_issue_list(frame, chunks[0]);
-        self.render_current_issue(frame, chunks[1]);
-        self.render_search(frame, chunks[2]);
+        match self.popup {
+            Some(Popup::SprintSummary) => self.render_sprint_summary(frame),
+            Some(Popup::Workload) => self.render_workload(frame),
+            Some(Popup::QuickFilters) => self.render_quick_filters(frame),
+            Some(Popup::Facets) => self.render_facets(frame),
+            Some(Popup::SavedFilters) => self.render_saved_filters(frame),
+            Some(Popup::Notifications) => self.render_notifications(frame),
+            Some(Popup::Recovery) => self.render_recovery(frame),
+            Some(Popup::CopyLink) => self.render_copy_link(frame),
+            Some(Popup::Today) => self.render_today(frame),
+            Some(Popup::Receipts) => self.render_receipts(frame),
+            Some(Popup::Overlaps) => self.render_overlaps(frame),
+            Some(Popup::FollowUp) => self.render_follow_up(frame),
+            Some(Popup::Snooze) => self.render_snooze(frame),
+            Some(Popup::Snoozes) => self.render_snoozes(frame),
+            Some(Popup::Annotate) => self.render_annotate(frame),
+            Some(Popup::Timesheet) => self.render_timesheet(frame),
+            Some(Popup::ForeignTimer) => self.render_foreign_timer(frame),
+            Some(Popup::Estimation) => self.render_estimation(frame),
+            Some(Popup::Lap) => self.render_lap(frame),
+            Some(Popup::SubtaskPicker) => self.render_subtask_picker(frame),
+            Some(Popup::BillingAccount) => self.render_billing_account(frame),
+            Some(Popup::WorklogComment) => self.render_worklog_comment(frame),
+            Some(Popup::Clone) => self.render_clone(frame),
+            Some(Popup::Redistribute) => self.render_redistribution(frame),
+            Some(Popup::SprintPicker) => self.render_sprint_picker(frame),
+            Some(Popup::Review) => self.render_review(frame),
+            Some(Popup::MergeConfirm) => self.render_merge_confirm(frame),
+            Some(Popup::MaxDuration) => self.render_max_duration(frame),
+            Some(Popup::ClosedIssue) => self.render_closed_issue(frame),
+            Some(Popup::StartOffer) => self.render_start_offer(frame),
+            Some(Popup::ScriptActions) => self.render_script_actions(frame),
+            Some(Popup::Timeline) => self.render_timeline(frame),
+            Some(Popup::Stats) => self.render_stats(frame),
+            Some(Popup::EndOfDay) => self.render_day_summary(frame),
+            Some(Popup::Detail) => self.render_detail(frame),
+            Some(Popup::Composer) => {
+                self.render_detail(frame);
+                self.render_composer(frame);
+            }
+            None => {}
+        }
+        if self.popup.is_none() && self.jql_input.is_none() && !self.compact {
+            self.render_preview(frame, chunks[0]);
+        }
+        self.render_toast(frame);
+        self.config.theme.apply(frame.buffer_mut());
     }
 
     fn handle_events(&mut self) -> Result<()> {
+        let timeout = if self.search.is_pending() {
+            search::DEBOUNCE
+        } else if self.composer.is_pending() {
+            composer::DEBOUNCE
+        } else if self.jql_input.as_ref().is_some_and(JqlInput::is_pending) {
+            jql::DEBOUNCE
+        } else if let Some(remaining) = self.preview_remaining() {
+            remaining
+        } else if self.issue_loader.as_ref().is_some_and(IssueLoader::loading)
+            || self.submitter.as_ref().is_some_and(|submitter| submitter.in_flight() > 0)
+        {
+            // Keeps the spinner turning.
+            SPINNER_INTERVAL
+        } else {
+            Duration::from_secs(1)
+        };
+        match event::poll(timeout) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            // A signal arrived, it is handled by the main loop.
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => return Ok(()),
+            Err(error) => return Err(error.into()),
+        }
         match event::read()? {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 self.handle_key_event(key_event)
@@ -102,19 +796,99 @@ impl App {
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if let Some(popup) = self.popup {
+            self.handle_popup_key_event(popup, key_event);
+            return;
+        }
+        if self.jql_input.is_some() {
+            self.handle_jql_key_event(key_event);
+            return;
+        }
         if key_event.modifiers.contains(KeyModifiers::CONTROL) {
             match key_event.code {
+                KeyCode::Char('c') => self.quit(true),
                 KeyCode::Char('s') => self.deactivate_issue(),
                 KeyCode::Char('d') => self.clear_state(),
                 KeyCode::Char('y') => self.copy_mr_title(),
+                KeyCode::Char('g') => self.copy_commit_message(),
+                KeyCode::Char('t') => self.popup = Some(Popup::Today),
+                KeyCode::Char('e') => self.log_meeting(),
+                KeyCode::Char('k') if self.selected_issue().is_some() => {
+                    self.popup = Some(Popup::CopyLink)
+                }
+                KeyCode::Char('v') => self.accept_focus_suggestion(),
+                KeyCode::Char('q') if self.active_issue.is_some() => {
+                    self.lap_input.clear();
+                    self.popup = Some(Popup::Lap)
+                }
+                KeyCode::Char('x') if self.selected_issue().is_some() => {
+                    self.follow_up_input.clear();
+                    self.popup = Some(Popup::FollowUp)
+                }
+                KeyCode::Char('b') => self.open_sprint_popup(Popup::SprintSummary),
+                KeyCode::Char('w') => self.open_sprint_popup(Popup::Workload),
+                KeyCode::Char('f') => self.open_quick_filters(),
+                KeyCode::Char('l') => self.open_saved_filters(),
+                KeyCode::Char('a') => self.popup = Some(Popup::Notifications),
+                KeyCode::Char('u') => self.assign_selected_issue_to_me(),
+                KeyCode::Char('n') => self.open_sprint_picker(),
+                KeyCode::Char('p') => self.cycle_project_filter(),
+                KeyCode::Char('r') => self.popup = Some(Popup::Review),
+                KeyCode::Char('o') => self.open_detail(),
                 _ => ()
             }
             return
         } 
+        if key_event.modifiers.contains(KeyModifiers::ALT) {
+            match key_event.code {
+                KeyCode::Up => self.panes.grow(),
+                KeyCode::Down => self.panes.shrink(),
+                KeyCode::Char('s') => self.panes.collapse_search = !self.panes.collapse_search,
+                KeyCode::Char('r') => return self.toggle_review_queue(),
+                KeyCode::Char('c') => return self.open_clone(),
+                KeyCode::Char('d') => return self.open_redistribution(),
+                KeyCode::Char('m') => return self.search.cycle_mode(),
+                KeyCode::Char('w') => return self.open_receipts(),
+                KeyCode::Char('x') => return self.open_script_actions(),
+                KeyCode::Char('y') => return self.copy_mr_description(),
+                KeyCode::Char('t') => return self.open_timeline(),
+                KeyCode::Char('i') => return self.popup = Some(Popup::Stats),
+                KeyCode::Char('z') if self.selected_issue().is_some() => {
+                    self.snooze_input.clear();
+                    return self.popup = Some(Popup::Snooze);
+                }
+                KeyCode::Char('h') => return self.popup = Some(Popup::Snoozes),
+                KeyCode::Char('n') => return self.open_annotate(),
+                KeyCode::Char('a') => return self.open_timesheet(),
+                KeyCode::Char('q') => return self.cycle_query(),
+                KeyCode::Char('e') => return self.open_estimation(),
+                _ => return,
+            }
+            return self.persist_state();
+        }
+        if key_event.modifiers.contains(KeyModifiers::SHIFT) {
+            match key_event.code {
+                KeyCode::Up => return self.move_selected_issue(true),
+                KeyCode::Down => return self.move_selected_issue(false),
+                _ => (),
+            }
+        }
         match key_event.code {
-            KeyCode::Esc => self.exit(),
+            KeyCode::Esc if !self.search_input.is_empty() => self.search_input.clear(),
+            KeyCode::Esc => self.quit(false),
             KeyCode::Down => self.table_state.select_next(),
             KeyCode::Up => self.table_state.select_previous(),
+            KeyCode::PageDown => self.table_state.scroll_down_by(20),
+            KeyCode::PageUp => self.table_state.scroll_up_by(20),
+            KeyCode::F(5) => self.refresh_issues(),
+            KeyCode::Char('F') if self.search_input.is_empty() => self.open_facets(),
+            KeyCode::Char(':') if self.search_input.is_empty() => {
+                self.jql_input = Some(JqlInput::new(self.active_jql.as_deref().unwrap_or("")));
+                if let (None, Some(poller)) = (&self.jql_data, &self.poller) {
+                    poller.poll(Poll::JqlData);
+                }
+                self.update_jql_suggestions();
+            }
             KeyCode::Char(char) => self.add_char(char),
             KeyCode::Backspace => self.delete_char(),
             KeyCode::Enter => self.activate_issue(),
@@ -122,225 +896,5135 @@ impl App {
         }
     }
 
-    fn activate_issue(&mut self) {
-        self.deactivate_issue();
-        self.active_issue = if let Some(issue_index) = self.table_state.selected() {
-            Some(self.filtered_issues.get(issue_index).unwrap().key.clone())
-        } else {
+    fn handle_jql_key_event(&mut self, key_event: KeyEvent) {
+        let Some(jql_input) = &mut self.jql_input else {
             return;
         };
-        self.activated_on = Some(Zoned::now());
-        self.persist_state()
-    }
-
-    fn deactivate_issue(&mut self) {
-        if let (Some(active_issue), Some(activated_on)) = (&self.active_issue, &self.activated_on) {
-            self.jira
-                .log_time(active_issue, activated_on, &Zoned::now())
-                .unwrap();
+        match key_event.code {
+            KeyCode::Esc => self.jql_input = None,
+            KeyCode::Down => jql_input.select_next(),
+            KeyCode::Up => jql_input.select_previous(),
+            KeyCode::Tab => {
+                jql_input.accept_suggestion();
+                self.update_jql_suggestions();
+            }
+            KeyCode::Enter => self.run_jql(),
+            KeyCode::Backspace => {
+                jql_input.input.pop();
+                self.update_jql_suggestions();
+            }
+            KeyCode::Char(_) => {
+                jql_input.input.extend(typed_char(key_event));
+                self.update_jql_suggestions();
+            }
+            _ => {}
         }
-        self.clear_state();
     }
 
-    fn clear_state(&mut self) {
-        self.active_issue = None;
-        self.activated_on = None;
-        self.persist_state();
+    /// Completes the typed JQL from the autocomplete data; values are looked up in Jira
+    /// once typing pauses.
+    fn update_jql_suggestions(&mut self) {
+        let Some(jql_input) = &mut self.jql_input else {
+            return;
+        };
+        jql_input.typed();
+        let Some(data) = &self.jql_data else {
+            return;
+        };
+        let (expect, partial) = jql::context(&jql_input.input);
+        let mut suggestions = jql::suggestions(data, &expect, &partial);
+        suggestions.truncate(8);
+        jql_input.suggestions = suggestions;
+        jql_input.selected = 0;
     }
 
-    fn copy_mr_title(&self) {
-        let issue = self.get_active_issue();
-
-        if let Some(issue) = issue {
-        let issue_string = format!("[{}] {}", issue.key, issue.summary);
-
-        let mut clipboard = Clipboard::new().unwrap();
-        clipboard.set_text(issue_string).unwrap();
+    /// Has the poller look up values for the JQL value being typed, once typing paused.
+    fn check_jql_values(&mut self) {
+        let Some(jql_input) = &mut self.jql_input else {
+            return;
+        };
+        let Some((field, value)) = jql_input.due_value() else {
+            return;
+        };
+        if let Some(poller) = &self.poller {
+            poller.poll(Poll::JqlValues {
+                input: jql_input.input.clone(),
+                field,
+                value,
+            });
         }
     }
 
-    fn add_char(&mut self, new_char: char) {
-        self.search_input.push(new_char)
+    /// Puts the values Jira found before the other suggestions.
+    fn show_jql_values(&mut self, input: &str, mut values: Vec<String>) {
+        // The input may have changed while Jira answered.
+        let Some(jql_input) = self.jql_input.as_mut().filter(|jql_input| jql_input.input == input) else {
+            return;
+        };
+        values.append(&mut jql_input.suggestions);
+        values.truncate(8);
+        jql_input.suggestions = values;
+        jql_input.selected = 0;
     }
 
-    fn delete_char(&mut self) {
-        let mut chars = self.search_input.chars();
-        chars.next_back();
-        self.search_input = chars.as_str().to_string()
+    /// Has the typed JQL validated; it becomes the source of the issue list once Jira
+    /// accepts it.
+    fn run_jql(&mut self) {
+        let Some(jql_input) = &self.jql_input else {
+            return;
+        };
+        let jql = jql_input.input.trim().to_string();
+        if jql.is_empty() {
+            self.active_jql = None;
+            self.jql_input = None;
+            self.refresh_issues();
+            return;
+        }
+        if let Some(poller) = &self.poller {
+            poller.poll(Poll::ValidateJql { jql });
+        }
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    fn finish_jql_validation(&mut self, jql: String, errors: Result<Vec<String>>) {
+        // The query may have been edited or dropped while Jira validated it.
+        let Some(jql_input) = self.jql_input.as_mut().filter(|jql_input| jql_input.input.trim() == jql) else {
+            return;
+        };
+        match errors {
+            Ok(errors) if !errors.is_empty() => {
+                jql_input.errors = errors;
+                jql_input.suggestions.clear();
+            }
+            Err(err) => {
+                jql_input.errors = vec![format!("Could not validate the query: {err}")];
+                jql_input.suggestions.clear();
+            }
+            Ok(_) => {
+                self.active_jql = Some(jql);
+                self.jql_input = None;
+                self.refresh_issues();
+                self.table_state.select(Some(0));
+            }
+        }
     }
 
-    fn render_issue_list(&mut self, frame: &mut Frame, area: Rect) {
-        let title = Line::from(" Jiratrack ".bold());
-        let instructions = Line::from(vec![
-            " Activate Issue ".into(),
-            "<Enter>  ".blue().bold(),
-            " Submit Worklog ".into(),
-            "<C-s>  ".blue().bold(),
-            " Cancel Worklog ".into(),
-            "<C-d>  ".blue().bold(),
-            " Copy Active MR Title ".into(),
-            "<C-y>  ".blue().bold(),
-            " Quit ".into(),
-            "<esc> ".blue().bold(),
-        ]);
+    fn handle_popup_key_event(&mut self, popup: Popup, key_event: KeyEvent) {
+        match (popup, key_event.code) {
+            (Popup::QuickFilters, KeyCode::Down) => self.quick_filter_state.select_next(),
+            (Popup::QuickFilters, KeyCode::Up) => self.quick_filter_state.select_previous(),
+            (Popup::QuickFilters, KeyCode::Char(' ')) => self.toggle_quick_filter(),
+            (Popup::QuickFilters, KeyCode::Esc | KeyCode::Enter) => {
+                self.popup = None;
+                self.refresh_issues();
+            }
+            (Popup::Facets, KeyCode::Down) => self.facet_state.select_next(),
+            (Popup::Facets, KeyCode::Up) => self.facet_state.select_previous(),
+            (Popup::Facets, KeyCode::Char(' ')) => self.toggle_facet(),
+            (Popup::Facets, KeyCode::Char('c')) => {
+                self.search.clear_facets();
+                self.table_state.select(Some(0));
+            }
+            (Popup::ScriptActions, KeyCode::Down) => self.script_action_state.select_next(),
+            (Popup::ScriptActions, KeyCode::Up) => self.script_action_state.select_previous(),
+            (Popup::ScriptActions, KeyCode::Enter) => self.run_script_action(),
+            (Popup::Timeline, KeyCode::Left) => self.timeline_gap = self.timeline_gap.saturating_sub(1),
+            (Popup::Timeline, KeyCode::Right) => self.timeline_gap += 1,
+            (Popup::Timeline, KeyCode::Enter) => self.backfill_gap(),
+            (Popup::SavedFilters, KeyCode::Down) => self.saved_filter_state.select_next(),
+            (Popup::SavedFilters, KeyCode::Up) => self.saved_filter_state.select_previous(),
+            (Popup::SavedFilters, KeyCode::Enter) => self.load_saved_filter(),
+            (Popup::SavedFilters, KeyCode::Char('c')) => {
+                self.active_jql = None;
+                self.popup = None;
+                self.refresh_issues();
+            }
+            (Popup::Notifications, KeyCode::Down) => self.notification_state.select_next(),
+            (Popup::Notifications, KeyCode::Up) => self.notification_state.select_previous(),
+            (Popup::Notifications, KeyCode::Char('d') | KeyCode::Delete) => self.dismiss_notification(),
+            (Popup::Notifications, KeyCode::Char('c')) => {
+                self.notifications.clear();
+                self.popup = None;
+            }
+            (Popup::Notifications, KeyCode::Enter) => self.open_notification(),
+            (Popup::Recovery, KeyCode::Down) => self.recovery_state.select_next(),
+            (Popup::Recovery, KeyCode::Up) => self.recovery_state.select_previous(),
+            (Popup::Recovery, KeyCode::Char('r')) => self.recover_journal_entry(true),
+            (Popup::Recovery, KeyCode::Char('d')) => self.recover_journal_entry(false),
+            (Popup::CopyLink, KeyCode::Char(format @ ('u' | 'm' | 'h'))) => self.copy_link(format),
+            (Popup::ForeignTimer, KeyCode::Char('y')) => self.adopt_foreign_timer(),
+            (Popup::ForeignTimer, KeyCode::Char('n') | KeyCode::Esc) => {
+                self.ignored_timer = self.foreign_timer.take();
+                self.popup = None;
+            }
+            (Popup::ForeignTimer, _) => {}
+            (Popup::Estimation, KeyCode::Enter) => self.save_estimate(),
+            (Popup::Estimation, KeyCode::Tab | KeyCode::Right) => self.estimation.next(),
+            (Popup::Estimation, KeyCode::BackTab | KeyCode::Left) => self.estimation.previous(),
+            (Popup::Estimation, KeyCode::Backspace) => {
+                self.estimation.input.pop();
+            }
+            (Popup::Estimation, KeyCode::Char(_)) => self.estimation.input.extend(typed_char(key_event)),
+            (Popup::Estimation, KeyCode::Esc) => self.popup = None,
+            (Popup::Estimation, _) => {}
+            (Popup::Timesheet, KeyCode::Down) => self.timesheet_state.select_next(),
+            (Popup::Timesheet, KeyCode::Up) => self.timesheet_state.select_previous(),
+            (Popup::Timesheet, KeyCode::Enter) => self.submit_selected_week(),
+            (Popup::Receipts, KeyCode::Down) => self.receipts_state.select_next(),
+            (Popup::Receipts, KeyCode::Up) => self.receipts_state.select_previous(),
+            (Popup::Receipts, KeyCode::Enter) => self.copy_receipt_link(),
+            (Popup::FollowUp, KeyCode::Enter) => self.set_follow_up(),
+            (Popup::FollowUp, KeyCode::Delete) => self.clear_follow_up(),
+            (Popup::FollowUp, KeyCode::Backspace) => {
+                self.follow_up_input.pop();
+            }
+            (Popup::FollowUp, KeyCode::Char(_)) => self.follow_up_input.extend(typed_char(key_event)),
+            (Popup::FollowUp, KeyCode::Esc) => self.popup = None,
+            (Popup::FollowUp, _) => {}
+            (Popup::Snooze, KeyCode::Enter) => self.snooze_selected_issue(),
+            (Popup::Snooze, KeyCode::Backspace) => {
+                self.snooze_input.pop();
+            }
+            (Popup::Snooze, KeyCode::Char(_)) => self.snooze_input.extend(typed_char(key_event)),
+            (Popup::Snooze, KeyCode::Esc) => self.popup = None,
+            (Popup::Snooze, _) => {}
+            (Popup::Annotate, KeyCode::Enter) => self.save_annotation(),
+            (Popup::Annotate, KeyCode::Tab) => self.annotation_draft.tag = TagColor::cycle(self.annotation_draft.tag),
+            (Popup::Annotate, KeyCode::Delete) => {
+                self.annotation_draft = Annotation::default();
+                self.save_annotation();
+            }
+            (Popup::Annotate, KeyCode::Backspace) => {
+                self.annotation_draft.note.pop();
+            }
+            (Popup::Annotate, KeyCode::Char(_)) => self.annotation_draft.note.extend(typed_char(key_event)),
+            (Popup::Annotate, KeyCode::Esc) => self.popup = None,
+            (Popup::Annotate, _) => {}
+            (Popup::Snoozes, KeyCode::Down) => self.snooze_state.select_next(),
+            (Popup::Snoozes, KeyCode::Up) => self.snooze_state.select_previous(),
+            (Popup::Snoozes, KeyCode::Enter | KeyCode::Delete) => self.wake_selected_snooze(),
+            (Popup::Lap, KeyCode::Enter) => self.add_lap(),
+            (Popup::Lap, KeyCode::Backspace) => {
+                self.lap_input.pop();
+            }
+            (Popup::Lap, KeyCode::Char(_)) => self.lap_input.extend(typed_char(key_event)),
+            (Popup::Lap, KeyCode::Esc) => self.popup = None,
+            (Popup::Lap, _) => {}
+            (Popup::Redistribute, KeyCode::Enter) => self.submit_redistribution(),
+            (Popup::Redistribute, KeyCode::Esc) => {
+                self.redistribution = None;
+                self.popup = None;
+            }
+            (Popup::Redistribute, code) => {
+                let Some(redistribution) = &mut self.redistribution else {
+                    return;
+                };
+                match code {
+                    KeyCode::Down | KeyCode::Tab => redistribution.select_next(),
+                    KeyCode::Up | KeyCode::BackTab => redistribution.select_previous(),
+                    KeyCode::Backspace => {
+                        redistribution.input_mut().pop();
+                    }
+                    KeyCode::Char(_) => redistribution.input_mut().extend(typed_char(key_event)),
+                    _ => {}
+                }
+            }
+            (Popup::Clone, KeyCode::Enter) => self.clone_selected_issue(),
+            (Popup::Clone, KeyCode::Backspace) => {
+                self.clone_input.pop();
+            }
+            (Popup::Clone, KeyCode::Char(_)) => self.clone_input.extend(typed_char(key_event)),
+            (Popup::Clone, KeyCode::Esc) => self.popup = None,
+            (Popup::Clone, _) => {}
+            (Popup::BillingAccount, KeyCode::Enter | KeyCode::Esc) => self.confirm_billing_account(),
+            (Popup::BillingAccount, KeyCode::Backspace) => {
+                self.billing_input.pop();
+            }
+            (Popup::BillingAccount, KeyCode::Char(_)) => self.billing_input.extend(typed_char(key_event)),
+            (Popup::BillingAccount, _) => {}
+            (Popup::WorklogComment, KeyCode::Enter) => self.confirm_worklog_comment(true),
+            (Popup::WorklogComment, KeyCode::Esc) => self.confirm_worklog_comment(false),
+            (Popup::WorklogComment, KeyCode::Backspace) => {
+                self.comment_input.pop();
+            }
+            (Popup::WorklogComment, KeyCode::Char(_)) => self.comment_input.extend(typed_char(key_event)),
+            (Popup::WorklogComment, _) => {}
+            (Popup::SubtaskPicker, KeyCode::Down) => self.subtask_state.select_next(),
+            (Popup::SubtaskPicker, KeyCode::Up) => self.subtask_state.select_previous(),
+            (Popup::SubtaskPicker, KeyCode::Enter) => {
+                let selected = self.subtask_state.selected().unwrap_or(0);
+                self.pick_subtask(selected);
+            }
+            (Popup::SprintPicker, KeyCode::Down) => self.sprint_picker_state.select_next(),
+            (Popup::SprintPicker, KeyCode::Up) => self.sprint_picker_state.select_previous(),
+            (Popup::SprintPicker, KeyCode::Enter) => self.move_selected_issue_to_sprint(),
+            (Popup::Review, KeyCode::Down) => self.review_state.select_next(),
+            (Popup::Review, KeyCode::Up) => self.review_state.select_previous(),
+            (Popup::Review, KeyCode::Char('+')) => self.adjust_pending_session(5 * 60),
+            (Popup::Review, KeyCode::Char('-')) => self.adjust_pending_session(-5 * 60),
+            (Popup::Review, KeyCode::Char('m')) => self.merge_pending_session(),
+            (Popup::Review, KeyCode::Char('r')) => self.round_pending_sessions(),
+            (Popup::Review, KeyCode::Char('d') | KeyCode::Delete) => self.delete_pending_session(),
+            (Popup::Review, KeyCode::Enter) => self.submit_reviewed_sessions(),
+            (Popup::Overlaps, KeyCode::Enter) => {
+                self.popup = Some(Popup::Review);
+                self.overlaps.clear();
+                self.submit_pending_sessions();
+            }
+            (Popup::Overlaps, KeyCode::Esc | KeyCode::Char('q')) => {
+                self.popup = Some(Popup::Review);
+                self.overlaps.clear();
+            }
+            (Popup::MergeConfirm, KeyCode::Char('y')) => self.resolve_merge(true),
+            (Popup::MergeConfirm, KeyCode::Char('n') | KeyCode::Esc) => self.resolve_merge(false),
+            (Popup::MergeConfirm, _) => {}
+            (Popup::ClosedIssue, KeyCode::Char('y')) => self.resolve_closed_issue(true),
+            (Popup::ClosedIssue, KeyCode::Char('n') | KeyCode::Esc) => self.resolve_closed_issue(false),
+            (Popup::ClosedIssue, _) => {}
+            (Popup::StartOffer, KeyCode::Char('y')) => self.resolve_start_offer(true),
+            (Popup::StartOffer, KeyCode::Char('n') | KeyCode::Esc) => self.resolve_start_offer(false),
+            (Popup::StartOffer, _) => {}
+            (Popup::EndOfDay, KeyCode::Char('s')) => self.leave(true),
+            (Popup::EndOfDay, KeyCode::Char('q') | KeyCode::Enter) => self.leave(false),
+            (Popup::EndOfDay, KeyCode::Esc) => {
+                self.day_summary = None;
+                self.popup = None;
+            }
+            (Popup::EndOfDay, _) => {}
+            (Popup::MaxDuration, KeyCode::Char(choice)) => self.resolve_oversized_session(choice),
+            (Popup::MaxDuration, KeyCode::Esc) => self.resolve_oversized_session('n'),
+            (Popup::Detail, KeyCode::Down) => self.scroll_detail(1, true),
+            (Popup::Detail, KeyCode::Up) => self.scroll_detail(1, false),
+            (Popup::Detail, KeyCode::PageDown) => self.scroll_detail(10, true),
+            (Popup::Detail, KeyCode::PageUp) => self.scroll_detail(10, false),
+            (Popup::Detail, KeyCode::Tab) => {
+                if let Some(detail) = &mut self.detail {
+                    detail.next_tab();
+                }
+                self.load_attachment_preview();
+            }
+            (Popup::Detail, KeyCode::Char('n')) => self.load_more_comments(),
+            (Popup::Detail, KeyCode::Left | KeyCode::Right) => {
+                if let Some(detail) = &mut self.detail {
+                    detail.select_attachment(key_event.code == KeyCode::Right);
+                }
+                self.load_attachment_preview();
+            }
+            (Popup::Detail, KeyCode::End) => self.jump_to_newest_comment(),
+            (Popup::Detail, KeyCode::Char('c')) => {
+                self.composer = Composer::default();
+                self.popup = Some(Popup::Composer);
+            }
+            (Popup::Composer, KeyCode::Esc) => self.popup = Some(Popup::Detail),
+            (Popup::Composer, KeyCode::Down) => self.composer.select_next(),
+            (Popup::Composer, KeyCode::Up) => self.composer.select_previous(),
+            (Popup::Composer, KeyCode::Tab) => {
+                self.composer.accept_suggestion();
+            }
+            (Popup::Composer, KeyCode::Enter) => self.confirm_composer(),
+            (Popup::Composer, KeyCode::Backspace) => {
+                self.composer.delete_char();
+                self.composer.typed();
+            }
+            (Popup::Composer, KeyCode::Char(_)) => {
+                if let Some(new_char) = typed_char(key_event) {
+                    self.composer.push_char(new_char);
+                    self.composer.typed();
+                }
+            }
+            (Popup::Composer, _) => {}
+            (_, KeyCode::Esc | KeyCode::Char('q')) => self.popup = None,
+            _ => {}
+        }
+    }
 
-        let selected_style = Style::default().bg(self.colors.selected_bg_color);
+    fn open_quick_filters(&mut self) {
+        if self.quick_filters.is_empty() {
+            self.quick_filters = self
+                .jiras()
+                .enumerate()
+                .flat_map(|(index, jira)| {
+                    let quick_filters = jira.get_quick_filters().unwrap_or_default();
+                    quick_filters.into_iter().map(move |filter| (index, filter))
+                })
+                .collect();
+        }
+        self.popup = Some(Popup::QuickFilters);
+    }
 
-        let block = Block::bordered()
-            .title(title.centered())
-            .title_bottom(instructions.centered())
-            .border_set(border::THICK);
+    fn toggle_quick_filter(&mut self) {
+        let Some((instance, filter)) = self
+            .quick_filter_state
+            .selected()
+            .and_then(|index| self.quick_filters.get(index))
+        else {
+            return;
+        };
+        let id = (*instance, filter.id);
+        match self.active_quick_filters.iter().position(|active| *active == id) {
+            Some(index) => {
+                self.active_quick_filters.remove(index);
+            }
+            None => self.active_quick_filters.push(id),
+        }
+    }
 
-        let header = ["Key", "Time Spent", "Assignee", "Title"]
-            .into_iter()
-            .map(Cell::from)
-            .collect::<Row>()
-            .height(1);
+    fn open_facets(&mut self) {
+        self.facet_values = facets::values(&self.issues);
+        self.popup = Some(Popup::Facets);
+    }
 
-        let rows: Vec<Row> = self
-            .filtered_issues
-            .iter()
-            .map(|issue| {
-                let cols = [
-                    &issue.key,
-                    &issue.time_spent,
-                    &issue.assignee,
-                    &issue.summary,
-                ];
-                cols.iter()
-                    .map(|content| Cell::from(Text::from(content.to_string())))
-                    .collect::<Row>()
-                    .height(1)
-            })
-            .collect();
+    /// Shows or hides the issues with the value selected in the filter menu.
+    fn toggle_facet(&mut self) {
+        let Some(facet_value) = self
+            .facet_state
+            .selected()
+            .and_then(|index| self.facet_values.get(index))
+        else {
+            return;
+        };
+        self.search.toggle_facet(facet_value.facet, &facet_value.value);
+        self.table_state.select(Some(0));
+    }
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(10),
-                Constraint::Length(12),
-                Constraint::Length(20),
-                Constraint::Min(20),
-            ],
-        )
-        .header(header)
-        .row_highlight_style(selected_style)
-        .highlight_symbol(">>")
-        .block(block);
-        frame.render_stateful_widget(table, area, &mut self.table_state);
+    fn open_saved_filters(&mut self) {
+        self.saved_filters = self.jira.get_favourite_filters().unwrap_or_default();
+        self.popup = Some(Popup::SavedFilters);
     }
 
-    fn render_current_issue(&self, frame: &mut Frame, area: Rect) {
-        let title = Line::from("  Current Issue  ".bold());
-        let block = Block::bordered().title(title);
-        let duration = match &self.activated_on {
-            Some(zoned) => format!("{:#}", (&Zoned::now() - zoned).round(Unit::Second).unwrap()),
-            None => "/".to_string(),
+    /// Replaces the sprint issues by the results of the selected saved filter.
+    fn load_saved_filter(&mut self) {
+        let Some(filter) = self
+            .saved_filter_state
+            .selected()
+            .and_then(|index| self.saved_filters.get(index))
+        else {
+            return;
         };
+        self.active_jql = Some(filter.jql.clone());
+        self.popup = None;
+        self.refresh_issues();
+        self.table_state.select(Some(0));
+    }
 
-        let text = match &self.get_active_issue() {
-            Some(issue) => format!(" {} {} ({})", issue.key, issue.summary, duration),
-            None => " No issue active".to_string(),
+    /// Lists the issues awaiting my review, or the sprint again.
+    fn toggle_review_queue(&mut self) {
+        let jql = self.review_queue_jql();
+        self.active_jql = if self.active_jql.as_ref() == Some(&jql) {
+            None
+        } else {
+            Some(jql)
         };
-        let p = Paragraph::new(text).block(block);
-
-        frame.render_widget(p, area)
+        self.refresh_issues();
+        self.table_state.select(Some(0));
     }
 
-    fn get_active_issue(&self) -> Option<Issue> {
-        let active_issue = self.active_issue.as_ref()?;
-        self.issues
+    /// Lists the issues of the next query of the config, after the last one those of
+    /// the default query again.
+    fn cycle_query(&mut self) {
+        if self.config.queries.is_empty() {
+            return self.show_error("No queries configured".to_string());
+        }
+        let current = self
+            .config
+            .queries
             .iter()
-            .find(|issue| &issue.key == active_issue)
-            .cloned()
+            .position(|query| self.active_jql.as_ref() == Some(&query.jql));
+        let next = current.map_or(0, |index| index + 1);
+        self.active_jql = self.config.queries.get(next).map(|query| query.jql.clone());
+        self.refresh_issues();
+        self.table_state.select(Some(0));
     }
 
-    fn search_issues(&self) -> Vec<Issue> {
-        let mut issues = self
-            .issues
+    fn review_queue_jql(&self) -> String {
+        jira::review_queue_jql(&self.config.review_status, &self.config.reviewer_field)
+    }
+
+    /// Reloads the issue list in the background, or right away in plain mode.
+    fn refresh_issues(&mut self) {
+        let filters: Vec<(usize, String)> = self
+            .quick_filters
             .iter()
-            .filter_map(|issue| Some((issue, fuzzy_match(&issue.summary, &self.search_input)?)))
-            .collect::<Vec<(&Issue, i64)>>();
-        issues.sort_by_key(|(_, score)| -*score);
-        issues
-            .into_iter()
-            .map(|(issue, _)| issue)
-            .cloned()
-            .collect()
+            .filter(|(instance, filter)| self.active_quick_filters.contains(&(*instance, filter.id)))
+            .map(|(instance, filter)| (*instance, filter.jql.clone()))
+            .collect();
+        if let Some(loader) = &mut self.issue_loader {
+            return loader.load(IssueQuery {
+                jql: self.active_jql.clone(),
+                filters,
+            });
+        }
+        match self.fetch_issues(filters) {
+            Ok(issues) => self.replace_issues(issues),
+            Err(_) => self.refresh_failed = true,
+        }
     }
 
-    fn render_search(&self, frame: &mut Frame, area: Rect) {
-        let title = Line::from(" Search Input ".bold());
-        let block = Block::bordered().title(title);
+    fn replace_issues(&mut self, issues: Vec<Issue>) {
+        let previous = std::mem::replace(&mut self.issues, issues);
+        self.issues_fetched = Some(Instant::now());
+        self.refresh_failed = false;
+        self.run_status_change_rules(&previous);
+        if let Some(key) = self.select_after_load.take() {
+            self.search
+                .update(&self.issues, &self.search_input, self.project_filter.as_deref());
+            let position = self
+                .search
+                .matches()
+                .iter()
+                .position(|index| self.issues[*index].key == key);
+            if let Some(position) = position {
+                self.table_state.select(Some(position));
+            }
+        }
+    }
 
-        let p = Paragraph::new("> ".to_string() + &self.search_input).block(block);
+    /// Sprint issues of the main instance merged with those of the additional instances,
+    /// or the first page of results of the active JQL query.
+    fn fetch_issues(&mut self, filters: Vec<(usize, String)>) -> Result<Vec<Issue>> {
+        self.next_page = None;
+        let query = IssueQuery {
+            jql: self.active_jql.clone(),
+            filters,
+        };
+        let loaded = background::fetch_issues(&self.issue_sources(), &query)?;
+        self.next_page = loaded.next_page;
+        Ok(loaded.issues)
+    }
 
-        frame.render_widget(p, area)
+    /// The sources the issue list is fetched from, the main one first.
+    fn issue_sources(&self) -> Vec<&dyn IssueSource> {
+        match &self.file_source {
+            Some(source) => vec![source],
+            None => self.jiras().map(|jira| jira as &dyn IssueSource).collect(),
+        }
     }
 
-    fn get_state_path(&self) -> PathBuf {
-        home_dir()
-            .unwrap()
-            .join(".local/share/jiratrack/state.json")
+    /// The main instance, then the additional ones in order.
+    fn jiras(&self) -> impl Iterator<Item = &Jira> {
+        std::iter::once(&self.jira).chain(&self.instances)
     }
 
-    fn persist_state(&self) {
-        let path = self.get_state_path();
-        fs::create_dir_all(path.parent().unwrap()).unwrap();
-        let state = self.get_persisted_state();
-        let file = fs::File::create(path).unwrap();
-        serde_json::to_writer(file, &state).unwrap();
+    /// The active sprints, with the index of their instance and the instance itself.
+    fn active_sprints(&self) -> impl Iterator<Item = (usize, &Jira, &Sprint)> {
+        self.jiras()
+            .zip(&self.sprints)
+            .enumerate()
+            .filter_map(|(index, (jira, sprint))| Some((index, jira, sprint.as_ref()?)))
     }
 
-    fn load_state(&mut self) {
-        let path = self.get_state_path();
-        if let Ok(file) = File::open(path) {
-            let data: PersistedState = serde_json::from_reader(file).expect("Invalid state");
-            self.active_issue = data.active_issue;
-            self.activated_on = data.activated_on;
+    /// Has the loader fetch the next page of JQL results once the selection gets close
+    /// to the end.
+    fn load_next_page(&mut self) {
+        let Some(loader) = self.issue_loader.as_mut().filter(|loader| !loader.loading()) else {
+            return;
+        };
+        let selected = self.table_state.selected().unwrap_or(0);
+        if self.next_page.is_none() || selected + ROW_MARGIN < self.search.matches().len() {
+            return;
+        }
+        if let Some((jql, page_token)) = self.next_page.take() {
+            loader.load_page(jql, page_token);
         }
     }
 
-    fn get_persisted_state(&self) -> PersistedState {
-        PersistedState {
-            active_issue: self.active_issue.clone(),
-            activated_on: self.activated_on.clone(),
+    /// Queues the details of the issues on screen for loading in the background.
+    fn prefetch_visible_issues(&mut self) {
+        let offset = self.table_state.offset();
+        let keys: Vec<String> = (offset..offset + self.visible_rows)
+            .filter_map(|index| self.filtered_issue(index))
+            .map(|issue| issue.key.clone())
+            .collect();
+        if let Some(prefetcher) = &mut self.prefetcher {
+            for key in keys {
+                prefetcher.request(&key);
+            }
         }
     }
-}
-
-#[derive(Debug)]
-struct AppColor {
-    selected_bg_color: Color,
-}
 
-impl Default for AppColor {
-    fn default() -> Self {
-        AppColor {
-            selected_bg_color: Color::DarkGray,
+    /// The instance an issue lives on: the one it was listed from, or else the one of
+    /// its project key.
+    fn jira_for(&self, issue_key: &str) -> &Jira {
+        if let Some(issue) = self.find_issue(issue_key) {
+            return self.jiras().nth(issue.instance).unwrap_or(&self.jira);
         }
+        self.instances
+            .iter()
+            .find(|instance| instance.owns_issue(issue_key))
+            .unwrap_or(&self.jira)
     }
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
 
-    #[test]
-    fn test_filter_issues() {
-        let mut app = App::new();
-        app.issues = app.jira.get_current_sprint_issues().unwrap();
-        app.search_issues();
+    /// Moves the selected issue one row up or down in the board ranking.
+    fn move_selected_issue(&mut self, up: bool) {
+        let Some(index) = self.table_state.selected() else {
+            return;
+        };
+        let neighbor_index = if up {
+            match index.checked_sub(1) {
+                Some(neighbor_index) => neighbor_index,
+                None => return,
+            }
+        } else {
+            index + 1
+        };
+        let (Some(issue), Some(neighbor)) = (
+            self.filtered_issue(index),
+            self.filtered_issue(neighbor_index),
+        ) else {
+            return;
+        };
+        let (key, other_key) = (issue.key.clone(), neighbor.key.clone());
+        if !self.check_permission(&key, Action::Schedule) {
+            return;
+        }
+        self.swap_issues(&key, &other_key);
+        self.pending_updates.push(IssueUpdate::Rank {
+            key,
+            other_key,
+            before: up,
+        });
+        self.table_state.select(Some(neighbor_index));
+    }
+
+    fn swap_issues(&mut self, key: &str, other_key: &str) {
+        let position = self.issues.iter().position(|i| i.key == key);
+        let other_position = self.issues.iter().position(|i| i.key == other_key);
+        if let (Some(position), Some(other_position)) = (position, other_position) {
+            self.issues.swap(position, other_position);
+        }
+    }
+
+    /// Fetches what I may do in the configured projects. Projects whose permissions
+    /// could not be fetched are not restricted.
+    fn load_permissions(&mut self) {
+        for (project, granted) in background::fetch_permissions(self.jiras()) {
+            self.permissions.set(&project, granted);
+        }
+    }
+
+    /// Takes the profile fetched on startup once it arrived. Until then, and for what
+    /// could not be fetched, the sprint and user stay unknown and times are shown in
+    /// the system time zone.
+    fn check_profile(&mut self) {
+        let Some(Ok(profile)) = self.profile_loader.as_ref().map(Receiver::try_recv) else {
+            return;
+        };
+        self.profile_loader = None;
+        self.sprints = profile.sprints;
+        self.myself = profile.myself;
+        let jiras = std::iter::once(&mut self.jira).chain(self.instances.iter_mut());
+        for (jira, time_zone) in jiras.zip(profile.time_zones) {
+            if let Some(time_zone) = time_zone {
+                jira.set_time_zone(time_zone);
+            }
+        }
+        self.apply_profile_locale();
+        self.apply_owner_preference();
+        for (project, granted) in profile.permissions {
+            self.permissions.set(&project, granted);
+        }
+        self.timing.phase("user profile");
+    }
+
+    /// Whether the action is allowed on the issue, showing the reason when it is not.
+    fn check_permission(&mut self, issue_key: &str, action: Action) -> bool {
+        let project = issue_key.split('-').next().unwrap_or_default();
+        match self.permissions.denial(project, action) {
+            Some(reason) => {
+                self.show_error(reason);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Whether the action is allowed on the selected issue, as far as is known.
+    fn selected_issue_allows(&self, action: Action) -> bool {
+        self.selected_issue()
+            .is_none_or(|issue| self.permissions.denial(issue.project(), action).is_none())
+    }
+
+    /// Shows times in the worklog timezone and takes the formats the config leaves
+    /// open from the locale of the Jira profile.
+    fn apply_profile_locale(&mut self) {
+        let tag = self.myself.as_ref().and_then(|myself| myself.locale.as_deref());
+        self.config.profile_locale = Locale {
+            time_zone: Some(self.jira.time_zone().clone()),
+            ..tag.map(Locale::from_tag).unwrap_or_default()
+        };
+    }
+
+    /// Lists my issues first or only my issues, as configured.
+    fn apply_owner_preference(&mut self) {
+        if self.config.my_issues_first || self.config.only_my_issues {
+            let account_id = self.myself.as_ref().map(|myself| myself.account_id.clone());
+            self.search.set_owner(account_id, self.config.only_my_issues);
+        }
+    }
+
+    fn assign_selected_issue_to_me(&mut self) {
+        let Some(myself) = self.myself.clone() else {
+            return;
+        };
+        let Some(key) = self
+            .table_state
+            .selected()
+            .and_then(|index| self.filtered_issue(index))
+            .map(|issue| issue.key.clone())
+        else {
+            return;
+        };
+        if !self.check_permission(&key, Action::Assign) {
+            return;
+        }
+        let Some(issue) = self.issues.iter_mut().find(|issue| issue.key == key) else {
+            return;
+        };
+        let previous = issue.clone();
+        issue.assignee = myself.display_name;
+        issue.assignee_id = Some(myself.account_id.clone());
+        self.pending_updates.push(IssueUpdate::Assign {
+            previous,
+            account_id: myself.account_id,
+        });
+    }
+
+    /// Sends the changes already shown in the issue list to Jira. Confirmed issues are
+    /// reloaded from the server, rejected changes are rolled back and reported.
+    /// Returns whether there was anything to send.
+    fn send_pending_updates(&mut self) -> bool {
+        if self.pending_updates.is_empty() {
+            return false;
+        }
+        for update in std::mem::take(&mut self.pending_updates) {
+            let update = match (update, &mut self.submitter) {
+                (IssueUpdate::Log { previous, session }, Some(submitter)) => {
+                    let journal_id = self.journal.begin(Intent::LogWork(session.clone()));
+                    submitter.submit(Submission {
+                        journal_id,
+                        session,
+                        previous,
+                        confirmed: false,
+                    });
+                    continue;
+                }
+                (update, _) => update,
+            };
+            let (key, result) = match &update {
+                IssueUpdate::Assign { previous, account_id } => (
+                    previous.key.clone(),
+                    self.jira_for(&previous.key).assign_issue(&previous.key, account_id),
+                ),
+                IssueUpdate::Log { session, .. } => {
+                    (session.issue_key.clone(), self.submit_session(session))
+                }
+                IssueUpdate::Rank { key, other_key, before } => {
+                    (key.clone(), self.jira_for(key).rank_issue(key, other_key, *before))
+                }
+            };
+            match result {
+                Ok(()) => self.reconcile_issue(&key),
+                Err(error) => {
+                    self.roll_back(update);
+                    self.show_error(format!("{key}: {error}"));
+                }
+            }
+        }
+        true
+    }
+
+    /// Takes the issue list once the loader fetched it.
+    fn check_issue_loader(&mut self) {
+        let Some(result) = self.issue_loader.as_mut().and_then(IssueLoader::try_take) else {
+            return;
+        };
+        let first = !self.loaded;
+        self.loaded = true;
+        if first {
+            self.timing.phase("issues");
+        }
+        match result {
+            Ok(loaded) if loaded.more => {
+                self.next_page = loaded.next_page;
+                self.issues.extend(loaded.issues);
+            }
+            Ok(loaded) => {
+                self.next_page = loaded.next_page;
+                self.replace_issues(loaded.issues);
+            }
+            Err(error) => {
+                self.refresh_failed = true;
+                if first {
+                    self.show_error(format!("Could not load the issues: {error}"));
+                }
+            }
+        }
+    }
+
+    /// Records the worklogs created in the background, or rolls back those Jira refused.
+    fn check_submissions(&mut self) {
+        let Some(submitter) = &mut self.submitter else {
+            return;
+        };
+        for submitted in submitter.take_results() {
+            self.finish_submission(submitted);
+        }
+    }
+
+    /// Waits for the worklogs still being sent, so none is lost when quitting.
+    fn wait_for_submissions(&mut self) {
+        while let Some(submitted) = self.submitter.as_mut().and_then(Submitter::wait) {
+            self.finish_submission(submitted);
+        }
+    }
+
+    fn finish_submission(&mut self, submitted: Submitted) {
+        let Submitted {
+            submission,
+            result,
+            booked,
+            overlapping,
+            unchecked,
+            refreshed,
+        } = submitted;
+        self.journal.finish(submission.journal_id);
+        let key = submission.session.issue_key.clone();
+        for error in unchecked {
+            self.show_error(format!("{key}: Could not check for overlapping worklogs: {error}"));
+        }
+        if !overlapping.is_empty() {
+            // Held back, so it waits in the review until the overlap is confirmed.
+            let Submission { session, previous, .. } = submission;
+            self.roll_back(IssueUpdate::Log { previous, session });
+            match self.popup {
+                None | Some(Popup::Review | Popup::Overlaps) => {
+                    // The session was put back last in the review.
+                    let index = self.history.pending.len() - 1;
+                    let held = overlap::overlaps(&self.history.pending[index..], &overlapping, MIN_OVERLAP_S);
+                    self.overlaps.extend(held.into_iter().map(|overlap| Overlap { session: index, ..overlap }));
+                    self.popup = Some(Popup::Overlaps);
+                }
+                Some(_) => self.show_error(format!("{key}: Overlaps logged work, kept for the review <C-r>")),
+            }
+            return;
+        }
+        match result {
+            Ok(worklog_id) => {
+                self.record_worklog(&submission.session, worklog_id);
+                if let Some(issue) = refreshed {
+                    self.apply_issue(issue);
+                }
+                if let Err(error) = booked {
+                    self.show_error(format!("{key}: Logged, but not booked on a billing account: {error}"));
+                }
+            }
+            Err(error) => {
+                let Submission { session, previous, .. } = submission;
+                self.roll_back(IssueUpdate::Log { previous, session });
+                self.show_error(format!("{key}: {error}"));
+            }
+        }
+    }
+
+    fn reconcile_issue(&mut self, key: &str) {
+        if let Ok(issue) = self.jira_for(key).get_issue(key) {
+            self.apply_issue(issue);
+        }
+    }
+
+    /// Replaces the listed issue with the one Jira returned, running the status rules
+    /// when it moved.
+    fn apply_issue(&mut self, mut issue: Issue) {
+        let key = issue.key.clone();
+        let status = issue.status.clone();
+        let Some(current) = self.issues.iter_mut().find(|current| current.key == key) else {
+            return;
+        };
+        let moved = current.status != status;
+        issue.set_instance(current.instance);
+        *current = issue;
+        if moved {
+            self.run_rules(Trigger::StatusChange, &key, Some(&status));
+        }
+    }
+
+    fn roll_back(&mut self, update: IssueUpdate) {
+        let previous = match update {
+            IssueUpdate::Assign { previous, .. } => Some(previous),
+            IssueUpdate::Log { previous, session } => {
+                // Keep the session so it can be submitted again from the review screen.
+                self.history.pending.push(session);
+                self.history.persist();
+                previous
+            }
+            IssueUpdate::Rank { key, other_key, .. } => {
+                self.swap_issues(&key, &other_key);
+                None
+            }
+        };
+        if let Some(previous) = previous {
+            if let Some(current) = self.issues.iter_mut().find(|current| current.key == previous.key) {
+                *current = previous;
+            }
+        }
+    }
+
+    /// Runs the actions of the automation rules matching the trigger, or only notes
+    /// them in the dry-run log for dry runs.
+    fn run_rules(&mut self, trigger: Trigger, issue_key: &str, status: Option<&str>) {
+        let event = RuleEvent {
+            trigger,
+            issue_key,
+            status,
+        };
+        for (action, dry_run) in rules::evaluate(&self.config.rules, &event) {
+            if dry_run || self.jira.dry_run() {
+                dryrun::note(&action.describe(issue_key));
+                continue;
+            }
+            if let Err(error) = self.run_action(&action, issue_key) {
+                self.show_error(format!("Rule failed to {}: {error}", action.describe(issue_key)));
+            }
+        }
+    }
+
+    fn run_action(&mut self, action: &RuleAction, issue_key: &str) -> Result<()> {
+        let jira = self.jira_for(issue_key);
+        match action {
+            RuleAction::Transition(status) => jira.transition_issue(issue_key, status),
+            RuleAction::Assign => jira
+                .get_myself()
+                .and_then(|myself| jira.assign_issue(issue_key, &myself.account_id)),
+            RuleAction::Comment(text) => jira.add_comment(issue_key, &adf::from_text(text)),
+            RuleAction::Command(command) => rules::run_command(command),
+            RuleAction::OfferTimer => {
+                if self.active_issue.as_deref() != Some(issue_key) {
+                    self.start_offer = self.find_issue(issue_key).cloned();
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn load_scripts(&mut self) {
+        let Some(path) = &self.config.script_path else {
+            return;
+        };
+        match Scripts::load(path) {
+            Ok(scripts) => self.scripts = Some(scripts),
+            Err(error) => self.show_error(format!("Could not load {}: {error}", path.display())),
+        }
+    }
+
+    fn open_script_actions(&mut self) {
+        if self.config.script_actions.is_empty() {
+            return self.show_error("No script_actions configured".to_string());
+        }
+        self.popup = Some(Popup::ScriptActions);
+    }
+
+    /// Runs the selected script action on the selected issue: a string it returns is
+    /// copied, a map is run like the actions of a rule.
+    fn run_script_action(&mut self) {
+        self.popup = None;
+        let (Some(scripts), Some(issue)) = (&self.scripts, self.selected_issue()) else {
+            return;
+        };
+        let Some(action) = self
+            .script_action_state
+            .selected()
+            .and_then(|index| self.config.script_actions.get(index))
+        else {
+            return;
+        };
+        let issue_key = issue.key.clone();
+        match scripts.action(&action.function, issue) {
+            Ok(ScriptOutcome::Nothing) => {}
+            Ok(ScriptOutcome::Copy(text)) => {
+                let result = Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text));
+                if let Err(error) = result {
+                    self.show_error(format!("Could not copy: {error}"));
+                }
+            }
+            Ok(ScriptOutcome::Actions(actions)) => {
+                for action in actions {
+                    if self.jira.dry_run() {
+                        dryrun::note(&action.describe(&issue_key));
+                    } else if let Err(error) = self.run_action(&action, &issue_key) {
+                        self.show_error(format!("Could not {}: {error}", action.describe(&issue_key)));
+                    }
+                }
+                self.reconcile_issue(&issue_key);
+            }
+            Err(error) => self.show_error(error.to_string()),
+        }
+    }
+
+    /// Runs the status change rules for the listed issues whose status differs from
+    /// the previous list.
+    fn run_status_change_rules(&mut self, previous: &[Issue]) {
+        if self.config.rules.is_empty() {
+            return;
+        }
+        let changed: Vec<(String, String)> = rules::status_changes(previous, &self.issues)
+            .into_iter()
+            .map(|(key, status)| (key.to_string(), status.to_string()))
+            .collect();
+        for (key, status) in changed {
+            self.run_rules(Trigger::StatusChange, &key, Some(&status));
+        }
+    }
+
+    fn show_error(&mut self, message: String) {
+        self.toast = Some((message, Instant::now()));
+    }
+
+    /// Cycles the project filter through all configured projects and back to none.
+    fn cycle_project_filter(&mut self) {
+        let mut projects = self.config.projects();
+        for instance in &self.config.instances {
+            projects.extend(instance.projects.iter().cloned());
+        }
+        let next_index = match &self.project_filter {
+            Some(project) => projects.iter().position(|p| p == project).map(|i| i + 1),
+            None => Some(0),
+        };
+        self.project_filter = next_index.and_then(|index| projects.get(index).cloned());
+        self.table_state.select(Some(0));
+    }
+
+    fn open_detail(&mut self) {
+        let Some(issue) = self
+            .table_state
+            .selected()
+            .and_then(|index| self.filtered_issue(index))
+        else {
+            return;
+        };
+        self.open_issue_detail(issue.clone());
+    }
+
+    fn open_issue_detail(&mut self, issue: Issue) {
+        let prefetched = self
+            .prefetcher
+            .as_mut()
+            .and_then(|prefetcher| prefetcher.take(&issue.key));
+        let details = prefetched
+            .unwrap_or_else(|| IssueDetails::fetch(self.jira_for(&issue.key), &issue.key));
+        let mut detail = DetailView::new(issue, details.description, details.names);
+        detail.comments = details.comments;
+        detail.comments_total = details.comments_total;
+        detail.set_attachments(details.attachments);
+        detail.status_times = details.status_times;
+        detail.activity = details.activity;
+        self.detail = Some(detail);
+        self.popup = Some(Popup::Detail);
+        self.load_attachment_preview();
+    }
+
+    /// Looks up the display names of users mentioned in the detail view.
+    fn resolve_mentions(&mut self) {
+        let Some(detail) = &self.detail else {
+            return;
+        };
+        let mut mention_ids = adf::mention_ids(&detail.description);
+        for comment in &detail.comments {
+            mention_ids.extend(adf::mention_ids(&comment.body));
+        }
+        mention_ids.sort();
+        mention_ids.dedup();
+        let jira = self.jira_for(&detail.issue.key);
+        let names: Vec<(String, String)> = mention_ids
+            .into_iter()
+            .filter(|id| !detail.names.contains_key(id))
+            .filter_map(|id| Some((id.clone(), jira.get_user_display_name(&id).ok()?)))
+            .collect();
+        if let Some(detail) = &mut self.detail {
+            detail.names.extend(names);
+        }
+    }
+
+    fn load_more_comments(&mut self) {
+        let Some(detail) = &self.detail else {
+            return;
+        };
+        if !detail.has_more_comments() {
+            return;
+        }
+        let jira = self.jira_for(&detail.issue.key);
+        let page = jira.get_comments(&detail.issue.key, detail.comments.len(), COMMENT_PAGE_SIZE);
+        if let (Ok((comments, total)), Some(detail)) = (page, &mut self.detail) {
+            detail.comments.extend(comments);
+            detail.comments_total = total;
+        }
+        self.resolve_mentions();
+    }
+
+    fn jump_to_newest_comment(&mut self) {
+        let Some(detail) = &mut self.detail else {
+            return;
+        };
+        detail.tab = DetailTab::Comments;
+        while self.detail.as_ref().is_some_and(|detail| detail.has_more_comments()) {
+            let loaded = self.detail.as_ref().map(|detail| detail.comments.len());
+            self.load_more_comments();
+            if self.detail.as_ref().map(|detail| detail.comments.len()) == loaded {
+                break;
+            }
+        }
+        if let Some(detail) = &mut self.detail {
+            detail.scroll_to_end();
+        }
+    }
+
+    /// Downloads and decodes the selected image attachment when the attachments tab is
+    /// shown, or the description embedding it.
+    fn load_attachment_preview(&mut self) {
+        let Some(detail) = &self.detail else {
+            return;
+        };
+        if !detail.shows_preview() || detail.preview.is_some() {
+            return;
+        }
+        let Some(attachment) = detail.selected_attachment() else {
+            return;
+        };
+        let picker = self
+            .picker
+            .get_or_init(|| Picker::from_query_stdio().ok())
+            .filter(|picker| picker.protocol_type() != ProtocolType::Halfblocks);
+        let protocol = picker.and_then(|picker| {
+            let bytes = self
+                .jira_for(&detail.issue.key)
+                .download_attachment(&attachment.id)
+                .ok()?;
+            let image = image::load_from_memory(&bytes).ok()?;
+            Some(picker.new_resize_protocol(image))
+        });
+        if let Some(detail) = &mut self.detail {
+            detail.preview = Some(ImagePreview(protocol));
+        }
+    }
+
+    /// Searches the users for the mention being written, once typing paused.
+    fn check_mention_suggestions(&mut self) {
+        let Some(query) = self.composer.due_query() else {
+            return;
+        };
+        let Some(detail) = &self.detail else {
+            return;
+        };
+        let jira = self.jira_for(&detail.issue.key);
+        self.composer.suggestions = jira.search_users(&query).unwrap_or_default();
+        self.composer.selected = 0;
+    }
+
+    /// Picks the highlighted suggestion while mentioning, submits the comment otherwise.
+    fn confirm_composer(&mut self) {
+        if self.composer.mention_query().is_some() && self.composer.accept_suggestion() {
+            return;
+        }
+        self.submit_comment();
+    }
+
+    fn submit_comment(&mut self) {
+        let Some(detail) = &self.detail else {
+            return;
+        };
+        if self.composer.is_empty() {
+            return;
+        }
+        let jira = self.jira_for(&detail.issue.key);
+        if jira.add_comment(&detail.issue.key, &self.composer.to_adf()).is_ok() {
+            self.composer = Composer::default();
+            self.popup = Some(Popup::Detail);
+            if let Some(detail) = &mut self.detail {
+                detail.comments_total += 1;
+            }
+            self.jump_to_newest_comment();
+        }
+    }
+
+    fn scroll_detail(&mut self, lines: u16, down: bool) {
+        if let Some(detail) = &mut self.detail {
+            if down {
+                detail.scroll_down(lines);
+            } else {
+                detail.scroll_up(lines);
+            }
+        }
+    }
+
+    fn open_sprint_picker(&mut self) {
+        if let Some(key) = self.selected_issue().map(|issue| issue.key.clone()) {
+            if !self.check_permission(&key, Action::Schedule) {
+                return;
+            }
+        }
+        let jira = match self.selected_issue() {
+            Some(issue) => self.jira_for(&issue.key),
+            None => &self.jira,
+        };
+        self.target_sprints = jira.get_sprints("active,future").unwrap_or_default();
+        self.sprint_picker_state.select(Some(0));
+        self.popup = Some(Popup::SprintPicker);
+    }
+
+    /// Moves the selected issue to the picked sprint, the first entry being the backlog.
+    fn move_selected_issue_to_sprint(&mut self) {
+        self.popup = None;
+        let Some(issue) = self
+            .table_state
+            .selected()
+            .and_then(|index| self.filtered_issue(index))
+        else {
+            return;
+        };
+        let jira = self.jira_for(&issue.key);
+        let result = match self.sprint_picker_state.selected() {
+            Some(0) => jira.move_issue_to_backlog(&issue.key),
+            Some(index) => match self.target_sprints.get(index - 1) {
+                Some(sprint) => jira.move_issue_to_sprint(&issue.key, sprint.id),
+                None => return,
+            },
+            None => return,
+        };
+        if result.is_ok() {
+            self.refresh_issues();
+        }
+    }
+
+    fn activate_issue(&mut self) {
+        let Some(issue) = self
+            .table_state
+            .selected()
+            .map(|issue_index| self.filtered_issue(issue_index).unwrap().clone())
+        else {
+            return self.deactivate_issue();
+        };
+        if issue.subtasks.is_empty() {
+            return self.start_timer(issue.key);
+        }
+        self.subtask_parent = Some(issue);
+        self.subtask_state.select(Some(0));
+        self.popup = Some(Popup::SubtaskPicker);
+    }
+
+    /// Starts the timer for the parent (`0`) or one of its subtasks.
+    fn pick_subtask(&mut self, index: usize) {
+        self.popup = None;
+        let Some(parent) = self.subtask_parent.take() else {
+            return;
+        };
+        let issue_key = match index {
+            0 => parent.key,
+            index => match parent.subtasks.get(index - 1) {
+                Some(subtask) => subtask.key.clone(),
+                None => return,
+            },
+        };
+        self.start_timer(issue_key);
+    }
+
+    /// Starts the timer, first asking or refusing when the issue is closed, since
+    /// workflow validators reject time logged to closed issues.
+    fn start_timer(&mut self, issue_key: String) {
+        if !self.check_permission(&issue_key, Action::LogWork) {
+            return;
+        }
+        if let Some(status) = self.closed_status(&issue_key) {
+            if self.config.refuse_closed_issues {
+                return self.show_error(format!("{issue_key} is {status}, time can't be logged to it"));
+            }
+            self.pending_activation = Some(issue_key);
+            self.popup = Some(Popup::ClosedIssue);
+            return;
+        }
+        self.begin_timer(issue_key);
+    }
+
+    /// The status of the issue if it is closed.
+    fn closed_status(&self, issue_key: &str) -> Option<String> {
+        let issue = self.find_issue(issue_key)?;
+        (issue.is_done() || self.config.closed_statuses.contains(&issue.status))
+            .then(|| issue.status.clone())
+    }
+
+    fn resolve_closed_issue(&mut self, start: bool) {
+        self.popup = None;
+        if let (Some(issue_key), true) = (self.pending_activation.take(), start) {
+            self.begin_timer(issue_key);
+        }
+    }
+
+    fn begin_timer(&mut self, issue_key: String) {
+        self.deactivate_issue();
+        self.active_issue = Some(issue_key.clone());
+        self.activated_on = Some(Zoned::now());
+        self.persist_state();
+        let status = self.find_issue(&issue_key).map(|issue| issue.status.clone());
+        self.run_rules(Trigger::Activation, &issue_key, status.as_deref());
+    }
+
+    fn resolve_start_offer(&mut self, start: bool) {
+        self.popup = None;
+        if let (Some(issue), true) = (self.start_offer.take(), start) {
+            self.start_timer(issue.key);
+        }
+    }
+
+    /// Switches the timer to the issue of the checked out branch or, without one, to
+    /// the issue that stayed in focus.
+    fn accept_focus_suggestion(&mut self) {
+        let checkout = self.checkout_suggestion.take().map(|checkout| checkout.issue_key);
+        if let Some(issue_key) = checkout.or_else(|| self.focus_suggestion.take()) {
+            self.start_timer(issue_key);
+        }
+    }
+
+    fn deactivate_issue(&mut self) {
+        self.deactivate_issue_at(&Zoned::now());
+    }
+
+    /// Stops the timer as of `end` and submits the session.
+    fn deactivate_issue_at(&mut self, end: &Zoned) {
+        if let (Some(active_issue), Some(activated_on)) = (&self.active_issue, &self.activated_on) {
+            let mut session = Session::new(active_issue, activated_on, end);
+            let notes = lap::comment(&self.laps, &self.config.locale());
+            session.comment = self.worklog_comment(active_issue, notes);
+            session.account = self.config.billing_account(active_issue);
+            self.ledger.record(&session);
+            if self.config.ask_worklog_comment {
+                self.comment_input = session.comment.clone().unwrap_or_default();
+                self.uncommented_session = Some(session);
+                self.popup = Some(Popup::WorklogComment);
+            } else {
+                self.ask_billing_account(session);
+            }
+        }
+        self.clear_state();
+    }
+
+    fn ask_billing_account(&mut self, session: Session) {
+        if self.config.billing_attribute.is_some() && self.config.ask_billing_account {
+            self.billing_input = session.account.clone().unwrap_or_default();
+            self.unbilled_session = Some(session);
+            self.popup = Some(Popup::BillingAccount);
+        } else {
+            self.handle_stopped_session(session);
+        }
+    }
+
+    /// Logs the stopped session with the entered comment, or none if left empty. Without
+    /// `entered` it keeps the comment it would have had without asking.
+    pub(crate) fn confirm_worklog_comment(&mut self, entered: bool) {
+        self.popup = None;
+        let Some(mut session) = self.uncommented_session.take() else {
+            return;
+        };
+        if entered {
+            let comment = self.comment_input.trim();
+            session.comment = (!comment.is_empty()).then(|| comment.to_string());
+        }
+        self.ask_billing_account(session);
+    }
+
+    /// The comment of a new worklog: the `worklog_comment_template` filled in with the
+    /// notes taken during the session, or else the notes alone.
+    pub(crate) fn worklog_comment(&self, issue_key: &str, notes: Option<String>) -> Option<String> {
+        let Some(comment_template) = &self.config.worklog_comment_template else {
+            return notes;
+        };
+        let summary = self
+            .find_issue(issue_key)
+            .map(|issue| issue.summary.clone())
+            .unwrap_or_default();
+        let comment = template::render(
+            comment_template,
+            &[
+                ("key", issue_key),
+                ("summary", &summary),
+                ("project", issue_key.split('-').next().unwrap_or_default()),
+                ("notes", notes.as_deref().unwrap_or_default()),
+                ("branch", &template::git_branch().unwrap_or_default()),
+            ],
+        );
+        let comment = comment.trim();
+        (!comment.is_empty()).then(|| comment.to_string())
+    }
+
+    /// Books the stopped session on the entered billing account, or none if left empty.
+    pub(crate) fn confirm_billing_account(&mut self) {
+        self.popup = None;
+        let Some(mut session) = self.unbilled_session.take() else {
+            return;
+        };
+        let account = self.billing_input.trim();
+        session.account = (!account.is_empty()).then(|| account.to_string());
+        self.handle_stopped_session(session);
+    }
+
+    fn handle_stopped_session(&mut self, session: Session) {
+        if let Some(max_s) = self.max_worklog_s() {
+            if session.duration_s > max_s {
+                self.oversized_session = Some(session);
+                self.popup = Some(Popup::MaxDuration);
+                return;
+            }
+        }
+        match self.find_merge_target(&session) {
+            Some(target) => {
+                self.pending_merge = Some((session, target));
+                self.popup = Some(Popup::MergeConfirm);
+            }
+            None => self.store_session(session),
+        }
+    }
+
+    fn max_worklog_s(&self) -> Option<u64> {
+        Some((self.config.max_worklog_hours? * 3600.0) as u64)
+    }
+
+    /// Submits (`y`), caps (`c`), splits (`s`) or discards (`n`) an oversized session.
+    fn resolve_oversized_session(&mut self, choice: char) {
+        let (Some(session), Some(max_s)) = (&self.oversized_session, self.max_worklog_s()) else {
+            return;
+        };
+        let sessions = match choice {
+            'y' => vec![session.clone()],
+            'c' => vec![Session {
+                duration_s: max_s,
+                ..session.clone()
+            }],
+            's' => session.split(max_s),
+            'n' => vec![],
+            _ => return,
+        };
+        self.oversized_session = None;
+        self.popup = None;
+        for session in sessions {
+            self.store_session(session);
+        }
+    }
+
+    fn store_session(&mut self, session: Session) {
+        if self.config.review_mode {
+            self.history.pending.push(session);
+            self.history.persist();
+        } else if !session.is_loggable() {
+            // Jira would drop it, so it waits in the review to be merged or lengthened.
+            self.show_error(format!(
+                "{}: Less than a minute tracked, kept for the review <C-r>",
+                session.issue_key
+            ));
+            self.history.pending.push(session);
+            self.history.persist();
+        } else {
+            let issue = self.issues.iter_mut().find(|issue| issue.key == session.issue_key);
+            let previous = issue.map(|issue| {
+                let previous = issue.clone();
+                issue.time_spent += session.duration_s;
+                previous
+            });
+            self.pending_updates.push(IssueUpdate::Log { previous, session });
+        }
+    }
+
+    /// Finds the latest session on the same issue that ended within the merge window.
+    fn find_merge_target(&self, session: &Session) -> Option<MergeTarget> {
+        let window_s = self.config.merge_window_minutes? * 60;
+        if self.config.review_mode {
+            let index = self.history.pending.iter().rposition(|pending| {
+                pending.is_followed_by(session, window_s)
+            })?;
+            return Some(MergeTarget::Pending(index));
+        }
+        let index = self.history.entries.iter().rposition(|entry| {
+            entry.worklog_id.is_some() && entry.session().is_followed_by(session, window_s)
+        })?;
+        Some(MergeTarget::Submitted(index))
+    }
+
+    fn resolve_merge(&mut self, merge: bool) {
+        self.popup = None;
+        let Some((session, target)) = self.pending_merge.take() else {
+            return;
+        };
+        if !merge {
+            return self.store_session(session);
+        }
+        match target {
+            MergeTarget::Pending(index) => {
+                self.history.pending[index] = self.history.pending[index].merge(&session);
+            }
+            MergeTarget::Submitted(index) => {
+                let entry = &self.history.entries[index];
+                let merged = entry.session().merge(&session);
+                let worklog_id = entry.worklog_id.clone().unwrap();
+                if let Err(error) = self.update_worklog(&worklog_id, merged) {
+                    self.show_error(format!("{}: {error}", session.issue_key));
+                    self.store_session(session);
+                }
+            }
+        }
+        self.history.persist();
+    }
+
+    /// Replaces a submitted worklog by the given session, also in the local history.
+    fn update_worklog(&mut self, worklog_id: &str, session: Session) -> Result<()> {
+        let journal_id = self.journal.begin(Intent::UpdateWorklog {
+            worklog_id: worklog_id.to_string(),
+            session: session.clone(),
+        });
+        let result = self.jira_for(&session.issue_key).update_worklog(
+            &session.issue_key,
+            worklog_id,
+            &session.started,
+            session.duration_s,
+            session.comment.as_deref(),
+        );
+        self.journal.finish(journal_id);
+        result?;
+        let entry = self
+            .history
+            .entries
+            .iter_mut()
+            .find(|entry| entry.worklog_id.as_deref() == Some(worklog_id));
+        if let Some(entry) = entry {
+            entry.started = session.started;
+            entry.time_spent_s = session.duration_s;
+            entry.comment = session.comment;
+            self.history.persist();
+        }
+        Ok(())
+    }
+
+    fn submit_session(&mut self, session: &Session) -> Result<()> {
+        let journal_id = self.journal.begin(Intent::LogWork(session.clone()));
+        let jira = self.jira_for(&session.issue_key);
+        let result = jira.log_time(
+            &session.issue_key,
+            &session.started,
+            &session.ended(),
+            session.comment.as_deref(),
+        );
+        self.journal.finish(journal_id);
+        let worklog_id = result?;
+        self.record_worklog(session, worklog_id.clone());
+        // The worklog stands even when booking it fails, so it isn't submitted again.
+        if let (Some(worklog_id), Some(account)) = (worklog_id, &session.account) {
+            if let Err(error) = timesheet::book_account(self.tempo.as_ref(), &worklog_id, account) {
+                let key = &session.issue_key;
+                self.show_error(format!("{key}: Logged, but not booked on a billing account: {error}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the created worklog to the history, `None` when the session was too short
+    /// to be logged.
+    fn record_worklog(&mut self, session: &Session, worklog_id: Option<String>) {
+        let jira = self.jira_for(&session.issue_key);
+        if worklog_id.is_some() {
+            let entry = HistoryEntry {
+                issue_key: session.issue_key.clone(),
+                started: session.started.clone(),
+                time_spent_s: session.duration_s,
+                worklog_id,
+                logged_as: jira.log_as_account_id().map(|id| id.to_string()),
+                submitted: Some(Zoned::now()),
+                comment: session.comment.clone(),
+            };
+            self.history.record(entry);
+            let status = self.find_issue(&session.issue_key).map(|issue| issue.status.clone());
+            self.run_rules(Trigger::Submission, &session.issue_key, status.as_deref());
+        }
+    }
+
+    /// Retries or discards a write that was interrupted by a crash.
+    fn recover_journal_entry(&mut self, retry: bool) {
+        let Some(id) = self
+            .recovery_state
+            .selected()
+            .and_then(|index| self.journal.entries.get(index))
+            .map(|entry| entry.id)
+        else {
+            return;
+        };
+        let Some(entry) = self.journal.take(id) else {
+            return;
+        };
+        if retry {
+            let result = match entry.intent {
+                Intent::LogWork(session) => self.submit_session(&session),
+                Intent::UpdateWorklog { worklog_id, session } => {
+                    self.update_worklog(&worklog_id, session)
+                }
+            };
+            if let Err(error) = result {
+                self.show_error(error.to_string());
+            }
+        }
+        if self.journal.entries.is_empty() {
+            self.popup = None;
+        }
+    }
+
+    fn adjust_pending_session(&mut self, delta_s: i64) {
+        if let Some(session) = self
+            .review_state
+            .selected()
+            .and_then(|index| self.history.pending.get_mut(index))
+        {
+            session.adjust(delta_s);
+            self.history.persist();
+        }
+    }
+
+    /// Merges the selected session with the next pending session on the same issue.
+    fn merge_pending_session(&mut self) {
+        let Some(index) = self.review_state.selected() else {
+            return;
+        };
+        let Some(session) = self.history.pending.get(index) else {
+            return;
+        };
+        let other_index = self
+            .history
+            .pending
+            .iter()
+            .enumerate()
+            .skip(index + 1)
+            .find(|(_, other)| other.issue_key == session.issue_key)
+            .map(|(other_index, _)| other_index);
+        if let Some(other_index) = other_index {
+            let other = self.history.pending.remove(other_index);
+            self.history.pending[index] = self.history.pending[index].merge(&other);
+            self.history.persist();
+        }
+    }
+
+    fn round_pending_sessions(&mut self) {
+        for session in self.history.pending.iter_mut() {
+            session.round(self.config.round_to_minutes);
+        }
+        self.history.persist();
+    }
+
+    fn delete_pending_session(&mut self) {
+        if let Some(index) = self.review_state.selected() {
+            if index < self.history.pending.len() {
+                self.history.pending.remove(index);
+                self.history.persist();
+            }
+        }
+    }
+
+    /// Submits the pending sessions, unless some of them cover time that is already
+    /// logged in Jira; those are listed for confirmation first.
+    fn submit_reviewed_sessions(&mut self) {
+        if self.submitter.is_some() {
+            // The submitter checks each session and sends back the overlapping ones.
+            self.overlaps.clear();
+            self.send_pending_sessions(false);
+            return;
+        }
+        let pending = &self.history.pending;
+        let start = pending.iter().map(|session| session.started.clone()).min();
+        let end = pending.iter().map(Session::ended).max();
+        let (Some(start), Some(end)) = (start, end) else {
+            return;
+        };
+        let (worklogs, unchecked) = overlap::my_worklogs(self.jiras(), &start, &end);
+        // An instance that can't be read doesn't keep the others from being submitted.
+        for error in unchecked {
+            self.show_error(format!("Could not check for overlapping worklogs: {error}"));
+        }
+        self.overlaps = overlap::overlaps(&self.history.pending, &worklogs, MIN_OVERLAP_S);
+        if self.overlaps.is_empty() {
+            self.submit_pending_sessions();
+        } else {
+            self.popup = Some(Popup::Overlaps);
+        }
+    }
+
+    /// Submits all pending sessions, keeping the ones that failed for another attempt and
+    /// the ones too short for Jira.
+    fn submit_pending_sessions(&mut self) {
+        if self.submitter.is_some() {
+            self.send_pending_sessions(true);
+            return;
+        }
+        let pending = std::mem::take(&mut self.history.pending);
+        let mut kept = vec![];
+        for session in pending {
+            if !session.is_loggable() || self.submit_session(&session).is_err() {
+                kept.push(session);
+            }
+        }
+        self.keep_pending_sessions(kept);
+    }
+
+    /// Hands the pending sessions to the submitter, which puts back the ones Jira refused
+    /// or, unless `confirmed`, the ones overlapping logged work.
+    fn send_pending_sessions(&mut self, confirmed: bool) {
+        let pending = std::mem::take(&mut self.history.pending);
+        let (loggable, kept): (Vec<_>, Vec<_>) = pending.into_iter().partition(Session::is_loggable);
+        for session in loggable {
+            let journal_id = self.journal.begin(Intent::LogWork(session.clone()));
+            if let Some(submitter) = &mut self.submitter {
+                submitter.submit(Submission {
+                    journal_id,
+                    session,
+                    previous: None,
+                    confirmed,
+                });
+            }
+        }
+        self.keep_pending_sessions(kept);
+    }
+
+    fn keep_pending_sessions(&mut self, kept: Vec<Session>) {
+        let short = kept.iter().filter(|session| !session.is_loggable()).count();
+        if short > 0 {
+            self.show_error(format!("{short} sessions under a minute kept, merge or lengthen them"));
+        }
+        self.history.pending = kept;
+        self.history.persist();
+    }
+
+    fn clear_state(&mut self) {
+        if self.active_issue.is_some() {
+            self.idle_since = Zoned::now();
+            self.reminded = false;
+            self.last_issue = self.active_issue.clone();
+            self.stopped_on = Some(Zoned::now());
+        }
+        self.active_issue = None;
+        self.activated_on = None;
+        self.laps.clear();
+        self.persist_state();
+    }
+
+    /// Marks the current moment of the running session with the typed label.
+    fn add_lap(&mut self) {
+        self.popup = None;
+        let label = self.lap_input.trim();
+        if label.is_empty() {
+            return;
+        }
+        self.laps.push(Lap {
+            at: Zoned::now(),
+            label: label.to_string(),
+        });
+        self.persist_state();
+    }
+
+    fn tracking_reminder(&self) -> bool {
+        let Some(work_hours) = &self.config.work_hours else {
+            return false;
+        };
+        self.active_issue.is_none()
+            && reminder::should_remind(
+                work_hours,
+                &self.idle_since,
+                &Zoned::now(),
+                self.config.reminder_after_minutes,
+            )
+    }
+
+    fn check_reminder(&mut self) {
+        if self.reminded || !self.tracking_reminder() {
+            return;
+        }
+        self.reminded = true;
+        if self.config.desktop_notifications {
+            notify::send("Jiratrack", "No timer is running, don't forget to start tracking.");
+        }
+    }
+
+    /// Suggests switching the timer when another issue key stays in the output of
+    /// the focus command.
+    fn check_focus(&mut self) {
+        let Some(command) = &self.config.focus_command else {
+            return;
+        };
+        let now = Instant::now();
+        if !self.focus.is_due(now) {
+            return;
+        }
+        self.focus.observe(focus::read(command), now);
+        let after = Duration::from_secs(self.config.focus_switch_seconds);
+        let suggestion = self
+            .focus
+            .persistent(after, now)
+            .filter(|key| self.active_issue.as_deref().is_some_and(|active| active != *key))
+            .filter(|key| self.is_known_issue(key))
+            .map(|key| key.to_string());
+        if suggestion.is_some() && suggestion != self.focus_suggestion && self.config.desktop_notifications {
+            let message = format!("Working on {}? Switch the timer with <C-v>.", suggestion.as_deref().unwrap());
+            notify::send("Jiratrack", &message);
+        }
+        self.focus_suggestion = suggestion;
+    }
+
+    /// Adopts the timer started or stopped by another jiratrack, like `jiratrack toggle`
+    /// bound to a hotkey. The interface writes every change of its own right away, so
+    /// a different timer in the state file was set from outside. When another machine
+    /// wrote it, e.g. through a synced home directory, asks whether to adopt it.
+    fn check_state_file(&mut self) {
+        let path = self.get_state_path();
+        let Ok(modified) = fs::metadata(&path).and_then(|metadata| metadata.modified()) else {
+            return;
+        };
+        if self.state_modified.replace(modified) == Some(modified) {
+            return;
+        }
+        let Ok(file) = File::open(path) else {
+            return;
+        };
+        let Ok(data) = serde_json::from_reader::<_, PersistedState>(file) else {
+            return;
+        };
+        // Compared by the instant, the time zone may not survive the round trip.
+        fn timer<'a>(issue_key: &'a Option<String>, activated_on: &Option<Zoned>) -> Option<(&'a str, Timestamp)> {
+            issue_key.as_deref().zip(activated_on.as_ref().map(Zoned::timestamp))
+        }
+        let change = host::compare(
+            timer(&self.active_issue, &self.activated_on),
+            timer(&data.active_issue, &data.activated_on),
+            data.host.as_deref(),
+            &self.host,
+        );
+        match change {
+            StateChange::Unchanged | StateChange::Keep => return,
+            StateChange::Adopt => {}
+            StateChange::Conflict => {
+                let foreign = ForeignTimer {
+                    host: data.host.unwrap_or_default(),
+                    issue_key: data.active_issue,
+                    activated_on: data.activated_on,
+                };
+                if self.ignored_timer.as_ref() == Some(&foreign) {
+                    return;
+                }
+                if self.popup.is_some() {
+                    // Asked about once the popup closes.
+                    self.state_modified = None;
+                    return;
+                }
+                if self.config.desktop_notifications {
+                    let message = match &foreign.issue_key {
+                        Some(issue_key) => format!("{} started the timer on {issue_key}.", foreign.host),
+                        None => format!("{} stopped the timer.", foreign.host),
+                    };
+                    notify::send("Jiratrack", &message);
+                }
+                self.foreign_timer = Some(foreign);
+                self.popup = Some(Popup::ForeignTimer);
+                return;
+            }
+        }
+        self.active_issue = data.active_issue;
+        self.activated_on = data.activated_on;
+        self.laps = data.laps;
+        self.last_issue = data.last_issue;
+        self.stopped_on = data.stopped_on;
+        self.idle_since = Zoned::now();
+        self.reminded = false;
+    }
+
+    /// Takes over the timer of the other machine. A timer running here stops where the
+    /// other one started, so no time is tracked twice; when the other machine stopped
+    /// the timer, which it then submitted, the one here is dropped.
+    fn adopt_foreign_timer(&mut self) {
+        self.popup = None;
+        let Some(foreign) = self.foreign_timer.take() else {
+            return;
+        };
+        let (Some(issue_key), Some(activated_on)) = (foreign.issue_key, foreign.activated_on) else {
+            return self.clear_state();
+        };
+        self.deactivate_issue_at(&activated_on);
+        self.active_issue = Some(issue_key);
+        self.activated_on = Some(activated_on);
+        self.persist_state();
+    }
+
+    /// Switches the timer, or suggests to, when the git hook reports a checkout of a
+    /// branch of another issue.
+    fn check_checkout(&mut self) {
+        let Some(checkout) = Checkout::take(Timestamp::now()) else {
+            return;
+        };
+        if self.active_issue.as_ref().is_none_or(|active| *active == checkout.issue_key)
+            || !self.is_known_issue(&checkout.issue_key)
+        {
+            return;
+        }
+        if self.config.checkout_switch {
+            self.checkout_suggestion = None;
+            return self.start_timer(checkout.issue_key);
+        }
+        if self.config.desktop_notifications {
+            let message = format!(
+                "Checked out {}, switch the timer to {} with <C-v>.",
+                checkout.branch, checkout.issue_key
+            );
+            notify::send("Jiratrack", &message);
+        }
+        self.checkout_suggestion = Some(checkout);
+    }
+
+    /// Whether the key belongs to a listed issue or one of the configured projects.
+    fn is_known_issue(&self, key: &str) -> bool {
+        let project = key.split('-').next().unwrap_or("");
+        self.issues.iter().any(|issue| issue.key == key)
+            || std::iter::once(&self.config)
+                .chain(self.config.instance_configs().iter())
+                .any(|config| config.projects().iter().any(|known| known == project))
+    }
+
+    /// Announces reminders on issues once their time has come.
+    fn check_follow_ups(&mut self) {
+        let due = self.follow_ups.take_due(Timestamp::now());
+        if due.is_empty() {
+            return;
+        }
+        self.follow_ups.persist();
+        for follow_up in due {
+            let summary = self
+                .issues
+                .iter()
+                .find(|issue| issue.key == follow_up.issue_key)
+                .map(|issue| issue.summary.as_str())
+                .unwrap_or_default();
+            let message = format!("Reminder: {} {summary}", follow_up.issue_key);
+            if self.config.desktop_notifications {
+                notify::send("Jiratrack", &message);
+            }
+            self.toast = Some((message, Instant::now()));
+        }
+    }
+
+    /// Offers to log an ongoing meeting from the calendar while no timer runs.
+    fn check_meeting(&mut self) {
+        let (Some(path), Some(_)) = (&self.config.calendar_path, &self.config.meeting_issue) else {
+            return;
+        };
+        if self.calendar_loaded.is_none_or(|loaded| loaded.elapsed() > CALENDAR_RELOAD) {
+            self.calendar = calendar::load(path).unwrap_or_default();
+            self.calendar_loaded = Some(Instant::now());
+        }
+        let now = Zoned::now();
+        self.meeting = calendar::ongoing(&self.calendar, &now)
+            .filter(|_| self.active_issue.is_none())
+            .filter(|event| !self.handled_meetings.contains(&event.id()))
+            .cloned();
+        let Some(meeting) = &self.meeting else {
+            return;
+        };
+        if self.config.desktop_notifications && self.notified_meeting != Some(meeting.id()) {
+            let message = format!("{} is ongoing, log it with <C-e>.", meeting.summary);
+            notify::send("Jiratrack", &message);
+            self.notified_meeting = Some(meeting.id());
+        }
+    }
+
+    /// Opens the redistribution of a block of time over the listed issues, prefilled
+    /// with the working time today that was not tracked yet.
+    fn open_redistribution(&mut self) {
+        let now = Zoned::now();
+        let untracked_s = self.config.work_hours.as_ref().map_or(0, |work_hours| {
+            let running_s = match &self.activated_on {
+                Some(activated_on) if activated_on.date() == now.date() => {
+                    (now.timestamp().as_second() - activated_on.timestamp().as_second()).max(0) as u64
+                }
+                _ => 0,
+            };
+            work_hours
+                .elapsed_s(&now)
+                .saturating_sub(self.ledger.total_on(now.date()) + running_s)
+        });
+        let total = match untracked_s / 60 {
+            0 => String::new(),
+            _ => self.config.work_time().format(untracked_s),
+        };
+        let keys = self
+            .search
+            .matches()
+            .iter()
+            .map(|index| self.issues[*index].key.clone())
+            .collect();
+        self.redistribution = Some(Redistribution::new(total, keys));
+        self.popup = Some(Popup::Redistribute);
+    }
+
+    /// Logs the shares of the block as back-to-back sessions, ending when the running
+    /// timer started or now.
+    fn submit_redistribution(&mut self) {
+        let Some(redistribution) = &mut self.redistribution else {
+            return;
+        };
+        let allocations = match redistribution.allocations(&self.config.work_time()) {
+            Ok(allocations) => allocations,
+            Err(error) => {
+                redistribution.error = Some(error);
+                return;
+            }
+        };
+        let end = self.activated_on.clone().unwrap_or_else(Zoned::now);
+        for mut session in redistribute::sessions(&allocations, &end) {
+            session.comment = self.worklog_comment(&session.issue_key, None);
+            session.account = self.config.billing_account(&session.issue_key);
+            self.ledger.record(&session);
+            self.store_session(session);
+        }
+        self.redistribution = None;
+        self.popup = None;
+    }
+
+    /// Logs the whole ongoing meeting to the meeting issue.
+    fn log_meeting(&mut self) {
+        let (Some(meeting), Some(issue_key)) = (self.meeting.take(), &self.config.meeting_issue) else {
+            return;
+        };
+        let session = Session {
+            comment: self.worklog_comment(issue_key, None),
+            account: self.config.billing_account(issue_key),
+            ..Session::new(issue_key, &meeting.start, &meeting.end)
+        };
+        self.handled_meetings.push(meeting.id());
+        self.ledger.record(&session);
+        self.handle_stopped_session(session);
+    }
+
+    /// Has every instance polled for new notifications in the background once the poll
+    /// interval has passed.
+    fn check_notifications(&mut self) {
+        let interval_s = self.config.notification_poll_minutes as i64 * 60;
+        let now = Timestamp::now();
+        if interval_s == 0 || now.as_second() - self.notifications_checked.as_second() < interval_s {
+            return;
+        }
+        let since = self.notifications_checked;
+        self.notifications_checked = now;
+        if let Some(poller) = &self.poller {
+            poller.poll(Poll::Notifications { since });
+        }
+    }
+
+    /// Adds the polled notifications not seen yet, sending them to the desktop too.
+    fn note_notifications(&mut self, mut new_notifications: Vec<Notification>) {
+        new_notifications.retain(|notification| !self.notifications.contains(notification));
+        if self.config.desktop_notifications {
+            for notification in &new_notifications {
+                notify::send("Jiratrack", &notification.message());
+            }
+        }
+        self.notifications.extend(new_notifications);
+    }
+
+    /// Polls the issue the timer runs for, so comments and status changes by others
+    /// are noticed while working on it.
+    fn check_active_issue(&mut self) {
+        let (Some(issue_key), Some(activated_on)) = (self.active_issue.clone(), &self.activated_on) else {
+            self.active_issue_change = None;
+            return;
+        };
+        if self
+            .active_issue_change
+            .as_ref()
+            .is_some_and(|change| change.issue_key != issue_key)
+        {
+            self.active_issue_change = None;
+        }
+        let interval_s = self.config.active_issue_poll_minutes as i64 * 60;
+        let now = Timestamp::now();
+        if interval_s == 0 || now.as_second() - self.active_issue_checked.as_second() < interval_s {
+            return;
+        }
+        let since = self.active_issue_checked.max(activated_on.timestamp());
+        self.active_issue_checked = now;
+        if let Some(poller) = &self.poller {
+            poller.poll(Poll::ActiveIssue { issue_key, since });
+        }
+    }
+
+    /// Applies the answers to the periodic checks.
+    fn check_polls(&mut self) {
+        let Some(poller) = &self.poller else {
+            return;
+        };
+        for polled in poller.take_results() {
+            match polled {
+                Polled::ActiveIssue { issue_key, changes } => self.note_active_issue_changes(&issue_key, changes),
+                Polled::StartedIssues(issues) => self.offer_started_issue(issues),
+                Polled::Notifications(notifications) => self.note_notifications(notifications),
+                Polled::JqlData(data) => {
+                    self.jql_data = data;
+                    self.update_jql_suggestions();
+                }
+                Polled::JqlValues { input, values } => self.show_jql_values(&input, values),
+                Polled::ValidatedJql { jql, errors } => self.finish_jql_validation(jql, errors),
+            }
+        }
+    }
+
+    fn note_active_issue_changes(&mut self, issue_key: &str, mut changes: Vec<Notification>) {
+        // The timer may have moved on while Jira answered.
+        if self.active_issue.as_deref() != Some(issue_key) {
+            return;
+        }
+        changes.retain(|change| !self.notifications.contains(change));
+        if self.config.desktop_notifications {
+            for change in &changes {
+                notify::send("Jiratrack", &change.message());
+            }
+        }
+        if let Some(change) = changes.last() {
+            self.active_issue_change = Some(change.clone());
+        }
+        self.notifications.extend(changes);
+    }
+
+    /// Offers to start the timer on an issue assigned to the user that was moved to the
+    /// `auto_start_status` elsewhere, e.g. by dragging it on the web board. The offer
+    /// waits until no other popup is open.
+    fn check_start_offers(&mut self) {
+        let Some(status) = &self.config.auto_start_status else {
+            return;
+        };
+        if self.start_offer.is_some() {
+            if self.popup.is_none() {
+                self.popup = Some(Popup::StartOffer);
+            }
+            return;
+        }
+        let now = Timestamp::now();
+        if now.as_second() - self.start_offers_checked.as_second() < AUTO_START_POLL.as_secs() as i64 {
+            return;
+        }
+        let since = self.start_offers_checked;
+        self.start_offers_checked = now;
+        if let Some(poller) = &self.poller {
+            poller.poll(Poll::StartedIssues {
+                status: status.clone(),
+                since,
+            });
+        }
+    }
+
+    fn offer_started_issue(&mut self, issues: Vec<Issue>) {
+        let Some(status) = &self.config.auto_start_status else {
+            return;
+        };
+        // Jira is asked with a minute to spare, so the last answer may be repeated.
+        let seen = std::mem::replace(
+            &mut self.start_offers_seen,
+            issues.iter().map(|issue| issue.key.clone()).collect(),
+        );
+        let offer = issues.into_iter().find(|issue| {
+            self.active_issue.as_deref() != Some(issue.key.as_str()) && !seen.contains(&issue.key)
+        });
+        let Some(issue) = offer else {
+            return;
+        };
+        if self.config.desktop_notifications {
+            let message = format!("{} moved to {status}. Start the timer?", issue.key);
+            notify::send("Jiratrack", &message);
+        }
+        self.start_offer = Some(issue);
+    }
+
+    /// Applies issue changes pushed by Jira webhooks to the issue list.
+    fn apply_webhook_events(&mut self) {
+        let Some(receiver) = &self.webhook_events else {
+            return;
+        };
+        let events: Vec<WebhookEvent> = receiver.try_iter().collect();
+        for event in events {
+            match event {
+                WebhookEvent::Created(_) => self.refresh_issues(),
+                WebhookEvent::Updated(issue) => {
+                    let Some(key) = issue["key"].as_str() else {
+                        continue;
+                    };
+                    // Payloads without the fields the list shows are fetched instead.
+                    let updated = if schema::check(&schema::ISSUE, &issue).is_empty() {
+                        self.jira_for(key).parse_issue(&issue)
+                    } else {
+                        match self.jira_for(key).get_issue(key) {
+                            Ok(updated) => updated,
+                            Err(_) => {
+                                self.refresh_issues();
+                                continue;
+                            }
+                        }
+                    };
+                    if let Some(prefetcher) = &mut self.prefetcher {
+                        prefetcher.invalidate(key);
+                    }
+                    let index = self.issues.iter().position(|issue| issue.key == updated.key);
+                    let moved = index.is_some_and(|index| self.issues[index].status != updated.status);
+                    if moved {
+                        let status = updated.status.clone();
+                        self.run_rules(Trigger::StatusChange, key, Some(&status));
+                    }
+                    match index {
+                        Some(index) if updated.is_done() => {
+                            self.issues.remove(index);
+                        }
+                        Some(index) => self.issues[index] = updated,
+                        // The change may have moved the issue into the current query.
+                        None if self.jira_for(key).owns_issue(key) => self.refresh_issues(),
+                        None => {}
+                    }
+                }
+                WebhookEvent::Deleted(key) => self.issues.retain(|issue| issue.key != key),
+            }
+        }
+    }
+
+    fn dismiss_notification(&mut self) {
+        if let Some(index) = self.notification_state.selected() {
+            if index < self.notifications.len() {
+                self.notifications.remove(index);
+            }
+        }
+    }
+
+    fn open_notification(&mut self) {
+        let Some(notification) = self
+            .notification_state
+            .selected()
+            .and_then(|index| self.notifications.get(index))
+        else {
+            return;
+        };
+        let key = notification.issue_key.clone();
+        if let Ok(issue) = self.jira_for(&key).get_issue(&key) {
+            self.dismiss_notification();
+            self.open_issue_detail(issue);
+        }
+    }
+
+    fn copy_mr_title(&self) {
+        let issue = self.get_active_issue();
+
+        if let Some(issue) = issue {
+        let issue_string = format!("[{}] {}", issue.key, issue.summary);
+
+        let mut clipboard = Clipboard::new().unwrap();
+        clipboard.set_text(issue_string).unwrap();
+        }
+    }
+
+    fn copy_commit_message(&mut self) {
+        let Some(issue) = self.get_active_issue() else {
+            return;
+        };
+        let message = template::render(
+            &self.config.commit_template,
+            &[
+                ("key", &issue.key),
+                ("summary", &issue.summary),
+                ("project", issue.project()),
+            ],
+        );
+        let result = Clipboard::new().and_then(|mut clipboard| clipboard.set_text(message));
+        if let Err(error) = result {
+            self.show_error(format!("Could not copy the commit message: {error}"));
+        }
+    }
+
+    /// Copies the merge request description of the active issue, with the acceptance
+    /// criteria of its description as a checklist.
+    fn copy_mr_description(&mut self) {
+        let Some(issue) = self.get_active_issue() else {
+            return;
+        };
+        let jira = self.jira_for(&issue.key);
+        let description = match jira.get_issue_description(&issue.key) {
+            Ok(description) => description,
+            Err(error) => {
+                return self.show_error(format!("Could not load the description of {}: {error}", issue.key))
+            }
+        };
+        let link = jira.browse_url(&issue.key);
+        let criteria: Vec<String> = adf::acceptance_criteria(&description)
+            .iter()
+            .map(|criterion| format!("- [ ] {criterion}"))
+            .collect();
+        let description = template::render(
+            &self.config.mr_description_template,
+            &[
+                ("key", &issue.key),
+                ("summary", &issue.summary),
+                ("project", issue.project()),
+                ("link", &link),
+                ("criteria", &criteria.join("\n")),
+            ],
+        );
+        let result = Clipboard::new().and_then(|mut clipboard| clipboard.set_text(description));
+        if let Err(error) = result {
+            self.show_error(format!("Could not copy the merge request description: {error}"));
+        }
+    }
+
+    /// Asks for the summary of a copy of the selected issue, prefilled from the clone template.
+    fn open_clone(&mut self) {
+        let Some(key) = self.selected_issue().map(|issue| issue.key.clone()) else {
+            return;
+        };
+        if !self.check_permission(&key, Action::Create) {
+            return;
+        }
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        self.clone_input = template::render(
+            &self.config.clone_template,
+            &[
+                ("key", &issue.key),
+                ("summary", &issue.summary),
+                ("project", issue.project()),
+            ],
+        );
+        self.popup = Some(Popup::Clone);
+    }
+
+    /// Creates the copy of the selected issue and selects it in the reloaded list.
+    fn clone_selected_issue(&mut self) {
+        self.popup = None;
+        let summary = self.clone_input.trim().to_string();
+        let Some(key) = self.selected_issue().map(|issue| issue.key.clone()) else {
+            return;
+        };
+        if summary.is_empty() {
+            return;
+        }
+        match self.jira_for(&key).clone_issue(&key, &summary) {
+            Ok(clone) => {
+                self.show_error(format!("Cloned {key} as {clone}"));
+                self.select_after_load = Some(clone);
+                self.refresh_issues();
+            }
+            Err(error) => self.show_error(format!("Could not clone {key}: {error}")),
+        }
+    }
+
+    fn selected_issue(&self) -> Option<&Issue> {
+        self.filtered_issue(self.table_state.selected()?)
+    }
+
+    /// Copies the browse URL (`u`), a Markdown link (`m`) or a rich HTML link (`h`)
+    /// of the selected issue.
+    fn copy_link(&mut self, format: char) {
+        self.popup = None;
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let url = self.jira_for(&issue.key).browse_url(&issue.key);
+        let markdown = link::markdown(&issue.key, &issue.summary, &url);
+        let html = link::html(&issue.key, &issue.summary, &url);
+        let result = Clipboard::new().and_then(|mut clipboard| match format {
+            'u' => clipboard.set_text(url),
+            'm' => clipboard.set_text(markdown),
+            _ => clipboard.set_html(html, Some(markdown)),
+        });
+        if let Err(error) = result {
+            self.show_error(format!("Could not copy the link: {error}"));
+        }
+    }
+
+    fn open_receipts(&mut self) {
+        self.receipts_state.select(Some(0));
+        self.popup = Some(Popup::Receipts);
+    }
+
+    /// Lists the last weeks with the time logged in them and, with Tempo, whether they
+    /// were approved.
+    fn open_timesheet(&mut self) {
+        let current = self.config.locale().week_start(Zoned::now().date());
+        let account_id = self.myself.as_ref().map(|myself| myself.account_id.clone());
+        let holidays = Holidays::from_config(&self.config);
+        let day_s = self.config.work_time().hours_per_day * 3600.0;
+        let mut error = None;
+        let weeks: Vec<Week> = timesheet::week_starts(current)
+            .into_iter()
+            .map(|start| {
+                let end = start.saturating_add(6.days());
+                let logged_s = self
+                    .history
+                    .entries
+                    .iter()
+                    .filter(|entry| (start..=end).contains(&entry.started.date()))
+                    .map(|entry| entry.time_spent_s)
+                    .sum();
+                // Gives up on Tempo after the first failure instead of waiting for each week.
+                let approval = match (&self.tempo, &account_id, &error) {
+                    (Some(tempo), Some(account_id), None) => tempo
+                        .get_approval(account_id, start)
+                        .map_err(|failure| error = Some(failure))
+                        .ok(),
+                    _ => None,
+                };
+                let target_s = (holidays.workdays(start, end) as f64 * day_s).round() as u64;
+                Week {
+                    start,
+                    logged_s,
+                    target_s,
+                    approval,
+                }
+            })
+            .collect();
+        self.timesheet = weeks;
+        if let Some(error) = error {
+            self.show_error(format!("Could not read the approvals from Tempo: {error}"));
+        }
+        self.timesheet_state.select(Some(0));
+        self.popup = Some(Popup::Timesheet);
+    }
+
+    /// Submits the week selected in the timesheet for approval.
+    fn submit_selected_week(&mut self) {
+        let Some(index) = self.timesheet_state.selected().filter(|index| *index < self.timesheet.len()) else {
+            return;
+        };
+        let (Some(tempo), Some(myself)) = (&self.tempo, &self.myself) else {
+            return self.show_error("Set tempo_api_token to submit timesheets for approval".to_string());
+        };
+        let week = &self.timesheet[index];
+        if week.approval.as_ref().is_some_and(|approval| !approval.status.can_submit()) {
+            return;
+        }
+        match tempo.submit(&myself.account_id, week.start) {
+            Ok(status) => {
+                if let Some(approval) = &mut self.timesheet[index].approval {
+                    approval.status = status;
+                    approval.comment = None;
+                }
+            }
+            Err(error) => self.show_error(format!("Could not submit the week: {error}")),
+        }
+    }
+
+    /// Copies the permalink of the worklog selected in the receipts popup.
+    fn copy_receipt_link(&mut self) {
+        let receipts = self.history.receipts(self.config.receipt_count);
+        let Some(entry) = self.receipts_state.selected().and_then(|index| receipts.get(index)) else {
+            return;
+        };
+        let Some(worklog_id) = &entry.worklog_id else {
+            return;
+        };
+        let url = self.jira_for(&entry.issue_key).worklog_url(&entry.issue_key, worklog_id);
+        match Clipboard::new().and_then(|mut clipboard| clipboard.set_text(url)) {
+            Ok(()) => self.popup = None,
+            Err(error) => self.show_error(format!("Could not copy the link: {error}")),
+        }
+    }
+
+    /// Sets or snoozes the reminder on the selected issue to the typed time.
+    fn set_follow_up(&mut self) {
+        let Some(issue_key) = self.selected_issue().map(|issue| issue.key.clone()) else {
+            return;
+        };
+        let Some(at) = followup::parse_time(&self.follow_up_input, &Zoned::now()) else {
+            return self.show_error(format!("Unknown time {}, use e.g. 15:00 or 30m", self.follow_up_input));
+        };
+        self.follow_ups.set(&issue_key, at);
+        self.follow_ups.persist();
+        self.popup = None;
+    }
+
+    fn clear_follow_up(&mut self) {
+        if let Some(issue_key) = self.selected_issue().map(|issue| issue.key.clone()) {
+            self.follow_ups.clear(&issue_key);
+            self.follow_ups.persist();
+        }
+        self.popup = None;
+    }
+
+    /// Hides the selected issue until the typed day or until its status changes.
+    fn snooze_selected_issue(&mut self) {
+        let Some(issue) = self.selected_issue().cloned() else {
+            return;
+        };
+        let Some(until) = snooze::parse_until(&self.snooze_input, Zoned::now().date()) else {
+            return self.show_error(format!("Unknown day {}, use e.g. 2025-02-01, 3d or 2w", self.snooze_input));
+        };
+        self.snoozes.set(&issue, until);
+        self.snoozes.persist();
+        self.search.set_hidden(self.snoozes.keys());
+        self.popup = None;
+    }
+
+    /// Lists the issues without story points, to estimate them one after the other.
+    fn open_estimation(&mut self) {
+        match self.jira.get_unestimated_issues() {
+            Ok(issues) if issues.is_empty() => self.show_error("Every issue is estimated".to_string()),
+            Ok(issues) => {
+                self.estimation = Estimation::new(issues);
+                self.popup = Some(Popup::Estimation);
+            }
+            Err(error) => self.show_error(format!("Could not load the issues to estimate: {error}")),
+        }
+    }
+
+    /// Writes the typed story points to the current issue and moves on to the next.
+    fn save_estimate(&mut self) {
+        let Some(issue_key) = self.estimation.current().map(|issue| issue.key.clone()) else {
+            return self.popup = None;
+        };
+        let Some(points) = estimation::parse_points(&self.estimation.input) else {
+            return;
+        };
+        if !self.check_permission(&issue_key, Action::Edit) {
+            return;
+        }
+        match self.jira_for(&issue_key).set_story_points(&issue_key, points) {
+            Ok(()) => {
+                if let Some(issue) = self.issues.iter_mut().find(|issue| issue.key == issue_key) {
+                    issue.story_points = Some(points);
+                }
+                self.estimation.issues[self.estimation.index].story_points = Some(points);
+                self.estimation.estimated += 1;
+                self.estimation.next();
+            }
+            Err(error) => self.show_error(format!("Could not estimate {issue_key}: {error}")),
+        }
+    }
+
+    /// Edits the color tag and note of the selected issue.
+    fn open_annotate(&mut self) {
+        let Some(issue_key) = self.selected_issue().map(|issue| issue.key.clone()) else {
+            return;
+        };
+        self.annotation_draft = self.annotations.get(&issue_key).cloned().unwrap_or_default();
+        self.popup = Some(Popup::Annotate);
+    }
+
+    /// Keeps the edited annotation on the selected issue, removing it when empty.
+    fn save_annotation(&mut self) {
+        let Some(issue_key) = self.selected_issue().map(|issue| issue.key.clone()) else {
+            return;
+        };
+        let mut annotation = std::mem::take(&mut self.annotation_draft);
+        annotation.note = annotation.note.trim().to_string();
+        if annotation.is_empty() {
+            self.annotations.remove(&issue_key);
+        } else {
+            self.annotations.insert(issue_key, annotation);
+        }
+        self.persist_state();
+        self.popup = None;
+    }
+
+    /// Lists the snoozed issue selected in the snooze list again.
+    fn wake_selected_snooze(&mut self) {
+        let Some(snooze) = self
+            .snooze_state
+            .selected()
+            .and_then(|index| self.snoozes.entries.get(index))
+        else {
+            return;
+        };
+        let issue_key = snooze.issue_key.clone();
+        self.snoozes.clear(&issue_key);
+        self.snoozes.persist();
+        self.search.set_hidden(self.snoozes.keys());
+    }
+
+    /// Lists snoozed issues again once their day has come or their status changed.
+    fn check_snoozes(&mut self) {
+        if !self.loaded {
+            return;
+        }
+        let woken = self.snoozes.take_woken(&self.issues, Zoned::now().date());
+        if woken.is_empty() {
+            return;
+        }
+        self.snoozes.persist();
+        self.search.set_hidden(self.snoozes.keys());
+        let woken: Vec<String> = woken
+            .into_iter()
+            .map(|(snooze, reason)| format!("{} ({reason})", snooze.issue_key))
+            .collect();
+        self.show_error(format!("Listed again: {}", woken.join(", ")));
+    }
+
+    fn open_sprint_popup(&mut self, popup: Popup) {
+        let mut sprint_issues = vec![];
+        for (index, jira, sprint) in self.active_sprints() {
+            let mut issues = jira.get_sprint_issues(sprint.id).unwrap_or_default();
+            issues.iter_mut().for_each(|issue| issue.set_instance(index));
+            sprint_issues.extend(issues);
+        }
+        self.sprint_issues = match self.sprints.iter().any(Option::is_some) {
+            true => sprint_issues,
+            false => self.issues.clone(),
+        };
+        self.popup = Some(popup);
+    }
+
+    fn add_char(&mut self, new_char: char) {
+        self.search_input.push(new_char)
+    }
+
+    fn delete_char(&mut self) {
+        let mut chars = self.search_input.chars();
+        chars.next_back();
+        self.search_input = chars.as_str().to_string()
+    }
+
+    /// Quits on a termination signal or Ctrl-C without losing the running timer:
+    /// it is either kept for the next start or submitted, as configured.
+    fn shut_down(&mut self) {
+        if self.config.submit_on_exit {
+            if let (Some(active_issue), Some(activated_on)) = (&self.active_issue, &self.activated_on) {
+                let mut session = Session::new(active_issue, activated_on, &Zoned::now());
+                let notes = lap::comment(&self.laps, &self.config.locale());
+                session.comment = self.worklog_comment(active_issue, notes);
+                session.account = self.config.billing_account(active_issue);
+                self.ledger.record(&session);
+                self.store_session(session);
+                self.active_issue = None;
+                self.activated_on = None;
+                self.stopped_on = Some(Zoned::now());
+                self.laps.clear();
+            }
+        }
+        self.keep_held_sessions();
+        self.persist_state();
+        self.exit();
+    }
+
+    /// Moves the stopped sessions still waiting on a popup to the pending sessions, so
+    /// they can be submitted from the review screen after a restart.
+    fn keep_held_sessions(&mut self) {
+        let held = [
+            self.oversized_session.take(),
+            self.pending_merge.take().map(|(session, _)| session),
+            self.unbilled_session.take(),
+            self.uncommented_session.take(),
+        ];
+        let held: Vec<Session> = held.into_iter().flatten().collect();
+        if held.is_empty() {
+            return;
+        }
+        self.popup = None;
+        self.history.pending.extend(held);
+        self.history.persist();
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+    }
+
+    /// Quits, with `<C-c>` as a shut down, after showing the summary of the day once the
+    /// configured hour has passed and anything was tracked today.
+    fn quit(&mut self, shut_down: bool) {
+        let now = Zoned::now();
+        if now.hour() < self.config.end_of_day_hour {
+            return self.leave_now(shut_down);
+        }
+        let (submitted, unsubmitted) = self.sessions_on(&now);
+        let summary = DaySummary::new(&submitted, &unsubmitted, self.config.work_hours.as_ref(), &now);
+        if summary.issues.is_empty() && self.history.pending.is_empty() {
+            return self.leave_now(shut_down);
+        }
+        self.day_summary = Some((summary, shut_down));
+        self.popup = Some(Popup::EndOfDay);
+    }
+
+    /// The submitted and the unsubmitted sessions of the day, the running timer
+    /// counting as unsubmitted until `now`.
+    fn sessions_on(&self, now: &Zoned) -> (Vec<Session>, Vec<Session>) {
+        let submitted = self.history.entries_on(now.date()).iter().map(|entry| entry.session()).collect();
+        let mut unsubmitted = self.history.pending.clone();
+        if let (Some(active_issue), Some(activated_on)) = (&self.active_issue, &self.activated_on) {
+            unsubmitted.push(Session::new(active_issue, activated_on, now));
+        }
+        (submitted, unsubmitted)
+    }
+
+    fn timeline(&self) -> Timeline {
+        let now = Zoned::now();
+        let (submitted, unsubmitted) = self.sessions_on(&now);
+        Timeline::new(&submitted, &unsubmitted, self.config.work_hours.as_ref(), &now)
+    }
+
+    /// Shows today's sessions along the day with the latest gap selected.
+    fn open_timeline(&mut self) {
+        self.timeline_gap = self.timeline().gaps().len().saturating_sub(1);
+        self.popup = Some(Popup::Timeline);
+    }
+
+    /// Logs the gap selected on the timeline to the issue selected in the list.
+    fn backfill_gap(&mut self) {
+        let timeline = self.timeline();
+        let gaps = timeline.gaps();
+        let (Some(index), Some(issue_key)) = (
+            gaps.get(self.timeline_gap.min(gaps.len().saturating_sub(1))),
+            self.selected_issue().map(|issue| issue.key.clone()),
+        ) else {
+            return;
+        };
+        if !self.check_permission(&issue_key, Action::LogWork) {
+            return;
+        }
+        let gap = &timeline.segments[*index];
+        let session = Session {
+            comment: self.worklog_comment(&issue_key, None),
+            account: self.config.billing_account(&issue_key),
+            ..Session::new(&issue_key, &gap.start, &gap.end)
+        };
+        self.ledger.record(&session);
+        self.popup = None;
+        self.handle_stopped_session(session);
+        // Stays open for the next gap unless the session needs confirming first.
+        if self.popup.is_none() {
+            self.popup = Some(Popup::Timeline);
+        }
+    }
+
+    /// Quits from the summary of the day, submitting the pending sessions first if asked to.
+    fn leave(&mut self, submit: bool) {
+        self.popup = None;
+        let Some((_, shut_down)) = self.day_summary.take() else {
+            return;
+        };
+        if shut_down {
+            // Also moves the running timer to the pending sessions with `submit_on_exit`.
+            self.shut_down();
+        }
+        if submit {
+            self.submit_pending_sessions();
+        }
+        self.exit();
+    }
+
+    fn leave_now(&mut self, shut_down: bool) {
+        if shut_down {
+            self.shut_down();
+        } else {
+            self.exit();
+        }
+    }
+
+    fn render_issue_list(&mut self, frame: &mut Frame, area: Rect) {
+        let locale = self.config.locale();
+        let title = self.issue_list_title();
+        // Actions the selected issue does not permit are grayed out.
+        let gated = |span: Span<'static>, action| {
+            if self.selected_issue_allows(action) {
+                span
+            } else {
+                span.dark_gray()
+            }
+        };
+        let instructions = Line::from(vec![
+            gated(locale.label("Activate Issue").into(), Action::LogWork),
+            gated("<Enter>  ".blue().bold(), Action::LogWork),
+            locale.label("Submit Worklog").into(),
+            "<C-s>  ".blue().bold(),
+            locale.label("Cancel Worklog").into(),
+            "<C-d>  ".blue().bold(),
+            locale.label("Copy Active MR Title").into(),
+            "<C-y>  ".blue().bold(),
+            locale.label("Copy Commit Message").into(),
+            "<C-g>  ".blue().bold(),
+            locale.label("Copy MR Description").into(),
+            "<A-y>  ".blue().bold(),
+            locale.label("Copy Link").into(),
+            "<C-k>  ".blue().bold(),
+            locale.label("Remind Me").into(),
+            "<C-x>  ".blue().bold(),
+            locale.label("Lap").into(),
+            "<C-q>  ".blue().bold(),
+            locale.label("Sprint Summary").into(),
+            "<C-b>  ".blue().bold(),
+            locale.label("Workload").into(),
+            "<C-w>  ".blue().bold(),
+            locale.label("Quick Filters").into(),
+            "<C-f>  ".blue().bold(),
+            locale.label("Filter Menu").into(),
+            "<F>  ".blue().bold(),
+            gated(locale.label("Rank").into(), Action::Schedule),
+            gated("<S-Up/Down>  ".blue().bold(), Action::Schedule),
+            gated(locale.label("Move to Sprint").into(), Action::Schedule),
+            gated("<C-n>  ".blue().bold(), Action::Schedule),
+            locale.label("Switch Project").into(),
+            "<C-p>  ".blue().bold(),
+            locale.label("Review Sessions").into(),
+            "<C-r>  ".blue().bold(),
+            locale.label("Today's Worklogs").into(),
+            "<C-t>  ".blue().bold(),
+            locale.label("Timeline").into(),
+            "<A-t>  ".blue().bold(),
+            locale.label("Statistics").into(),
+            "<A-i>  ".blue().bold(),
+            locale.label("Timesheet").into(),
+            "<A-a>  ".blue().bold(),
+            locale.label("Snooze").into(),
+            "<A-z>  ".blue().bold(),
+            locale.label("Annotate").into(),
+            "<A-n>  ".blue().bold(),
+            locale.label("Details").into(),
+            "<C-o>  ".blue().bold(),
+            locale.label("JQL").into(),
+            "<:>  ".blue().bold(),
+            locale.label("Saved Filters").into(),
+            "<C-l>  ".blue().bold(),
+            locale.label("Notifications").into(),
+            "<C-a>  ".blue().bold(),
+            gated(locale.label("Assign to Me").into(), Action::Assign),
+            gated("<C-u>  ".blue().bold(), Action::Assign),
+            locale.label("Resize Panes").into(),
+            "<A-Up/Down>  ".blue().bold(),
+            locale.label("Toggle Search Bar").into(),
+            "<A-s>  ".blue().bold(),
+            locale.label("Review Queue").into(),
+            "<A-r>  ".blue().bold(),
+            locale.label("Search Mode").into(),
+            "<A-m>  ".blue().bold(),
+            gated(locale.label("Clone").into(), Action::Create),
+            gated("<A-c>  ".blue().bold(), Action::Create),
+            locale.label("Distribute Time").into(),
+            "<A-d>  ".blue().bold(),
+            locale.label("Receipts").into(),
+            "<A-w>  ".blue().bold(),
+            locale.label("Refresh").into(),
+            "<F5>  ".blue().bold(),
+            locale.label("Quit").into(),
+            "<esc> ".blue().bold(),
+        ]);
+
+        let selected_style = Style::default().bg(self.colors.selected_bg_color);
+
+        let mut block = Block::bordered()
+            .title(title.centered())
+            .title_bottom(instructions.centered())
+            .border_set(border::THICK);
+        if !self.notifications.is_empty() {
+            let badge = format!(" {} new ", self.notifications.len());
+            block = block.title(Line::from(badge.black().on_yellow().bold()).right_aligned());
+        }
+
+        let columns = if self.compact {
+            vec![Column::Key, Column::Title]
+        } else {
+            self.columns()
+        };
+        // The first column marks the issue the timer runs for.
+        let header = std::iter::once(Cell::from(""))
+            .chain(columns.iter().map(|column| Cell::from(self.column_header(column))))
+            .collect::<Row>()
+            .height(1);
+
+        // Only the rows around the viewport are built, so huge result sets draw quickly.
+        let matches = self.search.matches();
+        let chrome = if self.compact { 0 } else { 3 };
+        let row_height = if self.compact { 1 } else { self.config.row_height.clamp(1, 2) };
+        let visible = (area.height.saturating_sub(chrome) / row_height).max(1) as usize;
+        let title_width = self.title_width(&columns, area.width);
+        self.visible_rows = visible;
+        let selected = self
+            .table_state
+            .selected()
+            .map(|selected| selected.min(matches.len().saturating_sub(1)));
+        let mut offset = self.table_state.offset();
+        if let Some(selected) = selected {
+            offset = offset.min(selected).max((selected + 1).saturating_sub(visible));
+        }
+        let end = (offset + visible + ROW_MARGIN).min(matches.len());
+        let offset = offset.min(end);
+        let rows: Vec<Row> = matches[offset..end]
+            .iter()
+            .map(|index| &self.issues[*index])
+            .map(|issue| {
+                let active = self.active_issue.as_ref() == Some(&issue.key);
+                let due = self.follow_ups.is_due(&issue.key, Timestamp::now());
+                let marker = Cell::from(match (active, due) {
+                    (true, _) => "▶",
+                    (false, true) => "!",
+                    (false, false) => "",
+                });
+                let row = std::iter::once(marker)
+                    .chain(columns.iter().map(|column| match column {
+                        Column::Title if row_height == 2 => self.two_line_title(issue, title_width),
+                        _ => self.cell(issue, *column),
+                    }))
+                    .collect::<Row>()
+                    .height(row_height);
+                if active {
+                    row.green().bold()
+                } else if due {
+                    row.yellow().bold()
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let constraints = std::iter::once(Constraint::Length(1))
+            .chain(columns.iter().map(|column| column.constraint()));
+        let mut table = Table::new(rows, constraints)
+            .row_highlight_style(selected_style)
+            .highlight_symbol(">>");
+        if !self.compact {
+            table = table.header(header).block(block);
+        }
+        let mut viewport_state =
+            TableState::default().with_selected(selected.map(|selected| selected - offset));
+        frame.render_stateful_widget(table, area, &mut viewport_state);
+        if self.loaded && matches.is_empty() && !self.compact {
+            let message = if self.search_input.is_empty() {
+                "No issues here. Press : to search with JQL or <C-f> for quick filters."
+            } else {
+                "No matches. Press Esc to clear the search, then : for JQL."
+            };
+            let [_, message_area] = Layout::vertical([Constraint::Length(3), Constraint::Length(1)])
+                .areas(area.inner(Margin::new(2, 0)));
+            frame.render_widget(Paragraph::new(message.dark_gray().italic()).centered(), message_area);
+        }
+        self.table_state.select(selected);
+        *self.table_state.offset_mut() = offset;
+
+        let selected_key = selected
+            .and_then(|selected| matches.get(selected))
+            .map(|index| self.issues[*index].key.clone());
+        if selected_key.as_ref() != self.hovered.as_ref().map(|(key, _)| key) {
+            self.hovered = selected_key.map(|key| (key, Instant::now()));
+        }
+    }
+
+    /// Time left until the preview of the selected issue is shown.
+    fn preview_remaining(&self) -> Option<Duration> {
+        let (_, since) = self.hovered.as_ref()?;
+        PREVIEW_DELAY.checked_sub(since.elapsed()).filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Floating summary of the selected issue, once the selection rested on it.
+    fn render_preview(&mut self, frame: &mut Frame, list_area: Rect) {
+        let Some((key, since)) = &self.hovered else {
+            return;
+        };
+        if since.elapsed() < PREVIEW_DELAY {
+            return;
+        }
+        let Some(issue) = self.issues.iter().find(|issue| &issue.key == key) else {
+            return;
+        };
+        let Some(prefetcher) = &mut self.prefetcher else {
+            return;
+        };
+        prefetcher.request(key);
+        let Some(details) = prefetcher.peek(key) else {
+            return;
+        };
+
+        let width = 60.min(list_area.width / 2);
+        let status_color = self.status_colors.color(&issue.status, &issue.status_category);
+        let mut lines = vec![Line::from(vec!["Status: ".bold(), issue.status.clone().fg(status_color)])];
+        let description = adf::excerpt(&details.description, 3 * width as usize);
+        if !description.is_empty() {
+            lines.push(Line::from(description));
+        }
+        if let Some(comment) = details.comments.last() {
+            lines.push(Line::from(vec![
+                format!("{}: ", comment.author).bold(),
+                adf::excerpt(&comment.body, 2 * width as usize).into(),
+            ]));
+        }
+        let inner_width = width.saturating_sub(2).max(1) as usize;
+        let wrapped: usize = lines
+            .iter()
+            .map(|line| line.width().div_ceil(inner_width).max(1))
+            .sum();
+        let height = (wrapped as u16 + 2).min(list_area.height);
+        let paragraph = Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title(Line::from(format!(" {key} ").bold())));
+
+        let selected = self.table_state.selected().unwrap_or(0);
+        let row_y = list_area.y + 2 + selected.saturating_sub(self.table_state.offset()) as u16;
+        let y = if row_y + 1 + height <= list_area.bottom() {
+            row_y + 1
+        } else {
+            row_y.saturating_sub(height).max(list_area.y)
+        };
+        let area = Rect {
+            x: list_area.right().saturating_sub(width + 1),
+            y,
+            width,
+            height,
+        };
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+
+    fn columns(&self) -> Vec<Column> {
+        if let Some(columns) = &self.config.columns {
+            return self.with_script_columns(columns.clone());
+        }
+        let mut columns = vec![
+            Column::Key,
+            Column::TimeSpent,
+            Column::Today,
+            Column::Assignee,
+            Column::Title,
+        ];
+        if self.config.projects().len() > 1 || !self.instances.is_empty() {
+            columns.insert(0, Column::Project);
+        }
+        self.with_script_columns(columns)
+    }
+
+    /// The script columns placed before the title, or at the end without one.
+    fn with_script_columns(&self, mut columns: Vec<Column>) -> Vec<Column> {
+        if self.scripts.is_none() {
+            return columns;
+        }
+        let position = columns
+            .iter()
+            .position(|column| *column == Column::Title)
+            .unwrap_or(columns.len());
+        let script_columns = self
+            .config
+            .script_columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| Column::Script {
+                index,
+                width: column.width,
+            });
+        columns.splice(position..position, script_columns);
+        columns
+    }
+
+    fn column_header(&self, column: &Column) -> String {
+        match column {
+            Column::Script { index, .. } => self.config.script_columns[*index].header.clone(),
+            _ => self.config.locale().text(column.header()).to_string(),
+        }
+    }
+
+    fn cell(&self, issue: &Issue, column: Column) -> Cell<'static> {
+        if column == Column::Title {
+            // Matched characters are bold, showing why the issue ranks where it does.
+            let spans: Vec<Span> = self
+                .search
+                .highlight(&issue.summary)
+                .into_iter()
+                .map(|(text, matched)| if matched { text.bold() } else { text.into() })
+                .collect();
+            let annotation = self.annotations.get(&issue.key).map(Annotation::spans);
+            return Cell::from(Line::from([annotation.unwrap_or_default(), spans].concat()));
+        }
+        if column == Column::Status {
+            let color = self.status_colors.color(&issue.status, &issue.status_category);
+            return Cell::from(issue.status.clone().fg(color));
+        }
+        if column != Column::AssigneeInitials {
+            return Cell::from(Text::from(self.cell_content(issue, column)));
+        }
+        let (Some(account_id), false) = (&issue.assignee_id, issue.assignee.is_empty()) else {
+            return Cell::from("");
+        };
+        let badge = format!("{:^4}", avatar::initials(&issue.assignee));
+        Cell::from(badge.black().bold().bg(avatar::color(account_id)))
+    }
+
+    /// Columns left for the title in a list of the given width.
+    fn title_width(&self, columns: &[Column], width: u16) -> usize {
+        let fixed: u16 = columns
+            .iter()
+            .map(|column| match column.constraint() {
+                Constraint::Length(length) => length,
+                _ => 0,
+            })
+            .sum();
+        // Borders, the highlight symbol, the marker column and the spacing between columns.
+        let chrome = 2 + 2 + 1 + columns.len() as u16;
+        width.saturating_sub(fixed + chrome).max(1) as usize
+    }
+
+    /// The title over two lines, continuing where it is cut off or followed by the
+    /// epic and labels when it fits.
+    fn two_line_title(&self, issue: &Issue, width: usize) -> Cell<'static> {
+        let annotation = self.annotations.get(&issue.key).map(Annotation::spans).unwrap_or_default();
+        let annotation_width: usize = annotation.iter().map(|span| span.content.chars().count()).sum();
+        let wrap_point = column::wrap_point(&issue.summary, width.saturating_sub(annotation_width).max(1));
+        let (mut first, mut second) = (Line::from(annotation), Line::default());
+        let mut position = 0;
+        for (text, matched) in self.search.highlight(&issue.summary) {
+            let length = text.chars().count();
+            let split = wrap_point.map_or(length, |point| point.saturating_sub(position).min(length));
+            let split = text.char_indices().nth(split).map_or(text.len(), |(index, _)| index);
+            let (head, tail) = text.split_at(split);
+            for (line, part) in [(&mut first, head), (&mut second, tail)] {
+                if !part.is_empty() {
+                    let part = part.to_string();
+                    line.push_span(if matched { part.bold() } else { part.into() });
+                }
+            }
+            position += length;
+        }
+        if wrap_point.is_none() {
+            let details: Vec<String> = issue.epic.iter().chain(issue.labels.iter()).cloned().collect();
+            second = Line::from(details.join(" · ").dark_gray());
+        }
+        Cell::from(Text::from(vec![first, second]))
+    }
+
+    fn cell_content(&self, issue: &Issue, column: Column) -> String {
+        match column {
+            Column::Project => issue.project().to_string(),
+            Column::Key => issue.key.clone(),
+            Column::TimeSpent if issue.aggregate_time_spent > issue.time_spent => {
+                // Includes the time logged on subtasks.
+                format!("Σ {}", self.config.work_time().format(issue.aggregate_time_spent))
+            }
+            Column::TimeSpent => self.config.work_time().format(issue.time_spent),
+            Column::Today => {
+                let now = Zoned::now();
+                let mut seconds = self.ledger.seconds_on(now.date(), &issue.key);
+                if let (Some(key), Some(activated_on)) = (&self.active_issue, &self.activated_on) {
+                    if key == &issue.key {
+                        seconds += Session::new(key, activated_on, &now).duration_s;
+                    }
+                }
+                match seconds {
+                    0 => String::new(),
+                    _ => self.config.work_time().format(seconds),
+                }
+            }
+            Column::Assignee => issue.assignee.clone(),
+            Column::Status => issue.status.clone(),
+            Column::AssigneeInitials => avatar::initials(&issue.assignee),
+            Column::Title => issue.summary.clone(),
+            Column::Script { index, .. } => match &self.scripts {
+                Some(scripts) => scripts.column(&self.config.script_columns[index].function, issue),
+                None => String::new(),
+            },
+        }
+    }
+
+    /// The title of the issue list, tinted yellow once the issues are older than
+    /// `stale_after_minutes` and red when refreshing them failed.
+    fn issue_list_title(&self) -> Line<'static> {
+        let mut title = self.issue_list_heading();
+        if let Some(submitter) = self.submitter.as_ref().filter(|submitter| submitter.in_flight() > 0) {
+            let submitting = format!(
+                "| {} {} ",
+                background::spinner(submitter.started),
+                self.config.locale().text("Submitting worklogs...")
+            );
+            title.push_span(submitting.yellow());
+        }
+        let Some(fetched) = self.issues_fetched else {
+            return title;
+        };
+        let age_s = fetched.elapsed().as_secs();
+        let age = statustime::format(age_s);
+        if self.refresh_failed {
+            title.push_span(format!("| Refresh failed, from {age} ago <F5> ").bold());
+            return title.red();
+        }
+        // Webhooks keep the list up to date without refreshing.
+        let stale_s = self.config.stale_after_minutes * 60;
+        if self.webhook_events.is_none() && stale_s > 0 && age_s >= stale_s {
+            title.push_span(format!("| Updated {age} ago <F5> "));
+            return title.yellow();
+        }
+        title
+    }
+
+    fn issue_list_heading(&self) -> Line<'static> {
+        if !self.loaded {
+            let spinner = self
+                .issue_loader
+                .as_ref()
+                .map_or("", |loader| background::spinner(loader.started));
+            let loading = format!("| {spinner} {} ", self.config.locale().text("Loading issues..."));
+            return Line::from(vec![" Jiratrack ".bold(), loading.italic()]);
+        }
+        if self.active_jql == Some(self.review_queue_jql()) {
+            let review_queue = format!("| {} ", self.config.locale().text("Review Queue"));
+            return Line::from(vec![" Jiratrack ".bold(), review_queue.into()]);
+        }
+        let mut spans = vec![" Jiratrack ".bold()];
+        for (_, _, sprint) in self.active_sprints() {
+            spans.push(format!("| {} ", sprint.name).into());
+            if !sprint.goal.is_empty() {
+                spans.push(format!("- {} ", sprint.goal).italic());
+            }
+            if let Some(countdown) = sprint.countdown(Zoned::now().timestamp()) {
+                spans.push(format!("({countdown}) ").yellow());
+            }
+        }
+        Line::from(spans)
+    }
+
+    fn render_current_issue(&self, frame: &mut Frame, area: Rect) {
+        let locale = self.config.locale();
+        let title = Line::from(format!("  {}  ", locale.text("Current Issue")).bold());
+        let mut block = Block::bordered().title(title);
+        if let Some(warning) = self.clock_skew_warning() {
+            block = block.title(Line::from(warning.red().bold()).right_aligned());
+        }
+        if let Some(account_id) = self.jira.log_as_account_id() {
+            block = block.title_bottom(format!(" Logging as {account_id} ").magenta());
+        }
+        if self.jira.dry_run() {
+            let badge = Line::from(locale.label("Dry run").black().on_yellow().bold()).right_aligned();
+            block = block.title_bottom(badge);
+        }
+        let duration = match &self.activated_on {
+            Some(zoned) => format!("{:#}", (&Zoned::now() - zoned).round(Unit::Second).unwrap()),
+            None => "/".to_string(),
+        };
+
+        let mut text = match &self.get_active_issue() {
+            Some(issue) => format!(" {} {} ({})", issue.key, issue.summary, duration),
+            None => format!(" {}", locale.text("No issue active")),
+        };
+        if let Some(lap) = self.laps.last() {
+            text += &format!(" · {} laps, last at {}: {}", self.laps.len(), locale.time(&lap.at), lap.label);
+        }
+        if self.tracking_reminder() {
+            let idle = (&Zoned::now() - &self.idle_since).round(Unit::Minute).unwrap();
+            block = block
+                .border_style(Style::default().fg(Color::Yellow))
+                .title_bottom(format!(" No timer running for {idle:#}, start tracking? ").yellow().bold());
+        }
+        if let (Some(meeting), Some(issue_key)) = (&self.meeting, &self.config.meeting_issue) {
+            let suggestion = format!(
+                " In \"{}\" since {}, log it to {issue_key}? <C-e> ",
+                meeting.summary,
+                locale.time(&meeting.start)
+            );
+            block = block
+                .border_style(Style::default().fg(Color::Yellow))
+                .title_bottom(suggestion.yellow().bold());
+        }
+        if let Some(checkout) = &self.checkout_suggestion {
+            let suggestion = format!(
+                " Checked out {}, switch the timer to {}? <C-v> ",
+                checkout.branch, checkout.issue_key
+            );
+            block = block
+                .border_style(Style::default().fg(Color::Yellow))
+                .title_bottom(suggestion.yellow().bold());
+        } else if let Some(issue_key) = &self.focus_suggestion {
+            block = block
+                .border_style(Style::default().fg(Color::Yellow))
+                .title_bottom(format!(" Working on {issue_key}? Switch the timer <C-v> ").yellow().bold());
+        }
+        // The badge goes away with the notification, e.g. when it is dismissed.
+        let change = self
+            .active_issue_change
+            .as_ref()
+            .filter(|change| self.notifications.contains(change));
+        if let Some(change) = change {
+            let badge = format!(" {} ", change.message()).black().on_magenta().bold();
+            block = block
+                .border_style(Style::default().fg(Color::Magenta))
+                .title(Line::from(badge).right_aligned());
+        }
+        let p = Paragraph::new(text).wrap(Wrap { trim: true }).block(block);
+
+        frame.render_widget(p, area)
+    }
+
+    fn render_detail(&mut self, frame: &mut Frame) {
+        if let Some(detail) = &mut self.detail {
+            detail.render(frame, popup_area(frame.area(), 120, frame.area().height.saturating_sub(2)));
+        }
+    }
+
+    fn render_composer(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("New Comment").bold()))
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Mention").into(),
+                    "@  ".blue().bold(),
+                    locale.label("Pick").into(),
+                    "<tab>  ".blue().bold(),
+                    locale.label("Submit").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Cancel").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let mut lines = vec![Line::from(format!("> {}", self.composer.text()))];
+        for (index, user) in self.composer.suggestions.iter().enumerate() {
+            let line = Line::from(format!("   @{}", user.display_name));
+            lines.push(if index == self.composer.selected {
+                line.bg(self.colors.selected_bg_color)
+            } else {
+                line
+            });
+        }
+
+        let height = lines.len() as u16 + 2;
+        let area = popup_area(frame.area(), 100, height);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(block), area);
+    }
+
+    fn render_sprint_summary(&self, frame: &mut Frame) {
+        let summary = SprintSummary::from_issues(&self.sprint_issues);
+        let work_time = self.config.work_time();
+        let names: Vec<&str> = self.active_sprints().map(|(_, _, sprint)| sprint.name.as_str()).collect();
+        let title = match names.is_empty() {
+            false => format!(" {} ", names.join(" | ")),
+            true => " Sprint Summary ".to_string(),
+        };
+        let block = Block::bordered()
+            .title(Line::from(title.bold()).centered())
+            .title_bottom(Line::from(vec![" Close ".into(), "<esc> ".blue().bold()]).centered())
+            .border_set(border::THICK);
+
+        let lines = vec![
+            Line::from(format!(
+                " Issues:    {} done / {} open",
+                summary.done, summary.open
+            )),
+            Line::from(format!(
+                " Time:      {} logged / {} estimated",
+                work_time.format(summary.logged_s),
+                work_time.format(summary.estimated_s)
+            )),
+            Line::from(format!(
+                " Points:    {} remaining / {} total",
+                summary.points_remaining, summary.points_total
+            )),
+        ];
+
+        let area = popup_area(frame.area(), 50, 7);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn render_workload(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let work_time = self.config.work_time();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Team Workload").bold()).centered())
+            .title_bottom(Line::from(vec![locale.label("Close").into(), "<esc> ".blue().bold()]).centered())
+            .border_set(border::THICK);
+
+        let header = ["Assignee", "Issues", "Logged", "Estimated"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .bold();
+        let workloads = workload_by_assignee(&self.sprint_issues);
+        let rows: Vec<Row> = workloads
+            .iter()
+            .map(|workload| {
+                Row::new([
+                    workload.assignee.clone(),
+                    workload.issues.to_string(),
+                    work_time.format(workload.logged_s),
+                    work_time.format(workload.estimated_s),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(20),
+                Constraint::Length(8),
+                Constraint::Length(12),
+                Constraint::Length(12),
+            ],
+        )
+        .header(header)
+        .block(block);
+
+        let area = popup_area(frame.area(), 64, workloads.len() as u16 + 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(table, area);
+    }
+
+    fn render_quick_filters(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Quick Filters").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Toggle").into(),
+                    "<space>  ".blue().bold(),
+                    locale.label("Apply").into(),
+                    "<enter> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let items: Vec<ListItem> = self
+            .quick_filters
+            .iter()
+            .map(|(instance, filter)| {
+                let marker = if self.active_quick_filters.contains(&(*instance, filter.id)) {
+                    "[x] "
+                } else {
+                    "[ ] "
+                };
+                ListItem::new(format!("{marker}{}", filter.name))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(self.colors.selected_bg_color))
+            .block(block);
+
+        let area = popup_area(frame.area(), 50, self.quick_filters.len() as u16 + 2);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.quick_filter_state);
+    }
+
+    fn render_facets(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Filter Menu").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    " Toggle ".into(),
+                    "<space>  ".blue().bold(),
+                    " Clear ".into(),
+                    "<c>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let facets = self.search.facets();
+        let items: Vec<ListItem> = self
+            .facet_values
+            .iter()
+            .map(|facet_value| {
+                let marker = if facets.is_selected(facet_value.facet, &facet_value.value) {
+                    "[x] "
+                } else {
+                    "[ ] "
+                };
+                ListItem::new(Line::from(vec![
+                    marker.into(),
+                    format!("{:<11}", facet_value.facet.name()).dark_gray(),
+                    facet_value.value.clone().into(),
+                    format!(" ({})", facet_value.count).dark_gray(),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(self.colors.selected_bg_color))
+            .block(block);
+
+        let height = (self.facet_values.len() as u16 + 2).min(frame.area().height);
+        let area = popup_area(frame.area(), 50, height);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.facet_state);
+    }
+
+    fn render_script_actions(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Script Actions").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Run").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let items: Vec<ListItem> = self
+            .config
+            .script_actions
+            .iter()
+            .map(|action| ListItem::new(format!(" {}", action.name)))
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(self.colors.selected_bg_color))
+            .block(block);
+
+        let height = self.config.script_actions.len() as u16 + 2;
+        let area = popup_area(frame.area(), 50, height);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.script_action_state);
+    }
+
+    fn render_saved_filters(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Favourite Filters").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Load").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Sprint Issues").into(),
+                    "<c>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let items: Vec<ListItem> = if self.saved_filters.is_empty() {
+            vec![ListItem::new(" No favourite filters".italic())]
+        } else {
+            self.saved_filters
+                .iter()
+                .map(|filter| {
+                    let marker = if self.active_jql.as_ref() == Some(&filter.jql) {
+                        "> "
+                    } else {
+                        "  "
+                    };
+                    ListItem::new(Line::from(vec![
+                        format!("{marker}{}", filter.name).into(),
+                        format!("  {}", filter.owner).dark_gray(),
+                    ]))
+                })
+                .collect()
+        };
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(self.colors.selected_bg_color))
+            .block(block);
+
+        let height = self.saved_filters.len().max(1) as u16 + 2;
+        let area = popup_area(frame.area(), 60, height);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.saved_filter_state);
+    }
+
+    /// Shows the last error in the top right corner for a few seconds.
+    fn render_toast(&mut self, frame: &mut Frame) {
+        let Some((message, shown_on)) = &self.toast else {
+            return;
+        };
+        if shown_on.elapsed() > Duration::from_secs(5) {
+            self.toast = None;
+            return;
+        }
+        let area = frame.area();
+        let width = (message.chars().count() as u16 + 4).min(area.width);
+        let toast_area = Rect {
+            x: area.x + area.width - width,
+            y: area.y,
+            width,
+            height: 3.min(area.height),
+        };
+        let block = Block::bordered()
+            .title(Line::from(" Error ".bold()))
+            .border_style(Style::default().fg(Color::Red));
+        let p = Paragraph::new(message.clone().red()).block(block);
+        frame.render_widget(Clear, toast_area);
+        frame.render_widget(p, toast_area);
+    }
+
+    fn render_recovery(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Interrupted Actions").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Retry").into(),
+                    "<r>  ".blue().bold(),
+                    locale.label("Discard").into(),
+                    "<d>  ".blue().bold(),
+                    locale.label("Later").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let work_time = self.config.work_time();
+        let items: Vec<ListItem> = self
+            .journal
+            .entries
+            .iter()
+            .map(|entry| ListItem::new(format!(" Interrupted while {}", entry.intent.describe(&work_time))))
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(self.colors.selected_bg_color))
+            .block(block);
+
+        let area = popup_area(frame.area(), 70, self.journal.entries.len() as u16 + 2);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.recovery_state);
+    }
+
+    fn render_foreign_timer(&self, frame: &mut Frame) {
+        let Some(foreign) = &self.foreign_timer else {
+            return;
+        };
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Timer on Another Machine").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Adopt").into(),
+                    "<y>  ".blue().bold(),
+                    locale.label("Ignore").into(),
+                    "<n> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let running_here = self.active_issue.is_some();
+        let text = match (&foreign.issue_key, &foreign.activated_on) {
+            (Some(issue_key), Some(activated_on)) => vec![
+                Line::from(format!(
+                    " {} started the timer on {issue_key} at {}.",
+                    foreign.host,
+                    locale.weekday_time(activated_on)
+                )),
+                Line::from(if running_here {
+                    " Adopting it stops the timer here as of then."
+                } else {
+                    " Adopting it runs the timer here too."
+                }),
+            ],
+            _ => vec![
+                Line::from(format!(" {} stopped the timer and submitted its worklog.", foreign.host)),
+                Line::from(" Adopting it drops the timer here without submitting it."),
+            ],
+        };
+
+        let area = popup_area(frame.area(), 70, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_estimation(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let estimation = &self.estimation;
+        let progress = format!(
+            " {} {} / {} ",
+            locale.text("Estimate"),
+            (estimation.index + 1).min(estimation.issues.len()),
+            estimation.issues.len()
+        );
+        let block = Block::bordered()
+            .title(Line::from(progress.bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Save").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Skip").into(),
+                    "<tab>  ".blue().bold(),
+                    locale.label("Back").into(),
+                    "<shift-tab>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let text = match estimation.current() {
+            Some(issue) => {
+                let mut details = format!(" {} · {}", issue.status, issue.assignee);
+                if let Some(points) = issue.story_points {
+                    details.push_str(&format!(" · {points} {}", locale.text("points")));
+                }
+                vec![
+                    Line::from(vec![format!(" {} ", issue.key).bold(), issue.summary.clone().into()]),
+                    Line::from(details.dark_gray()),
+                    Line::from(""),
+                    Line::from(format!(" {} > {}", locale.text("Story points"), estimation.input)),
+                ]
+            }
+            None => vec![
+                Line::from(format!(" {} estimated, nothing left.", estimation.estimated)),
+                Line::from(" <shift-tab> goes back to an issue, <enter> closes.".dark_gray()),
+            ],
+        };
+
+        let area = popup_area(frame.area(), 80, 6);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_timesheet(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let work_time = self.config.work_time();
+        let mut instructions = vec![];
+        if self.tempo.is_some() {
+            instructions.extend([locale.label("Submit for Approval").into(), "<enter>  ".blue().bold()]);
+        }
+        instructions.extend([locale.label("Close").into(), "<esc> ".blue().bold()]);
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Timesheet").bold()).centered())
+            .title_bottom(Line::from(instructions).centered())
+            .border_set(border::THICK);
+
+        let header = ["Week", "Time Spent", "Approval"]
+            .map(|label| locale.text(label))
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .bold();
+        let rows: Vec<Row> = self
+            .timesheet
+            .iter()
+            .map(|week| {
+                let dates = format!("{} – {}", week.start, week.end().strftime("%m-%d"));
+                // Tempo knows about worklogs from elsewhere and the time required.
+                let Some(approval) = &week.approval else {
+                    let logged = format!("{} / {}", work_time.format(week.logged_s), work_time.format(week.target_s));
+                    return Row::new([dates, logged]);
+                };
+                let logged = format!(
+                    "{} / {}",
+                    work_time.format(approval.logged_s),
+                    work_time.format(approval.required_s)
+                );
+                let mut status = locale.text(approval.status.label()).to_string();
+                if let Some(comment) = &approval.comment {
+                    status = format!("{status}: {comment}");
+                }
+                let status = match approval.status {
+                    ApprovalStatus::Open => status.into(),
+                    ApprovalStatus::InReview => status.yellow(),
+                    ApprovalStatus::Approved => status.green(),
+                    ApprovalStatus::Rejected => status.red().bold(),
+                };
+                Row::new([Cell::from(dates), Cell::from(logged), Cell::from(status)])
+            })
+            .collect();
+        let height = self.timesheet.len() as u16 + 4;
+        let table = Table::new(
+            rows,
+            [Constraint::Length(22), Constraint::Length(18), Constraint::Min(10)],
+        )
+        .header(header)
+        .row_highlight_style(Style::default().bg(self.colors.selected_bg_color))
+        .block(block);
+
+        let area = popup_area(frame.area(), 80, height);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(table, area, &mut self.timesheet_state);
+    }
+
+    fn render_receipts(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let work_time = self.config.work_time();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Receipts").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Copy Link").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let receipts = self.history.receipts(self.config.receipt_count);
+        // Worklogs submitted on behalf of someone else get a column naming them.
+        let delegated = receipts.iter().any(|entry| entry.logged_as.is_some());
+        let mut labels = vec!["Submitted", "Key", "Duration", "Link"];
+        let mut widths = vec![
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(10),
+            Constraint::Min(10),
+        ];
+        if delegated {
+            labels.insert(3, "Logged For");
+            widths.insert(3, Constraint::Length(26));
+        }
+        let header = labels
+            .into_iter()
+            .map(|label| Cell::from(locale.text(label)))
+            .collect::<Row>()
+            .bold();
+        let rows: Vec<Row> = receipts
+            .iter()
+            .map(|entry| {
+                let submitted = entry
+                    .submitted
+                    .as_ref()
+                    .map(|submitted| locale.weekday_time(submitted))
+                    .unwrap_or_else(|| "-".to_string());
+                let url = entry
+                    .worklog_id
+                    .as_deref()
+                    .map(|worklog_id| self.jira_for(&entry.issue_key).worklog_url(&entry.issue_key, worklog_id))
+                    .unwrap_or_default();
+                let mut cells = vec![
+                    Cell::from(submitted),
+                    Cell::from(entry.issue_key.clone()),
+                    Cell::from(work_time.format(entry.time_spent_s)),
+                    Cell::from(url),
+                ];
+                if delegated {
+                    let logged_for = entry.logged_as.clone().unwrap_or_default();
+                    cells.insert(3, Cell::from(logged_for.magenta()));
+                }
+                Row::new(cells)
+            })
+            .collect();
+        let height = receipts.len() as u16 + 4;
+        let table = Table::new(rows, widths)
+        .header(header)
+        .row_highlight_style(Style::default().bg(self.colors.selected_bg_color))
+        .block(block);
+
+        let area = popup_area(frame.area(), 110, height);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(table, area, &mut self.receipts_state);
+    }
+
+    fn render_overlaps(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let work_time = self.config.work_time();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Overlapping Worklogs").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Submit Anyway").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Back").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let header = ["Session", "Worklog", "Overlap"]
+            .map(|label| locale.text(label))
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .bold();
+        let describe = |session: &Session| {
+            format!(
+                "{} {} {}",
+                session.issue_key,
+                locale.weekday_time(&session.started),
+                work_time.format(session.duration_s)
+            )
+        };
+        let rows: Vec<Row> = self
+            .overlaps
+            .iter()
+            .filter_map(|overlap| {
+                let session = self.history.pending.get(overlap.session)?;
+                Some(Row::new([
+                    describe(session),
+                    describe(&overlap.worklog),
+                    work_time.format(overlap.overlap_s),
+                ]))
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(32),
+                Constraint::Length(32),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header)
+        .block(block);
+
+        let area = popup_area(frame.area(), 90, self.overlaps.len() as u16 + 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(table, area);
+    }
+
+    fn render_today(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let work_time = self.config.work_time();
+        let entries = self.history.entries_on(Zoned::now().date());
+        let total: u64 = entries.iter().map(|entry| entry.time_spent_s).sum();
+        let total = format!("{}{} ", locale.label("Total"), work_time.format(total));
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Today's Worklogs").bold()).centered())
+            .title(Line::from(total.bold()).right_aligned())
+            .title_bottom(
+                Line::from(vec![locale.label("Close").into(), "<esc> ".blue().bold()]).centered(),
+            )
+            .border_set(border::THICK);
+
+        let header = ["Started", "Key", "Duration", "Title"]
+            .map(|label| locale.text(label))
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .bold();
+        let rows: Vec<Row> = entries
+            .iter()
+            .map(|entry| {
+                let summary = self
+                    .issues
+                    .iter()
+                    .find(|issue| issue.key == entry.issue_key)
+                    .map(|issue| issue.summary.clone())
+                    .unwrap_or_default();
+                Row::new([
+                    locale.time(&entry.started),
+                    entry.issue_key.clone(),
+                    work_time.format(entry.time_spent_s),
+                    summary,
+                ])
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(9),
+                Constraint::Length(12),
+                Constraint::Length(10),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header)
+        .block(block);
+
+        let area = popup_area(frame.area(), 90, entries.len() as u16 + 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(table, area);
+    }
+
+    fn render_timeline(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let work_time = self.config.work_time();
+        let timeline = self.timeline();
+        let gaps = timeline.gaps();
+        self.timeline_gap = self.timeline_gap.min(gaps.len().saturating_sub(1));
+        let selected_gap = gaps.get(self.timeline_gap).copied();
+        let backfill_to = self.selected_issue().map(|issue| issue.key.clone()).unwrap_or_default();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Timeline").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Select Gap").into(),
+                    "<Left/Right>  ".blue().bold(),
+                    format!("{}{backfill_to} ", locale.label("Backfill to")).into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let area = popup_area(frame.area(), 120, 11);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let width = inner.width;
+        // Each issue keeps its color, in the order the issues were first worked on.
+        let mut issue_keys: Vec<&str> = vec![];
+        for segment in &timeline.segments {
+            if let SegmentKind::Session { issue_key, .. } = &segment.kind {
+                if !issue_keys.contains(&issue_key.as_str()) {
+                    issue_keys.push(issue_key);
+                }
+            }
+        }
+        let color = |issue_key: &str| {
+            let index = issue_keys.iter().position(|key| *key == issue_key).unwrap_or(0);
+            TIMELINE_COLORS[index % TIMELINE_COLORS.len()]
+        };
+
+        let mut axis = vec![' '; width as usize];
+        let mut hour = timeline.start.clone();
+        while hour <= timeline.end {
+            let label: Vec<char> = locale.time(&hour).chars().collect();
+            if let Some(column) = timeline.column(&hour, width) {
+                let column = (column as usize).min(axis.len().saturating_sub(label.len()));
+                let free = axis[column.saturating_sub(1)..(column + label.len() + 1).min(axis.len())]
+                    .iter()
+                    .all(|c| *c == ' ');
+                // On a very narrow terminal the label may not fit at all.
+                if free && column + label.len() <= axis.len() {
+                    axis[column..column + label.len()].copy_from_slice(&label);
+                }
+            }
+            hour = hour.saturating_add(1.hour());
+        }
+        let blocks: Vec<Span> = timeline
+            .columns(width)
+            .into_iter()
+            .map(|index| match index.map(|index| (index, &timeline.segments[index].kind)) {
+                Some((_, SegmentKind::Session { issue_key, submitted: true })) => "█".fg(color(issue_key)),
+                Some((_, SegmentKind::Session { issue_key, .. })) => "▓".fg(color(issue_key)),
+                Some((index, SegmentKind::Gap)) if Some(index) == selected_gap => "░".yellow().bold(),
+                Some((_, SegmentKind::Gap)) => "░".dark_gray(),
+                None => "·".dark_gray(),
+            })
+            .collect();
+
+        let mut legend: Vec<Span> = vec![];
+        for issue_key in &issue_keys {
+            let time_s: u64 = timeline
+                .segments
+                .iter()
+                .filter(|segment| matches!(&segment.kind, SegmentKind::Session { issue_key: key, .. } if key == issue_key))
+                .map(|segment| segment.duration_s())
+                .sum();
+            legend.push("█ ".fg(color(issue_key)));
+            legend.push(format!("{issue_key} {}   ", work_time.format(time_s)).into());
+        }
+        legend.push("▓ ".into());
+        legend.push(locale.text("Unsubmitted").into());
+        let gap_line = match selected_gap.map(|index| &timeline.segments[index]) {
+            Some(gap) => Line::from(vec![
+                format!("{}: ", locale.text("Untracked")).yellow().bold(),
+                format!(
+                    "{} - {} ({})",
+                    locale.time(&gap.start),
+                    locale.time(&gap.end),
+                    work_time.format(gap.duration_s())
+                )
+                .into(),
+            ]),
+            None => Line::from(locale.text("No untracked time").dark_gray()),
+        };
+
+        let lines = vec![
+            Line::from(axis.into_iter().collect::<String>()),
+            Line::from(blocks.clone()),
+            Line::from(blocks.clone()),
+            Line::from(blocks),
+            Line::default(),
+            Line::from(legend),
+            Line::default(),
+            gap_line,
+        ];
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    fn stats(&self) -> Stats {
+        let now = Zoned::now();
+        let mut sessions: Vec<Session> = self.history.entries.iter().map(HistoryEntry::session).collect();
+        sessions.extend(self.history.pending.iter().cloned());
+        if let (Some(active_issue), Some(activated_on)) = (&self.active_issue, &self.activated_on) {
+            sessions.push(Session::new(active_issue, activated_on, &now));
+        }
+        let epic_of = |key: &str| self.find_issue(key).and_then(|issue| issue.epic.clone());
+        Stats::new(&sessions, epic_of, &self.config.work_time(), &Holidays::from_config(&self.config), now.date())
+    }
+
+    fn render_stats(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let stats = self.stats();
+        let hours = |seconds: u64| format!("{:.1}h", seconds as f64 / 3600.0);
+        let minutes = |seconds: u64| format!("{}m", seconds / 60);
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Statistics").bold()).centered())
+            .title(Line::from(format!(" {STATS_DAYS} {} ", locale.text("days")).dark_gray()).right_aligned())
+            .title_bottom(Line::from(vec![locale.label("Close").into(), "<esc> ".blue().bold()]).centered())
+            .border_set(border::THICK);
+
+        let row = |label: &'static str, value: String| {
+            Line::from(vec![format!(" {:<22}", locale.text(label)).bold(), value.into()])
+        };
+        let share = stats.tracked_s as f64 / stats.target_s.max(1) as f64;
+        let bar_width = 40;
+        let filled = ((share * bar_width as f64).round() as usize).min(bar_width);
+        let mut lines = vec![
+            row(
+                "Sessions",
+                format!("{}, {} {}", stats.sessions, minutes(stats.average_session_s), locale.text("on average")),
+            ),
+            row(
+                "Context switches",
+                format!("{:.1} {}", stats.switches_per_day, locale.text("per tracked day")),
+            ),
+            row(
+                "Tracked",
+                format!(
+                    "{} / {} ({:.0}%)",
+                    hours(stats.tracked_s),
+                    hours(stats.target_s),
+                    share * 100.0
+                ),
+            ),
+            Line::from(vec![
+                format!(" {:<22}", "").into(),
+                "█".repeat(filled).green(),
+                "░".repeat(bar_width - filled).dark_gray(),
+            ]),
+            row("Days off", format!("{} {}", stats.days_off, locale.text("left out of the target"))),
+            Line::default(),
+            Line::from(format!(" {}", locale.text("Most tracked epics")).bold()),
+        ];
+        if stats.epics.is_empty() {
+            lines.push(Line::from(format!("   {}", locale.text("Nothing tracked yet")).dark_gray()));
+        }
+        for (epic, time_s) in &stats.epics {
+            let epic = epic.as_deref().unwrap_or(locale.text("Other"));
+            lines.push(Line::from(format!("   {:<40} {:>8}", epic, hours(*time_s))));
+        }
+
+        let area = popup_area(frame.area(), 70, lines.len() as u16 + 2);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn render_copy_link(&self, frame: &mut Frame) {
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(Line::from(format!(" Copy Link to {} ", issue.key).bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    " URL ".into(),
+                    "<u>  ".blue().bold(),
+                    " Markdown ".into(),
+                    "<m>  ".blue().bold(),
+                    " Rich Text ".into(),
+                    "<h>  ".blue().bold(),
+                    " Close ".into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let url = self.jira_for(&issue.key).browse_url(&issue.key);
+        let text = vec![
+            Line::from(format!(" {url}")),
+            Line::from(format!(" {}", link::markdown(&issue.key, &issue.summary, &url))),
+        ];
+
+        let area = popup_area(frame.area(), 80, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_lap(&self, frame: &mut Frame) {
+        let block = Block::bordered()
+            .title(Line::from(format!(" Lap {} ", self.laps.len() + 1).bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    " Add ".into(),
+                    "<enter>  ".blue().bold(),
+                    " Cancel ".into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let text = vec![
+            Line::from(" What just happened? Laps are submitted as the worklog comment.".dark_gray()),
+            Line::from(format!(" > {}", self.lap_input)),
+        ];
+
+        let area = popup_area(frame.area(), 70, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_follow_up(&self, frame: &mut Frame) {
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(Line::from(format!(" Remind Me About {} ", issue.key).bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    " Set ".into(),
+                    "<enter>  ".blue().bold(),
+                    " Remove ".into(),
+                    "<del>  ".blue().bold(),
+                    " Close ".into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let locale = self.config.locale();
+        let current = match self.follow_ups.get(&issue.key) {
+            Some(follow_up) => {
+                let at = follow_up.at.to_zoned(jiff::tz::TimeZone::system());
+                format!(" Currently at {}, set a new time to snooze.", locale.weekday_time(&at))
+            }
+            None => " At a time like 15:00 or after a delay like 30m or 2h.".to_string(),
+        };
+        let text = vec![
+            Line::from(current.dark_gray()),
+            Line::from(format!(" > {}", self.follow_up_input)),
+        ];
+
+        let area = popup_area(frame.area(), 60, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_snooze(&self, frame: &mut Frame) {
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(format!(" {} {} ", locale.text("Snooze"), issue.key).bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Snooze").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let hint = format!(
+            " Hidden until {} leaves {}, or a day like 2025-02-01, 3d or 2w.",
+            issue.key, issue.status
+        );
+        let text = vec![
+            Line::from(hint.dark_gray()),
+            Line::from(format!(" > {}", self.snooze_input)),
+        ];
+
+        let area = popup_area(frame.area(), 70, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_annotate(&self, frame: &mut Frame) {
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(format!(" {} {} ", locale.text("Annotate"), issue.key).bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Save").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Color").into(),
+                    "<tab>  ".blue().bold(),
+                    locale.label("Delete").into(),
+                    "<del>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let tag = match self.annotation_draft.tag {
+            Some(tag) => "●".fg(tag.color()).bold(),
+            None => "○".dark_gray(),
+        };
+        let text = vec![
+            Line::from(" Only kept on this machine, never sent to Jira.".dark_gray()),
+            Line::from(vec![" ".into(), tag, format!(" > {}", self.annotation_draft.note).into()]),
+        ];
+
+        let area = popup_area(frame.area(), 70, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_snoozes(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Snoozed Issues").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Wake").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let items: Vec<ListItem> = if self.snoozes.entries.is_empty() {
+            vec![ListItem::new(" No snoozed issues".italic())]
+        } else {
+            self.snoozes
+                .entries
+                .iter()
+                .map(|snooze| {
+                    let until = match snooze.until {
+                        Some(until) => format!("until {until} or leaving {}", snooze.status),
+                        None => format!("until leaving {}", snooze.status),
+                    };
+                    ListItem::new(Line::from(vec![
+                        format!(" {:<12}", snooze.issue_key).bold(),
+                        until.dark_gray(),
+                    ]))
+                })
+                .collect()
+        };
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(self.colors.selected_bg_color))
+            .block(block);
+
+        let height = self.snoozes.entries.len().max(1) as u16 + 2;
+        let area = popup_area(frame.area(), 70, height);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.snooze_state);
+    }
+
+    fn render_notifications(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Notifications").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Open Issue").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Dismiss").into(),
+                    "<d>  ".blue().bold(),
+                    locale.label("Dismiss All").into(),
+                    "<c>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let time_zone = jiff::tz::TimeZone::system();
+        let items: Vec<ListItem> = if self.notifications.is_empty() {
+            vec![ListItem::new(" No new notifications".italic())]
+        } else {
+            self.notifications
+                .iter()
+                .map(|notification| {
+                    let created = notification.created.to_zoned(time_zone.clone());
+                    ListItem::new(Line::from(vec![
+                        format!("{} ", self.config.locale().time(&created)).dark_gray(),
+                        notification.message().into(),
+                    ]))
+                })
+                .collect()
+        };
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(self.colors.selected_bg_color))
+            .block(block);
+
+        let height = self.notifications.len().max(1) as u16 + 2;
+        let area = popup_area(frame.area(), 80, height);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.notification_state);
+    }
+
+    fn render_redistribution(&self, frame: &mut Frame) {
+        let Some(redistribution) = &self.redistribution else {
+            return;
+        };
+        let locale = self.config.locale();
+        let work_time = self.config.work_time();
+        let allocations = redistribution.allocations(&work_time).unwrap_or_default();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Distribute Time").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Next").into(),
+                    "<tab>  ".blue().bold(),
+                    locale.label("Submit").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Cancel").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let marker = |row: usize| if redistribution.selected == row { "> " } else { "  " };
+        let mut lines = vec![
+            Line::from(format!("{}Block: {}", marker(0), redistribution.total)),
+            Line::from(
+                " Give each issue a share like 40% or a fixed chunk like 30m."
+                    .dark_gray(),
+            ),
+            match &redistribution.error {
+                Some(error) => Line::from(format!(" {error}").red()),
+                None => Line::from(""),
+            },
+        ];
+        for (index, (key, input)) in redistribution.rows.iter().enumerate() {
+            let summary = self
+                .issues
+                .iter()
+                .find(|issue| &issue.key == key)
+                .map(|issue| issue.summary.as_str())
+                .unwrap_or_default();
+            let allocated = allocations
+                .iter()
+                .find(|(allocated_key, _)| allocated_key == key)
+                .map(|(_, seconds)| format!(" = {}", work_time.format(*seconds)))
+                .unwrap_or_default();
+            let mut line = Line::from(vec![
+                format!("{}{key:<10} ", marker(index + 1)).into(),
+                format!("{input:<8}").bold(),
+                allocated.green(),
+                format!("  {summary}").dark_gray(),
+            ]);
+            if redistribution.selected == index + 1 {
+                line = line.style(Style::default().bg(self.colors.selected_bg_color));
+            }
+            lines.push(line);
+        }
+
+        let area = popup_area(frame.area(), 90, 24);
+        // Keep the selected issue in view once the list is longer than the popup.
+        let selected_line = match redistribution.selected {
+            0 => 0,
+            row => row + 2,
+        };
+        let scroll = (selected_line + 1).saturating_sub(area.height.saturating_sub(2) as usize);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(lines).block(block).scroll((scroll as u16, 0)),
+            area,
+        );
+    }
+
+    fn render_clone(&self, frame: &mut Frame) {
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(Line::from(format!(" Clone {} ", issue.key).bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    " Create ".into(),
+                    "<enter>  ".blue().bold(),
+                    " Cancel ".into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let text = vec![
+            Line::from(" Summary of the copy, which keeps the description, labels and components.".dark_gray()),
+            Line::from(format!(" > {}", self.clone_input)),
+        ];
+
+        let area = popup_area(frame.area(), 80, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_billing_account(&self, frame: &mut Frame) {
+        let Some(session) = &self.unbilled_session else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(Line::from(format!(" Billing Account for {} ", session.issue_key).bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    " Confirm ".into(),
+                    "<enter> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let text = vec![
+            Line::from(
+                format!(" {} tracked, leave empty to book on no account.", self.config.work_time().format(session.duration_s))
+                    .dark_gray(),
+            ),
+            Line::from(format!(" > {}", self.billing_input)),
+        ];
+
+        let area = popup_area(frame.area(), 60, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_worklog_comment(&self, frame: &mut Frame) {
+        let Some(session) = &self.uncommented_session else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(Line::from(format!(" Worklog Comment for {} ", session.issue_key).bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    " Confirm ".into(),
+                    "<enter>".blue().bold(),
+                    " Keep default ".into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let text = vec![
+            Line::from(
+                format!(" {} tracked, leave empty to log without a comment.", self.config.work_time().format(session.duration_s))
+                    .dark_gray(),
+            ),
+            Line::from(format!(" > {}", self.comment_input)),
+        ];
+
+        let area = popup_area(frame.area(), 80, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_subtask_picker(&mut self, frame: &mut Frame) {
+        let Some(parent) = &self.subtask_parent else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(Line::from(format!(" Log Time to {} ", parent.key).bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    " Start ".into(),
+                    "<enter>  ".blue().bold(),
+                    " Cancel ".into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let mut items = vec![ListItem::new(format!("{} {}", parent.key, parent.summary).bold())];
+        items.extend(parent.subtasks.iter().map(|subtask| {
+            ListItem::new(format!("  ↳ {} {} ({})", subtask.key, subtask.summary, subtask.status))
+        }));
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(self.colors.selected_bg_color))
+            .block(block);
+
+        let area = popup_area(frame.area(), 80, parent.subtasks.len() as u16 + 3);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.subtask_state);
+    }
+
+    fn render_sprint_picker(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Move to Sprint").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Move").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Cancel").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let mut items = vec![ListItem::new("Backlog")];
+        items.extend(
+            self.target_sprints
+                .iter()
+                .map(|sprint| ListItem::new(sprint.name.clone())),
+        );
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(self.colors.selected_bg_color))
+            .block(block);
+
+        let area = popup_area(frame.area(), 50, self.target_sprints.len() as u16 + 3);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.sprint_picker_state);
+    }
+
+    fn render_review(&mut self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let work_time = self.config.work_time();
+        let week_start = locale.week_start(Zoned::now().date());
+        let week_start = week_start.to_zoned(jiff::tz::TimeZone::system()).unwrap();
+        let logged = self.history.logged_since(&week_start);
+        let week = format!("{}{} ", locale.label("This week"), work_time.format(logged));
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Review Sessions").bold()).centered())
+            .title(Line::from(week).right_aligned())
+            .title_bottom(
+                Line::from(vec![
+                    " +/-5m ".into(),
+                    "<+/->  ".blue().bold(),
+                    locale.label("Merge").into(),
+                    "<m>  ".blue().bold(),
+                    locale.label("Round").into(),
+                    "<r>  ".blue().bold(),
+                    locale.label("Delete").into(),
+                    "<d>  ".blue().bold(),
+                    locale.label("Submit All").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Close").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let header = ["Key", "Started", "Duration"]
+            .map(|label| locale.text(label))
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .bold();
+        let rows: Vec<Row> = self
+            .history
+            .pending
+            .iter()
+            .map(|session| {
+                // Too short for Jira, these stay pending when submitting.
+                let duration = match session.is_loggable() {
+                    true => Cell::from(work_time.format(session.duration_s)),
+                    false => Cell::from(work_time.format(session.duration_s).red()),
+                };
+                Row::new([
+                    Cell::from(session.issue_key.clone()),
+                    Cell::from(locale.weekday_time(&session.started)),
+                    duration,
+                ])
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(Style::default().bg(self.colors.selected_bg_color))
+        .block(block);
+
+        let area = popup_area(frame.area(), 90, self.history.pending.len() as u16 + 4);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(table, area, &mut self.review_state);
+    }
+
+    fn render_merge_confirm(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let Some((session, target)) = &self.pending_merge else {
+            return;
+        };
+        let previous = match target {
+            MergeTarget::Pending(index) => self.history.pending[*index].clone(),
+            MergeTarget::Submitted(index) => self.history.entries[*index].session(),
+        };
+        let work_time = self.config.work_time();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Merge Sessions").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Merge").into(),
+                    "<y>  ".blue().bold(),
+                    locale.label("Keep Separate").into(),
+                    "<n> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let text = vec![
+            Line::from(format!(
+                " Previous session on {} at {} ({})",
+                previous.issue_key,
+                locale.time(&previous.started),
+                work_time.format(previous.duration_s)
+            )),
+            Line::from(format!(
+                " Merge with this session ({}) into a single worklog?",
+                work_time.format(session.duration_s)
+            )),
+        ];
+
+        let area = popup_area(frame.area(), 64, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_day_summary(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let work_time = self.config.work_time();
+        let Some((summary, _)) = &self.day_summary else {
+            return;
+        };
+        let total = format!("{}{} ", locale.label("Total"), work_time.format(summary.total_s()));
+        let block = Block::bordered()
+            .title(Line::from(locale.label("End of Day").bold()).centered())
+            .title(Line::from(total.bold()).right_aligned())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Submit Pending and Quit").into(),
+                    "<s>  ".blue().bold(),
+                    locale.label("Quit").into(),
+                    "<q>  ".blue().bold(),
+                    locale.label("Cancel").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+
+        let mut text: Vec<Line> = summary
+            .issues
+            .iter()
+            .map(|issue| {
+                let mut line = Line::from(format!(
+                    " {:<12} {:>9}",
+                    issue.issue_key,
+                    work_time.format(issue.submitted_s + issue.unsubmitted_s)
+                ));
+                if issue.unsubmitted_s > 0 {
+                    let unsubmitted = format!("  {} unsubmitted", work_time.format(issue.unsubmitted_s));
+                    line.push_span(unsubmitted.yellow());
+                }
+                line
+            })
+            .collect();
+        text.push(Line::default());
+        if let Some(active_issue) = &self.active_issue {
+            text.push(Line::from(format!(" The timer on {active_issue} is still running.").yellow()));
+        }
+        match self.history.pending.len() {
+            0 => text.push(Line::from(" All stopped sessions are submitted.")),
+            1 => text.push(Line::from(" One session is not submitted yet.".yellow())),
+            pending => text.push(Line::from(format!(" {pending} sessions are not submitted yet.").yellow())),
+        }
+        for (from, to) in &summary.gaps {
+            text.push(Line::from(
+                format!(" Nothing tracked from {} to {}.", locale.time(from), locale.time(to)).yellow(),
+            ));
+        }
+
+        let area = popup_area(frame.area(), 70, text.len() as u16 + 2);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_closed_issue(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let Some(issue) = self.pending_activation.as_ref().and_then(|key| self.find_issue(key)) else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Closed Issue").bold().red()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Track Anyway").into(),
+                    "<y>  ".blue().bold(),
+                    locale.label("Cancel").into(),
+                    "<n> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let text = vec![
+            Line::from(format!(" {} is {}.", issue.key, issue.status)),
+            Line::from(" Time logged to closed issues may be rejected later."),
+        ];
+
+        let area = popup_area(frame.area(), 64, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_start_offer(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let Some(issue) = &self.start_offer else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Issue Started").bold()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Start Timer").into(),
+                    "<y>  ".blue().bold(),
+                    locale.label("Cancel").into(),
+                    "<n> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let text = vec![
+            Line::from(format!(" {} {}", issue.key, issue.summary)),
+            Line::from(format!(" was moved to {}.", issue.status)),
+        ];
+
+        let area = popup_area(frame.area(), 64, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).wrap(Wrap { trim: false }).block(block), area);
+    }
+
+    fn render_max_duration(&self, frame: &mut Frame) {
+        let locale = self.config.locale();
+        let (Some(session), Some(max_s)) = (&self.oversized_session, self.max_worklog_s()) else {
+            return;
+        };
+        let work_time = self.config.work_time();
+        let block = Block::bordered()
+            .title(Line::from(locale.label("Long Worklog").bold().red()).centered())
+            .title_bottom(
+                Line::from(vec![
+                    locale.label("Submit").into(),
+                    "<y>  ".blue().bold(),
+                    locale.label("Cap").into(),
+                    "<c>  ".blue().bold(),
+                    locale.label("Split").into(),
+                    "<s>  ".blue().bold(),
+                    locale.label("Discard").into(),
+                    "<n> ".blue().bold(),
+                ])
+                .centered(),
+            )
+            .border_set(border::THICK);
+        let text = vec![
+            Line::from(format!(
+                " {} was tracked for {} since {},",
+                session.issue_key,
+                work_time.format(session.duration_s),
+                locale.weekday_time(&session.started)
+            )),
+            Line::from(format!(
+                " which exceeds the maximum of {} per worklog.",
+                work_time.format(max_s)
+            )),
+        ];
+
+        let area = popup_area(frame.area(), 64, 4);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn clock_skew_warning(&self) -> Option<String> {
+        let skew = self.jira.clock_skew_s()?;
+        if skew.abs() <= self.config.max_clock_skew_s {
+            return None;
+        }
+        Some(format!(" Local clock is off by {skew}s from Jira "))
+    }
+
+    /// How many of the loaded issues are listed, like `14 of 62 issues`.
+    fn match_count(&self) -> String {
+        format!("{} of {} issues", self.search.matches().len(), self.issues.len())
+    }
+
+    /// Whether the search bar has anything to show.
+    fn is_searching(&self) -> bool {
+        !self.search_input.is_empty()
+            || self.jql_input.is_some()
+            || self.active_jql.is_some()
+            || self.project_filter.is_some()
+            || !self.search.facets().is_empty()
+    }
+
+    /// The active issue, which may be a subtask of a listed issue.
+    fn get_active_issue(&self) -> Option<Issue> {
+        self.find_issue(self.active_issue.as_ref()?).cloned()
+    }
+
+    /// The listed issue or subtask with the key.
+    fn find_issue(&self, issue_key: &str) -> Option<&Issue> {
+        self.issues
+            .iter()
+            .chain(self.issues.iter().flat_map(|issue| issue.subtasks.iter()))
+            .find(|issue| issue.key == issue_key)
+    }
+
+    /// The issue shown at `index` in the filtered issue list.
+    fn filtered_issue(&self, index: usize) -> Option<&Issue> {
+        let index = self.search.matches().get(index)?;
+        self.issues.get(*index)
+    }
+
+    /// The active issue and its timer on a single line.
+    fn render_compact_status(&self, frame: &mut Frame, area: Rect) {
+        let locale = self.config.locale();
+        let line = match (self.get_active_issue(), &self.activated_on) {
+            (Some(issue), Some(activated_on)) => {
+                let duration = (&Zoned::now() - activated_on).round(Unit::Second).unwrap();
+                Line::from(vec![
+                    format!("▶ {} ", issue.key).green().bold(),
+                    format!("{duration:#} ").bold(),
+                    issue.summary.into(),
+                ])
+            }
+            _ => Line::from(locale.text("No issue active").italic()),
+        };
+        frame.render_widget(Paragraph::new(line), area);
+    }
+
+    fn render_compact_search(&self, frame: &mut Frame, area: Rect) {
+        let line = match &self.jql_input {
+            Some(jql_input) => format!("JQL> {}", jql_input.input),
+            None => format!("> {}  {}", self.search_input, self.match_count()),
+        };
+        frame.render_widget(Paragraph::new(line), area);
+    }
+
+    fn render_search(&self, frame: &mut Frame, area: Rect) {
+        if let Some(jql_input) = &self.jql_input {
+            return self.render_jql_input(jql_input, frame, area);
+        }
+        let (mode, _) = self.search.mode().of_query(&self.search_input);
+        let title = Line::from(vec![" Search Input ".bold(), format!("({}) ", mode.name()).dark_gray()]);
+        let mut block = Block::bordered()
+            .title(title)
+            .title(Line::from(format!(" {} ", self.match_count())).right_aligned());
+        if let Some(project) = &self.project_filter {
+            block = block.title(Line::from(format!(" Project: {project} ").yellow()).right_aligned());
+        }
+        if !self.search.facets().is_empty() {
+            let filters = format!(" Filters: {} <F> ", self.search.facets().len());
+            block = block.title(Line::from(filters.yellow()).right_aligned());
+        }
+        if !self.snoozes.entries.is_empty() {
+            let snoozed = format!(" Snoozed: {} <A-h> ", self.snoozes.entries.len());
+            block = block.title(Line::from(snoozed.dark_gray()).right_aligned());
+        }
+        if let Some(jql) = &self.active_jql {
+            let query = self.config.queries.iter().find(|query| query.jql == *jql);
+            let title = match query {
+                Some(query) => format!(" {}: {jql} <A-q> ", query.name),
+                None => format!(" JQL: {jql} "),
+            };
+            block = block.title_bottom(Line::from(title.cyan()));
+        }
+
+        let p = Paragraph::new(format!("> {}", self.search_input)).block(block);
+
+        frame.render_widget(p, area)
+    }
+
+    fn render_jql_input(&self, jql_input: &JqlInput, frame: &mut Frame, area: Rect) {
+        let locale = self.config.locale();
+        let mut block = Block::bordered()
+            .title(Line::from(locale.label("JQL Query").bold()))
+            .title(
+                Line::from(vec![
+                    locale.label("Complete").into(),
+                    "<tab>  ".blue().bold(),
+                    locale.label("Run").into(),
+                    "<enter>  ".blue().bold(),
+                    locale.label("Cancel").into(),
+                    "<esc> ".blue().bold(),
+                ])
+                .right_aligned(),
+            );
+        if !jql_input.errors.is_empty() {
+            block = block.title_bottom(Line::from(format!(" {} ", jql_input.errors.join(" ")).red()));
+        }
+        let p = Paragraph::new(format!("JQL> {}", jql_input.input)).block(block);
+        frame.render_widget(p, area);
+
+        if jql_input.suggestions.is_empty() {
+            return;
+        }
+        let height = (jql_input.suggestions.len() as u16 + 2).min(area.y);
+        let suggestions_area = Rect {
+            x: area.x + 5 + (jql_input.input.len() as u16).min(area.width.saturating_sub(45)),
+            y: area.y - height,
+            width: 40.min(area.width),
+            height,
+        };
+        let items: Vec<ListItem> = jql_input
+            .suggestions
+            .iter()
+            .map(|suggestion| ListItem::new(suggestion.clone()))
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(self.colors.selected_bg_color))
+            .block(Block::bordered());
+        let mut state = ListState::default().with_selected(Some(jql_input.selected));
+        frame.render_widget(Clear, suggestions_area);
+        frame.render_stateful_widget(list, suggestions_area, &mut state);
+    }
+
+    fn get_state_path(&self) -> PathBuf {
+        home_dir()
+            .unwrap()
+            .join(".local/share/jiratrack/state.json")
+    }
+
+    fn persist_state(&self) {
+        self.write_state(&self.get_persisted_state());
+    }
+
+    fn write_state(&self, state: &PersistedState) {
+        let path = self.get_state_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = fs::File::create(path).unwrap();
+        serde_json::to_writer(file, state).unwrap();
+    }
+
+    /// Loads the state and history merged with the sync backend, adopting a timer
+    /// started or stopped on another machine since.
+    fn load_synced_state(&mut self) {
+        self.load_state();
+        self.history = History::load();
+        if self.sync.is_some() {
+            self.sync_state();
+            self.load_state();
+        }
+    }
+
+    /// Merges the state and history with the sync backend. A different timer the merge
+    /// brings into the state file is then asked about by `check_state_file`.
+    fn sync_state(&mut self) {
+        let Some(sync) = &self.sync else {
+            return;
+        };
+        self.synced = Some(Instant::now());
+        let result = sync.sync(self.get_persisted_state(), self.history.clone());
+        self.apply_sync(result);
+    }
+
+    /// Takes over what a sync merged. The timer may have changed since the sync started,
+    /// so the merged state is merged with the current one again.
+    fn apply_sync(&mut self, result: Result<(PersistedState, History)>) {
+        match result {
+            Ok((state, history)) => {
+                let state = sync::merge_state(self.get_persisted_state(), state);
+                for (issue_key, annotation) in &state.annotations {
+                    self.annotations.entry(issue_key.clone()).or_insert_with(|| annotation.clone());
+                }
+                self.write_state(&state);
+                sync::merge_history(&mut self.history, history);
+                self.history.persist();
+                self.sync_failing = false;
+            }
+            Err(error) => {
+                if !self.sync_failing {
+                    self.show_error(format!("Could not sync: {error}"));
+                }
+                self.sync_failing = true;
+            }
+        }
+    }
+
+    fn check_sync(&mut self) {
+        let Some(syncer) = &mut self.syncer else {
+            return;
+        };
+        if let Some(result) = syncer.try_take() {
+            return self.apply_sync(result);
+        }
+        if self.synced.is_none_or(|synced| synced.elapsed() >= SYNC_INTERVAL) {
+            self.synced = Some(Instant::now());
+            let (state, history) = (self.get_persisted_state(), self.history.clone());
+            if let Some(syncer) = &mut self.syncer {
+                syncer.sync(state, history);
+            }
+        }
+    }
+
+    fn load_state(&mut self) {
+        let path = self.get_state_path();
+        if let Ok(file) = File::open(path) {
+            let data: PersistedState = serde_json::from_reader(file).expect("Invalid state");
+            self.active_issue = data.active_issue;
+            self.activated_on = data.activated_on;
+            self.panes = data.panes;
+            self.laps = data.laps;
+            self.annotations = data.annotations;
+            self.last_issue = data.last_issue;
+            self.stopped_on = data.stopped_on;
+        }
+    }
+
+    fn get_persisted_state(&self) -> PersistedState {
+        PersistedState {
+            active_issue: self.active_issue.clone(),
+            activated_on: self.activated_on.clone(),
+            panes: self.panes,
+            laps: self.laps.clone(),
+            annotations: self.annotations.clone(),
+            last_issue: self.last_issue.clone(),
+            host: Some(self.host.clone()),
+            stopped_on: self.stopped_on.clone(),
+        }
+    }
+}
+
+/// The character typed into a text input, `None` for chords like Ctrl-S that would
+/// otherwise be inserted as their letter.
+fn typed_char(key_event: KeyEvent) -> Option<char> {
+    match key_event.code {
+        KeyCode::Char(_) if key_event.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => None,
+        KeyCode::Char(new_char) => Some(new_char),
+        _ => None,
+    }
+}
+
+/// Centered area of at most `width` x `height` cells within `area`.
+fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Colors of the issues on the timeline, repeated after the last.
+const TIMELINE_COLORS: [Color; 6] = [
+    Color::Blue,
+    Color::Green,
+    Color::Magenta,
+    Color::Cyan,
+    Color::LightRed,
+    Color::LightBlue,
+];
+
+#[derive(Debug)]
+struct AppColor {
+    selected_bg_color: Color,
+}
+
+impl Default for AppColor {
+    fn default() -> Self {
+        AppColor {
+            selected_bg_color: Color::DarkGray,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_filter_issues() {
+        let mut app = App::new(Config::from_config_file().unwrap());
+        app.issues = app.jira.get_current_sprint_issues().unwrap();
+        app.search.update(&app.issues, "", None);
     }
 
     #[test]
     fn test_persist_state() {
-        let app = App::new();
+        let app = App::new(Config::from_config_file().unwrap());
         app.persist_state();
         assert!(fs::exists("/Users/rubenh/.local/share/jiratrack/state.json").unwrap())
     }
+
+    #[test]
+    fn test_typed_char() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        assert_eq!(typed_char(key(KeyCode::Char('s'), KeyModifiers::NONE)), Some('s'));
+        assert_eq!(typed_char(key(KeyCode::Char('S'), KeyModifiers::SHIFT)), Some('S'));
+        assert_eq!(typed_char(key(KeyCode::Char('s'), KeyModifiers::CONTROL)), None);
+        assert_eq!(typed_char(key(KeyCode::Char('c'), KeyModifiers::ALT)), None);
+        assert_eq!(typed_char(key(KeyCode::Enter, KeyModifiers::NONE)), None);
+    }
 }
@@ -0,0 +1,234 @@
+use jiff::Timestamp;
+use serde_json::Value;
+
+use crate::{adf, jira::parse_timestamp};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationKind {
+    Assigned,
+    Mentioned,
+    Reply,
+    Comment,
+    /// The issue was moved to the status.
+    Transitioned(String),
+}
+
+/// Something that happened on an issue and concerns the current user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub issue_key: String,
+    pub summary: String,
+    pub kind: NotificationKind,
+    pub author: String,
+    pub created: Timestamp,
+}
+
+impl Notification {
+    pub fn message(&self) -> String {
+        let action = match &self.kind {
+            NotificationKind::Assigned => "assigned you to",
+            NotificationKind::Mentioned => "mentioned you on",
+            NotificationKind::Reply => "replied to you on",
+            NotificationKind::Comment => "commented on",
+            NotificationKind::Transitioned(status) => {
+                return format!("{} moved {} {} to {status}", self.author, self.issue_key, self.summary)
+            }
+        };
+        format!("{} {action} {} {}", self.author, self.issue_key, self.summary)
+    }
+}
+
+/// Notifications for `account_id` found in an issue fetched with its comments
+/// and changelog, limited to what happened after `since`.
+pub fn from_issue(issue: &Value, account_id: &str, since: Timestamp) -> Vec<Notification> {
+    let fields = &issue["fields"];
+    let notification = |kind, author: &Value, created| Notification {
+        issue_key: issue["key"].as_str().unwrap_or("").to_string(),
+        summary: fields["summary"].as_str().unwrap_or("").to_string(),
+        kind,
+        author: author["displayName"].as_str().unwrap_or("").to_string(),
+        created,
+    };
+    let is_new = |author: &Value, created: &Value| {
+        let created = parse_timestamp(created).filter(|created| *created > since)?;
+        (author["accountId"] != account_id).then_some(created)
+    };
+
+    let mut notifications = vec![];
+    for history in array(&issue["changelog"]["histories"]) {
+        let Some(created) = is_new(&history["author"], &history["created"]) else {
+            continue;
+        };
+        let assigned = array(&history["items"])
+            .iter()
+            .any(|item| item["field"] == "assignee" && item["to"] == account_id);
+        if assigned {
+            notifications.push(notification(NotificationKind::Assigned, &history["author"], created));
+        }
+    }
+
+    let comments = array(&fields["comment"]["comments"]);
+    let owns_issue =
+        fields["assignee"]["accountId"] == account_id || fields["reporter"]["accountId"] == account_id;
+    for comment in comments {
+        let Some(created) = is_new(&comment["author"], &comment["created"]) else {
+            continue;
+        };
+        let parent_id = comment["parentId"]
+            .as_u64()
+            .map(|id| id.to_string())
+            .or_else(|| comment["parentId"].as_str().map(|id| id.to_string()));
+        let replies_to_me = parent_id.is_some_and(|parent_id| {
+            comments.iter().any(|parent| {
+                parent["id"] == parent_id.as_str() && parent["author"]["accountId"] == account_id
+            })
+        });
+        let kind = if adf::mention_ids(&comment["body"]).iter().any(|id| id == account_id) {
+            NotificationKind::Mentioned
+        } else if replies_to_me {
+            NotificationKind::Reply
+        } else if owns_issue {
+            NotificationKind::Comment
+        } else {
+            continue;
+        };
+        notifications.push(notification(kind, &comment["author"], created));
+    }
+    notifications
+}
+
+/// Comments and status changes by others on the issue the timer runs for, found in
+/// the issue fetched with its comments and changelog after `since`.
+pub fn from_active_issue(issue: &Value, account_id: &str, since: Timestamp) -> Vec<Notification> {
+    let fields = &issue["fields"];
+    let notification = |kind, author: &Value, created| Notification {
+        issue_key: issue["key"].as_str().unwrap_or("").to_string(),
+        summary: fields["summary"].as_str().unwrap_or("").to_string(),
+        kind,
+        author: author["displayName"].as_str().unwrap_or("").to_string(),
+        created,
+    };
+    let is_new = |author: &Value, created: &Value| {
+        let created = parse_timestamp(created).filter(|created| *created > since)?;
+        (author["accountId"] != account_id).then_some(created)
+    };
+
+    let mut notifications = vec![];
+    for history in array(&issue["changelog"]["histories"]) {
+        let Some(created) = is_new(&history["author"], &history["created"]) else {
+            continue;
+        };
+        let status = array(&history["items"])
+            .iter()
+            .filter(|item| item["field"] == "status")
+            .find_map(|item| item["toString"].as_str());
+        if let Some(status) = status {
+            let kind = NotificationKind::Transitioned(status.to_string());
+            notifications.push(notification(kind, &history["author"], created));
+        }
+    }
+    for comment in array(&fields["comment"]["comments"]) {
+        if let Some(created) = is_new(&comment["author"], &comment["created"]) {
+            notifications.push(notification(NotificationKind::Comment, &comment["author"], created));
+        }
+    }
+    notifications.sort_by_key(|notification| notification.created);
+    notifications
+}
+
+fn array(value: &Value) -> &[Value] {
+    value.as_array().map(|values| values.as_slice()).unwrap_or(&[])
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_from_issue() {
+        let sam = json!({"accountId": "sam", "displayName": "Sam"});
+        let me = json!({"accountId": "me", "displayName": "Me"});
+        let issue = json!({
+            "key": "CLI-1",
+            "fields": {
+                "summary": "Fix login",
+                "assignee": me,
+                "reporter": sam,
+                "comment": {"comments": [
+                    {"id": "1", "author": me, "created": "2025-01-10T09:00:00.000+0000", "body": {}},
+                    {"id": "2", "parentId": 1, "author": sam, "created": "2025-01-10T11:00:00.000+0000", "body": {}},
+                    {"id": "3", "author": sam, "created": "2025-01-10T11:05:00.000+0000", "body": {
+                        "type": "doc",
+                        "content": [{"type": "paragraph", "content": [{"type": "mention", "attrs": {"id": "me"}}]}]
+                    }},
+                    {"id": "4", "author": sam, "created": "2025-01-10T09:30:00.000+0000", "body": {}}
+                ]}
+            },
+            "changelog": {"histories": [
+                {"author": sam, "created": "2025-01-10T10:30:00.000+0000", "items": [
+                    {"field": "assignee", "to": "me"}
+                ]},
+                {"author": sam, "created": "2025-01-10T10:40:00.000+0000", "items": [
+                    {"field": "status", "to": "3"}
+                ]}
+            ]}
+        });
+        let since = "2025-01-10T10:00:00Z".parse().unwrap();
+        let kinds: Vec<NotificationKind> = from_issue(&issue, "me", since)
+            .into_iter()
+            .map(|notification| notification.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [NotificationKind::Assigned, NotificationKind::Reply, NotificationKind::Mentioned]
+        );
+    }
+
+    #[test]
+    fn test_from_active_issue() {
+        let qa = json!({"accountId": "qa", "displayName": "QA"});
+        let me = json!({"accountId": "me", "displayName": "Me"});
+        let issue = json!({
+            "key": "CLI-1",
+            "fields": {
+                "summary": "Fix login",
+                "comment": {"comments": [
+                    {"id": "1", "author": qa, "created": "2025-01-10T09:00:00.000+0000", "body": {}},
+                    {"id": "2", "author": me, "created": "2025-01-10T10:20:00.000+0000", "body": {}},
+                    {"id": "3", "author": qa, "created": "2025-01-10T10:50:00.000+0000", "body": {}}
+                ]}
+            },
+            "changelog": {"histories": [
+                {"author": me, "created": "2025-01-10T10:10:00.000+0000", "items": [
+                    {"field": "status", "toString": "Done"}
+                ]},
+                {"author": qa, "created": "2025-01-10T10:40:00.000+0000", "items": [
+                    {"field": "status", "toString": "Reopened"}
+                ]}
+            ]}
+        });
+        let since = "2025-01-10T10:00:00Z".parse().unwrap();
+        let messages: Vec<String> = from_active_issue(&issue, "me", since)
+            .iter()
+            .map(Notification::message)
+            .collect();
+        assert_eq!(
+            messages,
+            ["QA moved CLI-1 Fix login to Reopened", "QA commented on CLI-1 Fix login"]
+        );
+    }
+
+    #[test]
+    fn test_message() {
+        let notification = Notification {
+            issue_key: "CLI-1".to_string(),
+            summary: "Fix login".to_string(),
+            kind: NotificationKind::Mentioned,
+            author: "Sam".to_string(),
+            created: Timestamp::UNIX_EPOCH,
+        };
+        assert_eq!(notification.message(), "Sam mentioned you on CLI-1 Fix login");
+    }
+}
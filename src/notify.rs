@@ -0,0 +1,28 @@
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Shows a desktop notification, silently doing nothing when unsupported. Doesn't wait
+/// for the notifier, the child is reaped on a thread of its own.
+pub fn send(title: &str, message: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {:?} with title {:?}",
+            message, title
+        );
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(script);
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.arg(title).arg(message);
+        command
+    };
+    let spawned = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = spawned {
+        thread::spawn(move || child.wait());
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::Error;
+use jiff::Zoned;
+
+use crate::{jira::Jira, session::Session};
+
+/// Overlaps shorter than this are rounding noise between back-to-back worklogs.
+pub const MIN_OVERLAP_S: u64 = 60;
+
+/// A session about to be submitted that covers time already logged in Jira.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlap {
+    /// Index of the session in the sessions that were checked.
+    pub session: usize,
+    /// The existing worklog.
+    pub worklog: Session,
+    pub overlap_s: u64,
+}
+
+/// Seconds both sessions cover.
+pub fn overlap_s(a: &Session, b: &Session) -> u64 {
+    let start = a.started.clone().max(b.started.clone());
+    let end = a.ended().min(b.ended());
+    (end.timestamp().as_second() - start.timestamp().as_second()).max(0) as u64
+}
+
+/// Pairs of sessions and existing worklogs, on any issue, overlapping by at least
+/// `min_s` seconds.
+pub fn overlaps(sessions: &[Session], worklogs: &[Session], min_s: u64) -> Vec<Overlap> {
+    sessions
+        .iter()
+        .enumerate()
+        .flat_map(|(index, session)| {
+            worklogs.iter().filter_map(move |worklog| {
+                let overlap_s = overlap_s(session, worklog);
+                (overlap_s >= min_s.max(1)).then(|| Overlap {
+                    session: index,
+                    worklog: worklog.clone(),
+                    overlap_s,
+                })
+            })
+        })
+        .collect()
+}
+
+/// The worklogs of the user between `start` and `end` on all instances. Instances that
+/// can't be read are left out, with the reason.
+pub fn my_worklogs<'a>(
+    jiras: impl Iterator<Item = &'a Jira>,
+    start: &Zoned,
+    end: &Zoned,
+) -> (Vec<Session>, Vec<Error>) {
+    let mut worklogs = vec![];
+    let mut errors = vec![];
+    for jira in jiras {
+        match jira.get_my_worklogs(start, end) {
+            Ok(found) => worklogs.extend(found),
+            Err(error) => errors.push(error),
+        }
+    }
+    (worklogs, errors)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session(issue_key: &str, started: &str, duration_s: u64) -> Session {
+        Session {
+            issue_key: issue_key.to_string(),
+            started: started.parse().unwrap(),
+            duration_s,
+            comment: None,
+            account: None,
+        }
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let sessions = [
+            session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 3600),
+            session("IMG-2", "2025-01-10T13:00:00+00:00[UTC]", 1800),
+        ];
+        let worklogs = [
+            session("OPS-4", "2025-01-10T09:45:00+00:00[UTC]", 3600),
+            session("IMG-2", "2025-01-10T13:30:00+00:00[UTC]", 600),
+            session("IMG-2", "2025-01-10T12:30:00+01:00[Europe/Berlin]", 1800),
+        ];
+        let overlaps = overlaps(&sessions, &worklogs, MIN_OVERLAP_S);
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].session, 0);
+        assert_eq!(overlaps[0].worklog.issue_key, "OPS-4");
+        assert_eq!(overlaps[0].overlap_s, 900);
+    }
+}
@@ -0,0 +1,74 @@
+use ratatui::layout::Constraint;
+use serde::{Deserialize, Serialize};
+
+const MIN_CURRENT_ISSUE_HEIGHT: u16 = 3;
+const MAX_CURRENT_ISSUE_HEIGHT: u16 = 12;
+const SEARCH_HEIGHT: u16 = 3;
+
+/// Sizes of the panes below the issue list, kept between starts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Panes {
+    pub current_issue_height: u16,
+    /// Hide the search bar while nothing is being searched.
+    pub collapse_search: bool,
+}
+
+impl Default for Panes {
+    fn default() -> Self {
+        Panes {
+            current_issue_height: MIN_CURRENT_ISSUE_HEIGHT,
+            collapse_search: false,
+        }
+    }
+}
+
+impl Panes {
+    /// Grows the current issue pane by a row, taking it from the issue list.
+    pub fn grow(&mut self) {
+        self.current_issue_height = (self.current_issue_height + 1).min(MAX_CURRENT_ISSUE_HEIGHT);
+    }
+
+    pub fn shrink(&mut self) {
+        self.current_issue_height = (self.current_issue_height - 1).max(MIN_CURRENT_ISSUE_HEIGHT);
+    }
+
+    /// Heights of the issue list, the current issue and the search bar.
+    pub fn constraints(&self, searching: bool) -> [Constraint; 3] {
+        let search_height = if self.collapse_search && !searching {
+            0
+        } else {
+            SEARCH_HEIGHT
+        };
+        [
+            Constraint::Min(1),
+            Constraint::Length(self.current_issue_height),
+            Constraint::Length(search_height),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resize() {
+        let mut panes = Panes::default();
+        panes.shrink();
+        assert_eq!(panes.current_issue_height, MIN_CURRENT_ISSUE_HEIGHT);
+        for _ in 0..20 {
+            panes.grow();
+        }
+        assert_eq!(panes.current_issue_height, MAX_CURRENT_ISSUE_HEIGHT);
+    }
+
+    #[test]
+    fn test_constraints() {
+        let panes = Panes {
+            current_issue_height: 5,
+            collapse_search: true,
+        };
+        assert_eq!(panes.constraints(false)[2], Constraint::Length(0));
+        assert_eq!(panes.constraints(true)[1..], [Constraint::Length(5), Constraint::Length(3)]);
+    }
+}
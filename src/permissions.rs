@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// Actions that need a project permission in Jira.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    LogWork,
+    Assign,
+    Schedule,
+    Create,
+    Edit,
+}
+
+pub const ACTIONS: [Action; 5] = [
+    Action::LogWork,
+    Action::Assign,
+    Action::Schedule,
+    Action::Create,
+    Action::Edit,
+];
+
+impl Action {
+    /// Key of the permission in `/rest/api/3/mypermissions`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Action::LogWork => "WORK_ON_ISSUES",
+            Action::Assign => "ASSIGN_ISSUES",
+            Action::Schedule => "SCHEDULE_ISSUES",
+            Action::Create => "CREATE_ISSUES",
+            Action::Edit => "EDIT_ISSUES",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Action::LogWork => "Work On Issues",
+            Action::Assign => "Assign Issues",
+            Action::Schedule => "Schedule Issues",
+            Action::Create => "Create Issues",
+            Action::Edit => "Edit Issues",
+        }
+    }
+}
+
+/// The keys of the permissions the user has in a `mypermissions` response.
+pub fn granted(body: &Value) -> Vec<String> {
+    body["permissions"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, permission)| permission["havePermission"] == true)
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+/// The permissions of the current user per project, as far as they were fetched.
+#[derive(Debug, Default)]
+pub struct Permissions {
+    projects: HashMap<String, Vec<String>>,
+}
+
+impl Permissions {
+    pub fn set(&mut self, project: &str, granted: Vec<String>) {
+        self.projects.insert(project.to_string(), granted);
+    }
+
+    /// Why the action is not allowed in the project, `None` when it is allowed or the
+    /// permissions of the project are unknown.
+    pub fn denial(&self, project: &str, action: Action) -> Option<String> {
+        let granted = self.projects.get(project)?;
+        if granted.iter().any(|key| key == action.key()) {
+            return None;
+        }
+        Some(format!("You lack the {} permission in {project}", action.name()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_denial() {
+        let body = json!({"permissions": {
+            "WORK_ON_ISSUES": {"havePermission": true},
+            "ASSIGN_ISSUES": {"havePermission": false}
+        }});
+        let mut permissions = Permissions::default();
+        permissions.set("IMG", granted(&body));
+        assert_eq!(permissions.denial("IMG", Action::LogWork), None);
+        assert_eq!(
+            permissions.denial("IMG", Action::Assign).as_deref(),
+            Some("You lack the Assign Issues permission in IMG")
+        );
+        assert_eq!(permissions.denial("OPS", Action::Assign), None);
+    }
+}
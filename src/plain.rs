@@ -0,0 +1,326 @@
+use std::io::{BufRead, Write};
+
+use anyhow::{anyhow, Result};
+use jiff::{Unit, Zoned};
+
+use crate::{
+    history::History,
+    jira::{self, Issue},
+    journal::Journal,
+    ledger::Ledger,
+    App, Popup,
+};
+
+const HELP: &str = "Commands:
+  <number>   start tracking the issue with that number
+  s          stop tracking and submit the worklog
+  d          stop tracking without submitting
+  /<text>    show only issues matching the text, / alone shows all
+             prefix the text with ex: for a substring or re: for a regex
+  l          list the issues again
+  a          show the active issue
+  r          reload the issues from Jira
+  q          quit";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Start(usize),
+    Stop,
+    Cancel,
+    Search(String),
+    List,
+    Active,
+    Refresh,
+    Help,
+    Quit,
+}
+
+fn parse_command(line: &str) -> Option<Command> {
+    let line = line.trim();
+    if let Some(text) = line.strip_prefix('/') {
+        return Some(Command::Search(text.trim().to_string()));
+    }
+    if let Ok(number) = line.parse::<usize>() {
+        return number.checked_sub(1).map(Command::Start);
+    }
+    match line {
+        "s" => Some(Command::Stop),
+        "d" => Some(Command::Cancel),
+        "l" | "" => Some(Command::List),
+        "a" => Some(Command::Active),
+        "r" => Some(Command::Refresh),
+        "?" | "h" | "help" => Some(Command::Help),
+        "q" | "quit" => Some(Command::Quit),
+        _ => None,
+    }
+}
+
+fn issue_line(number: usize, issue: &Issue, time_spent: &str) -> String {
+    format!(
+        "{number}. {}, {}, {}, {time_spent} spent",
+        issue.key, issue.summary, issue.status
+    )
+}
+
+impl App {
+    /// Runs without the full-screen interface, reading one command per line,
+    /// so the tracker can be used with a screen reader.
+    pub fn run_plain(&mut self, mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+        self.load_state();
+        self.history = History::load();
+        self.ledger = Ledger::load();
+        self.journal = Journal::load();
+        if !self.journal.entries.is_empty() {
+            writeln!(
+                output,
+                "{} worklogs were interrupted, start without --plain to retry them.",
+                self.journal.entries.len()
+            )?;
+        }
+        writeln!(output, "Loading issues...")?;
+        self.issues = self.fetch_issues(vec![])?;
+        self.loaded = true;
+        self.jira.sync_time_zone()?;
+        self.myself = self.jira.get_myself().ok();
+        self.apply_profile_locale();
+        self.apply_owner_preference();
+        self.search.update(&self.issues, "", None);
+        self.write_active_issue(&mut output)?;
+        self.write_issue_list(&mut output)?;
+        writeln!(output, "Type ? for help.")?;
+
+        while !self.exit {
+            write!(output, "> ")?;
+            output.flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                break;
+            }
+            match parse_command(&line) {
+                Some(Command::Start(index)) => match self.filtered_issue(index) {
+                    Some(_) => {
+                        self.table_state.select(Some(index));
+                        self.activate_issue();
+                        self.resolve_plain_popup(&mut input, &mut output)?;
+                        if let Some(key) = &self.active_issue {
+                            writeln!(output, "Tracking {key}.")?;
+                        }
+                    }
+                    None => writeln!(output, "There is no issue number {}.", index + 1)?,
+                },
+                Some(Command::Stop) => {
+                    if self.active_issue.is_none() {
+                        writeln!(output, "No issue active.")?;
+                        continue;
+                    }
+                    self.deactivate_issue();
+                    self.resolve_plain_popup(&mut input, &mut output)?;
+                    if self.config.review_mode {
+                        writeln!(output, "Stopped, the session awaits review.")?;
+                    } else {
+                        writeln!(output, "Stopped.")?;
+                    }
+                }
+                Some(Command::Cancel) => {
+                    self.clear_state();
+                    writeln!(output, "Stopped without submitting.")?;
+                }
+                Some(Command::Search(text)) => {
+                    self.search_input = text;
+                    self.search.update(&self.issues, &self.search_input, None);
+                    self.write_issue_list(&mut output)?;
+                }
+                Some(Command::List) => self.write_issue_list(&mut output)?,
+                Some(Command::Active) => self.write_active_issue(&mut output)?,
+                Some(Command::Refresh) => {
+                    self.refresh_issues();
+                    self.search.update(&self.issues, &self.search_input, None);
+                    self.write_issue_list(&mut output)?;
+                }
+                Some(Command::Help) => writeln!(output, "{HELP}")?,
+                Some(Command::Quit) => self.shut_down(),
+                None => writeln!(output, "Unknown command, type ? for help.")?,
+            }
+            self.send_pending_updates();
+            if let Some((message, _)) = self.toast.take() {
+                writeln!(output, "Error: {message}")?;
+            }
+        }
+        self.send_pending_updates();
+        Ok(())
+    }
+
+    /// Starts the timer on the issue given on the command line as a key or, in the
+    /// first configured project, as a bare number. Unknown keys get close matches suggested.
+    pub fn start_plain(&mut self, key: &str, mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+        let project = self.config.projects().first().cloned().unwrap_or_default();
+        let key = jira::normalize_key(key, &project).ok_or_else(|| anyhow!("{key} is not an issue key"))?;
+        self.load_synced_state();
+        self.ledger = Ledger::load();
+        self.issues = self.fetch_issues(vec![])?;
+        if self.find_issue(&key).is_none() {
+            match self.jira_for(&key).get_issue(&key) {
+                Ok(issue) => self.issues.push(issue),
+                Err(error) => {
+                    let candidates: Vec<String> = self.issues.iter().map(|issue| issue.key.clone()).collect();
+                    let close_keys = jira::close_keys(&key, &candidates);
+                    if close_keys.is_empty() {
+                        return Err(anyhow!("{key} not found: {error}"));
+                    }
+                    return Err(anyhow!("{key} not found, did you mean {}?", close_keys.join(" or ")));
+                }
+            }
+        }
+        self.jira.sync_time_zone()?;
+        self.load_permissions();
+        self.start_timer(key);
+        self.resolve_plain_popup(&mut input, &mut output)?;
+        self.send_pending_updates();
+        self.sync_state();
+        if let Some((message, _)) = self.toast.take() {
+            writeln!(output, "Error: {message}")?;
+        }
+        self.write_active_issue(&mut output)?;
+        Ok(())
+    }
+
+    /// Stops the timer and submits the session or, with none running, starts it on the
+    /// issue tracked last, for binding to a desktop-wide hotkey.
+    pub fn toggle_plain(&mut self, mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+        self.load_synced_state();
+        let Some(issue_key) = self.active_issue.clone() else {
+            let last_issue = self
+                .last_issue
+                .clone()
+                .or_else(|| History::load().last_issue_key())
+                .ok_or_else(|| anyhow!("No issue tracked yet, use jiratrack start <key> first"))?;
+            return self.start_plain(&last_issue, input, output);
+        };
+        self.ledger = Ledger::load();
+        self.issues = self.fetch_issues(vec![])?;
+        self.jira.sync_time_zone()?;
+        self.load_permissions();
+        self.deactivate_issue();
+        self.resolve_plain_popup(&mut input, &mut output)?;
+        self.send_pending_updates();
+        self.sync_state();
+        if let Some((message, _)) = self.toast.take() {
+            writeln!(output, "Error: {message}")?;
+        }
+        writeln!(output, "Stopped {issue_key}.")?;
+        Ok(())
+    }
+
+    fn write_issue_list(&self, output: &mut impl Write) -> Result<()> {
+        let work_time = self.config.work_time();
+        let matches = self.search.matches();
+        if matches.is_empty() {
+            writeln!(output, "No issues.")?;
+        }
+        for (number, index) in matches.iter().enumerate() {
+            let issue = &self.issues[*index];
+            let time_spent = work_time.format(issue.time_spent);
+            let mut line = issue_line(number + 1, issue, &time_spent);
+            if self.active_issue.as_ref() == Some(&issue.key) {
+                line.push_str(", active");
+            }
+            writeln!(output, "{line}")?;
+        }
+        Ok(())
+    }
+
+    fn write_active_issue(&self, output: &mut impl Write) -> Result<()> {
+        match (&self.active_issue, &self.activated_on) {
+            (Some(key), Some(activated_on)) => {
+                let elapsed = (&Zoned::now() - activated_on).round(Unit::Minute).unwrap();
+                writeln!(output, "Tracking {key} for {elapsed:#}.")?
+            }
+            _ => writeln!(output, "No issue active.")?,
+        }
+        Ok(())
+    }
+
+    /// Asks the questions the full interface shows popups for, until answered.
+    fn resolve_plain_popup(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<()> {
+        loop {
+            let question = match self.popup {
+                Some(Popup::MaxDuration) => {
+                    "The session exceeds the maximum duration. \
+                     Submit, cap, split or discard? [y/c/s/n]"
+                }
+                Some(Popup::MergeConfirm) => "Merge with the previous session on this issue? [y/n]",
+                Some(Popup::ClosedIssue) => "The issue is closed. Track time on it anyway? [y/n]",
+                Some(Popup::SubtaskPicker) => "Log to the issue itself or a subtask? [number]",
+                Some(Popup::BillingAccount) => "Billing account, or enter to keep the default?",
+                Some(Popup::WorklogComment) => "Worklog comment, or enter to keep the default?",
+                _ => return Ok(()),
+            };
+            if let (Some(Popup::ClosedIssue), Some(key)) = (self.popup, &self.pending_activation) {
+                writeln!(output, "{key} is {}.", self.closed_status(key).unwrap_or_default())?;
+            }
+            if let Some(Popup::BillingAccount) = self.popup {
+                writeln!(output, "Default billing account: {}", self.billing_input)?;
+            }
+            if let Some(Popup::WorklogComment) = self.popup {
+                writeln!(output, "Default comment: {}", self.comment_input)?;
+            }
+            if let (Some(Popup::SubtaskPicker), Some(parent)) = (self.popup, &self.subtask_parent) {
+                writeln!(output, "0. {} {}", parent.key, parent.summary)?;
+                for (index, subtask) in parent.subtasks.iter().enumerate() {
+                    writeln!(output, "{}. {} {}", index + 1, subtask.key, subtask.summary)?;
+                }
+            }
+            writeln!(output, "{question}")?;
+            write!(output, "> ")?;
+            output.flush()?;
+            let mut line = String::new();
+            let answer = match input.read_line(&mut line)? {
+                0 => 'n',
+                _ => line.trim().chars().next().unwrap_or(' '),
+            };
+            match self.popup {
+                Some(Popup::MaxDuration) => self.resolve_oversized_session(answer),
+                Some(Popup::MergeConfirm) if matches!(answer, 'y' | 'n') => {
+                    self.resolve_merge(answer == 'y')
+                }
+                Some(Popup::ClosedIssue) => self.resolve_closed_issue(answer == 'y'),
+                Some(Popup::BillingAccount) => {
+                    if !line.trim().is_empty() {
+                        self.billing_input = line.trim().to_string();
+                    }
+                    self.confirm_billing_account();
+                }
+                Some(Popup::WorklogComment) => {
+                    self.comment_input = line.trim().to_string();
+                    self.confirm_worklog_comment(!line.trim().is_empty());
+                }
+                Some(Popup::SubtaskPicker) => match line.trim().parse() {
+                    Ok(index) => self.pick_subtask(index),
+                    // Without an answer the timer stays as it was.
+                    Err(_) if line.is_empty() => self.popup = None,
+                    Err(_) => {}
+                },
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("3\n"), Some(Command::Start(2)));
+        assert_eq!(parse_command("0"), None);
+        assert_eq!(parse_command("/ login "), Some(Command::Search("login".to_string())));
+        assert_eq!(parse_command(""), Some(Command::List));
+        assert_eq!(parse_command("s"), Some(Command::Stop));
+        assert_eq!(parse_command("x"), None);
+    }
+}
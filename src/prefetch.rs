@@ -0,0 +1,143 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use jiff::Timestamp;
+use serde_json::Value;
+
+use crate::{
+    activity::{self, Event},
+    adf,
+    config::Config,
+    detail::COMMENT_PAGE_SIZE,
+    jira::{Attachment, Comment, Jira},
+    statustime,
+};
+
+/// Number of issues fetched at the same time.
+const WORKERS: usize = 4;
+
+/// Everything the detail view shows besides the issue itself.
+#[derive(Debug, Clone, Default)]
+pub struct IssueDetails {
+    pub description: Value,
+    pub comments: Vec<Comment>,
+    pub comments_total: usize,
+    pub attachments: Vec<Attachment>,
+    pub status_times: Vec<(String, u64)>,
+    /// Status changes and worklogs, the comments are added when showing them.
+    pub activity: Vec<Event>,
+    /// Display names of the users mentioned in the description and comments.
+    pub names: HashMap<String, String>,
+}
+
+impl IssueDetails {
+    pub fn fetch(jira: &Jira, key: &str) -> IssueDetails {
+        let description = jira.get_issue_description(key).unwrap_or_default();
+        let (comments, comments_total) =
+            jira.get_comments(key, 0, COMMENT_PAGE_SIZE).unwrap_or_default();
+        let attachments = jira.get_attachments(key).unwrap_or_default();
+        let changelog = jira.get_changelog(key).unwrap_or_default();
+        let status_times = statustime::from_issue(&changelog, Timestamp::now());
+        let mut activity = activity::status_changes(&changelog);
+        activity.extend(jira.get_worklogs(key).unwrap_or_default());
+
+        let mut mention_ids = adf::mention_ids(&description);
+        for comment in &comments {
+            mention_ids.extend(adf::mention_ids(&comment.body));
+        }
+        mention_ids.sort();
+        mention_ids.dedup();
+        let names = mention_ids
+            .into_iter()
+            .filter_map(|id| Some((id.clone(), jira.get_user_display_name(&id).ok()?)))
+            .collect();
+
+        IssueDetails {
+            description,
+            comments,
+            comments_total,
+            attachments,
+            status_times,
+            activity,
+            names,
+        }
+    }
+}
+
+/// Loads issue details in background threads, so the detail view opens without waiting.
+#[derive(Debug)]
+pub struct Prefetcher {
+    requests: Sender<String>,
+    results: Receiver<(String, IssueDetails)>,
+    requested: HashSet<String>,
+    details: HashMap<String, IssueDetails>,
+}
+
+impl Prefetcher {
+    pub fn start(config: &Config) -> Prefetcher {
+        let (requests, request_receiver) = mpsc::channel::<String>();
+        let (result_sender, results) = mpsc::channel();
+        let request_receiver = Arc::new(Mutex::new(request_receiver));
+        for _ in 0..WORKERS {
+            let request_receiver = Arc::clone(&request_receiver);
+            let result_sender = result_sender.clone();
+            let config = config.clone();
+            thread::spawn(move || {
+                let jira = Jira::from_config(&config);
+                let instances: Vec<Jira> =
+                    config.instance_configs().iter().map(Jira::from_config).collect();
+                loop {
+                    let Ok(key) = request_receiver.lock().unwrap().recv() else {
+                        return;
+                    };
+                    let jira = instances
+                        .iter()
+                        .find(|instance| instance.owns_issue(&key))
+                        .unwrap_or(&jira);
+                    let details = jira.in_background(|jira| IssueDetails::fetch(jira, &key));
+                    if result_sender.send((key, details)).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+        Prefetcher {
+            requests,
+            results,
+            requested: HashSet::new(),
+            details: HashMap::new(),
+        }
+    }
+
+    /// Queues the issue unless it was already requested.
+    pub fn request(&mut self, key: &str) {
+        if self.requested.insert(key.to_string()) {
+            let _ = self.requests.send(key.to_string());
+        }
+    }
+
+    /// The prefetched details of an issue, if they arrived already.
+    pub fn take(&mut self, key: &str) -> Option<IssueDetails> {
+        self.details.extend(self.results.try_iter());
+        self.requested.remove(key);
+        self.details.remove(key)
+    }
+
+    /// The prefetched details of an issue, keeping them for the detail view.
+    pub fn peek(&mut self, key: &str) -> Option<&IssueDetails> {
+        self.details.extend(self.results.try_iter());
+        self.details.get(key)
+    }
+
+    /// Drops the details of an issue that changed, so they are fetched again.
+    pub fn invalidate(&mut self, key: &str) {
+        self.details.remove(key);
+        self.requested.remove(key);
+    }
+}
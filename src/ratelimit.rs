@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use jiff::Timestamp;
+
+/// Minimum time between two background requests, so bursts are spread out.
+const BACKGROUND_INTERVAL: Duration = Duration::from_millis(500);
+pub const MAX_RETRIES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Priority {
+    /// Actions the user is waiting for, such as submitting a worklog. These are sent
+    /// right away and fail while rate limited, so the interface never waits on them.
+    #[default]
+    Interactive,
+    /// Periodic refreshes, which are spread out and dropped while rate limited.
+    Background,
+}
+
+/// Keeps track of the rate limits reported by Jira and spreads out background requests.
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    last_request: Option<Instant>,
+    limited_until: Option<Instant>,
+}
+
+impl Scheduler {
+    /// How long a request has to wait before it may be sent, or `None` when it
+    /// should not be sent at all.
+    pub fn delay(&self, priority: Priority, now: Instant) -> Option<Duration> {
+        if self.limited_until.is_some_and(|until| until > now) {
+            return None;
+        }
+        match priority {
+            Priority::Interactive => Some(Duration::ZERO),
+            Priority::Background => Some(
+                self.last_request
+                    .map(|last| (last + BACKGROUND_INTERVAL).saturating_duration_since(now))
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+
+    /// Claims the next slot for a request, returning how long to wait for it.
+    pub fn reserve(&mut self, priority: Priority, now: Instant) -> Option<Duration> {
+        let delay = self.delay(priority, now)?;
+        self.record_request(now + delay);
+        Some(delay)
+    }
+
+    pub fn record_request(&mut self, now: Instant) {
+        self.last_request = Some(self.last_request.map_or(now, |last| last.max(now)));
+    }
+
+    pub fn record_limit(&mut self, delay: Duration, now: Instant) {
+        self.limited_until = Some(now + delay);
+    }
+
+    /// How much longer Jira is rate limiting.
+    pub fn limited_for(&self, now: Instant) -> Option<Duration> {
+        self.limited_until
+            .map(|until| until.saturating_duration_since(now))
+            .filter(|limited_for| !limited_for.is_zero())
+    }
+}
+
+/// The scheduler for a Jira site, shared by every client and thread talking to it,
+/// since Jira counts the requests of a user across all of them.
+pub fn shared(atlassian_url: &str) -> Arc<Mutex<Scheduler>> {
+    static SCHEDULERS: OnceLock<Mutex<HashMap<String, Arc<Mutex<Scheduler>>>>> = OnceLock::new();
+    let mut schedulers = SCHEDULERS.get_or_init(Default::default).lock().unwrap();
+    schedulers
+        .entry(atlassian_url.trim_end_matches('/').to_string())
+        .or_default()
+        .clone()
+}
+
+/// How long to back off according to the rate limit headers of a response.
+///
+/// `Retry-After` is sent with `429` responses, `X-RateLimit-Remaining` and
+/// `X-RateLimit-Reset` announce the limit before it is hit.
+pub fn backoff(
+    retry_after: Option<&str>,
+    remaining: Option<&str>,
+    reset: Option<&str>,
+    now: Timestamp,
+) -> Option<Duration> {
+    if let Some(seconds) = retry_after.and_then(|seconds| seconds.trim().parse::<u64>().ok()) {
+        return Some(Duration::from_secs(seconds));
+    }
+    if remaining?.trim().parse::<u64>().ok()? > 0 {
+        return None;
+    }
+    let reset: Timestamp = reset?.trim().parse().ok()?;
+    let seconds = (reset.as_second() - now.as_second()).max(1);
+    Some(Duration::from_secs(seconds as u64))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let mut scheduler = Scheduler::default();
+        let now = Instant::now();
+        assert_eq!(scheduler.reserve(Priority::Background, now), Some(Duration::ZERO));
+        assert_eq!(scheduler.delay(Priority::Interactive, now), Some(Duration::ZERO));
+        assert_eq!(scheduler.reserve(Priority::Background, now), Some(BACKGROUND_INTERVAL));
+        assert_eq!(scheduler.delay(Priority::Background, now), Some(2 * BACKGROUND_INTERVAL));
+
+        scheduler.record_limit(Duration::from_secs(5), now);
+        assert_eq!(scheduler.delay(Priority::Interactive, now), None);
+        assert_eq!(scheduler.delay(Priority::Background, now), None);
+        assert_eq!(scheduler.limited_for(now), Some(Duration::from_secs(5)));
+        let later = now + Duration::from_secs(5);
+        assert_eq!(scheduler.delay(Priority::Interactive, later), Some(Duration::ZERO));
+        assert_eq!(scheduler.limited_for(later), None);
+    }
+
+    #[test]
+    fn test_shared() {
+        let scheduler = shared("https://example.atlassian.net");
+        assert!(Arc::ptr_eq(&scheduler, &shared("https://example.atlassian.net/")));
+        assert!(!Arc::ptr_eq(&scheduler, &shared("https://other.atlassian.net")));
+    }
+
+    #[test]
+    fn test_backoff() {
+        let now: Timestamp = "2025-01-10T10:00:00Z".parse().unwrap();
+        assert_eq!(backoff(Some("7"), None, None, now), Some(Duration::from_secs(7)));
+        assert_eq!(backoff(None, Some("12"), Some("2025-01-10T10:01:00Z"), now), None);
+        assert_eq!(
+            backoff(None, Some("0"), Some("2025-01-10T10:01:00Z"), now),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(backoff(None, None, None, now), None);
+    }
+}
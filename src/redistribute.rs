@@ -0,0 +1,173 @@
+use jiff::{ToSpan, Zoned};
+
+use crate::{duration::WorkTime, session::Session};
+
+/// The part of a block of time going to one issue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Share {
+    /// Percentage of what is left after the fixed chunks.
+    Percent(f64),
+    Fixed(u64),
+}
+
+/// Parses a share like `40%` or a Jira time string like `30m`.
+pub fn parse_share(input: &str, work_time: &WorkTime) -> Option<Share> {
+    let input = input.trim();
+    match input.strip_suffix('%') {
+        Some(percent) => percent.trim().parse().ok().filter(|percent| *percent >= 0.0).map(Share::Percent),
+        None => work_time.parse(input).map(Share::Fixed),
+    }
+}
+
+/// Splits `total_s` over the shares in whole minutes. Fixed chunks are taken first and
+/// the percentages split the rest; when they add up to 100% the last one also gets the
+/// rounding remainder, so nothing is lost.
+pub fn distribute(total_s: u64, shares: &[Share]) -> Result<Vec<u64>, String> {
+    let fixed_s: u64 = shares
+        .iter()
+        .map(|share| match share {
+            Share::Fixed(seconds) => *seconds,
+            Share::Percent(_) => 0,
+        })
+        .sum();
+    let percent: f64 = shares
+        .iter()
+        .map(|share| match share {
+            Share::Percent(percent) => *percent,
+            Share::Fixed(_) => 0.0,
+        })
+        .sum();
+    if fixed_s > total_s {
+        return Err("The fixed chunks are longer than the block".to_string());
+    }
+    if percent > 100.0 + 1e-6 {
+        return Err(format!("The percentages add up to {percent}%"));
+    }
+    let rest_s = total_s - fixed_s;
+    let mut seconds: Vec<u64> = shares
+        .iter()
+        .map(|share| match share {
+            Share::Fixed(seconds) => *seconds,
+            Share::Percent(percent) => (rest_s as f64 * percent / 100.0 / 60.0).floor() as u64 * 60,
+        })
+        .collect();
+    let last_percent = shares.iter().rposition(|share| matches!(share, Share::Percent(_)));
+    if let (Some(last), true) = (last_percent, (percent - 100.0).abs() <= 1e-6) {
+        let assigned: u64 = seconds.iter().sum();
+        seconds[last] += total_s - assigned;
+    }
+    Ok(seconds)
+}
+
+/// Back-to-back sessions for the allocations, the last one ending at `end`.
+pub fn sessions(allocations: &[(String, u64)], end: &Zoned) -> Vec<Session> {
+    let mut ended = end.clone();
+    let mut sessions: Vec<Session> = allocations
+        .iter()
+        .rev()
+        .filter(|(_, seconds)| *seconds > 0)
+        .map(|(issue_key, seconds)| {
+            let started = ended.checked_sub((*seconds as i64).seconds()).unwrap();
+            let session = Session::new(issue_key, &started, &ended);
+            ended = started;
+            session
+        })
+        .collect();
+    sessions.reverse();
+    sessions
+}
+
+/// A block of time being split over issues, as edited in the redistribution popup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redistribution {
+    pub total: String,
+    /// Issue keys with the share typed for them.
+    pub rows: Vec<(String, String)>,
+    /// `0` is the block itself, the issues follow.
+    pub selected: usize,
+    /// Why the last attempt to submit was refused.
+    pub error: Option<String>,
+}
+
+impl Redistribution {
+    pub fn new(total: String, issue_keys: Vec<String>) -> Self {
+        Redistribution {
+            total,
+            rows: issue_keys.into_iter().map(|key| (key, String::new())).collect(),
+            selected: 0,
+            error: None,
+        }
+    }
+
+    pub fn input_mut(&mut self) -> &mut String {
+        match self.selected {
+            0 => &mut self.total,
+            row => &mut self.rows[row - 1].1,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.rows.len());
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// The seconds going to each issue with a share.
+    pub fn allocations(&self, work_time: &WorkTime) -> Result<Vec<(String, u64)>, String> {
+        let total_s = work_time
+            .parse(&self.total)
+            .ok_or_else(|| format!("Invalid block of time \"{}\"", self.total))?;
+        let mut keys = vec![];
+        let mut shares = vec![];
+        for (key, input) in self.rows.iter().filter(|(_, input)| !input.trim().is_empty()) {
+            let share = parse_share(input, work_time).ok_or_else(|| format!("Invalid share \"{input}\" for {key}"))?;
+            keys.push(key.clone());
+            shares.push(share);
+        }
+        if shares.is_empty() {
+            return Err("Give at least one issue a share".to_string());
+        }
+        Ok(keys.into_iter().zip(distribute(total_s, &shares)?).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_share() {
+        let work_time = WorkTime::default();
+        assert_eq!(parse_share("40%", &work_time), Some(Share::Percent(40.0)));
+        assert_eq!(parse_share("1h 30m", &work_time), Some(Share::Fixed(5400)));
+        assert_eq!(parse_share("-5%", &work_time), None);
+        assert_eq!(parse_share("lots", &work_time), None);
+    }
+
+    #[test]
+    fn test_distribute() {
+        let thirds = [Share::Percent(33.3), Share::Percent(33.3), Share::Percent(33.4)];
+        let seconds = distribute(7200, &thirds).unwrap();
+        assert_eq!(seconds, vec![2340, 2340, 2520]);
+
+        let mixed = [Share::Fixed(1800), Share::Percent(50.0), Share::Percent(50.0)];
+        assert_eq!(distribute(7200, &mixed).unwrap(), vec![1800, 2700, 2700]);
+
+        assert_eq!(distribute(7200, &[Share::Percent(50.0)]).unwrap(), vec![3600]);
+        assert!(distribute(3600, &[Share::Fixed(7200)]).is_err());
+        assert!(distribute(3600, &[Share::Percent(60.0), Share::Percent(60.0)]).is_err());
+    }
+
+    #[test]
+    fn test_sessions() {
+        let end: Zoned = "2025-01-10T17:00:00+00:00[UTC]".parse().unwrap();
+        let allocations = [("IMG-1".to_string(), 3600), ("IMG-2".to_string(), 0), ("IMG-3".to_string(), 1800)];
+        let sessions = sessions(&allocations, &end);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].issue_key, "IMG-1");
+        assert_eq!(sessions[0].started.to_string(), "2025-01-10T15:30:00+00:00[UTC]");
+        assert_eq!(sessions[1].ended(), end);
+    }
+}
@@ -0,0 +1,83 @@
+use jiff::{civil::{Time, Weekday}, Zoned};
+use serde::{Deserialize, Serialize};
+
+/// Daily working hours, applying from Monday to Friday.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorkHours {
+    pub start: Time,
+    pub end: Time,
+}
+
+impl WorkHours {
+    pub fn contains(&self, now: &Zoned) -> bool {
+        if matches!(now.weekday(), Weekday::Saturday | Weekday::Sunday) {
+            return false;
+        }
+        let time = now.time();
+        self.start <= time && time < self.end
+    }
+
+    /// Seconds of today's working hours that have passed, `0` on weekends.
+    pub fn elapsed_s(&self, now: &Zoned) -> u64 {
+        if matches!(now.weekday(), Weekday::Saturday | Weekday::Sunday) {
+            return 0;
+        }
+        let end = now.time().min(self.end);
+        if end <= self.start {
+            return 0;
+        }
+        end.duration_since(self.start).as_secs() as u64
+    }
+}
+
+/// Whether to remind the user to start tracking after being idle since `idle_since`.
+pub fn should_remind(
+    work_hours: &WorkHours,
+    idle_since: &Zoned,
+    now: &Zoned,
+    after_minutes: u64,
+) -> bool {
+    let idle_s = now.timestamp().as_second() - idle_since.timestamp().as_second();
+    work_hours.contains(now) && idle_s >= after_minutes as i64 * 60
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn work_hours() -> WorkHours {
+        WorkHours {
+            start: "09:00".parse().unwrap(),
+            end: "17:00".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        let work_hours = work_hours();
+        // 2025-01-10 is a Friday
+        assert!(work_hours.contains(&"2025-01-10T10:00:00+00:00[UTC]".parse().unwrap()));
+        assert!(!work_hours.contains(&"2025-01-10T08:59:00+00:00[UTC]".parse().unwrap()));
+        assert!(!work_hours.contains(&"2025-01-10T17:00:00+00:00[UTC]".parse().unwrap()));
+        assert!(!work_hours.contains(&"2025-01-11T10:00:00+00:00[UTC]".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_elapsed_s() {
+        let work_hours = work_hours();
+        assert_eq!(work_hours.elapsed_s(&"2025-01-10T08:00:00+00:00[UTC]".parse().unwrap()), 0);
+        assert_eq!(work_hours.elapsed_s(&"2025-01-10T11:30:00+00:00[UTC]".parse().unwrap()), 9000);
+        assert_eq!(work_hours.elapsed_s(&"2025-01-10T20:00:00+00:00[UTC]".parse().unwrap()), 28800);
+        assert_eq!(work_hours.elapsed_s(&"2025-01-11T11:30:00+00:00[UTC]".parse().unwrap()), 0);
+    }
+
+    #[test]
+    fn test_should_remind() {
+        let work_hours = work_hours();
+        let idle_since = "2025-01-10T10:00:00+00:00[UTC]".parse().unwrap();
+        let soon = "2025-01-10T10:10:00+00:00[UTC]".parse().unwrap();
+        let later = "2025-01-10T10:20:00+00:00[UTC]".parse().unwrap();
+        assert!(!should_remind(&work_hours, &idle_since, &soon, 15));
+        assert!(should_remind(&work_hours, &idle_since, &later, 15));
+    }
+}
@@ -0,0 +1,138 @@
+use std::io::Write;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::{jira::parse_timestamp, statustime, App};
+
+/// How long a finished issue took, from its changelog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowTimes {
+    pub key: String,
+    pub summary: String,
+    /// From creation until it was resolved.
+    pub lead_s: u64,
+    /// From the first status change, usually when work started, until it was resolved.
+    pub cycle_s: Option<u64>,
+}
+
+impl FlowTimes {
+    /// Reads the flow times of an issue fetched with its changelog, `None` while unresolved.
+    pub fn from_issue(issue: &Value) -> Option<FlowTimes> {
+        let fields = &issue["fields"];
+        let created = parse_timestamp(&fields["created"])?;
+        let resolved = parse_timestamp(&fields["resolutiondate"])?;
+        let started = issue["changelog"]["histories"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|history| {
+                history["items"]
+                    .as_array()
+                    .is_some_and(|items| items.iter().any(|item| item["field"] == "status"))
+            })
+            .filter_map(|history| parse_timestamp(&history["created"]))
+            .min();
+        let seconds = |from: jiff::Timestamp| (resolved.as_second() - from.as_second()).max(0) as u64;
+        Some(FlowTimes {
+            key: issue["key"].as_str()?.to_string(),
+            summary: fields["summary"].as_str().unwrap_or_default().to_string(),
+            lead_s: seconds(created),
+            cycle_s: started.map(seconds),
+        })
+    }
+}
+
+/// The nearest-rank percentile of the values, `None` if there are none.
+pub fn percentile(values: &[u64], percent: u64) -> Option<u64> {
+    let mut sorted = values.to_vec();
+    sorted.sort();
+    let rank = (percent as usize * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+impl App {
+    /// Prints the lead and cycle time of the finished issues of a sprint, given as
+    /// `current`, its id or its name.
+    pub fn report_cycle_time(&mut self, sprint: &str, mut output: impl Write) -> Result<()> {
+        let sprint = if sprint == "current" {
+            self.jira.get_active_sprint()?
+        } else {
+            self.jira
+                .get_sprints("active,closed,future")?
+                .into_iter()
+                .find(|candidate| candidate.id.to_string() == sprint || candidate.name == sprint)
+        }
+        .ok_or_else(|| anyhow!("Sprint {sprint} not found"))?;
+
+        let times = self.jira.get_sprint_flow_times(sprint.id)?;
+        if times.is_empty() {
+            writeln!(output, "No finished issues in {}.", sprint.name)?;
+            return Ok(());
+        }
+        writeln!(output, "{}: {} finished issues", sprint.name, times.len())?;
+        writeln!(output)?;
+        writeln!(output, "{:<12} {:<10} {:<10} Summary", "Issue", "Lead", "Cycle")?;
+        for time in &times {
+            writeln!(
+                output,
+                "{:<12} {:<10} {:<10} {}",
+                time.key,
+                statustime::format(time.lead_s),
+                time.cycle_s.map(statustime::format).unwrap_or("-".to_string()),
+                time.summary
+            )?;
+        }
+        writeln!(output)?;
+
+        let lead: Vec<u64> = times.iter().map(|time| time.lead_s).collect();
+        let cycle: Vec<u64> = times.iter().filter_map(|time| time.cycle_s).collect();
+        writeln!(output, "{:<12} {:<10} Cycle", "Percentile", "Lead")?;
+        for percent in [50, 85, 95] {
+            let format = |values: &[u64]| {
+                percentile(values, percent).map(statustime::format).unwrap_or("-".to_string())
+            };
+            writeln!(output, "{:<12} {:<10} {}", format!("p{percent}"), format(&lead), format(&cycle))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_from_issue() {
+        let issue = json!({
+            "key": "IMG-1",
+            "fields": {
+                "summary": "Crop images",
+                "created": "2025-01-01T09:00:00.000+0000",
+                "resolutiondate": "2025-01-03T09:00:00.000+0000"
+            },
+            "changelog": {"histories": [
+                {"created": "2025-01-02T12:00:00.000+0000", "items": [{"field": "status"}]},
+                {"created": "2025-01-01T10:00:00.000+0000", "items": [{"field": "assignee"}]},
+                {"created": "2025-01-02T09:00:00.000+0000", "items": [{"field": "status"}]}
+            ]}
+        });
+        let times = FlowTimes::from_issue(&issue).unwrap();
+        assert_eq!(times.lead_s, 48 * 3600);
+        assert_eq!(times.cycle_s, Some(24 * 3600));
+
+        let unresolved = json!({"key": "IMG-2", "fields": {"created": "2025-01-01T09:00:00.000+0000"}});
+        assert_eq!(FlowTimes::from_issue(&unresolved), None);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let values = [5, 1, 4, 2, 3];
+        assert_eq!(percentile(&values, 50), Some(3));
+        assert_eq!(percentile(&values, 85), Some(5));
+        assert_eq!(percentile(&values, 0), Some(1));
+        assert_eq!(percentile(&[], 50), None);
+    }
+}
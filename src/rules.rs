@@ -0,0 +1,200 @@
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{jira::Issue, template};
+
+/// What happened to an issue that rules can react to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// The timer was started on the issue.
+    Activation,
+    /// Time was logged to the issue.
+    Submission,
+    /// The issue moved to another status.
+    StatusChange,
+}
+
+/// An automation rule from the config: when it applies and what it then does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub on: Trigger,
+    /// Only issues of this project.
+    pub project: Option<String>,
+    /// Only issues moved to or, for other triggers, in this status.
+    pub status: Option<String>,
+    /// Status to move the issue to.
+    pub transition: Option<String>,
+    /// Assign the issue to the current user.
+    #[serde(default)]
+    pub assign: bool,
+    pub comment: Option<String>,
+    /// Shell command to run.
+    pub command: Option<String>,
+    /// Offer to start the timer on the issue.
+    #[serde(default)]
+    pub offer_timer: bool,
+    /// Only write what the rule would do to the dry-run log.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// The issue a rule is evaluated for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuleEvent<'a> {
+    pub trigger: Trigger,
+    pub issue_key: &'a str,
+    /// The status of the issue, the new one for status changes, if known.
+    pub status: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleAction {
+    Transition(String),
+    Assign,
+    Comment(String),
+    Command(String),
+    OfferTimer,
+}
+
+impl RuleAction {
+    pub fn describe(&self, issue_key: &str) -> String {
+        match self {
+            RuleAction::Transition(status) => format!("move {issue_key} to {status}"),
+            RuleAction::Assign => format!("assign {issue_key} to me"),
+            RuleAction::Comment(text) => format!("comment on {issue_key}: {text}"),
+            RuleAction::Command(command) => format!("run for {issue_key}: {command}"),
+            RuleAction::OfferTimer => format!("offer to start the timer on {issue_key}"),
+        }
+    }
+}
+
+impl Rule {
+    pub fn matches(&self, event: &RuleEvent) -> bool {
+        let project = event.issue_key.split('-').next().unwrap_or("");
+        self.on == event.trigger
+            && self.project.as_ref().is_none_or(|expected| expected == project)
+            && self
+                .status
+                .as_ref()
+                .is_none_or(|expected| event.status.is_some_and(|status| status.eq_ignore_ascii_case(expected)))
+    }
+
+    /// The actions in the order they are run, with `{key}` and `{status}` filled in
+    /// the comment and the command.
+    pub fn actions(&self, event: &RuleEvent) -> Vec<RuleAction> {
+        let values = [("key", event.issue_key), ("status", event.status.unwrap_or(""))];
+        let mut actions = vec![];
+        if let Some(status) = &self.transition {
+            actions.push(RuleAction::Transition(status.clone()));
+        }
+        if self.assign {
+            actions.push(RuleAction::Assign);
+        }
+        if let Some(comment) = &self.comment {
+            actions.push(RuleAction::Comment(template::render(comment, &values)));
+        }
+        if let Some(command) = &self.command {
+            actions.push(RuleAction::Command(template::render(command, &values)));
+        }
+        if self.offer_timer {
+            actions.push(RuleAction::OfferTimer);
+        }
+        actions
+    }
+}
+
+/// The actions of all rules matching the event, each with whether its rule is a dry run.
+pub fn evaluate(rules: &[Rule], event: &RuleEvent) -> Vec<(RuleAction, bool)> {
+    rules
+        .iter()
+        .filter(|rule| rule.matches(event))
+        .flat_map(|rule| rule.actions(event).into_iter().map(|action| (action, rule.dry_run)))
+        .collect()
+}
+
+/// Issues whose status differs from the one they had before, with the new status.
+pub fn status_changes<'a>(before: &[Issue], after: &'a [Issue]) -> Vec<(&'a str, &'a str)> {
+    after
+        .iter()
+        .filter(|issue| {
+            before
+                .iter()
+                .any(|previous| previous.key == issue.key && previous.status != issue.status)
+        })
+        .map(|issue| (issue.key.as_str(), issue.status.as_str()))
+        .collect()
+}
+
+pub fn run_command(command: &str) -> Result<()> {
+    let status = Command::new("sh").arg("-c").arg(command).status()?;
+    if !status.success() {
+        return Err(anyhow!("\"{command}\" failed with {status}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Rules {
+        rules: Vec<Rule>,
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let Rules { rules } = toml::from_str(
+            r#"
+            [[rules]]
+            on = "activation"
+            project = "IMG"
+            transition = "In Progress"
+            assign = true
+
+            [[rules]]
+            on = "status_change"
+            status = "in review"
+            comment = "Ready for review: {key}"
+            command = "notify-send {key} {status}"
+            dry_run = true
+            "#,
+        )
+        .unwrap();
+
+        let activation = |issue_key| RuleEvent {
+            trigger: Trigger::Activation,
+            issue_key,
+            status: None,
+        };
+        assert_eq!(
+            evaluate(&rules, &activation("IMG-1")),
+            [
+                (RuleAction::Transition("In Progress".to_string()), false),
+                (RuleAction::Assign, false)
+            ]
+        );
+        assert!(evaluate(&rules, &activation("OPS-1")).is_empty());
+
+        let moved = RuleEvent {
+            trigger: Trigger::StatusChange,
+            issue_key: "OPS-1",
+            status: Some("In Review"),
+        };
+        assert_eq!(
+            evaluate(&rules, &moved),
+            [
+                (RuleAction::Comment("Ready for review: OPS-1".to_string()), true),
+                (RuleAction::Command("notify-send OPS-1 In Review".to_string()), true)
+            ]
+        );
+        let moved_back = RuleEvent {
+            status: Some("To Do"),
+            ..moved
+        };
+        assert!(evaluate(&rules, &moved_back).is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
+
+use dirs::home_dir;
+use jiff::Timestamp;
+use serde_json::Value;
+
+/// What a field of a Jira response holds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    String,
+    Number,
+    Object,
+    Array,
+}
+
+impl Kind {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Kind::String => value.is_string(),
+            Kind::Number => value.is_number(),
+            Kind::Object => value.is_object(),
+            Kind::Array => value.is_array(),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Kind::String => "a string",
+            Kind::Number => "a number",
+            Kind::Object => "an object",
+            Kind::Array => "an array",
+        })
+    }
+}
+
+/// The fields jiratrack reads from a kind of response, by dotted path.
+#[derive(Debug)]
+pub struct Shape {
+    pub name: &'static str,
+    pub fields: &'static [(&'static str, Kind)],
+}
+
+/// Issues as listed and fetched, subtasks included.
+pub const ISSUE: Shape = Shape {
+    name: "issue",
+    fields: &[
+        ("id", Kind::String),
+        ("key", Kind::String),
+        ("fields", Kind::Object),
+        ("fields.summary", Kind::String),
+        ("fields.status", Kind::Object),
+        ("fields.status.name", Kind::String),
+        ("fields.status.statusCategory.key", Kind::String),
+    ],
+};
+
+pub const USER: Shape = Shape {
+    name: "user",
+    fields: &[("accountId", Kind::String), ("displayName", Kind::String)],
+};
+
+pub const SPRINT: Shape = Shape {
+    name: "sprint",
+    fields: &[("id", Kind::Number), ("name", Kind::String)],
+};
+
+pub const WORKLOG: Shape = Shape {
+    name: "worklog",
+    fields: &[
+        ("started", Kind::String),
+        ("timeSpentSeconds", Kind::Number),
+        ("author.accountId", Kind::String),
+    ],
+};
+
+/// A field of a response that isn't what jiratrack reads it as.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    pub shape: &'static str,
+    pub path: String,
+    pub problem: Problem,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// Not in the response, with the path of a similar field that is, if any.
+    Missing { renamed_to: Option<String> },
+    WrongKind { expected: Kind, found: &'static str },
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.problem {
+            Problem::Missing { renamed_to: Some(renamed_to) } => {
+                write!(f, "{}: {} is missing, renamed to {renamed_to}?", self.shape, self.path)
+            }
+            Problem::Missing { renamed_to: None } => write!(f, "{}: {} is missing", self.shape, self.path),
+            Problem::WrongKind { expected, found } => {
+                write!(f, "{}: {} is {found} instead of {expected}", self.shape, self.path)
+            }
+        }
+    }
+}
+
+fn kind_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// The fields of the response that differ from the shape. A missing object is
+/// reported once, not again for each field in it.
+pub fn check(shape: &Shape, value: &Value) -> Vec<Drift> {
+    let mut drifts: Vec<Drift> = vec![];
+    for (path, kind) in shape.fields {
+        let mut current = value;
+        let mut walked = vec![];
+        for segment in path.split('.') {
+            walked.push(segment);
+            let parent = current;
+            current = &parent[segment];
+            let at = walked.join(".");
+            if drifts.iter().any(|drift| drift.path == at) {
+                break;
+            }
+            if current.is_null() {
+                let renamed_to = parent.as_object().and_then(|object| {
+                    let similar = object.keys().find(|key| similar(key, segment))?;
+                    walked.pop();
+                    walked.push(similar);
+                    Some(walked.join("."))
+                });
+                drifts.push(Drift {
+                    shape: shape.name,
+                    path: at,
+                    problem: Problem::Missing { renamed_to },
+                });
+                break;
+            }
+        }
+        if !current.is_null() && !kind.matches(current) {
+            drifts.push(Drift {
+                shape: shape.name,
+                path: path.to_string(),
+                problem: Problem::WrongKind {
+                    expected: *kind,
+                    found: kind_of(current),
+                },
+            });
+        }
+    }
+    drifts
+}
+
+/// Whether a field looks like a renamed one, like `status_name` for `statusName` or
+/// `summery` for `summary`.
+fn similar(key: &str, expected: &str) -> bool {
+    let normalize = |name: &str| name.replace(['_', '-'], "").to_lowercase();
+    let (key, expected) = (normalize(key), normalize(expected));
+    key == expected || (expected.len() > 3 && edit_distance(&key, &expected) <= 2)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (diagonal + usize::from(a_char != *b_char)).min(row[j] + 1).min(above + 1);
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+pub fn get_path() -> PathBuf {
+    home_dir().unwrap().join(".local/share/jiratrack/schema.log")
+}
+
+/// Drifts seen since the start, each logged once.
+fn seen() -> &'static Mutex<HashSet<String>> {
+    static SEEN: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    SEEN.get_or_init(Default::default)
+}
+
+/// Checks a response against the shape and appends new drifts to the schema log, so
+/// changes to the Jira API show up there instead of as blank columns.
+pub fn validate(shape: &Shape, value: &Value) {
+    for drift in check(shape, value) {
+        let message = drift.to_string();
+        if !seen().lock().unwrap().insert(message.clone()) {
+            continue;
+        }
+        let path = get_path();
+        let _ = fs::create_dir_all(path.parent().unwrap());
+        if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+            let _ = writeln!(file, "{} {message}", Timestamp::now());
+        }
+    }
+}
+
+/// The drifts logged since the start.
+pub fn found() -> Vec<String> {
+    let mut found: Vec<String> = seen().lock().unwrap().iter().cloned().collect();
+    found.sort();
+    found
+}
+
+/// The distinct drifts in the schema log, with when each was last seen, latest last.
+pub fn logged() -> Vec<(String, String)> {
+    let text = fs::read_to_string(get_path()).unwrap_or_default();
+    let mut logged: Vec<(String, String)> = vec![];
+    for (at, message) in text.lines().filter_map(|line| line.split_once(' ')) {
+        logged.retain(|(_, known)| known != message);
+        logged.push((at.to_string(), message.to_string()));
+    }
+    logged
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_check() {
+        let issue = json!({"id": "10001", "key": "IMG-1", "fields": {
+            "summary": "Fix login",
+            "status": {"name": "Open", "statusCategory": {"key": "new"}}
+        }});
+        assert!(check(&ISSUE, &issue).is_empty());
+
+        let issue = json!({"id": 10001, "key": "IMG-1", "fields": {
+            "title": "Fix login",
+            "status_name": "Open"
+        }});
+        let drifts: Vec<String> = check(&ISSUE, &issue).iter().map(Drift::to_string).collect();
+        assert_eq!(
+            drifts,
+            [
+                "issue: id is a number instead of a string",
+                "issue: fields.summary is missing",
+                "issue: fields.status is missing",
+            ]
+        );
+
+        let user = json!({"account_id": "5b10ac8d", "displayName": "Jane Doe"});
+        assert_eq!(
+            check(&USER, &user)[0].problem,
+            Problem::Missing {
+                renamed_to: Some("account_id".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_similar() {
+        assert!(similar("status_name", "statusName"));
+        assert!(similar("summery", "summary"));
+        assert!(!similar("title", "summary"));
+        assert!(!similar("ids", "id"));
+    }
+}
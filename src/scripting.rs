@@ -0,0 +1,201 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+
+use crate::{jira::Issue, rules::RuleAction};
+
+/// Version of the issue map and functions scripts see, returned by `api_version()`.
+/// Raised only when something is removed or changes meaning.
+pub const API_VERSION: i64 = 1;
+
+/// Keeps a runaway script from freezing the interface.
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// A list column filled by a script function taking the issue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptColumn {
+    pub header: String,
+    pub function: String,
+    #[serde(default = "default_width")]
+    pub width: u16,
+}
+
+fn default_width() -> u16 {
+    10
+}
+
+/// An action on the selected issue run by a script function taking the issue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptAction {
+    pub name: String,
+    pub function: String,
+}
+
+/// What a script action asks for in return.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptOutcome {
+    Nothing,
+    /// A string, copied to the clipboard.
+    Copy(String),
+    /// A map with the same keys as the actions of a rule.
+    Actions(Vec<RuleAction>),
+}
+
+/// The user's script, compiled once.
+pub struct Scripts {
+    engine: Engine,
+    ast: AST,
+}
+
+impl std::fmt::Debug for Scripts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scripts").finish_non_exhaustive()
+    }
+}
+
+impl Scripts {
+    pub fn load(path: &Path) -> Result<Scripts> {
+        let source = std::fs::read_to_string(path)?;
+        Scripts::compile(&source)
+    }
+
+    pub fn compile(source: &str) -> Result<Scripts> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.register_fn("api_version", || API_VERSION);
+        let ast = engine.compile(source)?;
+        Ok(Scripts { engine, ast })
+    }
+
+    fn call(&self, function: &str, issue: &Issue) -> Result<Dynamic> {
+        self.engine
+            .call_fn(&mut Scope::new(), &self.ast, function, (issue_map(issue),))
+            .map_err(|error| anyhow!("{function}: {error}"))
+    }
+
+    /// The text of a script column, the error when the function fails.
+    pub fn column(&self, function: &str, issue: &Issue) -> String {
+        match self.call(function, issue) {
+            Ok(value) if value.is_unit() => String::new(),
+            Ok(value) => value.to_string(),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    pub fn action(&self, function: &str, issue: &Issue) -> Result<ScriptOutcome> {
+        let value = self.call(function, issue)?;
+        if value.is_unit() {
+            return Ok(ScriptOutcome::Nothing);
+        }
+        let Some(map) = value.clone().try_cast::<Map>() else {
+            return Ok(ScriptOutcome::Copy(value.to_string()));
+        };
+        let text = |key: &str| map.get(key).filter(|value| !value.is_unit()).map(|value| value.to_string());
+        let mut actions = vec![];
+        if let Some(status) = text("transition") {
+            actions.push(RuleAction::Transition(status));
+        }
+        if map.get("assign").and_then(|assign| assign.as_bool().ok()) == Some(true) {
+            actions.push(RuleAction::Assign);
+        }
+        if let Some(comment) = text("comment") {
+            actions.push(RuleAction::Comment(comment));
+        }
+        if let Some(command) = text("command") {
+            actions.push(RuleAction::Command(command));
+        }
+        Ok(ScriptOutcome::Actions(actions))
+    }
+}
+
+/// The issue as scripts see it.
+fn issue_map(issue: &Issue) -> Map {
+    let strings = |values: &[String]| -> Array { values.iter().cloned().map(Dynamic::from).collect() };
+    let optional = |value: Option<Dynamic>| value.unwrap_or(Dynamic::UNIT);
+    let subtasks: Vec<String> = issue.subtasks.iter().map(|subtask| subtask.key.clone()).collect();
+    let mut map = Map::new();
+    map.insert("key".into(), issue.key.clone().into());
+    map.insert("project".into(), issue.project().to_string().into());
+    map.insert("summary".into(), issue.summary.clone().into());
+    map.insert("status".into(), issue.status.clone().into());
+    map.insert("status_category".into(), issue.status_category.clone().into());
+    map.insert("assignee".into(), issue.assignee.clone().into());
+    map.insert("time_spent".into(), (issue.time_spent as i64).into());
+    map.insert("aggregate_time_spent".into(), (issue.aggregate_time_spent as i64).into());
+    map.insert("estimate".into(), (issue.estimate as i64).into());
+    map.insert("story_points".into(), optional(issue.story_points.map(Dynamic::from)));
+    map.insert("labels".into(), strings(&issue.labels).into());
+    map.insert("components".into(), strings(&issue.components).into());
+    map.insert("epic".into(), optional(issue.epic.clone().map(Dynamic::from)));
+    map.insert("subtasks".into(), strings(&subtasks).into());
+    map
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn issue() -> Issue {
+        Issue {
+            id: "1".to_string(),
+            key: "IMG-1".to_string(),
+            summary: "Fix login".to_string(),
+            time_spent: 5400,
+            estimate: 7200,
+            assignee: "Sam".to_string(),
+            status: "In Progress".to_string(),
+            status_category: "indeterminate".to_string(),
+            aggregate_time_spent: 5400,
+            labels: vec!["ui".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_column() {
+        let scripts = Scripts::compile(
+            r#"
+            fn left(issue) { `${(issue.estimate - issue.time_spent) / 60}m` }
+            fn points(issue) { issue.story_points }
+            fn tags(issue) { issue.labels.len() + api_version() }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(scripts.column("left", &issue()), "30m");
+        assert_eq!(scripts.column("points", &issue()), "");
+        assert_eq!(scripts.column("tags", &issue()), "2");
+        assert!(scripts.column("missing", &issue()).starts_with("missing: "));
+    }
+
+    #[test]
+    fn test_action() {
+        let scripts = Scripts::compile(
+            r#"
+            fn branch(issue) {
+                let name = issue.summary.to_lower();
+                name.replace(" ", "-");
+                `${issue.key}-${name}`
+            }
+            fn review(issue) { #{ transition: "In Review", comment: `Please review ${issue.key}` } }
+            fn nothing(issue) { }
+            fn forever(issue) { loop { } }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            scripts.action("branch", &issue()).unwrap(),
+            ScriptOutcome::Copy("IMG-1-fix-login".to_string())
+        );
+        assert_eq!(
+            scripts.action("review", &issue()).unwrap(),
+            ScriptOutcome::Actions(vec![
+                RuleAction::Transition("In Review".to_string()),
+                RuleAction::Comment("Please review IMG-1".to_string()),
+            ])
+        );
+        assert_eq!(scripts.action("nothing", &issue()).unwrap(), ScriptOutcome::Nothing);
+        assert!(scripts.action("forever", &issue()).is_err());
+    }
+}
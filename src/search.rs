@@ -0,0 +1,414 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::OnceLock,
+    thread,
+    time::{Duration, Instant},
+};
+
+use fancy_regex::Regex;
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+
+use crate::{
+    facets::{Facet, FacetFilter},
+    jira::Issue,
+};
+
+/// Issue count above which typing is debounced instead of searching on every key.
+const DEBOUNCE_THRESHOLD: usize = 1000;
+pub const DEBOUNCE: Duration = Duration::from_millis(100);
+/// Issue count above which matching is spread over multiple threads.
+const PARALLEL_THRESHOLD: usize = 5000;
+
+/// Skim's matcher, which favors matches at word boundaries and consecutive
+/// characters, and only matches case when the query has uppercase letters.
+fn matcher() -> &'static SkimMatcherV2 {
+    static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+    MATCHER.get_or_init(|| SkimMatcherV2::default().smart_case())
+}
+
+/// How the query is matched against the summaries.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SearchMode {
+    #[default]
+    Fuzzy,
+    /// Case-insensitive substring.
+    Exact,
+    Regex,
+}
+
+impl SearchMode {
+    pub fn next(self) -> SearchMode {
+        match self {
+            SearchMode::Fuzzy => SearchMode::Exact,
+            SearchMode::Exact => SearchMode::Regex,
+            SearchMode::Regex => SearchMode::Fuzzy,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SearchMode::Fuzzy => "fuzzy",
+            SearchMode::Exact => "exact",
+            SearchMode::Regex => "regex",
+        }
+    }
+
+    /// The mode of the query, which an `ex:` or `re:` prefix overrides, and the query
+    /// without the prefix.
+    pub fn of_query(self, query: &str) -> (SearchMode, &str) {
+        if let Some(pattern) = query.strip_prefix("re:") {
+            (SearchMode::Regex, pattern)
+        } else if let Some(pattern) = query.strip_prefix("ex:") {
+            (SearchMode::Exact, pattern)
+        } else {
+            (self, query)
+        }
+    }
+}
+
+/// A query prepared for matching in one of the search modes.
+enum Pattern {
+    Fuzzy(String),
+    Exact(String),
+    /// `None` while the regex is invalid, e.g. half typed, which matches nothing.
+    Regex(Option<Regex>),
+}
+
+impl Pattern {
+    fn new(mode: SearchMode, query: &str) -> Pattern {
+        let (mode, pattern) = mode.of_query(query);
+        match mode {
+            SearchMode::Fuzzy => Pattern::Fuzzy(pattern.to_string()),
+            SearchMode::Exact => Pattern::Exact(pattern.to_lowercase()),
+            SearchMode::Regex => Pattern::Regex(Regex::new(pattern).ok()),
+        }
+    }
+
+    /// Higher is better, `None` if the text doesn't match.
+    fn score(&self, text: &str) -> Option<i64> {
+        match self {
+            Pattern::Fuzzy(pattern) => matcher().fuzzy_match(text, pattern),
+            Pattern::Exact(pattern) => text.to_lowercase().contains(pattern.as_str()).then_some(0),
+            Pattern::Regex(regex) => regex.as_ref()?.is_match(text).ok()?.then_some(0),
+        }
+    }
+
+    /// Indices of the matched characters in the text.
+    fn indices(&self, text: &str) -> Vec<usize> {
+        let (start, end) = match self {
+            Pattern::Fuzzy(pattern) if pattern.is_empty() => return vec![],
+            Pattern::Fuzzy(pattern) => {
+                return matcher()
+                    .fuzzy_indices(text, pattern)
+                    .map(|(_, indices)| indices)
+                    .unwrap_or_default()
+            }
+            Pattern::Exact(pattern) if pattern.is_empty() => return vec![],
+            Pattern::Exact(pattern) => {
+                let lowercase = text.to_lowercase();
+                let Some(start) = lowercase.find(pattern.as_str()) else {
+                    return vec![];
+                };
+                let start = lowercase[..start].chars().count();
+                (start, start + pattern.chars().count())
+            }
+            Pattern::Regex(regex) => match regex.as_ref().and_then(|regex| regex.find(text).ok().flatten()) {
+                Some(found) => {
+                    let start = text[..found.start()].chars().count();
+                    (start, start + found.as_str().chars().count())
+                }
+                None => return vec![],
+            },
+        };
+        (start..end).collect()
+    }
+}
+
+/// Fuzzy search over the issue list, keeping the matches between draws.
+#[derive(Debug, Default)]
+pub struct IssueSearch {
+    query: String,
+    mode: SearchMode,
+    project: Option<String>,
+    fingerprint: u64,
+    /// Account whose issues are listed first, and whether only they are listed.
+    owner: Option<String>,
+    only_owned: bool,
+    facets: FacetFilter,
+    /// Keys of the snoozed issues, which aren't listed.
+    hidden: Vec<String>,
+    /// Indices into the issue list, best match first.
+    matches: Vec<usize>,
+    typed_on: Option<Instant>,
+}
+
+impl IssueSearch {
+    pub fn matches(&self) -> &[usize] {
+        &self.matches
+    }
+
+    pub fn mode(&self) -> SearchMode {
+        self.mode
+    }
+
+    /// Switches to the next search mode, searching again on the next update.
+    pub fn cycle_mode(&mut self) {
+        self.mode = self.mode.next();
+        self.fingerprint = 0;
+    }
+
+    /// Lists the issues assigned to the account first, or only those with `only`.
+    pub fn set_owner(&mut self, account_id: Option<String>, only: bool) {
+        self.owner = account_id;
+        self.only_owned = only;
+    }
+
+    /// Hides the issues with the keys, searching again on the next update if they changed.
+    pub fn set_hidden(&mut self, keys: Vec<String>) {
+        if keys != self.hidden {
+            self.hidden = keys;
+            self.fingerprint = 0;
+        }
+    }
+
+    /// Values selected in the filter menu.
+    pub fn facets(&self) -> &FacetFilter {
+        &self.facets
+    }
+
+    /// Toggles a value in the filter menu, searching again on the next update.
+    pub fn toggle_facet(&mut self, facet: Facet, value: &str) {
+        self.facets.toggle(facet, value);
+        self.fingerprint = 0;
+    }
+
+    pub fn clear_facets(&mut self) {
+        self.facets = FacetFilter::default();
+        self.fingerprint = 0;
+    }
+
+    fn is_owned(&self, issue: &Issue) -> bool {
+        self.owner.is_some() && issue.assignee_id == self.owner
+    }
+
+    /// The text split into runs of characters matching the query (`true`) or not,
+    /// to show why an issue matched.
+    pub fn highlight(&self, text: &str) -> Vec<(String, bool)> {
+        let indices = Pattern::new(self.mode, &self.query).indices(text);
+        if indices.is_empty() {
+            return vec![(text.to_string(), false)];
+        }
+        let mut segments: Vec<(String, bool)> = vec![];
+        for (index, char) in text.chars().enumerate() {
+            let matched = indices.binary_search(&index).is_ok();
+            match segments.last_mut() {
+                Some((segment, segment_matched)) if *segment_matched == matched => segment.push(char),
+                _ => segments.push((char.to_string(), matched)),
+            }
+        }
+        segments
+    }
+
+    /// Whether a query change is waiting for the debounce delay to pass.
+    pub fn is_pending(&self) -> bool {
+        self.typed_on.is_some()
+    }
+
+    /// Recomputes the matches if the query, project filter or issues changed.
+    pub fn update(&mut self, issues: &[Issue], query: &str, project: Option<&str>) {
+        let fingerprint = fingerprint(issues, self.owner.as_deref(), self.only_owned);
+        let issues_changed = fingerprint != self.fingerprint || project != self.project.as_deref();
+        if !issues_changed && query == self.query {
+            self.typed_on = None;
+            return;
+        }
+        if !issues_changed && issues.len() > DEBOUNCE_THRESHOLD {
+            let typed_on = *self.typed_on.get_or_insert_with(Instant::now);
+            if typed_on.elapsed() < DEBOUNCE {
+                return;
+            }
+        }
+
+        // Appending to the query can only narrow down the previous matches, except
+        // for regexes or when a prefix switches the mode.
+        let (mode, _) = self.mode.of_query(query);
+        let narrows = mode != SearchMode::Regex && mode == self.mode.of_query(&self.query).0;
+        let candidates: Vec<usize> =
+            if !issues_changed && narrows && !self.query.is_empty() && query.starts_with(&self.query) {
+                std::mem::take(&mut self.matches)
+            } else {
+                (0..issues.len())
+                    .filter(|index| project.is_none_or(|project| issues[*index].project() == project))
+                    .filter(|index| !self.only_owned || self.is_owned(&issues[*index]))
+                    .filter(|index| self.facets.matches(&issues[*index]))
+                    .filter(|index| !self.hidden.contains(&issues[*index].key))
+                    .collect()
+            };
+        let mut scored = score(issues, &candidates, &Pattern::new(self.mode, query));
+        scored.sort_by_key(|(index, score)| (!self.is_owned(&issues[*index]), -*score, *index));
+        self.matches = scored.into_iter().map(|(index, _)| index).collect();
+        self.query = query.to_string();
+        self.project = project.map(|project| project.to_string());
+        self.fingerprint = fingerprint;
+        self.typed_on = None;
+    }
+}
+
+fn score(issues: &[Issue], candidates: &[usize], pattern: &Pattern) -> Vec<(usize, i64)> {
+    let score_chunk = |chunk: &[usize]| -> Vec<(usize, i64)> {
+        chunk
+            .iter()
+            .filter_map(|index| Some((*index, pattern.score(&issues[*index].summary)?)))
+            .collect()
+    };
+    if candidates.len() < PARALLEL_THRESHOLD {
+        return score_chunk(candidates);
+    }
+    let threads = thread::available_parallelism().map_or(4, |threads| threads.get());
+    let chunk_size = candidates.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let handles: Vec<_> = candidates
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || score_chunk(chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+/// Cheap hash of the searchable content and order of the issues, and of what
+/// decides which issues are listed first.
+fn fingerprint(issues: &[Issue], owner: Option<&str>, only_owned: bool) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for issue in issues {
+        issue.key.hash(&mut hasher);
+        issue.summary.hash(&mut hasher);
+        issue.assignee_id.hash(&mut hasher);
+    }
+    owner.hash(&mut hasher);
+    only_owned.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn issue(key: &str, summary: &str) -> Issue {
+        Issue {
+            id: key.to_string(),
+            key: key.to_string(),
+            summary: summary.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let mut issues = vec![
+            issue("CLI-1", "Fix login"),
+            issue("OPS-2", "Fix logout"),
+            issue("CLI-3", "Add logging"),
+        ];
+        let mut search = IssueSearch::default();
+        search.update(&issues, "", None);
+        assert_eq!(search.matches(), [0, 1, 2]);
+        search.update(&issues, "log", None);
+        assert_eq!(search.matches().len(), 3);
+        search.update(&issues, "logout", None);
+        assert_eq!(search.matches(), [1]);
+        search.update(&issues, "log", Some("CLI"));
+        assert_eq!(search.matches().len(), 2);
+
+        issues.push(issue("CLI-4", "Logout button"));
+        search.update(&issues, "logout", Some("CLI"));
+        assert_eq!(search.matches(), [3]);
+    }
+
+    #[test]
+    fn test_owner() {
+        let mut issues = vec![issue("CLI-1", "Fix login"), issue("CLI-2", "Fix logout")];
+        issues[1].assignee_id = Some("me".to_string());
+        let mut search = IssueSearch::default();
+        search.update(&issues, "", None);
+        assert_eq!(search.matches(), [0, 1]);
+        search.set_owner(Some("me".to_string()), false);
+        search.update(&issues, "", None);
+        assert_eq!(search.matches(), [1, 0]);
+        search.set_owner(Some("me".to_string()), true);
+        search.update(&issues, "", None);
+        assert_eq!(search.matches(), [1]);
+    }
+
+    #[test]
+    fn test_hidden() {
+        let issues = vec![issue("CLI-1", "Fix login"), issue("CLI-2", "Fix logout")];
+        let mut search = IssueSearch::default();
+        search.set_hidden(vec!["CLI-1".to_string()]);
+        search.update(&issues, "", None);
+        assert_eq!(search.matches(), [1]);
+        search.set_hidden(vec![]);
+        search.update(&issues, "", None);
+        assert_eq!(search.matches(), [0, 1]);
+    }
+
+    #[test]
+    fn test_word_boundaries_and_case() {
+        let issues = vec![issue("CLI-1", "Unlock button"), issue("CLI-2", "Fix login button")];
+        let mut search = IssueSearch::default();
+        search.update(&issues, "lo", None);
+        assert_eq!(search.matches(), [1, 0]);
+        search.update(&issues, "Lo", None);
+        assert!(search.matches().is_empty());
+    }
+
+    #[test]
+    fn test_modes() {
+        let issues = vec![
+            issue("CLI-1", "Fix login on Android"),
+            issue("CLI-2", "Add logging"),
+            issue("CLI-3", "Flaky sign in"),
+        ];
+        let mut search = IssueSearch::default();
+        search.update(&issues, "fi", None);
+        assert_eq!(search.matches().len(), 2);
+        search.cycle_mode();
+        search.update(&issues, "fi", None);
+        assert_eq!(search.matches(), [0]);
+        search.update(&issues, "re:^(Fix|Add) log", None);
+        assert_eq!(search.matches(), [0, 1]);
+        search.update(&issues, "re:(", None);
+        assert!(search.matches().is_empty());
+        search.update(&issues, "ex:LOG", None);
+        assert_eq!(search.matches(), [0, 1]);
+        assert_eq!(search.highlight("Add logging")[1], ("log".to_string(), true));
+    }
+
+    #[test]
+    fn test_highlight() {
+        let mut search = IssueSearch::default();
+        assert_eq!(search.highlight("Fix login"), vec![("Fix login".to_string(), false)]);
+        search.update(&[issue("CLI-1", "Fix login")], "fl", None);
+        assert_eq!(
+            search.highlight("Fix login"),
+            vec![
+                ("F".to_string(), true),
+                ("ix ".to_string(), false),
+                ("l".to_string(), true),
+                ("ogin".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parallel_score() {
+        let issues: Vec<Issue> = (0..PARALLEL_THRESHOLD + 10)
+            .map(|i| issue(&format!("CLI-{i}"), if i % 2 == 0 { "even" } else { "odd" }))
+            .collect();
+        let candidates: Vec<usize> = (0..issues.len()).collect();
+        let pattern = Pattern::new(SearchMode::Fuzzy, "odd");
+        assert_eq!(score(&issues, &candidates, &pattern).len(), issues.len() / 2);
+    }
+}
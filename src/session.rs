@@ -0,0 +1,189 @@
+use jiff::{ToSpan, Unit, Zoned};
+use serde::{Deserialize, Serialize};
+
+/// Jira refuses worklogs shorter than a minute.
+pub const MIN_WORKLOG_S: u64 = 60;
+
+/// A block of tracked time on an issue that has not been submitted yet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub issue_key: String,
+    pub started: Zoned,
+    pub duration_s: u64,
+    /// Worklog comment, e.g. the laps taken during the session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Billing account the worklog is booked on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+impl Session {
+    pub fn new(issue_key: &str, started: &Zoned, ended: &Zoned) -> Session {
+        let duration_s = (ended - started).total(Unit::Second).unwrap().floor().max(0.0);
+        Session {
+            issue_key: issue_key.to_string(),
+            started: started.clone(),
+            duration_s: duration_s as u64,
+            comment: None,
+            account: None,
+        }
+    }
+
+    pub fn ended(&self) -> Zoned {
+        &self.started + (self.duration_s as i64).seconds()
+    }
+
+    /// Whether the session is long enough to be logged.
+    pub fn is_loggable(&self) -> bool {
+        self.duration_s >= MIN_WORKLOG_S
+    }
+
+    /// Combines two sessions on the same issue, keeping the earliest start.
+    pub fn merge(&self, other: &Session) -> Session {
+        Session {
+            issue_key: self.issue_key.clone(),
+            started: self.started.clone().min(other.started.clone()),
+            duration_s: self.duration_s + other.duration_s,
+            comment: match (&self.comment, &other.comment) {
+                (Some(comment), Some(other)) => Some(format!("{comment}\n{other}")),
+                (comment, other) => comment.clone().or(other.clone()),
+            },
+            account: self.account.clone().or(other.account.clone()),
+        }
+    }
+
+    /// Rounds the duration to the nearest multiple of `minutes`, never below one block.
+    pub fn round(&mut self, minutes: u64) {
+        if minutes == 0 {
+            return;
+        }
+        let block_s = minutes * 60;
+        let blocks = ((self.duration_s + block_s / 2) / block_s).max(1);
+        self.duration_s = blocks * block_s;
+    }
+
+    /// Whether `next` started at most `window_s` seconds after this session ended.
+    pub fn is_followed_by(&self, next: &Session, window_s: u64) -> bool {
+        if self.issue_key != next.issue_key {
+            return false;
+        }
+        let gap_s = (&next.started - &self.ended()).total(Unit::Second).unwrap();
+        gap_s >= 0.0 && gap_s <= window_s as f64
+    }
+
+    /// Splits the session into consecutive sessions of at most `max_s` seconds,
+    /// the comment staying with the first.
+    pub fn split(&self, max_s: u64) -> Vec<Session> {
+        if max_s == 0 {
+            return vec![self.clone()];
+        }
+        let mut sessions = vec![];
+        let mut started = self.started.clone();
+        let mut remaining_s = self.duration_s;
+        while remaining_s > 0 {
+            let duration_s = remaining_s.min(max_s);
+            let session = Session {
+                issue_key: self.issue_key.clone(),
+                started: started.clone(),
+                duration_s,
+                comment: if sessions.is_empty() { self.comment.clone() } else { None },
+                account: self.account.clone(),
+            };
+            started = session.ended();
+            remaining_s -= duration_s;
+            sessions.push(session);
+        }
+        sessions
+    }
+
+    pub fn adjust(&mut self, delta_s: i64) {
+        self.duration_s = (self.duration_s as i64 + delta_s).max(0) as u64;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session(key: &str, started: &str, duration_s: u64) -> Session {
+        Session {
+            issue_key: key.to_string(),
+            started: started.parse().unwrap(),
+            duration_s,
+            comment: None,
+            account: None,
+        }
+    }
+
+    #[test]
+    fn test_new_and_ended() {
+        let started: Zoned = "2025-01-10T09:00:00+01:00[Europe/Brussels]".parse().unwrap();
+        let ended: Zoned = "2025-01-10T10:30:00+01:00[Europe/Brussels]".parse().unwrap();
+        let session = Session::new("IMG-1", &started, &ended);
+        assert_eq!(session.duration_s, 5400);
+        assert_eq!(session.ended(), ended);
+        assert!(session.is_loggable());
+        assert!(!Session::new("IMG-1", &started, &(&started + 59.seconds())).is_loggable());
+    }
+
+    #[test]
+    fn test_merge() {
+        let first = session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 600);
+        let second = session("IMG-1", "2025-01-10T08:00:00+00:00[UTC]", 420);
+        let merged = second.merge(&first);
+        assert_eq!(merged, session("IMG-1", "2025-01-10T08:00:00+00:00[UTC]", 1020));
+
+        let billed = Session {
+            account: Some("ACME".to_string()),
+            ..first.clone()
+        };
+        assert_eq!(second.merge(&billed).account.as_deref(), Some("ACME"));
+    }
+
+    #[test]
+    fn test_round() {
+        let mut short = session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 120);
+        short.round(15);
+        assert_eq!(short.duration_s, 900);
+
+        let mut long = session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 22 * 60 + 40);
+        long.round(15);
+        assert_eq!(long.duration_s, 1800);
+
+        let mut exact = session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 3600);
+        exact.round(15);
+        assert_eq!(exact.duration_s, 3600);
+    }
+
+    #[test]
+    fn test_is_followed_by() {
+        let first = session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 600);
+        let close = session("IMG-1", "2025-01-10T09:15:00+00:00[UTC]", 600);
+        let far = session("IMG-1", "2025-01-10T11:00:00+00:00[UTC]", 600);
+        let other = session("IMG-2", "2025-01-10T09:15:00+00:00[UTC]", 600);
+        assert!(first.is_followed_by(&close, 10 * 60));
+        assert!(!first.is_followed_by(&close, 60));
+        assert!(!first.is_followed_by(&far, 10 * 60));
+        assert!(!first.is_followed_by(&other, 10 * 60));
+    }
+
+    #[test]
+    fn test_split() {
+        let long = session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 20 * 3600);
+        let sessions = long.split(8 * 3600);
+        let durations: Vec<u64> = sessions.iter().map(|s| s.duration_s).collect();
+        assert_eq!(durations, [8 * 3600, 8 * 3600, 4 * 3600]);
+        assert_eq!(sessions[1].started, sessions[0].ended());
+        assert_eq!(sessions[2].ended(), long.ended());
+    }
+
+    #[test]
+    fn test_adjust() {
+        let mut session = session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 120);
+        session.adjust(300);
+        assert_eq!(session.duration_s, 420);
+        session.adjust(-1000);
+        assert_eq!(session.duration_s, 0);
+    }
+}
@@ -0,0 +1,135 @@
+use std::io::Write;
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+
+use crate::{
+    duration::WorkTime,
+    jira::{Issue, Sprint},
+    App,
+};
+
+/// Sprint and its issues as JSON, with times in seconds.
+pub fn json(sprint: &Sprint, issues: &[Issue]) -> Value {
+    let issues: Vec<Value> = issues
+        .iter()
+        .map(|issue| {
+            json!({
+                "key": issue.key,
+                "summary": issue.summary,
+                "status": issue.status,
+                "assignee": issue.assignee,
+                "time_spent_s": issue.time_spent,
+                "estimate_s": issue.estimate,
+                "story_points": issue.story_points,
+            })
+        })
+        .collect();
+    json!({
+        "sprint": {
+            "id": sprint.id,
+            "name": sprint.name,
+            "goal": sprint.goal,
+            "end_date": sprint.end_date.map(|end_date| end_date.to_string()),
+        },
+        "issues": issues,
+    })
+}
+
+/// Sprint and its issues as a Markdown table, for sprint review documents.
+pub fn markdown(sprint: &Sprint, issues: &[Issue], work_time: &WorkTime) -> String {
+    let mut lines = vec![format!("## {}", escape(&sprint.name))];
+    if !sprint.goal.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("Goal: {}", escape(&sprint.goal)));
+    }
+    lines.push(String::new());
+    lines.push("| Issue | Summary | Status | Assignee | Logged | Estimate |".to_string());
+    lines.push("| --- | --- | --- | --- | --- | --- |".to_string());
+    for issue in issues {
+        let estimate = match issue.estimate {
+            0 => "-".to_string(),
+            estimate => work_time.format(estimate),
+        };
+        lines.push(format!(
+            "| {} | {} | {} | {} | {} | {} |",
+            issue.key,
+            escape(&issue.summary),
+            escape(&issue.status),
+            escape(&issue.assignee),
+            work_time.format(issue.time_spent),
+            estimate
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Escapes the characters that would end a table cell or start formatting.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('|', "\\|").replace('*', "\\*").replace('_', "\\_")
+}
+
+impl App {
+    /// Prints the active sprint with all of its issues as `json` or `markdown`.
+    pub fn export_snapshot(&mut self, format: &str, mut output: impl Write) -> Result<()> {
+        let sprint = self
+            .jira
+            .get_active_sprint()?
+            .ok_or_else(|| anyhow!("No active sprint"))?;
+        let issues = self.jira.get_sprint_issues(sprint.id)?;
+        match format {
+            "json" => writeln!(output, "{}", serde_json::to_string_pretty(&json(&sprint, &issues))?)?,
+            "markdown" => write!(output, "{}", markdown(&sprint, &issues, &self.config.work_time()))?,
+            _ => return Err(anyhow!("Unknown format {format}, use json or markdown")),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sprint() -> Sprint {
+        Sprint {
+            id: 7,
+            name: "Sprint 7".to_string(),
+            goal: String::new(),
+            end_date: None,
+        }
+    }
+
+    fn issue() -> Issue {
+        Issue {
+            id: "1".to_string(),
+            key: "IMG-1".to_string(),
+            summary: "Crop | resize".to_string(),
+            time_spent: 5400,
+            assignee: "Sam".to_string(),
+            status: "In Review".to_string(),
+            status_category: "indeterminate".to_string(),
+            story_points: Some(3.0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_markdown() {
+        let table = markdown(&sprint(), &[issue()], &WorkTime::default());
+        assert_eq!(
+            table,
+            "## Sprint 7\n\n\
+             | Issue | Summary | Status | Assignee | Logged | Estimate |\n\
+             | --- | --- | --- | --- | --- | --- |\n\
+             | IMG-1 | Crop \\| resize | In Review | Sam | 1h 30m | - |\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let snapshot = json(&sprint(), &[issue()]);
+        assert_eq!(snapshot["sprint"]["name"], "Sprint 7");
+        assert_eq!(snapshot["issues"][0]["time_spent_s"], 5400);
+        assert_eq!(snapshot["issues"][0]["story_points"], 3.0);
+    }
+}
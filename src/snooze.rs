@@ -0,0 +1,155 @@
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+};
+
+use dirs::home_dir;
+use jiff::{civil::Date, ToSpan};
+use serde::{Deserialize, Serialize};
+
+use crate::jira::Issue;
+
+/// An issue hidden from the list until a day or until its status changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snooze {
+    pub issue_key: String,
+    /// The status when it was snoozed, the issue comes back once it has another.
+    pub status: String,
+    /// First day the issue is listed again, `None` to wait for the status change.
+    pub until: Option<Date>,
+}
+
+impl Snooze {
+    /// Why the snooze is over, `None` while it lasts. Issues that aren't loaded keep
+    /// their snooze.
+    pub fn woken_by(&self, issues: &[Issue], today: Date) -> Option<String> {
+        if let Some(issue) = issues.iter().find(|issue| issue.key == self.issue_key) {
+            if issue.status != self.status {
+                return Some(format!("moved to {}", issue.status));
+            }
+        }
+        let until = self.until.filter(|until| *until <= today)?;
+        Some(format!("snoozed until {until}"))
+    }
+}
+
+/// Issues hidden from the list, kept between starts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Snoozes {
+    pub entries: Vec<Snooze>,
+}
+
+impl Snoozes {
+    fn get_path() -> PathBuf {
+        home_dir()
+            .unwrap()
+            .join(".local/share/jiratrack/snoozes.json")
+    }
+
+    pub fn load() -> Snoozes {
+        match File::open(Snoozes::get_path()) {
+            Ok(file) => serde_json::from_reader(file).expect("Invalid snoozes"),
+            Err(_) => Snoozes::default(),
+        }
+    }
+
+    pub fn persist(&self) {
+        let path = Snoozes::get_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = File::create(path).unwrap();
+        serde_json::to_writer(file, self).unwrap();
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.entries.iter().map(|entry| entry.issue_key.clone()).collect()
+    }
+
+    /// Snoozes the issue, replacing an earlier snooze of it.
+    pub fn set(&mut self, issue: &Issue, until: Option<Date>) {
+        self.clear(&issue.key);
+        self.entries.push(Snooze {
+            issue_key: issue.key.clone(),
+            status: issue.status.clone(),
+            until,
+        });
+    }
+
+    pub fn clear(&mut self, issue_key: &str) {
+        self.entries.retain(|entry| entry.issue_key != issue_key);
+    }
+
+    /// Removes the snoozes that are over and returns them with the reason.
+    pub fn take_woken(&mut self, issues: &[Issue], today: Date) -> Vec<(Snooze, String)> {
+        let mut woken = vec![];
+        self.entries.retain(|entry| match entry.woken_by(issues, today) {
+            Some(reason) => {
+                woken.push((entry.clone(), reason));
+                false
+            }
+            None => true,
+        });
+        woken
+    }
+}
+
+/// Parses the first day to list the issue again, like `2025-02-01`, `3d` or `2w`.
+/// Empty input snoozes until the status changes.
+pub fn parse_until(input: &str, today: Date) -> Option<Option<Date>> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Some(None);
+    }
+    if let Some(days) = input.strip_suffix('d') {
+        return today.checked_add(days.parse::<i64>().ok()?.days()).ok().map(Some);
+    }
+    if let Some(weeks) = input.strip_suffix('w') {
+        return today.checked_add(weeks.parse::<i64>().ok()?.weeks()).ok().map(Some);
+    }
+    let date: Date = input.parse().ok()?;
+    (date > today).then_some(Some(date))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn issue(key: &str, status: &str) -> Issue {
+        Issue {
+            key: key.to_string(),
+            status: status.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_until() {
+        let today: Date = "2025-01-10".parse().unwrap();
+        let until = |input: &str| parse_until(input, today).map(|until| until.map(|date| date.to_string()));
+        assert_eq!(until(""), Some(None));
+        assert_eq!(until("3d"), Some(Some("2025-01-13".to_string())));
+        assert_eq!(until("2w"), Some(Some("2025-01-24".to_string())));
+        assert_eq!(until("2025-02-01"), Some(Some("2025-02-01".to_string())));
+        assert_eq!(until("2025-01-01"), None);
+        assert_eq!(until("later"), None);
+    }
+
+    #[test]
+    fn test_take_woken() {
+        let today: Date = "2025-01-10".parse().unwrap();
+        let mut snoozes = Snoozes::default();
+        snoozes.set(&issue("IMG-1", "Blocked"), None);
+        snoozes.set(&issue("IMG-2", "Blocked"), Some("2025-01-11".parse().unwrap()));
+        snoozes.set(&issue("IMG-3", "Blocked"), None);
+
+        let issues = [issue("IMG-1", "Blocked"), issue("IMG-2", "Blocked"), issue("IMG-3", "To Do")];
+        let woken = snoozes.take_woken(&issues, today);
+        assert_eq!(woken.len(), 1);
+        assert_eq!(woken[0].0.issue_key, "IMG-3");
+        assert_eq!(woken[0].1, "moved to To Do");
+        assert_eq!(snoozes.keys(), ["IMG-1", "IMG-2"]);
+
+        let woken = snoozes.take_woken(&issues, "2025-01-11".parse().unwrap());
+        assert_eq!(woken[0].1, "snoozed until 2025-01-11");
+        assert_eq!(snoozes.keys(), ["IMG-1"]);
+    }
+}
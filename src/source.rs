@@ -0,0 +1,122 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    config::Config,
+    duration::WorkTime,
+    jira::{self, Issue, Jira},
+};
+
+/// Where the issue list comes from, selected with `issue_source`. Worklogs and every
+/// other write still go to Jira.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceConfig {
+    /// The configured Jira instances.
+    #[default]
+    Jira,
+    /// A JSON file in the format of a Jira search response.
+    File(PathBuf),
+}
+
+/// A backend feeding the issue list.
+pub trait IssueSource {
+    /// The open issues of the current sprint, narrowed down by the JQL filters.
+    fn sprint_issues(&self, filters: &[&str]) -> Result<Vec<Issue>>;
+
+    /// One page of the issues matching the JQL, with the token of the next page.
+    fn search(&self, jql: &str, page_token: Option<&str>) -> Result<(Vec<Issue>, Option<String>)>;
+}
+
+impl IssueSource for Jira {
+    fn sprint_issues(&self, filters: &[&str]) -> Result<Vec<Issue>> {
+        self.get_filtered_sprint_issues(filters)
+    }
+
+    fn search(&self, jql: &str, page_token: Option<&str>) -> Result<(Vec<Issue>, Option<String>)> {
+        self.get_issues_page(jql, page_token)
+    }
+}
+
+/// Issues read from a file, e.g. a search response saved with curl, to work offline or
+/// try the interface out. The file is read again on every refresh.
+#[derive(Debug)]
+pub struct FileSource {
+    path: PathBuf,
+    story_points_field: Option<String>,
+    work_time: WorkTime,
+}
+
+impl FileSource {
+    pub fn new(path: PathBuf, config: &Config) -> Self {
+        FileSource {
+            path,
+            story_points_field: config.story_points_field.clone(),
+            work_time: config.work_time(),
+        }
+    }
+
+    fn issues(&self) -> Result<Vec<Issue>> {
+        let body: Value = serde_json::from_str(&fs::read_to_string(&self.path)?)?;
+        Ok(body["issues"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|issue| jira::issue_from_json(issue, self.story_points_field.as_deref(), &self.work_time))
+            .collect())
+    }
+}
+
+impl IssueSource for FileSource {
+    /// All issues of the file that are not done; JQL filters can't be applied to it.
+    fn sprint_issues(&self, _filters: &[&str]) -> Result<Vec<Issue>> {
+        Ok(self.issues()?.into_iter().filter(|issue| !issue.is_done()).collect())
+    }
+
+    fn search(&self, _jql: &str, _page_token: Option<&str>) -> Result<(Vec<Issue>, Option<String>)> {
+        Err(anyhow!("{} can't be searched with JQL", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Settings {
+        issue_source: SourceConfig,
+    }
+
+    #[test]
+    fn test_source_config() {
+        let settings: Settings = toml::from_str(r#"issue_source = { file = "issues.json" }"#).unwrap();
+        assert_eq!(settings.issue_source, SourceConfig::File("issues.json".into()));
+        let settings: Settings = toml::from_str(r#"issue_source = "jira""#).unwrap();
+        assert_eq!(settings.issue_source, SourceConfig::Jira);
+    }
+
+    #[test]
+    fn test_file_source() {
+        let path = std::env::temp_dir().join(format!("jiratrack-issues-{}.json", std::process::id()));
+        let issue = |key: &str, category: &str| {
+            serde_json::json!({"id": "1", "key": key, "fields": {
+                "summary": "Fix login",
+                "status": {"name": "Open", "statusCategory": {"key": category}},
+                "timetracking": {"timeSpent": "1h 30m"}
+            }})
+        };
+        let body = serde_json::json!({"issues": [issue("IMG-1", "new"), issue("IMG-2", "done")]});
+        fs::write(&path, body.to_string()).unwrap();
+
+        let source = FileSource::new(path.clone(), &Config::default());
+        let issues = source.sprint_issues(&[]).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "IMG-1");
+        assert_eq!(issues[0].time_spent, 5400);
+        assert!(source.search("project = IMG", None).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use jiff::{civil::Date, ToSpan};
+
+use crate::{duration::WorkTime, holidays::Holidays, session::Session};
+
+/// Number of days, up to and including today, the statistics cover.
+pub const STATS_DAYS: i64 = 30;
+
+/// Number of epics listed as most tracked.
+const TOP_EPICS: usize = 5;
+
+/// Figures about how time was tracked lately, computed from the local history only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub sessions: usize,
+    pub average_session_s: u64,
+    /// Days anything was tracked on.
+    pub tracked_days: usize,
+    /// Changes to another issue between consecutive sessions of a day, per tracked day.
+    pub switches_per_day: f64,
+    /// Epics by tracked time, the most tracked first. Issues without a known epic count
+    /// as `None`.
+    pub epics: Vec<(Option<String>, u64)>,
+    pub tracked_s: u64,
+    /// Hours per day on the working days of the period.
+    pub target_s: u64,
+    /// Weekdays of the period that were off and left out of the target.
+    pub days_off: usize,
+}
+
+impl Stats {
+    pub fn new(
+        sessions: &[Session],
+        epic_of: impl Fn(&str) -> Option<String>,
+        work_time: &WorkTime,
+        holidays: &Holidays,
+        today: Date,
+    ) -> Stats {
+        let first_day = today.saturating_sub((STATS_DAYS - 1).days());
+        let mut sessions: Vec<&Session> = sessions
+            .iter()
+            .filter(|session| (first_day..=today).contains(&session.started.date()))
+            .collect();
+        sessions.sort_by(|a, b| a.started.cmp(&b.started));
+
+        let tracked_s: u64 = sessions.iter().map(|session| session.duration_s).sum();
+        let mut days: Vec<Date> = sessions.iter().map(|session| session.started.date()).collect();
+        days.dedup();
+        let switches = sessions
+            .windows(2)
+            .filter(|pair| pair[0].started.date() == pair[1].started.date() && pair[0].issue_key != pair[1].issue_key)
+            .count();
+
+        let mut epics: Vec<(Option<String>, u64)> = vec![];
+        for session in &sessions {
+            let epic = epic_of(&session.issue_key);
+            match epics.iter_mut().find(|(known, _)| *known == epic) {
+                Some((_, time_s)) => *time_s += session.duration_s,
+                None => epics.push((epic, session.duration_s)),
+            }
+        }
+        epics.sort_by_key(|(_, time_s)| std::cmp::Reverse(*time_s));
+        epics.truncate(TOP_EPICS);
+
+        let workdays = holidays.workdays(first_day, today) as f64;
+        Stats {
+            sessions: sessions.len(),
+            average_session_s: tracked_s.checked_div(sessions.len() as u64).unwrap_or(0),
+            tracked_days: days.len(),
+            switches_per_day: if days.is_empty() {
+                0.0
+            } else {
+                switches as f64 / days.len() as f64
+            },
+            epics,
+            tracked_s,
+            target_s: (workdays * work_time.hours_per_day * 3600.0).round() as u64,
+            days_off: holidays.days_off(first_day, today),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session(issue_key: &str, started: &str, duration_s: u64) -> Session {
+        Session {
+            issue_key: issue_key.to_string(),
+            started: started.parse().unwrap(),
+            duration_s,
+            comment: None,
+            account: None,
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let sessions = [
+            session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 3600),
+            session("IMG-2", "2025-01-10T10:00:00+00:00[UTC]", 1800),
+            session("IMG-1", "2025-01-10T11:00:00+00:00[UTC]", 1800),
+            session("IMG-1", "2025-01-09T09:00:00+00:00[UTC]", 3600),
+            session("OPS-4", "2025-01-08T09:00:00+00:00[UTC]", 1800),
+            session("IMG-1", "2024-11-01T09:00:00+00:00[UTC]", 3600),
+        ];
+        let epic_of = |key: &str| key.starts_with("IMG").then(|| "Login".to_string());
+        let today = "2025-01-10".parse().unwrap();
+        let stats = Stats::new(&sessions, epic_of, &WorkTime::default(), &Holidays::default(), today);
+        assert_eq!(stats.sessions, 5);
+        assert_eq!(stats.average_session_s, 2520);
+        assert_eq!(stats.tracked_days, 3);
+        assert_eq!(stats.switches_per_day, 2.0 / 3.0);
+        assert_eq!(stats.epics, [(Some("Login".to_string()), 10800), (None, 1800)]);
+        assert_eq!(stats.tracked_s, 12600);
+        // 2024-12-12 to 2025-01-10 has 22 weekdays.
+        assert_eq!(stats.target_s, 22 * 8 * 3600);
+        assert_eq!(stats.days_off, 0);
+
+        let holidays = Holidays::new(&[], ["2024-12-25".parse().unwrap(), "2024-12-28".parse().unwrap()]);
+        let stats = Stats::new(&sessions, epic_of, &WorkTime::default(), &holidays, today);
+        // The Saturday off was no working day anyway.
+        assert_eq!(stats.target_s, 21 * 8 * 3600);
+        assert_eq!(stats.days_off, 1);
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+/// Colors of the Okabe-Ito palette, told apart with every common color vision
+/// deficiency.
+pub const SKY_BLUE: Color = Color::Rgb(0x56, 0xb4, 0xe9);
+pub const ORANGE: Color = Color::Rgb(0xe6, 0x9f, 0x00);
+pub const BLUISH_GREEN: Color = Color::Rgb(0x00, 0x9e, 0x73);
+pub const VERMILLION: Color = Color::Rgb(0xd5, 0x5e, 0x00);
+pub const REDDISH_PURPLE: Color = Color::Rgb(0xcc, 0x79, 0xa7);
+
+/// A color in the config, a name like `light_blue` or a hex code like `#0072b2`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ConfigColor(pub Color);
+
+impl TryFrom<String> for ConfigColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Color::from_str(value.trim())
+            .map(ConfigColor)
+            .map_err(|_| format!("{value} is not a color like light_blue or #0072b2"))
+    }
+}
+
+impl From<ConfigColor> for String {
+    fn from(color: ConfigColor) -> Self {
+        color.0.to_string()
+    }
+}
+
+/// Colors of the statuses, configured per status name or category. Otherwise blocked
+/// and review statuses stand out and the rest is colored by category.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusColors {
+    /// By lowercase status name or category key.
+    overrides: BTreeMap<String, Color>,
+}
+
+impl StatusColors {
+    pub fn new(overrides: &HashMap<String, ConfigColor>) -> StatusColors {
+        StatusColors {
+            overrides: overrides
+                .iter()
+                .map(|(status, color)| (status.to_lowercase(), color.0))
+                .collect(),
+        }
+    }
+
+    /// The color of a status, by name like `In Review` before its category key like
+    /// `indeterminate`.
+    pub fn color(&self, status: &str, category: &str) -> Color {
+        let status = status.to_lowercase();
+        let overridden = self.overrides.get(&status).or_else(|| self.overrides.get(category));
+        if let Some(color) = overridden {
+            return *color;
+        }
+        if status.contains("block") {
+            return VERMILLION;
+        }
+        if status.contains("review") && category != "done" {
+            return REDDISH_PURPLE;
+        }
+        match category {
+            "new" => SKY_BLUE,
+            "indeterminate" => ORANGE,
+            "done" => BLUISH_GREEN,
+            _ => Color::Gray,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_config_color() {
+        let color = |value: &str| ConfigColor::try_from(value.to_string()).map(|color| color.0);
+        assert_eq!(color("light_blue"), Ok(Color::LightBlue));
+        assert_eq!(color("#cc79a7"), Ok(REDDISH_PURPLE));
+        assert!(color("blurple").is_err());
+    }
+
+    #[test]
+    fn test_color() {
+        let overrides = HashMap::from([
+            ("In Review".to_string(), ConfigColor(REDDISH_PURPLE)),
+            ("done".to_string(), ConfigColor(Color::Green)),
+        ]);
+        let colors = StatusColors::new(&overrides);
+        assert_eq!(colors.color("in review", "indeterminate"), REDDISH_PURPLE);
+        assert_eq!(colors.color("In Progress", "indeterminate"), ORANGE);
+        assert_eq!(colors.color("Closed", "done"), Color::Green);
+        let colors = StatusColors::default();
+        assert_eq!(colors.color("Blocked", "indeterminate"), VERMILLION);
+        assert_eq!(colors.color("Code Review", "indeterminate"), REDDISH_PURPLE);
+        assert_eq!(colors.color("Archived", "undefined"), Color::Gray);
+    }
+}
@@ -0,0 +1,123 @@
+use jiff::Timestamp;
+use serde_json::Value;
+
+use crate::{duration::WorkTime, jira::parse_timestamp};
+
+/// Calendar time, as issues also wait in a status at night and over the weekend.
+const CALENDAR_TIME: WorkTime = WorkTime {
+    hours_per_day: 24.0,
+    days_per_week: 7.0,
+};
+
+/// Seconds an issue spent in each status, in the order the statuses were first entered.
+/// Reads the status changes from an issue fetched with its changelog.
+pub fn from_issue(issue: &Value, now: Timestamp) -> Vec<(String, u64)> {
+    let fields = &issue["fields"];
+    let Some(created) = parse_timestamp(&fields["created"]) else {
+        return vec![];
+    };
+    let mut changes: Vec<(Timestamp, &str, &str)> = issue["changelog"]["histories"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|history| {
+            let changed = parse_timestamp(&history["created"])?;
+            let item = history["items"]
+                .as_array()?
+                .iter()
+                .find(|item| item["field"] == "status")?;
+            Some((changed, item["fromString"].as_str()?, item["toString"].as_str()?))
+        })
+        .collect();
+    changes.sort_by_key(|(changed, _, _)| *changed);
+
+    let mut status = match changes.first() {
+        Some((_, from, _)) => from.to_string(),
+        None => fields["status"]["name"].as_str().unwrap_or_default().to_string(),
+    };
+    let mut entered = created;
+    let mut times: Vec<(String, u64)> = vec![];
+    let mut add = |status: &str, from: Timestamp, to: Timestamp| {
+        let seconds = (to.as_second() - from.as_second()).max(0) as u64;
+        match times.iter_mut().find(|(name, _)| name == status) {
+            Some((_, total)) => *total += seconds,
+            None => times.push((status.to_string(), seconds)),
+        }
+    };
+    for (changed, _, to) in changes {
+        add(&status, entered, changed);
+        status = to.to_string();
+        entered = changed;
+    }
+    add(&status, entered, now);
+    times
+}
+
+/// Formats a time in status like `3d 4h`, leaving out minutes once it exceeds a day.
+pub fn format(seconds: u64) -> String {
+    if seconds < 60 {
+        return "<1m".to_string();
+    }
+    let rounded = if seconds >= 24 * 3600 {
+        seconds / 3600 * 3600
+    } else {
+        seconds
+    };
+    CALENDAR_TIME.format(rounded)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_from_issue() {
+        let issue = json!({
+            "fields": {"created": "2025-01-01T09:00:00.000+0000", "status": {"name": "Done"}},
+            "changelog": {"histories": [
+                {"created": "2025-01-03T09:00:00.000+0000", "items": [
+                    {"field": "status", "fromString": "In Review", "toString": "In Progress"}
+                ]},
+                {"created": "2025-01-01T10:00:00.000+0000", "items": [
+                    {"field": "assignee"},
+                    {"field": "status", "fromString": "To Do", "toString": "In Progress"}
+                ]},
+                {"created": "2025-01-02T09:00:00.000+0000", "items": [
+                    {"field": "status", "fromString": "In Progress", "toString": "In Review"}
+                ]},
+                {"created": "2025-01-03T12:00:00.000+0000", "items": [
+                    {"field": "status", "fromString": "In Progress", "toString": "Done"}
+                ]}
+            ]}
+        });
+        let now = "2025-01-04T12:00:00Z".parse().unwrap();
+        assert_eq!(
+            from_issue(&issue, now),
+            [
+                ("To Do".to_string(), 3600),
+                ("In Progress".to_string(), 23 * 3600 + 3 * 3600),
+                ("In Review".to_string(), 24 * 3600),
+                ("Done".to_string(), 24 * 3600),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_issue_without_changes() {
+        let issue = json!({
+            "fields": {"created": "2025-01-01T09:00:00.000+0000", "status": {"name": "To Do"}}
+        });
+        let now = "2025-01-01T09:30:00Z".parse().unwrap();
+        assert_eq!(from_issue(&issue, now), [("To Do".to_string(), 1800)]);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format(3 * 24 * 3600 + 4 * 3600 + 59 * 60), "3d 4h");
+        assert_eq!(format(90 * 60), "1h 30m");
+        assert_eq!(format(10 * 3600), "10h");
+        assert_eq!(format(10), "<1m");
+    }
+}
@@ -0,0 +1,140 @@
+use crate::jira::Issue;
+
+/// Sprint totals shown in the burndown summary popup.
+#[derive(Debug, Default, PartialEq)]
+pub struct SprintSummary {
+    pub done: usize,
+    pub open: usize,
+    pub estimated_s: u64,
+    pub logged_s: u64,
+    pub points_total: f64,
+    pub points_remaining: f64,
+}
+
+impl SprintSummary {
+    pub fn from_issues(issues: &[Issue]) -> Self {
+        let mut summary = SprintSummary::default();
+        for issue in issues {
+            let points = issue.story_points.unwrap_or(0.0);
+            if issue.is_done() {
+                summary.done += 1;
+            } else {
+                summary.open += 1;
+                summary.points_remaining += points;
+            }
+            summary.points_total += points;
+            summary.estimated_s += issue.estimate;
+            summary.logged_s += issue.time_spent;
+        }
+        summary
+    }
+}
+
+/// Per-assignee totals shown in the team workload popup.
+#[derive(Debug, PartialEq)]
+pub struct AssigneeWorkload {
+    pub assignee: String,
+    pub issues: usize,
+    pub logged_s: u64,
+    pub estimated_s: u64,
+}
+
+pub fn workload_by_assignee(issues: &[Issue]) -> Vec<AssigneeWorkload> {
+    let mut workloads: Vec<AssigneeWorkload> = vec![];
+    for issue in issues {
+        let assignee = if issue.assignee.is_empty() {
+            "Unassigned"
+        } else {
+            &issue.assignee
+        };
+        let index = match workloads.iter().position(|w| w.assignee == assignee) {
+            Some(index) => index,
+            None => {
+                workloads.push(AssigneeWorkload {
+                    assignee: assignee.to_string(),
+                    issues: 0,
+                    logged_s: 0,
+                    estimated_s: 0,
+                });
+                workloads.len() - 1
+            }
+        };
+        let workload = &mut workloads[index];
+        workload.issues += 1;
+        workload.logged_s += issue.time_spent;
+        workload.estimated_s += issue.estimate;
+    }
+    workloads.sort_by(|a, b| a.assignee.cmp(&b.assignee));
+    workloads
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn issue(status_category: &str, estimate: u64, time_spent: u64, points: f64) -> Issue {
+        assigned_issue("", status_category, estimate, time_spent, points)
+    }
+
+    fn assigned_issue(
+        assignee: &str,
+        status_category: &str,
+        estimate: u64,
+        time_spent: u64,
+        points: f64,
+    ) -> Issue {
+        Issue {
+            id: "1".to_string(),
+            key: "IMG-1".to_string(),
+            time_spent,
+            estimate,
+            assignee: assignee.to_string(),
+            status_category: status_category.to_string(),
+            story_points: Some(points),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_from_issues() {
+        let issues = [
+            issue("done", 3600, 7200, 3.0),
+            issue("indeterminate", 1800, 600, 5.0),
+            issue("new", 0, 0, 2.0),
+        ];
+        let summary = SprintSummary::from_issues(&issues);
+        assert_eq!(
+            summary,
+            SprintSummary {
+                done: 1,
+                open: 2,
+                estimated_s: 5400,
+                logged_s: 7800,
+                points_total: 10.0,
+                points_remaining: 7.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_workload_by_assignee() {
+        let issues = [
+            assigned_issue("Sam", "new", 3600, 0, 0.0),
+            assigned_issue("", "new", 0, 0, 0.0),
+            assigned_issue("Alex", "done", 1800, 1200, 0.0),
+            assigned_issue("Sam", "done", 3600, 4000, 0.0),
+        ];
+        let workloads = workload_by_assignee(&issues);
+        let assignees: Vec<_> = workloads.iter().map(|w| w.assignee.as_str()).collect();
+        assert_eq!(assignees, ["Alex", "Sam", "Unassigned"]);
+        assert_eq!(
+            workloads[1],
+            AssigneeWorkload {
+                assignee: "Sam".to_string(),
+                issues: 2,
+                logged_s: 4000,
+                estimated_s: 7200,
+            }
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose, Engine as _};
+use jiff::{Timestamp, Zoned};
+use serde::{de::DeserializeOwned, Serialize};
+use ureq::{Agent, AgentBuilder, Error};
+
+use crate::{config::Config, history::History, PersistedState};
+
+/// How often the running interface syncs.
+pub const SYNC_INTERVAL: Duration = Duration::from_secs(30);
+/// How often a file is merged again when another machine wrote it in the meantime.
+const MAX_ATTEMPTS: usize = 3;
+
+/// Copy of the timer and the submitted worklogs on a WebDAV folder or any endpoint
+/// answering GET and PUT, so they follow you across machines.
+#[derive(Debug)]
+pub struct SyncBackend {
+    url: String,
+    authorization: String,
+    agent: Agent,
+}
+
+impl SyncBackend {
+    /// The backend, `None` without a `sync_url`.
+    pub fn from_config(config: &Config) -> Option<SyncBackend> {
+        let url = config.sync_url.as_ref().filter(|url| !url.is_empty())?;
+        let token = config.sync_token.clone().unwrap_or_default();
+        let authorization = match &config.sync_user {
+            Some(user) => format!("Basic {}", general_purpose::STANDARD.encode(format!("{user}:{token}"))),
+            None => format!("Bearer {token}"),
+        };
+        Some(SyncBackend {
+            url: url.trim_end_matches('/').to_string(),
+            authorization,
+            agent: AgentBuilder::new().timeout(Duration::from_secs(10)).build(),
+        })
+    }
+
+    /// The remote file with its ETag, `None` if there is none yet.
+    fn get<T: DeserializeOwned>(&self, name: &str) -> Result<Option<(T, Option<String>)>> {
+        let request = self.agent.get(&format!("{}/{name}", self.url));
+        match request.set("Authorization", &self.authorization).call() {
+            Ok(response) => {
+                let etag = response.header("ETag").map(|etag| etag.to_string());
+                Ok(Some((response.into_json()?, etag)))
+            }
+            Err(Error::Status(404, _)) => Ok(None),
+            Err(Error::Status(code, response)) => Err(anyhow!("{code} {}", response.status_text())),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Merges the remote file into the local value and uploads the result, only if
+    /// the file is still the one merged, else it merges again. Backends without ETags
+    /// are overwritten.
+    fn sync_file<T: Serialize + DeserializeOwned>(&self, name: &str, merge: impl Fn(Option<T>) -> T) -> Result<T> {
+        for _ in 0..MAX_ATTEMPTS {
+            let remote = self.get::<T>(name)?;
+            let etag = remote.as_ref().map(|(_, etag)| etag.clone());
+            let merged = merge(remote.map(|(value, _)| value));
+            let request = self
+                .agent
+                .put(&format!("{}/{name}", self.url))
+                .set("Authorization", &self.authorization);
+            let request = match &etag {
+                None => request.set("If-None-Match", "*"),
+                Some(Some(etag)) => request.set("If-Match", etag),
+                Some(None) => request,
+            };
+            match request.send_json(&merged) {
+                Ok(_) => return Ok(merged),
+                Err(Error::Status(412, _)) => continue,
+                Err(Error::Status(code, response)) => return Err(anyhow!("{code} {}", response.status_text())),
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Err(anyhow!("{name} kept changing on the sync backend"))
+    }
+
+    /// Merges the remote state and worklogs into the local ones, uploads the results and
+    /// returns them.
+    pub fn sync(&self, state: PersistedState, mut history: History) -> Result<(PersistedState, History)> {
+        let state = self.sync_file("state.json", |remote| match remote {
+            Some(remote) => merge_state(state.clone(), remote),
+            None => state.clone(),
+        })?;
+        // Sessions awaiting review stay on the machine they were tracked on.
+        let submitted = self.sync_file("history.json", |remote| {
+            let mut submitted = History {
+                entries: history.entries.clone(),
+                pending: vec![],
+            };
+            if let Some(remote) = remote {
+                merge_history(&mut submitted, remote);
+            }
+            submitted
+        })?;
+        merge_history(&mut history, submitted);
+        Ok((state, history))
+    }
+}
+
+/// Syncs in a background thread, so a slow backend doesn't hold up the interface.
+#[derive(Debug)]
+pub struct Syncer {
+    requests: Sender<(PersistedState, History)>,
+    results: Receiver<Result<(PersistedState, History)>>,
+    in_flight: bool,
+}
+
+impl Syncer {
+    pub fn start(backend: SyncBackend) -> Syncer {
+        let (requests, request_receiver) = mpsc::channel::<(PersistedState, History)>();
+        let (result_sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            for (state, history) in request_receiver {
+                if result_sender.send(backend.sync(state, history)).is_err() {
+                    return;
+                }
+            }
+        });
+        Syncer {
+            requests,
+            results,
+            in_flight: false,
+        }
+    }
+
+    /// Starts syncing the state and history, unless a sync is still running.
+    pub fn sync(&mut self, state: PersistedState, history: History) {
+        if !self.in_flight {
+            self.in_flight = self.requests.send((state, history)).is_ok();
+        }
+    }
+
+    /// The merged state and history once the sync is done.
+    pub fn try_take(&mut self) -> Option<Result<(PersistedState, History)>> {
+        let result = self.results.try_recv().ok()?;
+        self.in_flight = false;
+        Some(result)
+    }
+}
+
+/// When the timer last started or stopped.
+fn timer_changed(state: &PersistedState) -> Option<Timestamp> {
+    state.activated_on.as_ref().or(state.stopped_on.as_ref()).map(Zoned::timestamp)
+}
+
+/// The local state with the timer of whichever side started or stopped it last. Pane
+/// sizes stay local, annotations made elsewhere are added.
+pub fn merge_state(mut local: PersistedState, remote: PersistedState) -> PersistedState {
+    if timer_changed(&remote) > timer_changed(&local) {
+        local.active_issue = remote.active_issue;
+        local.activated_on = remote.activated_on;
+        local.stopped_on = remote.stopped_on;
+        local.laps = remote.laps;
+        local.last_issue = remote.last_issue;
+        local.host = remote.host;
+    }
+    for (issue_key, annotation) in remote.annotations {
+        local.annotations.entry(issue_key).or_insert(annotation);
+    }
+    local
+}
+
+/// Adds the worklogs submitted elsewhere to the local history, in the order they were
+/// submitted.
+pub fn merge_history(local: &mut History, remote: History) {
+    let mut added = false;
+    for entry in remote.entries {
+        let known = local.entries.iter().any(|known| {
+            known.issue_key == entry.issue_key
+                && known.started.timestamp() == entry.started.timestamp()
+                && known.worklog_id == entry.worklog_id
+        });
+        if !known {
+            local.entries.push(entry);
+            added = true;
+        }
+    }
+    if added {
+        local
+            .entries
+            .sort_by_key(|entry| entry.submitted.as_ref().unwrap_or(&entry.started).timestamp());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::history::HistoryEntry;
+
+    /// A backend answering with the given statuses and ETags in order, sending back the
+    /// request line and conditional header of every request it got.
+    fn fake_backend(answers: Vec<(&'static str, Option<&'static str>, String)>) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for ((status, etag, body), stream) in answers.into_iter().zip(listener.incoming()) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut request = request.trim().to_string();
+                let mut length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    let Some((name, value)) = header.trim().split_once(": ") else {
+                        break;
+                    };
+                    match name.to_lowercase().as_str() {
+                        "content-length" => length = value.parse().unwrap(),
+                        "if-match" | "if-none-match" => request.push_str(&format!(" {name}: {value}")),
+                        _ => {}
+                    }
+                }
+                reader.read_exact(&mut vec![0; length]).unwrap();
+                sender.send(request).unwrap();
+                let etag = etag.map(|etag| format!("ETag: {etag}\r\n")).unwrap_or_default();
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\n{etag}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        (url, requests)
+    }
+
+    #[test]
+    fn test_sync_conflict() {
+        let remote = serde_json::to_string(&state(None, None, None, "desktop")).unwrap();
+        let (url, requests) = fake_backend(vec![
+            ("200 OK", Some("\"1\""), remote.clone()),
+            ("412 Precondition Failed", None, String::new()),
+            ("200 OK", Some("\"2\""), remote),
+            ("204 No Content", None, String::new()),
+            ("404 Not Found", None, String::new()),
+            ("201 Created", None, String::new()),
+        ]);
+        let config = Config {
+            sync_url: Some(url),
+            ..Default::default()
+        };
+        let backend = SyncBackend::from_config(&config).unwrap();
+        let local = state(Some("IMG-1"), Some("2025-01-10T09:00:00+00:00[UTC]"), None, "laptop");
+        let (state, _) = backend.sync(local, History::default()).unwrap();
+        assert_eq!(state.active_issue.as_deref(), Some("IMG-1"));
+        let requests: Vec<String> = requests.try_iter().collect();
+        assert_eq!(
+            requests,
+            [
+                "GET /state.json HTTP/1.1",
+                "PUT /state.json HTTP/1.1 If-Match: \"1\"",
+                "GET /state.json HTTP/1.1",
+                "PUT /state.json HTTP/1.1 If-Match: \"2\"",
+                "GET /history.json HTTP/1.1",
+                "PUT /history.json HTTP/1.1 If-None-Match: *",
+            ]
+        );
+    }
+
+    fn state(active_issue: Option<&str>, activated_on: Option<&str>, stopped_on: Option<&str>, host: &str) -> PersistedState {
+        PersistedState {
+            active_issue: active_issue.map(str::to_string),
+            activated_on: activated_on.map(|at| at.parse().unwrap()),
+            panes: Default::default(),
+            laps: vec![],
+            annotations: Default::default(),
+            last_issue: None,
+            host: Some(host.to_string()),
+            stopped_on: stopped_on.map(|at| at.parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_merge_state() {
+        let laptop = state(Some("IMG-1"), Some("2025-01-10T09:00:00+00:00[UTC]"), None, "laptop");
+        let desktop = state(Some("IMG-2"), Some("2025-01-10T10:00:00+00:00[UTC]"), None, "desktop");
+        let merged = merge_state(laptop, desktop);
+        assert_eq!(merged.active_issue.as_deref(), Some("IMG-2"));
+        assert_eq!(merged.host.as_deref(), Some("desktop"));
+
+        // Stopping the timer on the desktop after it started on the laptop stops it.
+        let laptop = state(Some("IMG-1"), Some("2025-01-10T09:00:00+00:00[UTC]"), None, "laptop");
+        let desktop = state(None, None, Some("2025-01-10T11:00:00+00:00[UTC]"), "desktop");
+        assert_eq!(merge_state(laptop, desktop).active_issue, None);
+
+        let laptop = state(Some("IMG-1"), Some("2025-01-10T12:00:00+00:00[UTC]"), None, "laptop");
+        let desktop = state(None, None, Some("2025-01-10T11:00:00+00:00[UTC]"), "desktop");
+        assert_eq!(merge_state(laptop, desktop).active_issue.as_deref(), Some("IMG-1"));
+    }
+
+    #[test]
+    fn test_merge_history() {
+        let entry = |issue_key: &str, started: &str| HistoryEntry {
+            issue_key: issue_key.to_string(),
+            started: started.parse().unwrap(),
+            time_spent_s: 900,
+            worklog_id: Some(format!("{issue_key}-worklog")),
+            logged_as: None,
+            submitted: None,
+            comment: None,
+        };
+        let mut local = History {
+            entries: vec![entry("IMG-1", "2025-01-10T09:00:00+00:00[UTC]")],
+            pending: vec![],
+        };
+        let remote = History {
+            entries: vec![
+                entry("IMG-2", "2025-01-10T08:00:00+00:00[UTC]"),
+                entry("IMG-1", "2025-01-10T09:00:00+00:00[UTC]"),
+            ],
+            pending: vec![],
+        };
+        merge_history(&mut local, remote);
+        let keys: Vec<&str> = local.entries.iter().map(|entry| entry.issue_key.as_str()).collect();
+        assert_eq!(keys, ["IMG-2", "IMG-1"]);
+    }
+}
@@ -0,0 +1,51 @@
+use std::process::Command;
+
+/// Replaces `{name}` placeholders with their values, leaving unknown ones as they are.
+pub fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = placeholder.find('}').and_then(|end| {
+            let name = &placeholder[1..end];
+            let (_, value) = values.iter().find(|(key, _)| *key == name)?;
+            Some((value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                rendered.push_str(value);
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// The checked out git branch in the working directory, `None` outside a repository.
+pub fn git_branch() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()
+        .ok()?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !branch.is_empty()).then_some(branch)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let values = [("key", "IMG-123"), ("summary", "Fix login")];
+        assert_eq!(render("feat({key}): {summary}", &values), "feat(IMG-123): Fix login");
+        assert_eq!(render("{key} {unknown} {", &values), "IMG-123 {unknown} {");
+        assert_eq!(render("{{key}}", &values), "{IMG-123}");
+    }
+}
@@ -0,0 +1,92 @@
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+use serde::{Deserialize, Serialize};
+
+/// How colors are shown, applied to every frame after it is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    #[default]
+    Default,
+    /// Bright colors only, with black text on highlighted backgrounds.
+    HighContrast,
+    /// No colors at all, highlights are shown reversed and bold instead.
+    NoColor,
+}
+
+impl Theme {
+    pub fn apply(&self, buffer: &mut Buffer) {
+        match self {
+            Theme::Default => {}
+            Theme::HighContrast => {
+                for cell in &mut buffer.content {
+                    if cell.bg != Color::Reset {
+                        cell.bg = Color::White;
+                        cell.fg = Color::Black;
+                        cell.modifier |= Modifier::BOLD;
+                    } else {
+                        cell.fg = brighten(cell.fg);
+                    }
+                }
+            }
+            Theme::NoColor => {
+                for cell in &mut buffer.content {
+                    if cell.bg != Color::Reset {
+                        cell.modifier |= Modifier::REVERSED | Modifier::BOLD;
+                    }
+                    cell.fg = Color::Reset;
+                    cell.bg = Color::Reset;
+                }
+            }
+        }
+    }
+}
+
+fn brighten(color: Color) -> Color {
+    match color {
+        Color::Red => Color::LightRed,
+        Color::Green => Color::LightGreen,
+        Color::Yellow => Color::LightYellow,
+        Color::Blue => Color::LightCyan,
+        Color::Magenta => Color::LightMagenta,
+        Color::Cyan => Color::LightCyan,
+        Color::Gray | Color::DarkGray => Color::White,
+        color => color,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ratatui::{layout::Rect, style::Stylize, text::Line, widgets::Widget};
+
+    fn buffer() -> Buffer {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 1));
+        let line = Line::from(vec!["ab".blue(), "cd".on_dark_gray(), "ef".into()]);
+        line.render(buffer.area, &mut buffer);
+        buffer
+    }
+
+    #[test]
+    fn test_no_color() {
+        let mut buffer = buffer();
+        Theme::NoColor.apply(&mut buffer);
+        assert!(buffer
+            .content
+            .iter()
+            .all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+        assert!(!buffer[(0, 0)].modifier.contains(Modifier::REVERSED));
+        assert!(buffer[(2, 0)].modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_high_contrast() {
+        let mut buffer = buffer();
+        Theme::HighContrast.apply(&mut buffer);
+        assert_eq!(buffer[(0, 0)].fg, Color::LightCyan);
+        assert_eq!((buffer[(2, 0)].fg, buffer[(2, 0)].bg), (Color::Black, Color::White));
+        assert_eq!(buffer[(4, 0)].fg, Color::Reset);
+    }
+}
@@ -0,0 +1,199 @@
+use jiff::{civil::Time, ToSpan, Zoned};
+
+use crate::{
+    reminder::WorkHours,
+    session::Session,
+    wrapup::{self, MIN_GAP_S},
+};
+
+/// Hours shown without configured working hours.
+const DEFAULT_START: Time = Time::constant(9, 0, 0, 0);
+const DEFAULT_END: Time = Time::constant(18, 0, 0, 0);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SegmentKind {
+    Session { issue_key: String, submitted: bool },
+    /// Untracked time before now.
+    Gap,
+}
+
+/// A stretch of the day on the timeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub start: Zoned,
+    pub end: Zoned,
+    pub kind: SegmentKind,
+}
+
+impl Segment {
+    pub fn duration_s(&self) -> u64 {
+        (self.end.timestamp().as_second() - self.start.timestamp().as_second()).max(0) as u64
+    }
+}
+
+/// Today's sessions and gaps along a time axis spanning the working hours, 09:00 to
+/// 18:00 without them, widened to whole hours to fit sessions outside of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline {
+    pub start: Zoned,
+    pub end: Zoned,
+    /// Sessions and gaps by their start.
+    pub segments: Vec<Segment>,
+}
+
+impl Timeline {
+    pub fn new(submitted: &[Session], unsubmitted: &[Session], work_hours: Option<&WorkHours>, now: &Zoned) -> Timeline {
+        let today = now.date();
+        let mut segments: Vec<Segment> = submitted
+            .iter()
+            .map(|session| (session, true))
+            .chain(unsubmitted.iter().map(|session| (session, false)))
+            .filter(|(session, _)| session.started.date() == today)
+            .map(|(session, submitted)| Segment {
+                start: session.started.clone(),
+                end: session.ended(),
+                kind: SegmentKind::Session {
+                    issue_key: session.issue_key.clone(),
+                    submitted,
+                },
+            })
+            .collect();
+        segments.sort_by(|a, b| a.start.cmp(&b.start));
+
+        let at = |time: Time| now.with().time(time).build().unwrap_or_else(|_| now.clone());
+        let mut start = at(work_hours.map_or(DEFAULT_START, |work_hours| work_hours.start));
+        let mut end = at(work_hours.map_or(DEFAULT_END, |work_hours| work_hours.end));
+        if let Some(first) = segments.first() {
+            start = start.min(at(Time::constant(first.start.hour(), 0, 0, 0)));
+        }
+        if let Some(last) = segments.iter().map(|segment| segment.end.clone()).max() {
+            if last > end {
+                let hour = at(Time::constant(last.hour(), 0, 0, 0));
+                end = if last == hour { hour } else { hour.saturating_add(1.hour()) };
+            }
+        }
+
+        let sessions: Vec<Session> = segments
+            .iter()
+            .map(|segment| Session::new("", &segment.start, &segment.end))
+            .collect();
+        let gaps = wrapup::gaps(&sessions, &start, &end.clone().min(now.clone()), MIN_GAP_S);
+        segments.extend(gaps.into_iter().map(|(start, end)| Segment {
+            start,
+            end,
+            kind: SegmentKind::Gap,
+        }));
+        segments.sort_by(|a, b| a.start.cmp(&b.start));
+        Timeline { start, end, segments }
+    }
+
+    /// Indices of the gaps in the segments.
+    pub fn gaps(&self) -> Vec<usize> {
+        (0..self.segments.len())
+            .filter(|index| self.segments[*index].kind == SegmentKind::Gap)
+            .collect()
+    }
+
+    fn offset_s(&self, time: &Zoned) -> i64 {
+        time.timestamp().as_second() - self.start.timestamp().as_second()
+    }
+
+    /// The column of `width` columns a time falls into, `None` outside the axis or
+    /// without any column.
+    pub fn column(&self, time: &Zoned, width: u16) -> Option<u16> {
+        let span_s = self.offset_s(&self.end).max(1);
+        let offset_s = self.offset_s(time);
+        (width > 0 && (0..=span_s).contains(&offset_s))
+            .then(|| ((offset_s * width as i64 / span_s) as u16).min(width.saturating_sub(1)))
+    }
+
+    /// For each of `width` columns the index of the segment covering its middle. A
+    /// segment too short to cover any middle takes the column it starts in.
+    pub fn columns(&self, width: u16) -> Vec<Option<usize>> {
+        let span_s = self.offset_s(&self.end).max(1);
+        let mut columns: Vec<Option<usize>> = (0..width as i64)
+            .map(|column| {
+                let middle_s = (2 * column + 1) * span_s / (2 * width as i64);
+                self.segments.iter().position(|segment| {
+                    self.offset_s(&segment.start) <= middle_s && middle_s < self.offset_s(&segment.end)
+                })
+            })
+            .collect();
+        for (index, segment) in self.segments.iter().enumerate() {
+            if columns.contains(&Some(index)) {
+                continue;
+            }
+            if let Some(column) = self.column(&segment.start, width) {
+                columns[column as usize] = Some(index);
+            }
+        }
+        columns
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session(issue_key: &str, started: &str, duration_s: u64) -> Session {
+        Session {
+            issue_key: issue_key.to_string(),
+            started: started.parse().unwrap(),
+            duration_s,
+            comment: None,
+            account: None,
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let submitted = [session("IMG-1", "2025-01-10T08:30:00+00:00[UTC]", 5400)];
+        let unsubmitted = [
+            session("IMG-2", "2025-01-10T11:00:00+00:00[UTC]", 1800),
+            session("IMG-9", "2025-01-09T11:00:00+00:00[UTC]", 1800),
+        ];
+        let now = "2025-01-10T12:00:00+00:00[UTC]".parse().unwrap();
+        let timeline = Timeline::new(&submitted, &unsubmitted, None, &now);
+        assert_eq!(timeline.start.time().to_string(), "08:00:00");
+        assert_eq!(timeline.end.time().to_string(), "18:00:00");
+        let segments: Vec<(String, SegmentKind)> = timeline
+            .segments
+            .iter()
+            .map(|segment| (segment.start.time().to_string(), segment.kind.clone()))
+            .collect();
+        let issue = |issue_key: &str, submitted| SegmentKind::Session {
+            issue_key: issue_key.to_string(),
+            submitted,
+        };
+        assert_eq!(
+            segments,
+            [
+                ("08:00:00".to_string(), SegmentKind::Gap),
+                ("08:30:00".to_string(), issue("IMG-1", true)),
+                ("10:00:00".to_string(), SegmentKind::Gap),
+                ("11:00:00".to_string(), issue("IMG-2", false)),
+                ("11:30:00".to_string(), SegmentKind::Gap),
+            ]
+        );
+        assert_eq!(timeline.segments[4].end.time().to_string(), "12:00:00");
+        assert_eq!(timeline.gaps(), [0, 2, 4]);
+    }
+
+    #[test]
+    fn test_columns() {
+        let sessions = [
+            session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 3 * 3600),
+            session("IMG-2", "2025-01-10T13:00:00+00:00[UTC]", 60),
+        ];
+        let now = "2025-01-10T18:00:00+00:00[UTC]".parse().unwrap();
+        let timeline = Timeline::new(&sessions, &[], None, &now);
+        // One column per hour from 09:00 to 18:00.
+        assert_eq!(
+            timeline.columns(9),
+            [Some(0), Some(0), Some(0), Some(1), Some(2), Some(3), Some(3), Some(3), Some(3)]
+        );
+        assert_eq!(timeline.column(&now, 9), Some(8));
+        assert_eq!(timeline.column(&now, 0), None);
+        assert!(timeline.columns(0).is_empty());
+    }
+}
@@ -0,0 +1,306 @@
+use anyhow::{anyhow, Result};
+use jiff::{civil::Date, ToSpan, Zoned};
+use serde_json::{json, Value};
+use ureq::{Agent, Error};
+
+use crate::{config::Config, dryrun};
+
+const TEMPO_URL: &str = "https://api.tempo.io/4";
+
+/// Number of weeks, the current one included, listed in the timesheet.
+pub const TIMESHEET_WEEKS: i64 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApprovalStatus {
+    Open,
+    InReview,
+    Approved,
+    Rejected,
+}
+
+impl ApprovalStatus {
+    fn from_key(key: &str) -> ApprovalStatus {
+        match key {
+            "IN_REVIEW" => ApprovalStatus::InReview,
+            "APPROVED" => ApprovalStatus::Approved,
+            "REJECTED" => ApprovalStatus::Rejected,
+            _ => ApprovalStatus::Open,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ApprovalStatus::Open => "Open",
+            ApprovalStatus::InReview => "In Review",
+            ApprovalStatus::Approved => "Approved",
+            ApprovalStatus::Rejected => "Rejected",
+        }
+    }
+
+    /// Whether the week can be submitted, again after fixing it if it was rejected.
+    pub fn can_submit(self) -> bool {
+        matches!(self, ApprovalStatus::Open | ApprovalStatus::Rejected)
+    }
+}
+
+/// The approval of a week of worklogs in Tempo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Approval {
+    pub status: ApprovalStatus,
+    pub logged_s: u64,
+    pub required_s: u64,
+    /// Comment left with the last change, e.g. why the week was rejected.
+    pub comment: Option<String>,
+}
+
+impl Approval {
+    pub fn from_json(value: &Value) -> Approval {
+        let comment = value["status"]["comment"].as_str().filter(|comment| !comment.is_empty());
+        Approval {
+            status: ApprovalStatus::from_key(value["status"]["key"].as_str().unwrap_or_default()),
+            logged_s: value["timeSpentSeconds"].as_u64().unwrap_or(0),
+            required_s: value["requiredSeconds"].as_u64().unwrap_or(0),
+            comment: comment.map(|comment| comment.to_string()),
+        }
+    }
+}
+
+/// A week of the timesheet, with the time in the local history and its approval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Week {
+    pub start: Date,
+    pub logged_s: u64,
+    /// Hours per day on the working days of the week.
+    pub target_s: u64,
+    /// `None` without Tempo or when it could not be read.
+    pub approval: Option<Approval>,
+}
+
+impl Week {
+    pub fn end(&self) -> Date {
+        self.start.saturating_add(6.days())
+    }
+}
+
+/// Start days of the weeks in the timesheet, the current week first.
+pub fn week_starts(current: Date) -> Vec<Date> {
+    (0..TIMESHEET_WEEKS)
+        .map(|weeks| current.saturating_sub(weeks.weeks()))
+        .collect()
+}
+
+/// Client for the timesheet approvals and work attributes of Tempo, which has its own
+/// API and token.
+#[derive(Debug)]
+pub struct Tempo {
+    token: String,
+    reviewer_account_id: Option<String>,
+    billing_attribute: Option<String>,
+    agent: Agent,
+    demo: bool,
+    dry_run: bool,
+}
+
+impl Tempo {
+    /// The client, `None` without a `tempo_api_token`. The demo needs no token.
+    pub fn from_config(config: &Config) -> Option<Tempo> {
+        let token = config.tempo_api_token.clone().filter(|token| !token.is_empty());
+        if token.is_none() && !config.demo {
+            return None;
+        }
+        Some(Tempo {
+            token: token.unwrap_or_default(),
+            reviewer_account_id: config.tempo_reviewer_account_id.clone(),
+            billing_attribute: config.billing_attribute.clone(),
+            agent: Agent::new(),
+            demo: config.demo,
+            dry_run: config.dry_run,
+        })
+    }
+
+    pub fn get_approval(&self, account_id: &str, week_start: Date) -> Result<Approval> {
+        let endpoint = format!("/timesheet-approvals/user/{account_id}");
+        if self.demo {
+            return Ok(Approval::from_json(&demo_approval(week_start, Zoned::now().date())));
+        }
+        let request = self.agent.get(&format!("{TEMPO_URL}{endpoint}"));
+        let response = self.call(period(request, week_start), None)?;
+        Ok(Approval::from_json(&response))
+    }
+
+    /// Submits the week for approval, to the configured reviewer or else the one Tempo
+    /// assigns, and returns the new status.
+    pub fn submit(&self, account_id: &str, week_start: Date) -> Result<ApprovalStatus> {
+        let endpoint = format!("/timesheet-approvals/user/{account_id}/submit");
+        let mut data = json!({ "comment": "Submitted from jiratrack" });
+        if let Some(reviewer) = &self.reviewer_account_id {
+            data["reviewerAccountId"] = json!(reviewer);
+        }
+        if self.dry_run {
+            dryrun::record("POST", &format!("{TEMPO_URL}{endpoint}"), Some(&data));
+        }
+        if self.demo || self.dry_run {
+            return Ok(ApprovalStatus::InReview);
+        }
+        let request = self.agent.post(&format!("{TEMPO_URL}{endpoint}"));
+        let response = self.call(period(request, week_start), Some(&data))?;
+        Ok(Approval::from_json(&response).status)
+    }
+
+    /// Books a worklog created in Jira on a billing account, by setting the work attribute
+    /// named by `billing_attribute` on its Tempo worklog.
+    pub fn book_account(&self, jira_worklog_id: &str, account: &str) -> Result<()> {
+        let Some(attribute) = &self.billing_attribute else {
+            return Ok(());
+        };
+        if self.demo {
+            return Ok(());
+        }
+        if self.dry_run {
+            let data = json!({ "attributes": [{ "key": attribute, "value": account }] });
+            let endpoint = format!("{TEMPO_URL}/worklogs/jira/{jira_worklog_id}");
+            dryrun::record("PUT", &endpoint, Some(&data));
+            return Ok(());
+        }
+        let ids = json!({ "jiraWorklogIds": [jira_worklog_id.parse::<u64>()?] });
+        let request = self.agent.post(&format!("{TEMPO_URL}/worklogs/jira-to-tempo"));
+        let mapping = self.call(request, Some(&ids))?;
+        // Tempo picks up worklogs created in Jira after a moment.
+        let tempo_worklog_id = mapping["results"][0]["tempoWorklogId"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("Tempo does not know worklog {jira_worklog_id} yet"))?;
+        let endpoint = format!("{TEMPO_URL}/worklogs/{tempo_worklog_id}");
+        let worklog = self.call(self.agent.get(&endpoint), None)?;
+        self.call(self.agent.put(&endpoint), Some(&with_attribute(&worklog, attribute, account)))?;
+        Ok(())
+    }
+
+    fn call(&self, request: ureq::Request, data: Option<&Value>) -> Result<Value> {
+        let request = request
+            .set("Accept", "application/json")
+            .set("Authorization", &format!("Bearer {}", self.token));
+        let response = match data {
+            Some(data) => request.send_json(data),
+            None => request.call(),
+        };
+        match response {
+            Ok(response) => Ok(response.into_json()?),
+            Err(Error::Status(code, response)) => {
+                let body: Value = response.into_json().unwrap_or_default();
+                let message = body["errors"][0]["message"].as_str().unwrap_or("Tempo refused the request");
+                Err(anyhow!("{code} {message}"))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+fn period(request: ureq::Request, week_start: Date) -> ureq::Request {
+    request
+        .query("from", &week_start.to_string())
+        .query("to", &week_start.saturating_add(6.days()).to_string())
+}
+
+/// The update of a Tempo worklog that keeps it as it is, except for the work attribute.
+fn with_attribute(worklog: &Value, key: &str, value: &str) -> Value {
+    let mut attributes: Vec<Value> = worklog["attributes"]["values"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|attribute| attribute["key"] != key)
+        .map(|attribute| json!({ "key": attribute["key"], "value": attribute["value"] }))
+        .collect();
+    attributes.push(json!({ "key": key, "value": value }));
+    let mut update = json!({
+        "authorAccountId": worklog["author"]["accountId"],
+        "startDate": worklog["startDate"],
+        "startTime": worklog["startTime"],
+        "timeSpentSeconds": worklog["timeSpentSeconds"],
+        "billableSeconds": worklog["billableSeconds"],
+        "attributes": attributes,
+    });
+    if let Some(description) = worklog["description"].as_str() {
+        update["description"] = json!(description);
+    }
+    update
+}
+
+/// Books a worklog on a billing account, which takes Tempo.
+pub fn book_account(tempo: Option<&Tempo>, jira_worklog_id: &str, account: &str) -> Result<()> {
+    let tempo = tempo.ok_or_else(|| anyhow!("Set tempo_api_token to book worklogs on billing accounts"))?;
+    tempo.book_account(jira_worklog_id, account)
+}
+
+/// Approvals for the demo: older weeks approved, last week rejected, this week open.
+fn demo_approval(week_start: Date, today: Date) -> Value {
+    let weeks_ago = (today - week_start).get_days() / 7;
+    let (key, comment) = match weeks_ago {
+        0 => ("OPEN", ""),
+        1 => ("REJECTED", "Friday is missing"),
+        _ => ("APPROVED", "Thanks!"),
+    };
+    json!({
+        "requiredSeconds": 40 * 3600,
+        "timeSpentSeconds": if weeks_ago == 1 { 32 * 3600 } else { 40 * 3600 },
+        "status": { "key": key, "comment": comment },
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_json() {
+        let approval = Approval::from_json(&json!({
+            "period": { "from": "2025-01-06", "to": "2025-01-12" },
+            "requiredSeconds": 144000,
+            "timeSpentSeconds": 115200,
+            "status": { "key": "REJECTED", "comment": "Friday is missing" },
+        }));
+        assert_eq!(approval.status, ApprovalStatus::Rejected);
+        assert!(approval.status.can_submit());
+        assert_eq!(approval.logged_s, 115200);
+        assert_eq!(approval.comment.as_deref(), Some("Friday is missing"));
+
+        let approval = Approval::from_json(&json!({ "status": { "key": "IN_REVIEW", "comment": "" } }));
+        assert_eq!(approval.status, ApprovalStatus::InReview);
+        assert!(!approval.status.can_submit());
+        assert_eq!(approval.comment, None);
+    }
+
+    #[test]
+    fn test_with_attribute() {
+        let worklog = json!({
+            "tempoWorklogId": 126,
+            "author": { "accountId": "5b10ac8d82e05b22cc7d4ef5" },
+            "startDate": "2025-01-10",
+            "startTime": "09:00:00",
+            "timeSpentSeconds": 1800,
+            "billableSeconds": 1800,
+            "description": "Fix login",
+            "attributes": { "values": [
+                { "key": "_Account_", "value": "ACME-OLD" },
+                { "key": "_Phase_", "value": "Build" },
+            ]},
+        });
+        let update = with_attribute(&worklog, "_Account_", "ACME-SUPPORT");
+        assert_eq!(update["authorAccountId"], "5b10ac8d82e05b22cc7d4ef5");
+        assert_eq!(update["timeSpentSeconds"], 1800);
+        assert_eq!(update["description"], "Fix login");
+        assert_eq!(
+            update["attributes"],
+            json!([
+                { "key": "_Phase_", "value": "Build" },
+                { "key": "_Account_", "value": "ACME-SUPPORT" },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_week_starts() {
+        let starts = week_starts(jiff::civil::date(2025, 1, 6));
+        assert_eq!(starts.len(), 4);
+        assert_eq!(starts[1], jiff::civil::date(2024, 12, 30));
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::{Duration, Instant};
+
+/// Durations of the startup phases, reported with `--timing`.
+#[derive(Debug)]
+pub struct Timing {
+    started: Instant,
+    last: Instant,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        let now = Instant::now();
+        Timing {
+            started: now,
+            last: now,
+            phases: vec![],
+        }
+    }
+}
+
+impl Timing {
+    /// Records the time spent since the previous phase ended.
+    pub fn phase(&mut self, name: &'static str) {
+        let now = Instant::now();
+        self.phases.push((name, now - self.last));
+        self.last = now;
+    }
+
+    pub fn report(&self) -> String {
+        let mut lines: Vec<String> = self
+            .phases
+            .iter()
+            .map(|(name, duration)| format!("{name:<20} {:>8.1} ms", duration.as_secs_f64() * 1000.0))
+            .collect();
+        let total = self.last - self.started;
+        lines.push(format!("{:<20} {:>8.1} ms", "total", total.as_secs_f64() * 1000.0));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut timing = Timing::default();
+        timing.phase("config");
+        timing.phase("issues");
+        let report = timing.report();
+        let names: Vec<&str> = report
+            .lines()
+            .map(|line| line.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(names, ["config", "issues", "total"]);
+        assert!(report.ends_with(" ms"));
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// An issue change received from a Jira webhook.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookEvent {
+    Created(Value),
+    Updated(Value),
+    Deleted(String),
+}
+
+impl WebhookEvent {
+    fn from_payload(payload: &Value) -> Option<WebhookEvent> {
+        let issue = &payload["issue"];
+        match payload["webhookEvent"].as_str()? {
+            "jira:issue_created" => Some(WebhookEvent::Created(issue.clone())),
+            "jira:issue_updated" => Some(WebhookEvent::Updated(issue.clone())),
+            "jira:issue_deleted" => Some(WebhookEvent::Deleted(issue["key"].as_str()?.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Largest webhook body accepted, Jira's issue payloads stay well below.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// Limit on the request line and headers together.
+const MAX_HEADERS: usize = 64 * 1024;
+
+/// How long a connection may stay silent before it is dropped, so a stalled client
+/// doesn't hold up the webhooks after it.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Starts listening for Jira webhooks on `address` and `port` in a background thread.
+///
+/// Requests have to carry the token as `?token=...` in the webhook URL, which also
+/// works behind a tunnel exposing the port publicly.
+pub fn listen(address: &str, port: u16, token: Option<String>) -> Result<Receiver<WebhookEvent>> {
+    let token = token
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| anyhow!("Set webhook_token to receive webhooks"))?;
+    let listener = TcpListener::bind((address, port))?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A failing connection should not stop the listener.
+            let _ = handle_connection(stream, &token, &sender);
+        }
+    });
+    Ok(receiver)
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    token: &str,
+    sender: &Sender<WebhookEvent>,
+) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(Read::take(&stream, (MAX_HEADERS + MAX_BODY) as u64));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    let length: usize = headers
+        .get("content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    let status = match authorize(&request_line, token) {
+        Err(status) => status,
+        Ok(()) if length > MAX_BODY => "413 Payload Too Large",
+        Ok(()) => {
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body)?;
+            respond(parse_body(&body), sender)?
+        }
+    };
+    stream.write_all(format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n").as_bytes())?;
+    Ok(())
+}
+
+fn respond(
+    event: Result<Option<WebhookEvent>, &'static str>,
+    sender: &Sender<WebhookEvent>,
+) -> Result<&'static str> {
+    Ok(match event {
+        Ok(Some(event)) => {
+            sender.send(event)?;
+            "204 No Content"
+        }
+        Ok(None) => "204 No Content",
+        Err(status) => status,
+    })
+}
+
+/// Checks the request line and token, before anything of the body is read.
+fn authorize(request_line: &str, token: &str) -> Result<(), &'static str> {
+    let mut parts = request_line.split_whitespace();
+    if parts.next() != Some("POST") {
+        return Err("405 Method Not Allowed");
+    }
+    let target = parts.next().unwrap_or("");
+    let query = target.split_once('?').map(|(_, query)| query).unwrap_or("");
+    let authorized = query
+        .split('&')
+        .any(|param| param.strip_prefix("token=") == Some(token));
+    if !authorized {
+        return Err("401 Unauthorized");
+    }
+    Ok(())
+}
+
+fn parse_body(body: &[u8]) -> Result<Option<WebhookEvent>, &'static str> {
+    let payload: Value = serde_json::from_slice(body).map_err(|_| "400 Bad Request")?;
+    Ok(WebhookEvent::from_payload(&payload))
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_authorize() {
+        assert_eq!(authorize("POST /?token=abc HTTP/1.1", "abc"), Ok(()));
+        assert_eq!(authorize("POST /?token=xyz HTTP/1.1", "abc"), Err("401 Unauthorized"));
+        assert_eq!(authorize("POST / HTTP/1.1", "abc"), Err("401 Unauthorized"));
+        assert_eq!(authorize("GET /?token=abc HTTP/1.1", "abc"), Err("405 Method Not Allowed"));
+    }
+
+    #[test]
+    fn test_parse_body() {
+        let body = json!({
+            "webhookEvent": "jira:issue_updated",
+            "issue": {"key": "CLI-1"}
+        })
+        .to_string();
+        assert_eq!(
+            parse_body(body.as_bytes()),
+            Ok(Some(WebhookEvent::Updated(json!({"key": "CLI-1"}))))
+        );
+        let deleted = json!({"webhookEvent": "jira:issue_deleted", "issue": {"key": "CLI-2"}});
+        assert_eq!(
+            parse_body(deleted.to_string().as_bytes()),
+            Ok(Some(WebhookEvent::Deleted("CLI-2".to_string())))
+        );
+        assert_eq!(parse_body(b"{"), Err("400 Bad Request"));
+    }
+
+    #[test]
+    fn test_listen() {
+        assert!(listen("127.0.0.1", 0, None).is_err());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, _receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle_connection(stream, "abc", &sender).unwrap();
+        });
+        let mut client = TcpStream::connect(address).unwrap();
+        write!(client, "POST /?token=abc HTTP/1.1\r\nContent-Length: 99999999999\r\n\r\n").unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 413"));
+    }
+}
@@ -0,0 +1,181 @@
+use jiff::{ToSpan, Zoned};
+
+use crate::{reminder::WorkHours, session::Session};
+
+/// Untracked stretches shorter than this are not worth flagging.
+pub const MIN_GAP_S: u64 = 15 * 60;
+
+/// Time tracked on one issue today.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssueDay {
+    pub issue_key: String,
+    pub submitted_s: u64,
+    pub unsubmitted_s: u64,
+}
+
+/// Today's tracked time, shown when quitting at the end of the day.
+#[derive(Debug, Default, PartialEq)]
+pub struct DaySummary {
+    /// Issues in the order they were first worked on.
+    pub issues: Vec<IssueDay>,
+    /// Untracked stretches of the working day.
+    pub gaps: Vec<(Zoned, Zoned)>,
+}
+
+impl DaySummary {
+    /// Summarizes the sessions of today; gaps are looked for within the working hours
+    /// when they are set, otherwise between the first and the last session.
+    pub fn new(
+        submitted: &[Session],
+        unsubmitted: &[Session],
+        work_hours: Option<&WorkHours>,
+        now: &Zoned,
+    ) -> DaySummary {
+        let today = now.date();
+        let mut sessions: Vec<(&Session, bool)> = submitted
+            .iter()
+            .map(|session| (session, true))
+            .chain(unsubmitted.iter().map(|session| (session, false)))
+            .filter(|(session, _)| session.started.date() == today)
+            .collect();
+        sessions.sort_by(|(a, _), (b, _)| a.started.cmp(&b.started));
+
+        let mut issues: Vec<IssueDay> = vec![];
+        for (session, is_submitted) in &sessions {
+            let index = match issues.iter().position(|issue| issue.issue_key == session.issue_key) {
+                Some(index) => index,
+                None => {
+                    issues.push(IssueDay {
+                        issue_key: session.issue_key.clone(),
+                        submitted_s: 0,
+                        unsubmitted_s: 0,
+                    });
+                    issues.len() - 1
+                }
+            };
+            if *is_submitted {
+                issues[index].submitted_s += session.duration_s;
+            } else {
+                issues[index].unsubmitted_s += session.duration_s;
+            }
+        }
+
+        let sessions: Vec<Session> = sessions.into_iter().map(|(session, _)| session.clone()).collect();
+        let window = match work_hours.filter(|work_hours| work_hours.elapsed_s(now) > 0) {
+            Some(work_hours) => now
+                .with()
+                .time(work_hours.start)
+                .build()
+                .ok()
+                .zip(now.with().time(work_hours.end).build().ok())
+                .map(|(start, end)| (start, end.min(now.clone()))),
+            None => sessions
+                .first()
+                .zip(sessions.iter().map(Session::ended).max())
+                .map(|(first, end)| (first.started.clone(), end)),
+        };
+        let gaps = window
+            .map(|(start, end)| gaps(&sessions, &start, &end, MIN_GAP_S))
+            .unwrap_or_default();
+        DaySummary { issues, gaps }
+    }
+
+    pub fn total_s(&self) -> u64 {
+        self.issues.iter().map(|issue| issue.submitted_s + issue.unsubmitted_s).sum()
+    }
+}
+
+/// Stretches between `start` and `end` of at least `min_gap_s` seconds that no
+/// session covers. The sessions must be sorted by their start.
+pub fn gaps(sessions: &[Session], start: &Zoned, end: &Zoned, min_gap_s: u64) -> Vec<(Zoned, Zoned)> {
+    let mut gaps = vec![];
+    let mut covered = start.clone();
+    let boundaries = sessions
+        .iter()
+        .map(|session| (session.started.clone(), session.ended()))
+        .chain([(end.clone(), end.clone())]);
+    for (started, ended) in boundaries {
+        let gap_end = started.min(end.clone());
+        if gap_end >= &covered + (min_gap_s as i64).seconds() {
+            gaps.push((covered.clone(), gap_end));
+        }
+        covered = covered.max(ended);
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session(issue_key: &str, started: &str, duration_s: u64) -> Session {
+        Session {
+            issue_key: issue_key.to_string(),
+            started: started.parse().unwrap(),
+            duration_s,
+            comment: None,
+            account: None,
+        }
+    }
+
+    #[test]
+    fn test_gaps() {
+        let sessions = [
+            session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 3600),
+            session("IMG-2", "2025-01-10T09:30:00+00:00[UTC]", 3600),
+            session("IMG-1", "2025-01-10T10:40:00+00:00[UTC]", 1800),
+            session("IMG-3", "2025-01-10T13:00:00+00:00[UTC]", 3600),
+        ];
+        let start = "2025-01-10T08:00:00+00:00[UTC]".parse().unwrap();
+        let end = "2025-01-10T14:05:00+00:00[UTC]".parse().unwrap();
+        let gaps: Vec<(String, String)> = gaps(&sessions, &start, &end, 15 * 60)
+            .into_iter()
+            .map(|(from, to)| (from.time().to_string(), to.time().to_string()))
+            .collect();
+        assert_eq!(
+            gaps,
+            [
+                ("08:00:00".to_string(), "09:00:00".to_string()),
+                ("11:10:00".to_string(), "13:00:00".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_day_summary() {
+        let submitted = [
+            session("IMG-1", "2025-01-10T09:00:00+00:00[UTC]", 3600),
+            session("IMG-9", "2025-01-09T09:00:00+00:00[UTC]", 3600),
+        ];
+        let unsubmitted = [
+            session("IMG-2", "2025-01-10T10:00:00+00:00[UTC]", 1800),
+            session("IMG-1", "2025-01-10T11:00:00+00:00[UTC]", 900),
+        ];
+        let work_hours = WorkHours {
+            start: "09:00".parse().unwrap(),
+            end: "17:00".parse().unwrap(),
+        };
+        let now = "2025-01-10T18:00:00+00:00[UTC]".parse().unwrap();
+        let summary = DaySummary::new(&submitted, &unsubmitted, Some(&work_hours), &now);
+        assert_eq!(
+            summary.issues,
+            [
+                IssueDay {
+                    issue_key: "IMG-1".to_string(),
+                    submitted_s: 3600,
+                    unsubmitted_s: 900,
+                },
+                IssueDay {
+                    issue_key: "IMG-2".to_string(),
+                    submitted_s: 0,
+                    unsubmitted_s: 1800,
+                },
+            ]
+        );
+        assert_eq!(summary.total_s(), 6300);
+        assert_eq!(summary.gaps.len(), 2);
+        assert_eq!(summary.gaps[0].0.time().to_string(), "10:30:00");
+        assert_eq!(summary.gaps[1].0.time().to_string(), "11:15:00");
+        assert_eq!(summary.gaps[1].1.time().to_string(), "17:00:00");
+    }
+}